@@ -1,21 +1,23 @@
 //! Backtest engine: runs all markets through strategy + risk pipeline,
-//! simulates fills (assumes immediate fill at market_ask), and settles PnL.
+//! simulates fills (assumes immediate fill at market_ask; market-making quotes
+//! rest in a queue-aware fill simulator), and settles PnL.
 
+use std::collections::{HashMap, HashSet};
 use std::time::Instant;
 
-use polymarket_crypto::config::{Config, Interval};
-use polymarket_crypto::engine::pipeline::{self, ProcessConfig, SignalSink};
+use polymarket_crypto::config::{BankrollMode, Config, HedgeVenue, Interval};
+use polymarket_crypto::engine::ensemble::Ensemble;
+use polymarket_crypto::engine::execution::ExecutionModel;
+use polymarket_crypto::engine::pipeline::{self, DeconflictionPolicy, HouseSide, ProcessConfig, SignalSink, StrategyHost};
+use polymarket_crypto::engine::queue_sim::QueueFillSim;
 use polymarket_crypto::engine::risk::StrategyRiskManager;
+use polymarket_crypto::engine::sizing::KellySizer;
 use polymarket_crypto::engine::state::{BinanceState, MarketState};
+use polymarket_crypto::math::fees::{FeeSchedule, Liquidity};
 use polymarket_crypto::math::oracle::OracleBasis;
-use polymarket_crypto::strategies::certainty_capture::CertaintyCapture;
-use polymarket_crypto::strategies::convexity_fade::ConvexityFade;
-use polymarket_crypto::strategies::cross_timeframe::CrossTimeframe;
-use polymarket_crypto::strategies::latency_arb::LatencyArb;
-use polymarket_crypto::strategies::lp_extreme::LpExtreme;
-use polymarket_crypto::strategies::strike_misalign::StrikeMisalign;
+use polymarket_crypto::strategies::params::StrategyParams;
+use polymarket_crypto::strategies::registry;
 use polymarket_crypto::math::pricing::{delta_bin, gamma_bin};
-use polymarket_crypto::strategies::{evaluate_filtered, Strategy};
 use polymarket_crypto::types::*;
 
 use crate::types::{MarketResult, TradeRecord};
@@ -84,67 +86,74 @@ pub fn backtest_config() -> Config {
         max_orders_per_market: 10,
         cooldown_ms: 5000,
         bankroll: 1000.0,
+        bankroll_mode: BankrollMode::Fixed,
+        wallet_sync_secs: 30,
         max_total_exposure_frac: 0.15,
         daily_loss_halt_frac: -0.03,
         weekly_loss_halt_frac: -0.08,
+        mtm_stop_loss_frac: 0.0,
+        mtm_take_profit_frac: 0.0,
+        strategy_stop_loss_frac: 0.0,
+        strategy_take_profit_frac: 0.0,
+        mtm_exit: false,
+        breaker_max_rejects: 0,
+        breaker_reject_cooloff_s: 0.0,
+        breaker_latency_p95_ms: 0.0,
+        breaker_latency_cooloff_s: 0.0,
+        breaker_adverse_window_s: 0.0,
+        breaker_adverse_limit: 0.0,
+        breaker_adverse_cooloff_s: 0.0,
+        breaker_calibration_limit: 0.0,
+        breaker_calibration_cooloff_s: 0.0,
+        risk_rules: Vec::new(),
+        exec_latency_ms: 250.0,
+        exec_min_fill_prob: 0.0,
         oracle_beta: 0.0,
         oracle_delta_s: 2.0,
+        oracle_cadence_s: 1.0,
         ewma_lambda: 0.94,
         sigma_floor_annual: 0.30,
         max_portfolio_delta: 0.0,
         max_portfolio_gamma_neg: 0.0,
-        strategy_latency_arb: true,
-        strategy_certainty_capture: true,
-        strategy_convexity_fade: true,
-        strategy_strike_misalign: true,
-        strategy_lp_extreme: true,
-        strategy_cross_timeframe: false,
+        max_dollar_delta: 0.0,
+        max_var_frac: 0.0,
+        var_confidence: 0.99,
+        var_paths: 2000,
+        var_assets: Vec::new(),
+        var_sample_secs: 5,
+        var_corr_window: 720,
+        kelly_fraction: 0.5,
+        kelly_fractions: HashMap::new(),
+        strategy_params: HashMap::new(),
+        strategy_toggles: HashMap::new(),
+        shadow_strategies: HashSet::new(),
+        ensemble: false,
+        deconflict_policy: String::new(),
+        deconflict_groups: Vec::new(),
+        max_direction_flips: 1,
+        house_side_min_confidence: 0.7,
+        hedge: false,
+        hedge_venue: HedgeVenue::Perp,
+        hedge_band_usd: 500.0,
+        hedge_step: 0.001,
+        hedge_fee_bps: 5.0,
+        hedge_dry_run: true,
+        hedge_rest_url: None,
+        binance_api_key: None,
+        binance_api_secret: None,
         dry_run: true,
         polymarket_private_key: None,
         polymarket_funder_address: None,
         polymarket_signature_type: 0,
+        polygon_rpc_url: String::new(),
     }
 }
 
 // ─── Strategy set ───
 
-struct StrategySet {
-    latency_arb: LatencyArb,
-    certainty_capture: CertaintyCapture,
-    convexity_fade: ConvexityFade,
-    cross_timeframe: CrossTimeframe,
-    strike_misalign: StrikeMisalign,
-    lp_extreme: LpExtreme,
-}
-
-impl StrategySet {
-    fn new() -> Self {
-        Self {
-            latency_arb: LatencyArb,
-            certainty_capture: CertaintyCapture,
-            convexity_fade: ConvexityFade,
-            cross_timeframe: CrossTimeframe,
-            strike_misalign: StrikeMisalign,
-            lp_extreme: LpExtreme,
-        }
-    }
-
-    fn binance_strategies(&self) -> Vec<&dyn Strategy> {
-        vec![&self.latency_arb, &self.lp_extreme]
-    }
-
-    fn pm_strategies(&self) -> Vec<&dyn Strategy> {
-        vec![
-            &self.certainty_capture,
-            &self.convexity_fade,
-            &self.cross_timeframe,
-            &self.lp_extreme,
-        ]
-    }
-
-    fn open_strategies(&self) -> Vec<&dyn Strategy> {
-        vec![&self.strike_misalign]
-    }
+/// Fresh instances of every registered strategy for one market (config toggles are ignored).
+fn new_strategy_host() -> StrategyHost {
+    StrategyHost::new(registry::build_all())
 }
 
 // ─── CSV Loaders ───
@@ -289,9 +298,14 @@ pub fn discover_markets(base_dir: &str) -> Vec<String> {
 
 /// SignalSink implementation for the backtest engine.
 /// Pushes fills and trade records directly into Vecs (no async channels).
+/// Quotes rest in `sim`; their trade record is completed when they fill.
 struct BacktestSink<'a> {
     fills: &'a mut Vec<Fill>,
     trade_records: &'a mut Vec<TradeRecord>,
+    host: &'a mut StrategyHost,
+    sim: &'a mut QueueFillSim,
+    /// Order ID → trade record template for resting quotes.
+    quotes: &'a mut HashMap<u64, TradeRecord>,
     market_idx: usize,
     strike: f64,
 }
@@ -302,13 +316,23 @@ impl<'a> SignalSink for BacktestSink<'a> {
     }
 
     fn on_order(&mut self, sig: &Signal, order: &Order, state: &MarketState, now_ms: i64) {
-        self.fills.push(Fill {
-            order_id: order.id,
-            strategy: sig.strategy,
-            side: sig.side,
-            price: order.price,
-            size: order.size,
-        });
+        self.host.on_order_sent(order, state);
+
+        let fee = state.info.fees.fill_fee(
+            order.price,
+            order.size,
+            Liquidity::of(order.order_type, order.post_only),
+        );
+        if !sig.quote {
+            self.fills.push(Fill {
+                order_id: order.id,
+                strategy: sig.strategy,
+                side: sig.side,
+                price: order.price,
+                size: order.size,
+                fee,
+            });
+        }
 
         let sigma = state.sigma_real();
         let s = state.s_est();
@@ -320,7 +344,7 @@ impl<'a> SignalSink for BacktestSink<'a> {
             0.0
         };
 
-        self.trade_records.push(TradeRecord {
+        let record = TradeRecord {
             market_idx: self.market_idx,
             order_id: order.id,
             strategy: sig.strategy.to_string(),
@@ -339,16 +363,30 @@ impl<'a> SignalSink for BacktestSink<'a> {
             distance_at_signal: (s - k).abs(),
             delta_at_fill: delta_bin(s, k, sigma, tau),
             gamma_at_fill: gamma_bin(s, k, sigma, tau),
+            fee,
             outcome: None,
             pnl: 0.0,
             won: false,
-        });
+        };
+        if sig.quote {
+            self.sim.post(order, state);
+            self.quotes.insert(order.id, record);
+        } else {
+            self.trade_records.push(record);
+        }
     }
 }
 
 // ─── Run backtest for a single market ───
 
-pub fn run_market(data_dir: &str, market_idx: usize, risk: &mut StrategyRiskManager, persistent_bs: Option<BinanceState>) -> Option<(MarketResult, BinanceState)> {
+pub fn run_market(
+    data_dir: &str,
+    market_idx: usize,
+    config: &Config,
+    risk: &mut StrategyRiskManager,
+    mut ensemble: Option<&mut Ensemble>,
+    persistent_bs: Option<BinanceState>,
+) -> Option<(MarketResult, BinanceState)> {
     let binance_trades = load_binance_csv(&format!("{}/binance.csv", data_dir));
     let pm_quotes = load_polymarket_csv(&format!("{}/polymarket.csv", data_dir));
     let book_snapshots = load_book_csv(&format!("{}/book.csv", data_dir));
@@ -369,7 +407,7 @@ pub fn run_market(data_dir: &str, market_idx: usize, risk: &mut StrategyRiskMana
         binance_trades.first().map(|t| t.price)?
     };
 
-    let oracle = OracleBasis::new(0.0, 2.0);
+    let oracle = OracleBasis::new(0.0, 2.0, 1.0);
     let bs = persistent_bs.unwrap_or_else(|| BinanceState::new(0.94, 10, 0.30, 60_000, 30_000));
     let mut state = MarketState::new(
         MarketInfo {
@@ -381,16 +419,21 @@ pub fn run_market(data_dir: &str, market_idx: usize, risk: &mut StrategyRiskMana
             strike,
             tick_size: 0.01,
             neg_risk: false,
+            fees: FeeSchedule::default(),
+            condition_id: String::new(),
         },
         bs,
         oracle,
     );
+    state.sizing = KellySizer::from_config(config);
+    state.execution = ExecutionModel::from_config(config);
+    state.params = StrategyParams::from_config(config);
 
-    let strats = StrategySet::new();
+    let mut strats = new_strategy_host();
+    strats.on_market_start(&state);
     let mut signal_buf: Vec<Signal> = Vec::new();
     let mut open_buf: Vec<Signal> = Vec::new();
-    let mut house_side: Option<Side> = None;
-    let mut flip_count: u32 = 0;
+    let mut house = HouseSide::default();
     let mut next_order_id: u64 = 1;
     let fake_instant = Instant::now();
     let n_events = events.len();
@@ -399,33 +442,58 @@ pub fn run_market(data_dir: &str, market_idx: usize, risk: &mut StrategyRiskMana
     let mut fills: Vec<Fill> = Vec::new();
     let mut trade_records: Vec<TradeRecord> = Vec::new();
 
+    // Resting market-making quotes and their trade record templates
+    let mut sim = QueueFillSim::new();
+    let mut quotes: HashMap<u64, TradeRecord> = HashMap::new();
+    let mut cancel_buf: Vec<u64> = Vec::new();
+
     for event in &events {
         apply_event(&mut state, event, fake_instant);
+        let now_ms = event.ts_ms();
+
+        // Resting quotes fill against the updated book before anything is re-evaluated
+        let fills_before = fills.len();
+        sim.on_market_update(&state, &mut fills);
+        if fills.len() > fills_before {
+            strats.on_resting_fills(&fills[fills_before..], &sim, &state);
+            for fill in &fills[fills_before..] {
+                if let Some(template) = quotes.get(&fill.order_id) {
+                    trade_records.push(TradeRecord {
+                        price: fill.price,
+                        size: fill.size,
+                        fee: fill.fee,
+                        ..template.clone()
+                    });
+                }
+                risk.on_fill(fill);
+                risk.greeks.on_fill(state.info.strike, state.info.end_ms, fill.side, fill.size);
+                state.position.record_fill(fill.side, fill.price, fill.size);
+            }
+            risk.greeks.recompute(state.s_est(), state.sigma_real(), now_ms, &state.oracle);
+        }
 
         if !state.has_data() {
             continue;
         }
 
-        let now_ms = event.ts_ms();
-
         // Evaluate strategies
         // Open window scales with market duration: ~5% of window, capped [15s, 300s]
         let market_duration_ms = market_info.end_ms - market_info.start_ms;
         let open_window_ms = (market_duration_ms / 20).clamp(15_000, 300_000);
         match event {
             ReplayEvent::Binance { .. } => {
-                evaluate_filtered(&strats.binance_strategies(), &state, now_ms, &mut signal_buf);
+                strats.evaluate(EvalTrigger::BinanceTrade, &state, now_ms, &mut signal_buf);
                 let elapsed_ms = now_ms - market_info.start_ms;
                 if elapsed_ms >= 0 && elapsed_ms <= open_window_ms {
-                    evaluate_filtered(&strats.open_strategies(), &state, now_ms, &mut open_buf);
+                    strats.evaluate(EvalTrigger::MarketOpen, &state, now_ms, &mut open_buf);
                     signal_buf.extend(open_buf.drain(..));
                 }
             }
             ReplayEvent::Polymarket { .. } | ReplayEvent::Book { .. } => {
-                evaluate_filtered(&strats.pm_strategies(), &state, now_ms, &mut signal_buf);
+                strats.evaluate(EvalTrigger::PolymarketQuote, &state, now_ms, &mut signal_buf);
                 let elapsed_ms = now_ms - market_info.start_ms;
                 if elapsed_ms >= 0 && elapsed_ms <= open_window_ms {
-                    evaluate_filtered(&strats.open_strategies(), &state, now_ms, &mut open_buf);
+                    strats.evaluate(EvalTrigger::MarketOpen, &state, now_ms, &mut open_buf);
                     signal_buf.extend(open_buf.drain(..));
                 }
             }
@@ -434,28 +502,33 @@ pub fn run_market(data_dir: &str, market_idx: usize, risk: &mut StrategyRiskMana
         // Shared signal pipeline: deconfliction, sorting, risk check, fill simulation
         let fills_before = fills.len();
         {
-            let config = ProcessConfig::backtest();
+            let process_config = ProcessConfig::backtest().with_deconfliction(DeconflictionPolicy::from_config(config));
             let mut sink = BacktestSink {
                 fills: &mut fills,
                 trade_records: &mut trade_records,
+                host: &mut strats,
+                sim: &mut sim,
+                quotes: &mut quotes,
                 market_idx,
                 strike,
             };
             pipeline::process_signals(
                 &mut signal_buf, &mut state, risk,
-                &mut house_side, &mut flip_count, &mut next_order_id, now_ms,
-                &config, &mut sink,
+                &mut house, &mut next_order_id, now_ms,
+                &process_config, ensemble.as_deref_mut(), &mut sink,
             );
         }
-        // Update portfolio Greeks for any new fills
+        pipeline::cancel_resting(&mut strats, &mut sim, risk, &state, &mut cancel_buf);
+
+        // Fills are immediate: ack + fill hooks, then portfolio Greeks
+        strats.on_simulated_fills(&fills[fills_before..], &state);
         for fill in &fills[fills_before..] {
-            risk.greeks.on_fill(fill.side, fill.size);
+            risk.on_fill(fill);
+            risk.greeks.on_fill(state.info.strike, state.info.end_ms, fill.side, fill.size);
+            state.position.record_fill(fill.side, fill.price, fill.size);
         }
         if fills.len() > fills_before {
-            risk.greeks.recompute(
-                state.s_est(), state.info.strike,
-                state.sigma_real(), state.tau_eff_s(now_ms),
-            );
+            risk.greeks.recompute(state.s_est(), state.sigma_real(), now_ms, &state.oracle);
         }
         signal_buf.clear();
     }
@@ -464,17 +537,24 @@ pub fn run_market(data_dir: &str, market_idx: usize, risk: &mut StrategyRiskMana
     let final_price = state.bn.binance_price;
     let final_distance = final_price - strike;
     let outcome = if final_distance >= 0.0 { Side::Up } else { Side::Down };
+    strats.on_market_end(outcome, &state);
+    if let Some(e) = ensemble {
+        e.on_market_end(outcome);
+    }
+    // Quotes still resting at expiry never filled
+    sim.clear();
 
     // Settle PnL
     let mut total_pnl = 0.0;
     let mut total_invested = 0.0;
     for trade in &mut trade_records {
         trade.outcome = Some(outcome);
-        let pnl = if trade.side == outcome {
+        let gross = if trade.side == outcome {
             (1.0 - trade.price) * trade.size
         } else {
             -(trade.price * trade.size)
         };
+        let pnl = gross - trade.fee;
         trade.pnl = pnl;
         trade.won = trade.side == outcome;
         total_pnl += pnl;
@@ -483,6 +563,7 @@ pub fn run_market(data_dir: &str, market_idx: usize, risk: &mut StrategyRiskMana
 
     // Settle risk manager for multi-market accumulation
     risk.settle_market(outcome, &fills);
+    risk.greeks.expire(market_info.end_ms);
 
     let dir_name = std::path::Path::new(data_dir)
         .file_name()
@@ -543,14 +624,16 @@ fn apply_event(state: &mut MarketState, event: &ReplayEvent, fake_instant: Insta
 
 // ─── Run all markets ───
 
-pub fn run_all_markets(market_dirs: &[String]) -> Vec<MarketResult> {
+/// `ensemble`: pool agreeing signals, with calibration carried across markets.
+pub fn run_all_markets(market_dirs: &[String], ensemble: bool) -> Vec<MarketResult> {
     let config = backtest_config();
     let mut risk = StrategyRiskManager::new(&config);
+    let mut ensemble = ensemble.then(Ensemble::new);
     let mut results = Vec::new();
     let mut persistent_bs: Option<BinanceState> = None;
 
     for (i, dir) in market_dirs.iter().enumerate() {
-        if let Some((result, bs)) = run_market(dir, i, &mut risk, persistent_bs.take()) {
+        if let Some((result, bs)) = run_market(dir, i, &config, &mut risk, ensemble.as_mut(), persistent_bs.take()) {
             results.push(result);
             persistent_bs = Some(bs);
         }
@@ -3,6 +3,8 @@
 //!
 //! Usage: cargo run --bin backtest -- <data_dir>
 //!   e.g. cargo run --bin backtest -- logs/5m
+//!        cargo run --bin backtest -- --compare=flow_momentum,latency_arb logs/5m
+//!        cargo run --bin backtest -- --ensemble logs/5m
 //!
 //! The data_dir can point to:
 //!   - A single market directory (with binance.csv, polymarket.csv, etc.)
@@ -25,6 +27,8 @@ use crossterm::{
 };
 use ratatui::prelude::*;
 
+use polymarket_crypto::strategies::registry;
+
 use crate::types::{BacktestApp, Tab};
 
 fn handle_key(app: &mut BacktestApp, key: KeyEvent) -> bool {
@@ -250,18 +254,7 @@ fn print_dump(app: &BacktestApp) {
             }
         }
         let strat_str: String = strat_counts.iter()
-            .map(|(s, n)| {
-                let short = match s.as_str() {
-                    "latency_arb" => "LA",
-                    "certainty_capture" => "CC",
-                    "convexity_fade" => "CF",
-                    "cross_timeframe" => "CT",
-                    "strike_misalign" => "SM",
-                    "lp_extreme" => "LP",
-                    _ => "??",
-                };
-                format!("{}:{}", short, n)
-            })
+            .map(|(s, n)| format!("{}:{}", registry::short(s), n))
             .collect::<Vec<_>>().join(" ");
 
         let outcome_str = if m.outcome == Side::Up { "UP" } else { "DN" };
@@ -389,18 +382,82 @@ fn print_dump(app: &BacktestApp) {
     println!();
 }
 
+// ─── Compare mode: head-to-head of two strategies from the same run ───
+
+fn print_compare(app: &BacktestApp, a: &str, b: &str) {
+    use polymarket_crypto::types::Side;
+
+    println!("\u{2500}\u{2500}\u{2500} HEAD-TO-HEAD: {} vs {} \u{2500}\u{2500}\u{2500}", a, b);
+    println!("{:<20} {:>5} {:>6} {:>7} {:>9} {:>9} {:>7} {:>6} {:>8} {:>8} {:>5}",
+        "Strategy", "Trd", "Mkts", "WR%", "PnL", "Invested", "ROI%", "PF", "AvgEdge", "PnL/Trd", "AvgT");
+    println!("{:-<100}", "");
+    for name in [a, b] {
+        let Some(s) = app.strategy_stats.get(name) else {
+            println!("{:<20} (no trades)", name);
+            continue;
+        };
+        let n_markets = app.markets.iter().filter(|m| m.trades.iter().any(|t| t.strategy == name)).count();
+        let per_trade = if s.n_orders > 0 { s.total_pnl / s.n_orders as f64 } else { 0.0 };
+        println!("{:<20} {:>5} {:>6} {:>6.1}% ${:>+8.2} ${:>8.2} {:>6.1}% {:>6.2} {:>8.4} ${:>+7.2} {:>4.0}s",
+            name, s.n_orders, n_markets, s.win_rate() * 100.0, s.total_pnl, s.total_invested,
+            s.roi(), s.profit_factor(), s.avg_edge(), per_trade, s.avg_time_left_s);
+    }
+    println!();
+
+    // Per-market overlap: did they trade the same markets, the same way?
+    let (mut only_a, mut only_b, mut both, mut same_side) = (0usize, 0usize, 0usize, 0usize);
+    let (mut pnl_only_a, mut pnl_only_b, mut pnl_both_a, mut pnl_both_b) = (0.0, 0.0, 0.0, 0.0);
+    for m in &app.markets {
+        let side_pnl = |name: &str| -> Option<(Side, f64)> {
+            let trades: Vec<_> = m.trades.iter().filter(|t| t.strategy == name).collect();
+            let first = trades.first()?;
+            Some((first.side, trades.iter().map(|t| t.pnl).sum()))
+        };
+        match (side_pnl(a), side_pnl(b)) {
+            (Some((sa, pa)), Some((sb, pb))) => {
+                both += 1;
+                if sa == sb { same_side += 1; }
+                pnl_both_a += pa;
+                pnl_both_b += pb;
+            }
+            (Some((_, pa)), None) => { only_a += 1; pnl_only_a += pa; }
+            (None, Some((_, pb))) => { only_b += 1; pnl_only_b += pb; }
+            (None, None) => {}
+        }
+    }
+    let corr = match (
+        app.correlation_names.iter().position(|n| n == a),
+        app.correlation_names.iter().position(|n| n == b),
+    ) {
+        (Some(i), Some(j)) => app.strategy_correlations[i][j],
+        _ => 0.0,
+    };
+    println!("  Markets only {:<18} {:>4}   PnL ${:>+8.2}", a, only_a, pnl_only_a);
+    println!("  Markets only {:<18} {:>4}   PnL ${:>+8.2}", b, only_b, pnl_only_b);
+    println!("  Markets with both           {:>4}   PnL ${:>+8.2} / ${:>+8.2}   same side {}/{}",
+        both, pnl_both_a, pnl_both_b, same_side, both);
+    println!("  Per-market PnL correlation  {:>+.2}", corr);
+    println!();
+}
+
 fn main() -> io::Result<()> {
     let args: Vec<String> = std::env::args().collect();
 
     let dump_mode = args.iter().any(|a| a == "--dump");
+    let ensemble = args.iter().any(|a| a == "--ensemble");
+    let compare = args.iter()
+        .find_map(|a| a.strip_prefix("--compare="))
+        .and_then(|v| v.split_once(','));
     let data_dir = args.iter().skip(1).find(|a| !a.starts_with("--"));
 
     let data_dir = match data_dir {
         Some(d) => d.as_str(),
         None => {
-            eprintln!("Usage: backtest [--dump] <data_dir>");
+            eprintln!("Usage: backtest [--dump] [--ensemble] [--compare=<a>,<b>] <data_dir>");
             eprintln!("  e.g. cargo run --bin backtest -- logs/5m");
-            eprintln!("  --dump  Print results to stdout instead of TUI");
+            eprintln!("  --dump            Print results to stdout instead of TUI");
+            eprintln!("  --compare=<a>,<b> Print a head-to-head of two strategies instead of TUI");
+            eprintln!("  --ensemble        Pool agreeing signals into calibration-weighted orders");
             std::process::exit(1);
         }
     };
@@ -415,7 +472,7 @@ fn main() -> io::Result<()> {
     eprintln!("Found {} market(s)", market_dirs.len());
 
     eprintln!("Running backtest...");
-    let results = engine::run_all_markets(&market_dirs);
+    let results = engine::run_all_markets(&market_dirs, ensemble);
     eprintln!("Completed {} market(s)", results.len());
 
     if results.is_empty() {
@@ -435,6 +492,11 @@ fn main() -> io::Result<()> {
 
     if dump_mode {
         print_dump(&app);
+    }
+    if let Some((a, b)) = compare {
+        print_compare(&app, a, b);
+    }
+    if dump_mode || compare.is_some() {
         return Ok(());
     }
 
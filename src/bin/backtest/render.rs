@@ -1,6 +1,7 @@
 use ratatui::prelude::*;
 use ratatui::widgets::*;
 
+use polymarket_crypto::strategies::registry::{self, StrategyColor};
 use polymarket_crypto::types::Side;
 
 use crate::types::{BacktestApp, Tab};
@@ -21,27 +22,23 @@ fn pnl_color(pnl: f64) -> Color {
 }
 
 fn strategy_color(name: &str) -> Color {
-    match name {
-        "latency_arb" => YELLOW,
-        "certainty_capture" => Color::LightCyan,
-        "convexity_fade" => Color::LightMagenta,
-        "cross_timeframe" => Color::LightBlue,
-        "strike_misalign" => Color::LightRed,
-        "lp_extreme" => Color::LightGreen,
-        _ => WHITE,
+    match registry::color(name) {
+        StrategyColor::Yellow => YELLOW,
+        StrategyColor::LightCyan => Color::LightCyan,
+        StrategyColor::LightMagenta => Color::LightMagenta,
+        StrategyColor::LightBlue => Color::LightBlue,
+        StrategyColor::LightRed => Color::LightRed,
+        StrategyColor::LightGreen => Color::LightGreen,
+        StrategyColor::LightYellow => Color::LightYellow,
+        StrategyColor::White => WHITE,
+        StrategyColor::Cyan => CYAN,
+        StrategyColor::Magenta => Color::Magenta,
+        StrategyColor::Red => RED,
     }
 }
 
 fn strategy_short(name: &str) -> &'static str {
-    match name {
-        "latency_arb" => "LA",
-        "certainty_capture" => "CC",
-        "convexity_fade" => "CF",
-        "cross_timeframe" => "CT",
-        "strike_misalign" => "SM",
-        "lp_extreme" => "LP",
-        _ => "??",
-    }
+    registry::short(name)
 }
 
 /// Heatmap-style color for correlation values (-1..+1)
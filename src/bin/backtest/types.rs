@@ -26,6 +26,8 @@ pub struct TradeRecord {
     // Greeks at fill time
     pub delta_at_fill: f64,
     pub gamma_at_fill: f64,
+    /// Signed fee at fill: positive = taker fee, negative = maker rebate.
+    pub fee: f64,
     // Settlement
     pub outcome: Option<Side>,
     pub pnl: f64,
@@ -7,6 +7,7 @@
 use std::time::Instant;
 
 use polymarket_crypto::engine::state::{BinanceState, MarketState};
+use polymarket_crypto::math::fees::FeeSchedule;
 use polymarket_crypto::math::oracle::OracleBasis;
 use polymarket_crypto::strategies::latency_arb::LatencyArb;
 use polymarket_crypto::strategies::certainty_capture::CertaintyCapture;
@@ -121,7 +122,7 @@ fn run_single_market(data_dir: &str) -> (Vec<BacktestEntry>, bool, u64) {
     eprintln!("Strike: ${:.2}{}", strike, if market_info.strike > 0.0 { " (from market_info)" } else { " (from first Binance trade)" });
 
     // Initialize MarketState with persistent BinanceState
-    let oracle = OracleBasis::new(0.0, 2.0);
+    let oracle = OracleBasis::new(0.0, 2.0, 1.0);
     let bs = BinanceState::new(0.94, 10, 0.30, 60_000, 30_000);
     let mut state = MarketState::new(
         MarketInfo {
@@ -133,6 +134,8 @@ fn run_single_market(data_dir: &str) -> (Vec<BacktestEntry>, bool, u64) {
             strike,
             tick_size: 0.01,
             neg_risk: false,
+            fees: FeeSchedule::default(),
+            condition_id: String::new(),
         },
         bs,
         oracle,
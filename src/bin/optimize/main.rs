@@ -0,0 +1,362 @@
+//! Parameter optimiser: walk-forward grid search over each strategy's tunable
+//! thresholds (`StrategySpec::params`), replaying recorded markets through the
+//! backtest engine.
+//!
+//! Markets are grouped by the UTC date they start on. Each fold trains on
+//! `--train-days` consecutive recorded days and tests on the `--test-days` that
+//! follow; folds roll forward by the test length. For every parameter set the
+//! report shows the out-of-sample Sharpe (per-market PnL mean / std, as in the
+//! backtest), trade win rate and max drawdown over all test windows, and how
+//! many folds picked it. The walk-forward line stitches the test windows of the
+//! set each fold picked on its training window — the honest estimate of running
+//! the procedure. The set that scores best on the most recent training window is
+//! written to `--out`, ready for `STRATEGY_PARAMS_FILE`.
+//!
+//! Strategies are searched one at a time with the others at their defaults;
+//! every run still goes through the full strategy set and shared risk limits.
+//!
+//! Usage: cargo run --release --bin optimize -- [options] <data_dir>
+//!   e.g. cargo run --release --bin optimize -- logs/5m
+//!        cargo run --release --bin optimize -- --strategy=latency_arb --train-days=5 logs/5m
+
+#[allow(dead_code)]
+#[path = "../backtest/engine.rs"]
+mod engine;
+#[allow(dead_code)]
+#[path = "../backtest/types.rs"]
+mod types;
+
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use chrono::NaiveDate;
+
+use polymarket_crypto::engine::risk::StrategyRiskManager;
+use polymarket_crypto::engine::state::BinanceState;
+use polymarket_crypto::strategies::params::Param;
+use polymarket_crypto::strategies::registry::{self, StrategySpec};
+
+const DEFAULT_TRAIN_DAYS: usize = 3;
+const DEFAULT_TEST_DAYS: usize = 1;
+const DEFAULT_MIN_TRADES: u32 = 5;
+const DEFAULT_OUT: &str = "strategy_params.conf";
+
+// ─── Search space ───
+
+/// One point of a strategy's grid: a value per declared parameter.
+type ParamSet = Vec<f64>;
+
+/// Cartesian product of the strategy's grids, in declaration order.
+fn grid(params: &[Param]) -> Vec<ParamSet> {
+    params.iter().fold(vec![Vec::new()], |sets, p| {
+        sets.iter()
+            .flat_map(|set| p.grid.iter().map(move |&v| {
+                let mut next = set.clone();
+                next.push(v);
+                next
+            }))
+            .collect()
+    })
+}
+
+fn describe(params: &[Param], set: &[f64]) -> String {
+    params.iter().zip(set).map(|(p, v)| format!("{}={}", p.name, v)).collect::<Vec<_>>().join(" ")
+}
+
+// ─── Runs ───
+
+/// One strategy's per-market results under one parameter set
+/// (`None` where the market could not be replayed).
+struct Run {
+    markets: Vec<Option<MarketPnl>>,
+}
+
+#[derive(Clone, Copy, Default)]
+struct MarketPnl {
+    pnl: f64,
+    trades: u32,
+    wins: u32,
+}
+
+impl Run {
+    fn rows<'a>(&'a self, idx: &'a [usize]) -> impl Iterator<Item = MarketPnl> + 'a {
+        idx.iter().filter_map(|&i| self.markets[i])
+    }
+}
+
+/// Replay every market in order with `strategy` set to `set`, carrying risk and
+/// Binance state across markets as `engine::run_all_markets` does.
+fn run_set(dirs: &[String], spec: &StrategySpec, set: &[f64]) -> Run {
+    let mut config = engine::backtest_config();
+    for (p, &v) in spec.params.iter().zip(set) {
+        config.strategy_params.insert(format!("{}.{}", spec.name, p.name), v);
+    }
+    let mut risk = StrategyRiskManager::new(&config);
+    let mut persistent_bs: Option<BinanceState> = None;
+    let mut markets = vec![None; dirs.len()];
+
+    for (i, dir) in dirs.iter().enumerate() {
+        if let Some((result, bs)) = engine::run_market(dir, i, &config, &mut risk, None, persistent_bs.take()) {
+            let mut m = MarketPnl::default();
+            for t in result.trades.iter().filter(|t| t.strategy == spec.name) {
+                m.pnl += t.pnl;
+                m.trades += 1;
+                if t.pnl > 0.0 {
+                    m.wins += 1;
+                }
+            }
+            markets[i] = Some(m);
+            persistent_bs = Some(bs);
+        }
+    }
+    Run { markets }
+}
+
+/// Run every set, spread over the available cores.
+fn run_grid(dirs: &[String], spec: &StrategySpec, sets: &[ParamSet]) -> Vec<Run> {
+    let workers = std::thread::available_parallelism().map_or(1, |n| n.get()).min(sets.len());
+    let next = AtomicUsize::new(0);
+    let done = AtomicUsize::new(0);
+    let runs: Mutex<Vec<Option<Run>>> = Mutex::new((0..sets.len()).map(|_| None).collect());
+
+    std::thread::scope(|s| {
+        for _ in 0..workers {
+            s.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                if i >= sets.len() {
+                    break;
+                }
+                let run = run_set(dirs, spec, &sets[i]);
+                runs.lock().unwrap()[i] = Some(run);
+                let n = done.fetch_add(1, Ordering::Relaxed) + 1;
+                eprint!("\r  {}: {}/{} sets", spec.name, n, sets.len());
+            });
+        }
+    });
+    eprintln!();
+
+    runs.into_inner().unwrap().into_iter().map(|r| r.expect("every set ran")).collect()
+}
+
+// ─── Metrics ───
+
+#[derive(Clone, Copy, Default)]
+struct Metrics {
+    sharpe: f64,
+    win_rate: f64,
+    max_dd: f64,
+    pnl: f64,
+    trades: u32,
+}
+
+/// Sharpe over per-market PnL (same convention as the backtest), trade win rate,
+/// and max drawdown of the cumulative PnL, in market order.
+fn metrics(rows: impl Iterator<Item = MarketPnl>) -> Metrics {
+    let rows: Vec<MarketPnl> = rows.collect();
+    let mut m = Metrics::default();
+    let (mut wins, mut peak, mut equity) = (0u32, 0.0f64, 0.0f64);
+    for r in &rows {
+        m.pnl += r.pnl;
+        m.trades += r.trades;
+        wins += r.wins;
+        equity += r.pnl;
+        peak = peak.max(equity);
+        m.max_dd = m.max_dd.max(peak - equity);
+    }
+    if m.trades > 0 {
+        m.win_rate = wins as f64 / m.trades as f64;
+    }
+    if rows.len() >= 2 {
+        let mean = m.pnl / rows.len() as f64;
+        let var = rows.iter().map(|r| (r.pnl - mean).powi(2)).sum::<f64>() / (rows.len() - 1) as f64;
+        if var > 0.0 {
+            m.sharpe = mean / var.sqrt();
+        }
+    }
+    m
+}
+
+// ─── Walk-forward ───
+
+struct Fold {
+    train: Vec<usize>,
+    test: Vec<usize>,
+}
+
+/// Rolling folds over recorded days: `train_days` then `test_days`, stepping by `test_days`.
+fn folds(days: &[(NaiveDate, Vec<usize>)], train_days: usize, test_days: usize) -> Vec<Fold> {
+    let flat = |range: &[(NaiveDate, Vec<usize>)]| range.iter().flat_map(|(_, idx)| idx.iter().copied()).collect();
+    (0..)
+        .map(|k| k * test_days)
+        .take_while(|&start| start + train_days + test_days <= days.len())
+        .map(|start| Fold {
+            train: flat(&days[start..start + train_days]),
+            test: flat(&days[start + train_days..start + train_days + test_days]),
+        })
+        .collect()
+}
+
+/// Index of the set with the best Sharpe on `idx` among those with at least
+/// `min_trades` trades there; the default set when none qualifies.
+fn select(runs: &[Run], idx: &[usize], min_trades: u32, default: usize) -> usize {
+    runs.iter()
+        .map(|r| metrics(r.rows(idx)))
+        .enumerate()
+        .filter(|(_, m)| m.trades >= min_trades)
+        .fold(None, |best: Option<(usize, f64)>, (i, m)| match best {
+            Some((_, s)) if s >= m.sharpe => best,
+            _ => Some((i, m.sharpe)),
+        })
+        .map_or(default, |(i, _)| i)
+}
+
+fn arg<T: std::str::FromStr>(args: &[String], key: &str, default: T) -> T {
+    args.iter()
+        .find_map(|a| a.strip_prefix(key))
+        .map(|v| v.parse().unwrap_or_else(|_| {
+            eprintln!("Invalid value for {}{}", key, v);
+            std::process::exit(1);
+        }))
+        .unwrap_or(default)
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let only = args.iter().find_map(|a| a.strip_prefix("--strategy="));
+    let train_days: usize = arg(&args, "--train-days=", DEFAULT_TRAIN_DAYS);
+    let test_days: usize = arg(&args, "--test-days=", DEFAULT_TEST_DAYS);
+    let min_trades: u32 = arg(&args, "--min-trades=", DEFAULT_MIN_TRADES);
+    let out: String = arg(&args, "--out=", DEFAULT_OUT.to_string());
+    let data_dir = args.iter().skip(1).find(|a| !a.starts_with("--"));
+
+    let data_dir = match data_dir {
+        Some(d) if train_days > 0 && test_days > 0 => d.as_str(),
+        _ => {
+            eprintln!("Usage: optimize [--strategy=<name>] [--train-days=N] [--test-days=N] [--min-trades=N] [--out=<path>] <data_dir>");
+            eprintln!("  e.g. cargo run --release --bin optimize -- logs/5m");
+            eprintln!("  --strategy=<name>  Only search this strategy (default: all registered)");
+            eprintln!("  --train-days=N     Training window in recorded days (default {})", DEFAULT_TRAIN_DAYS);
+            eprintln!("  --test-days=N      Test window in recorded days (default {})", DEFAULT_TEST_DAYS);
+            eprintln!("  --min-trades=N     Trades a set needs in a training window to be picked (default {})", DEFAULT_MIN_TRADES);
+            eprintln!("  --out=<path>       Params file to write (default {})", DEFAULT_OUT);
+            std::process::exit(1);
+        }
+    };
+
+    let specs: Vec<&StrategySpec> = match only {
+        Some(name) => match registry::spec(name) {
+            Some(spec) => vec![spec],
+            None => {
+                eprintln!("Unknown strategy '{}'", name);
+                std::process::exit(1);
+            }
+        },
+        None => registry::STRATEGIES.iter().collect(),
+    };
+
+    eprintln!("Discovering markets in {}...", data_dir);
+    let mut dirs: Vec<(i64, String)> = engine::discover_markets(data_dir)
+        .into_iter()
+        .map(|d| (engine::load_market_info(&format!("{}/market_info.txt", d)).start_ms, d))
+        .filter(|(start_ms, _)| *start_ms > 0)
+        .collect();
+    dirs.sort();
+    if dirs.is_empty() {
+        eprintln!("No dated market data found in {}", data_dir);
+        eprintln!("Expected directories containing binance.csv + polymarket.csv + market_info.txt");
+        std::process::exit(1);
+    }
+
+    // Group market indices by UTC start date
+    let mut days: Vec<(NaiveDate, Vec<usize>)> = Vec::new();
+    for (i, (start_ms, _)) in dirs.iter().enumerate() {
+        let date = chrono::DateTime::from_timestamp_millis(*start_ms).map_or(NaiveDate::MIN, |t| t.date_naive());
+        match days.last_mut() {
+            Some((last, idx)) if *last == date => idx.push(i),
+            _ => days.push((date, vec![i])),
+        }
+    }
+    let dirs: Vec<String> = dirs.into_iter().map(|(_, d)| d).collect();
+
+    let folds = folds(&days, train_days, test_days);
+    if folds.is_empty() {
+        eprintln!(
+            "Found {} recorded day(s); walk-forward needs at least {} (train {} + test {})",
+            days.len(), train_days + test_days, train_days, test_days,
+        );
+        std::process::exit(1);
+    }
+    let first = days.first().map(|d| d.0).unwrap_or_default();
+    let last = days.last().map(|d| d.0).unwrap_or_default();
+    eprintln!("Found {} market(s) over {} day(s), {}..{} -- {} fold(s)", dirs.len(), days.len(), first, last, folds.len());
+
+    let final_train: Vec<usize> = days[days.len() - train_days..].iter().flat_map(|(_, idx)| idx.iter().copied()).collect();
+    let all_test: Vec<usize> = folds.iter().flat_map(|f| f.test.iter().copied()).collect();
+
+    let mut file = String::new();
+    let _ = writeln!(file, "# Strategy parameters chosen by `optimize` (walk-forward: train {}d / test {}d)", train_days, test_days);
+    let _ = writeln!(file, "# Data: {} -- {} markets, {}..{}", data_dir, dirs.len(), first, last);
+    let _ = writeln!(file, "# Load with STRATEGY_PARAMS_FILE=<this file>");
+
+    for spec in specs {
+        if spec.params.is_empty() {
+            continue;
+        }
+        let sets = grid(spec.params);
+        let default = sets
+            .iter()
+            .position(|s| s.iter().zip(spec.params).all(|(v, p)| *v == p.default))
+            .unwrap_or(0);
+        let runs = run_grid(&dirs, spec, &sets);
+
+        // Walk-forward: each fold picks on its training window, scored on its test window
+        let picks: Vec<usize> = folds.iter().map(|f| select(&runs, &f.train, min_trades, default)).collect();
+        let stitched = metrics(folds.iter().zip(&picks).flat_map(|(f, &p)| runs[p].rows(&f.test).collect::<Vec<_>>()));
+        let chosen = select(&runs, &final_train, min_trades, default);
+
+        println!();
+        println!("\u{2500}\u{2500}\u{2500} {} ({} sets \u{00d7} {} folds) \u{2500}\u{2500}\u{2500}", spec.name, sets.len(), folds.len());
+        println!("  {:<48} {:>8} {:>6} {:>9} {:>10} {:>6} {:>6}", "Parameters", "OOS Shrp", "Win%", "MaxDD", "PnL", "Trd", "Picked");
+        for (i, (set, run)) in sets.iter().zip(&runs).enumerate() {
+            let m = metrics(run.rows(&all_test));
+            let picked = picks.iter().filter(|&&p| p == i).count();
+            let tag = match (i == chosen, i == default) {
+                (true, true) => " *chosen (default)",
+                (true, false) => " *chosen",
+                (false, true) => " (default)",
+                _ => "",
+            };
+            println!(
+                "  {:<48} {:>8.3} {:>5.1}% {:>9} {:>10} {:>6} {:>3}/{:<2}{}",
+                describe(spec.params, set), m.sharpe, m.win_rate * 100.0,
+                format!("${:.2}", m.max_dd), format!("${:+.2}", m.pnl), m.trades,
+                picked, folds.len(), tag,
+            );
+        }
+        println!(
+            "  walk-forward OOS: Sharpe {:.3} | win {:.1}% | max DD ${:.2} | PnL ${:+.2} | {} trades",
+            stitched.sharpe, stitched.win_rate * 100.0, stitched.max_dd, stitched.pnl, stitched.trades,
+        );
+        println!("  chosen (last {}d): {}", train_days, describe(spec.params, &sets[chosen]));
+
+        let _ = writeln!(file);
+        let _ = writeln!(
+            file,
+            "# {}: walk-forward OOS Sharpe {:.3}, win {:.1}%, max DD ${:.2}, {} trades over {} folds",
+            spec.name, stitched.sharpe, stitched.win_rate * 100.0, stitched.max_dd, stitched.trades, folds.len(),
+        );
+        for (p, v) in spec.params.iter().zip(&sets[chosen]) {
+            let _ = writeln!(file, "{}.{} = {}", spec.name, p.name, v);
+        }
+    }
+
+    match std::fs::write(&out, file) {
+        Ok(()) => eprintln!("\nWrote {} (load with STRATEGY_PARAMS_FILE={})", out, out),
+        Err(e) => {
+            eprintln!("\nCannot write {}: {}", out, e);
+            std::process::exit(1);
+        }
+    }
+}
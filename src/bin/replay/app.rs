@@ -2,22 +2,22 @@ use std::collections::VecDeque;
 use std::io::{self, Write as IoWrite};
 use std::time::Instant;
 
+use polymarket_crypto::engine::execution::ExecutionModel;
+use polymarket_crypto::engine::pipeline::{self, HouseSide, ProcessConfig, SignalSink, StrategyHost};
+use polymarket_crypto::engine::queue_sim::QueueFillSim;
 use polymarket_crypto::engine::risk::StrategyRiskManager;
+use polymarket_crypto::engine::sizing::KellySizer;
 use polymarket_crypto::engine::state::{BinanceState, MarketState};
+use polymarket_crypto::math::fees::{FeeSchedule, Liquidity};
 use polymarket_crypto::math::oracle::OracleBasis;
 use polymarket_crypto::math::pricing::{delta_bin, p_fair, z_score};
-use polymarket_crypto::math::regime::Regime;
-use polymarket_crypto::strategies::certainty_capture::CertaintyCapture;
-use polymarket_crypto::strategies::convexity_fade::ConvexityFade;
-use polymarket_crypto::strategies::cross_timeframe::CrossTimeframe;
-use polymarket_crypto::strategies::latency_arb::LatencyArb;
-use polymarket_crypto::strategies::lp_extreme::LpExtreme;
-use polymarket_crypto::strategies::strike_misalign::StrikeMisalign;
-use polymarket_crypto::strategies::{evaluate_filtered, Strategy};
+use polymarket_crypto::math::regime::{Regime, RegimeModel};
+use polymarket_crypto::strategies::params::StrategyParams;
+use polymarket_crypto::strategies::registry;
 use polymarket_crypto::types::*;
 
 use crate::types::{
-    App, LoadedMarketInfo, OrderEntry, ReplayEvent, SignalEntry, replay_config,
+    App, LoadedMarketInfo, OrderEntry, ReplayEvent, ReplayRun, SignalEntry, replay_config,
 };
 
 // ─── Constants ───
@@ -28,51 +28,30 @@ const PM_QUOTE_CHART_CAP: usize = 500;
 
 // ─── Strategy helpers (avoid repeating strategy setup in 3 places) ───
 
-struct StrategySet {
-    latency_arb: LatencyArb,
-    certainty_capture: CertaintyCapture,
-    convexity_fade: ConvexityFade,
-    cross_timeframe: CrossTimeframe,
-    strike_misalign: StrikeMisalign,
-    lp_extreme: LpExtreme,
+/// Fresh instances of every registered strategy, same set as the backtester.
+fn new_strategy_host() -> StrategyHost {
+    StrategyHost::new(registry::build_all())
 }
 
-impl StrategySet {
-    fn new() -> Self {
-        Self {
-            latency_arb: LatencyArb,
-            certainty_capture: CertaintyCapture,
-            convexity_fade: ConvexityFade,
-            cross_timeframe: CrossTimeframe,
-            strike_misalign: StrikeMisalign,
-            lp_extreme: LpExtreme,
-        }
-    }
-
-    fn binance_strategies(&self) -> Vec<&dyn Strategy> {
-        vec![&self.latency_arb, &self.lp_extreme]
-    }
-
-    fn pm_strategies(&self) -> Vec<&dyn Strategy> {
-        vec![
-            &self.certainty_capture,
-            &self.convexity_fade,
-            &self.cross_timeframe,
-            &self.lp_extreme,
-        ]
-    }
-
-    fn open_strategies(&self) -> Vec<&dyn Strategy> {
-        vec![&self.strike_misalign]
+/// New simulated run whose strategies start from `state`.
+fn new_run(state: &MarketState) -> ReplayRun {
+    let mut strategies = new_strategy_host();
+    strategies.on_market_start(state);
+    ReplayRun {
+        strategies,
+        risk: StrategyRiskManager::new(&replay_config()),
+        house: HouseSide::default(),
+        next_order_id: 1,
+        sim: QueueFillSim::new(),
     }
 }
 
 // ─── MarketState construction helper ───
 
 fn new_market_state(info: &LoadedMarketInfo, strike: f64) -> MarketState {
-    let oracle = OracleBasis::new(0.0, 2.0);
+    let oracle = OracleBasis::new(0.0, 2.0, 1.0);
     let bs = BinanceState::new(0.94, 10, 0.30, 60_000, 30_000);
-    MarketState::new(
+    let mut state = MarketState::new(
         MarketInfo {
             slug: info.slug.clone(),
             start_ms: info.start_ms,
@@ -82,10 +61,17 @@ fn new_market_state(info: &LoadedMarketInfo, strike: f64) -> MarketState {
             strike,
             tick_size: 0.01,
             neg_risk: false,
+            fees: FeeSchedule::default(),
+            condition_id: String::new(),
         },
         bs,
         oracle,
-    )
+    );
+    let config = replay_config();
+    state.sizing = KellySizer::from_config(&config);
+    state.execution = ExecutionModel::from_config(&config);
+    state.params = StrategyParams::from_config(&config);
+    state
 }
 
 // ─── Evaluate strategies for one event, collect signals & orders ───
@@ -93,7 +79,7 @@ fn new_market_state(info: &LoadedMarketInfo, strike: f64) -> MarketState {
 fn evaluate_event(
     event: &ReplayEvent,
     state: &MarketState,
-    strats: &StrategySet,
+    strats: &mut StrategyHost,
     start_ms: i64,
     signal_buf: &mut Vec<Signal>,
     open_buf: &mut Vec<Signal>,
@@ -102,75 +88,135 @@ fn evaluate_event(
     // Open window scales with market duration: ~5% of window, capped [15s, 300s]
     let market_duration_ms = state.info.end_ms - state.info.start_ms;
     let open_window_ms = (market_duration_ms / 20).clamp(15_000, 300_000);
-    match event {
-        ReplayEvent::Binance { .. } => {
-            evaluate_filtered(&strats.binance_strategies(), state, now_ms, signal_buf);
-            let elapsed_ms = now_ms - start_ms;
-            if elapsed_ms >= 0 && elapsed_ms <= open_window_ms {
-                evaluate_filtered(&strats.open_strategies(), state, now_ms, open_buf);
-                signal_buf.extend(open_buf.drain(..));
-            }
-        }
-        ReplayEvent::Polymarket { .. } | ReplayEvent::Book { .. } => {
-            evaluate_filtered(&strats.pm_strategies(), state, now_ms, signal_buf);
-            let elapsed_ms = now_ms - start_ms;
-            if elapsed_ms >= 0 && elapsed_ms <= open_window_ms {
-                evaluate_filtered(&strats.open_strategies(), state, now_ms, open_buf);
-                signal_buf.extend(open_buf.drain(..));
-            }
-        }
+    let trigger = match event {
+        ReplayEvent::Binance { .. } => EvalTrigger::BinanceTrade,
+        ReplayEvent::Polymarket { .. } | ReplayEvent::Book { .. } => EvalTrigger::PolymarketQuote,
+    };
+    strats.evaluate(trigger, state, now_ms, signal_buf);
+    let elapsed_ms = now_ms - start_ms;
+    if elapsed_ms >= 0 && elapsed_ms <= open_window_ms {
+        strats.evaluate(EvalTrigger::MarketOpen, state, now_ms, open_buf);
+        signal_buf.extend(open_buf.drain(..));
     }
 }
 
-fn record_signals_and_orders(
-    signal_buf: &[Signal],
-    state: &MarketState,
+/// SignalSink for the TUI: logs every signal and order, and assumes orders
+/// fill immediately at their (slipped) price, as the backtester does.
+/// Market-making quotes rest in `sim` instead.
+struct ReplaySink<'a> {
+    signal_log: &'a mut Vec<SignalEntry>,
+    order_log: &'a mut Vec<OrderEntry>,
+    host: &'a mut StrategyHost,
+    sim: &'a mut QueueFillSim,
+    fills: Vec<Fill>,
     event_idx: usize,
-    risk: &mut StrategyRiskManager,
-    house_side: &mut Option<Side>,
-    next_order_id: &mut u64,
-    signal_log: &mut Vec<SignalEntry>,
-    order_log: &mut Vec<OrderEntry>,
-    now_ms: i64,
-) {
-    let btc = state.bn.binance_price;
-    let time_left_s = state.time_left_s(now_ms);
+}
 
-    for sig in signal_buf {
-        signal_log.push(SignalEntry {
-            event_idx,
-            btc_price: btc,
+impl<'a> SignalSink for ReplaySink<'a> {
+    fn on_signal(&mut self, sig: &Signal, state: &MarketState, now_ms: i64) {
+        self.signal_log.push(SignalEntry {
+            event_idx: self.event_idx,
+            btc_price: state.bn.binance_price,
             strategy: sig.strategy.to_string(),
             side: format!("{}", sig.side),
             edge: sig.edge,
             fair_value: sig.fair_value,
             market_price: sig.market_price,
-            time_left_s,
+            time_left_s: state.time_left_s(now_ms),
             is_passive: sig.is_passive,
         });
+    }
 
-        if let Some(order) = risk.check_strategy(sig, state, *next_order_id, now_ms) {
-            if house_side.is_none() && !sig.is_passive {
-                *house_side = Some(sig.side);
-            }
-            order_log.push(OrderEntry {
-                event_idx,
-                btc_price: btc,
-                id: order.id,
-                strategy: sig.strategy.to_string(),
-                side: format!("{}", sig.side),
-                price: order.price,
-                size: order.size,
-                edge: sig.edge,
-                time_left_s,
-                is_passive: sig.is_passive,
-            });
-            risk.on_order_sent(sig.strategy, now_ms, order.size);
-            *next_order_id += 1;
+    fn on_order(&mut self, sig: &Signal, order: &Order, state: &MarketState, now_ms: i64) {
+        self.order_log.push(OrderEntry {
+            event_idx: self.event_idx,
+            btc_price: state.bn.binance_price,
+            id: order.id,
+            strategy: sig.strategy.to_string(),
+            side: format!("{}", sig.side),
+            price: order.price,
+            size: order.size,
+            edge: sig.edge,
+            time_left_s: state.time_left_s(now_ms),
+            is_passive: sig.is_passive,
+        });
+        self.host.on_order_sent(order, state);
+        if sig.quote {
+            self.sim.post(order, state);
+            return;
         }
+        self.fills.push(Fill {
+            order_id: order.id,
+            strategy: sig.strategy,
+            side: sig.side,
+            price: order.price,
+            size: order.size,
+            fee: state.info.fees.fill_fee(
+                order.price,
+                order.size,
+                Liquidity::of(order.order_type, order.post_only),
+            ),
+        });
     }
 }
 
+/// Run one event's signals through the shared pipeline and feed the simulated
+/// fills back to strategies, position and Greeks (mirrors the backtest engine).
+/// Resting quotes are advanced against the book first, and cancels applied after.
+fn process_event_signals(
+    signal_buf: &mut Vec<Signal>,
+    state: &mut MarketState,
+    run: &mut ReplayRun,
+    event_idx: usize,
+    signal_log: &mut Vec<SignalEntry>,
+    order_log: &mut Vec<OrderEntry>,
+    now_ms: i64,
+) {
+    let mut resting_fills = Vec::new();
+    run.sim.on_market_update(state, &mut resting_fills);
+    run.strategies.on_resting_fills(&resting_fills, &run.sim, state);
+    record_fills(&resting_fills, state, run, now_ms);
+
+    let mut sink = ReplaySink {
+        signal_log,
+        order_log,
+        host: &mut run.strategies,
+        sim: &mut run.sim,
+        fills: Vec::new(),
+        event_idx,
+    };
+    pipeline::process_signals(
+        signal_buf, state, &mut run.risk,
+        &mut run.house, &mut run.next_order_id, now_ms,
+        &ProcessConfig::backtest(), None, &mut sink,
+    );
+    let fills = sink.fills;
+    pipeline::cancel_resting(&mut run.strategies, &mut run.sim, &mut run.risk, state, &mut Vec::new());
+
+    run.strategies.on_simulated_fills(&fills, state);
+    record_fills(&fills, state, run, now_ms);
+}
+
+/// Apply simulated fills to risk exposure, position and portfolio Greeks.
+fn record_fills(fills: &[Fill], state: &mut MarketState, run: &mut ReplayRun, now_ms: i64) {
+    if fills.is_empty() {
+        return;
+    }
+    for fill in fills {
+        run.risk.on_fill(fill);
+        run.risk.greeks.on_fill(state.info.strike, state.info.end_ms, fill.side, fill.size);
+        state.position.record_fill(fill.side, fill.price, fill.size);
+    }
+    run.risk.greeks.recompute(state.s_est(), state.sigma_real(), now_ms, &state.oracle);
+}
+
+/// Settle the run's strategies once the last event has been applied.
+fn end_market(run: &mut ReplayRun, state: &MarketState) {
+    let outcome = if state.distance() >= 0.0 { Side::Up } else { Side::Down };
+    run.strategies.on_market_end(outcome, state);
+    run.sim.clear();
+}
+
 // ─── App implementation ───
 
 impl App {
@@ -181,6 +227,7 @@ impl App {
         data_dir: String,
     ) -> Self {
         let state = new_market_state(&market_info, strike);
+        let run = new_run(&state);
         App {
             events,
             cursor: 0,
@@ -201,9 +248,7 @@ impl App {
             down_ask_chart: VecDeque::with_capacity(PM_QUOTE_CHART_CAP),
             signal_log: Vec::new(),
             order_log: Vec::new(),
-            risk: StrategyRiskManager::new(&replay_config()),
-            house_side: None,
-            next_order_id: 1,
+            run,
             fake_instant: Instant::now(),
             status_msg: None,
         }
@@ -359,7 +404,6 @@ impl App {
     }
 
     pub fn step_forward(&mut self, n: usize) {
-        let strats = StrategySet::new();
         let mut signal_buf: Vec<Signal> = Vec::new();
         let mut open_buf: Vec<Signal> = Vec::new();
         let end = (self.cursor + n).min(self.events.len());
@@ -380,26 +424,24 @@ impl App {
                 _ => {}
             }
 
-            // Evaluate strategies & collect signals
+            // Evaluate strategies & run signals through the shared pipeline
             if self.state.has_data() {
                 evaluate_event(
-                    &event, &self.state, &strats,
+                    &event, &self.state, &mut self.run.strategies,
                     self.market_info.start_ms,
                     &mut signal_buf, &mut open_buf,
                 );
 
-                if let Some(hs) = self.house_side {
-                    signal_buf.retain(|s| s.is_passive || s.side == hs);
-                }
-
-                let now_ms = event.ts_ms();
-                record_signals_and_orders(
-                    &signal_buf, &self.state, i,
-                    &mut self.risk, &mut self.house_side, &mut self.next_order_id,
-                    &mut self.signal_log, &mut self.order_log, now_ms,
+                process_event_signals(
+                    &mut signal_buf, &mut self.state, &mut self.run, i,
+                    &mut self.signal_log, &mut self.order_log, event.ts_ms(),
                 );
             }
             signal_buf.clear();
+
+            if i + 1 == self.events.len() {
+                end_market(&mut self.run, &self.state);
+            }
         }
         self.cursor = end;
     }
@@ -408,49 +450,55 @@ impl App {
         let target = self.cursor.saturating_sub(n);
         self.signal_log.clear();
         self.order_log.clear();
-        self.risk = StrategyRiskManager::new(&replay_config());
-        self.house_side = None;
-        self.next_order_id = 1;
         self.jump_to(target);
 
         let sig_start = target.saturating_sub(1000);
         self.collect_signals_range(sig_start, target);
     }
 
+    /// Rewind to the first event with a fresh run.
+    pub fn restart(&mut self) {
+        self.signal_log.clear();
+        self.order_log.clear();
+        self.jump_to(0);
+        self.run = new_run(&self.state);
+    }
+
+    /// Rebuild the run by replaying `from..to` (strategies start at `from`).
     fn collect_signals_range(&mut self, from: usize, to: usize) {
         let (snap_cursor, snap_state) = self.nearest_snapshot(from);
         let mut tmp_state = snap_state.clone();
 
-        let strats = StrategySet::new();
         let mut signal_buf: Vec<Signal> = Vec::new();
         let mut open_buf: Vec<Signal> = Vec::new();
 
-        for i in snap_cursor..to {
+        for i in snap_cursor..from {
+            Self::apply_event(&mut tmp_state, &self.events[i], self.fake_instant);
+        }
+        self.run = new_run(&tmp_state);
+
+        for i in from..to {
             let event = &self.events[i];
             Self::apply_event(&mut tmp_state, event, self.fake_instant);
 
-            if i < from || !tmp_state.has_data() {
+            if !tmp_state.has_data() {
                 continue;
             }
 
             evaluate_event(
-                event, &tmp_state, &strats,
+                event, &tmp_state, &mut self.run.strategies,
                 self.market_info.start_ms,
                 &mut signal_buf, &mut open_buf,
             );
 
-            if let Some(hs) = self.house_side {
-                signal_buf.retain(|s| s.is_passive || s.side == hs);
-            }
-
-            let now_ms = event.ts_ms();
-            record_signals_and_orders(
-                &signal_buf, &tmp_state, i,
-                &mut self.risk, &mut self.house_side, &mut self.next_order_id,
-                &mut self.signal_log, &mut self.order_log, now_ms,
+            process_event_signals(
+                &mut signal_buf, &mut tmp_state, &mut self.run, i,
+                &mut self.signal_log, &mut self.order_log, event.ts_ms(),
             );
             signal_buf.clear();
         }
+        // Keep the live state coherent with the rebuilt run (positions from simulated fills)
+        self.state = tmp_state;
     }
 
     // ── CSV export ──
@@ -473,6 +521,8 @@ impl App {
             "sigma","z_score","p_fair","delta",
             "vwap",
             "regime","regime_frac","regime_trend_up",
+            "trend_prob","vr_ratio","hurst","hmm_trend_prob",
+            "atm_iv","iv_rv_spread","iv_term_slope",
             "ewma_n","ewma_sigma_raw",
             "up_bid","up_ask","down_bid","down_ask",
             "up_book_best_bid","up_book_best_ask","up_book_spread","up_book_microprice","up_book_imbalance","up_book_bid_depth5","up_book_ask_depth5",
@@ -484,12 +534,11 @@ impl App {
             .map_err(|e| format!("Write error: {}", e))?;
 
         let mut state = new_market_state(&self.market_info, self.state.info.strike);
-        let strats = StrategySet::new();
+        let mut run = new_run(&state);
         let mut signal_buf: Vec<Signal> = Vec::new();
         let mut open_buf: Vec<Signal> = Vec::new();
-        let mut risk = StrategyRiskManager::new(&replay_config());
-        let mut house_side: Option<Side> = None;
-        let mut next_order_id: u64 = 1;
+        let mut signal_log: Vec<SignalEntry> = Vec::new();
+        let mut order_log: Vec<OrderEntry> = Vec::new();
 
         for i in 0..up_to {
             let event = &self.events[i];
@@ -524,6 +573,11 @@ impl App {
                 format!("{:.2}", state.bn.vwap_tracker.vwap()),
                 regime_str.to_string(), format!("{:.4}", state.bn.regime.dominant_frac()),
                 format!("{}", state.bn.regime.trend_direction_up()),
+                format!("{:.4}", state.bn.trend_prob()), format!("{:.4}", state.bn.vr_regime.ratio()),
+                format!("{:.4}", state.bn.vr_regime.hurst()), format!("{:.4}", state.bn.hmm_regime.trend_prob()),
+                state.atm_iv().map_or(String::new(), |v| format!("{:.8}", v)),
+                state.iv_rv_spread().map_or(String::new(), |v| format!("{:.8}", v)),
+                state.iv_term_slope().map_or(String::new(), |v| format!("{:.4}", v)),
                 format!("{}", state.bn.ewma_vol.n_samples()), format!("{:.8}", state.bn.ewma_vol.sigma()),
                 format!("{}", state.up_bid), format!("{}", state.up_ask),
                 format!("{}", state.down_bid), format!("{}", state.down_ask),
@@ -537,22 +591,26 @@ impl App {
                 format!("{:.1}", state.down_book.bid_depth(5)), format!("{:.1}", state.down_book.ask_depth(5)),
             ];
 
-            // Evaluate strategies
-            let mut signals_this_event: Vec<&Signal> = Vec::new();
+            // Evaluate strategies and run them through the shared pipeline. Afterwards
+            // signal_buf holds the surviving signals in processing order, and the
+            // approved ones appear in order_log in that same order.
+            order_log.clear();
             if state.has_data() {
-                evaluate_event(event, &state, &strats, self.market_info.start_ms, &mut signal_buf, &mut open_buf);
-                if let Some(hs) = house_side {
-                    signal_buf.retain(|s| s.is_passive || s.side == hs);
-                }
-                signals_this_event = signal_buf.iter().collect();
+                evaluate_event(event, &state, &mut run.strategies, self.market_info.start_ms, &mut signal_buf, &mut open_buf);
+                process_event_signals(
+                    &mut signal_buf, &mut state, &mut run, i,
+                    &mut signal_log, &mut order_log, ts_ms,
+                );
+                signal_log.clear();
             }
 
-            if signals_this_event.is_empty() {
+            if signal_buf.is_empty() {
                 writeln!(w, "{},,,,,,,,,,,,,,,", base.join(","))
                     .map_err(|e| format!("Write error: {}", e))?;
             } else {
-                for sig in &signals_this_event {
-                    let order = risk.check_strategy(sig, &state, next_order_id, ts_ms);
+                let mut orders = order_log.iter();
+                let mut next_order = orders.next();
+                for sig in &signal_buf {
                     let mut row = base.clone();
 
                     row.push(sig.strategy.to_string());
@@ -564,21 +622,20 @@ impl App {
                     row.push(format!("{:.4}", sig.size_frac));
                     row.push(format!("{}", sig.is_passive));
 
-                    if let Some(ref ord) = order {
-                        if house_side.is_none() && !sig.is_passive {
-                            house_side = Some(sig.side);
+                    match next_order {
+                        Some(ord) if ord.strategy == sig.strategy => {
+                            row.push(format!("{}", ord.id));
+                            row.push(ord.strategy.clone());
+                            row.push(ord.side.clone());
+                            row.push(format!("{:.4}", ord.price));
+                            row.push(format!("{:.2}", ord.size));
+                            row.push(format!("{:.4}", ord.edge));
+                            row.push(format!("{}", ord.is_passive));
+                            next_order = orders.next();
+                        }
+                        _ => {
+                            for _ in 0..7 { row.push(String::new()); }
                         }
-                        row.push(format!("{}", next_order_id));
-                        row.push(sig.strategy.to_string());
-                        row.push(format!("{}", sig.side));
-                        row.push(format!("{:.4}", ord.price));
-                        row.push(format!("{:.2}", ord.size));
-                        row.push(format!("{:.4}", sig.edge));
-                        row.push(format!("{}", sig.is_passive));
-                        risk.on_order_sent(sig.strategy, ts_ms, ord.size);
-                        next_order_id += 1;
-                    } else {
-                        for _ in 0..7 { row.push(String::new()); }
                     }
 
                     writeln!(w, "{}", row.join(","))
@@ -586,6 +643,10 @@ impl App {
                 }
             }
             signal_buf.clear();
+
+            if i + 1 == self.events.len() {
+                end_market(&mut run, &state);
+            }
         }
 
         w.flush().map_err(|e| format!("Flush error: {}", e))?;
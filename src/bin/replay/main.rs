@@ -20,9 +20,7 @@ use crossterm::{
 };
 use ratatui::prelude::*;
 
-use polymarket_crypto::engine::risk::StrategyRiskManager;
-
-use crate::types::{App, replay_config};
+use crate::types::App;
 
 // ─── Convenience helpers on App used only by the event loop ───
 
@@ -92,12 +90,7 @@ fn handle_key(app: &mut App, key: KeyEvent) -> bool {
         // Jump to start
         KeyEvent { code: KeyCode::Home, .. }
         | KeyEvent { code: KeyCode::Char('g'), modifiers: KeyModifiers::NONE, .. } => {
-            app.signal_log.clear();
-            app.order_log.clear();
-            app.risk = StrategyRiskManager::new(&replay_config());
-            app.house_side = None;
-            app.next_order_id = 1;
-            app.jump_to(0);
+            app.restart();
         }
 
         // Jump to end
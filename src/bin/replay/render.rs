@@ -6,6 +6,7 @@ use ratatui::widgets::*;
 
 use polymarket_crypto::math::pricing::{delta_bin, p_fair, z_score};
 use polymarket_crypto::math::regime::Regime;
+use polymarket_crypto::strategies::registry::{self, StrategyColor};
 use polymarket_crypto::types::Side;
 
 use crate::types::App;
@@ -14,17 +15,22 @@ use crate::types::App;
 
 const BORDER: Style = Style::new().fg(Color::DarkGray);
 
-/// Strategy name → (short label, color)
+/// Strategy name → (short label, color), from the strategy registry
 fn strategy_style(name: &str) -> (&'static str, Color) {
-    match name {
-        "latency_arb"       => ("LA", Color::Yellow),
-        "certainty_capture" => ("CC", Color::LightCyan),
-        "convexity_fade"    => ("CF", Color::LightMagenta),
-        "cross_timeframe"   => ("CT", Color::LightBlue),
-        "strike_misalign"   => ("SM", Color::LightRed),
-        "lp_extreme"        => ("LP", Color::LightGreen),
-        _                   => ("??", Color::White),
-    }
+    let color = match registry::color(name) {
+        StrategyColor::Yellow       => Color::Yellow,
+        StrategyColor::LightCyan    => Color::LightCyan,
+        StrategyColor::LightMagenta => Color::LightMagenta,
+        StrategyColor::LightBlue    => Color::LightBlue,
+        StrategyColor::LightRed     => Color::LightRed,
+        StrategyColor::LightGreen   => Color::LightGreen,
+        StrategyColor::LightYellow  => Color::LightYellow,
+        StrategyColor::White        => Color::White,
+        StrategyColor::Cyan         => Color::Cyan,
+        StrategyColor::Magenta      => Color::Magenta,
+        StrategyColor::Red          => Color::Red,
+    };
+    (registry::short(name), color)
 }
 
 fn side_color(side: &str) -> Color {
@@ -281,9 +287,8 @@ fn render_pm_side(
     if x_min >= x_max { return; }
 
     // Fair value points per strategy
-    let strat_names = ["latency_arb", "certainty_capture", "convexity_fade",
-                       "cross_timeframe", "strike_misalign", "lp_extreme"];
-    let fair_sets: Vec<(&str, Color, Vec<(f64, f64)>)> = strat_names.iter().filter_map(|&sname| {
+    let fair_sets: Vec<(&str, Color, Vec<(f64, f64)>)> = registry::STRATEGIES.iter().filter_map(|spec| {
+        let sname = spec.name;
         let pts: Vec<(f64, f64)> = app.signal_log.iter()
             .filter(|s| s.strategy == sname && s.side == side_filter)
             .filter(|s| (s.event_idx as f64) >= x_min && (s.event_idx as f64) <= x_max)
@@ -368,6 +373,8 @@ fn render_metrics(app: &App, area: Rect, buf: &mut Buffer) {
         Regime::Ambiguous => ("Ambiguous", Color::DarkGray),
     };
 
+    let trend_prob = s.bn.trend_prob();
+
     let dist = s.distance();
     let lines = vec![
         metric_line("σ    ", format!("{:.6}", sigma), Color::White),
@@ -378,6 +385,8 @@ fn render_metrics(app: &App, area: Rect, buf: &mut Buffer) {
             Span::styled("reg  ", Style::default().fg(Color::DarkGray)),
             Span::styled(format!("{} ({:.0}%)", regime_str, s.bn.regime.dominant_frac() * 100.0), Style::default().fg(regime_color)),
         ]),
+        metric_line("trnd ", format!("p={:.2} H={:.2}", trend_prob, s.bn.vr_regime.hurst()),
+            if trend_prob > 0.85 { Color::Yellow } else { Color::White }),
         metric_line("vwap ", format!("${:.1}", s.bn.vwap_tracker.vwap()), Color::White),
         Line::from(vec![
             Span::styled("dist ", Style::default().fg(Color::DarkGray)),
@@ -468,7 +477,7 @@ fn render_orders(app: &App, area: Rect, buf: &mut Buffer) {
     let header = Row::new(vec!["#", "Strategy", "Side", "Price", "Size", "Edge", "T-left", "T"])
         .style(Style::default().fg(Color::Cyan).bold());
 
-    let house_str = match app.house_side {
+    let house_str = match app.run.house.side {
         Some(Side::Up) => " house=UP",
         Some(Side::Down) => " house=DN",
         None => "",
@@ -1,10 +1,11 @@
-use std::collections::VecDeque;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::time::Instant;
 
 use polymarket_crypto::config::Config;
+use polymarket_crypto::engine::pipeline::{HouseSide, StrategyHost};
+use polymarket_crypto::engine::queue_sim::QueueFillSim;
 use polymarket_crypto::engine::risk::StrategyRiskManager;
 use polymarket_crypto::engine::state::MarketState;
-use polymarket_crypto::types::Side;
 
 // ─── CSV row types (each bin owns its own) ───
 
@@ -111,6 +112,17 @@ pub struct OrderEntry {
     pub is_passive: bool,
 }
 
+// ─── Simulated run (strategies + risk), rebuilt when navigation rewinds ───
+
+pub struct ReplayRun {
+    pub strategies: StrategyHost,
+    pub risk: StrategyRiskManager,
+    pub house: HouseSide,
+    pub next_order_id: u64,
+    /// Resting market-making quotes, filled against the recorded book.
+    pub sim: QueueFillSim,
+}
+
 // ─── App state ───
 
 pub struct App {
@@ -139,9 +151,7 @@ pub struct App {
     // Signal & order logs
     pub signal_log: Vec<SignalEntry>,
     pub order_log: Vec<OrderEntry>,
-    pub risk: StrategyRiskManager,
-    pub house_side: Option<Side>,
-    pub next_order_id: u64,
+    pub run: ReplayRun,
 
     pub fake_instant: Instant,
     pub status_msg: Option<(String, Instant)>,
@@ -164,24 +174,65 @@ pub fn replay_config() -> Config {
         max_orders_per_market: 10,
         cooldown_ms: 5000,
         bankroll: 1000.0,
+        bankroll_mode: polymarket_crypto::config::BankrollMode::Fixed,
+        wallet_sync_secs: 30,
         max_total_exposure_frac: 0.15,
         daily_loss_halt_frac: -0.03,
         weekly_loss_halt_frac: -0.08,
+        mtm_stop_loss_frac: 0.0,
+        mtm_take_profit_frac: 0.0,
+        strategy_stop_loss_frac: 0.0,
+        strategy_take_profit_frac: 0.0,
+        mtm_exit: false,
+        breaker_max_rejects: 0,
+        breaker_reject_cooloff_s: 0.0,
+        breaker_latency_p95_ms: 0.0,
+        breaker_latency_cooloff_s: 0.0,
+        breaker_adverse_window_s: 0.0,
+        breaker_adverse_limit: 0.0,
+        breaker_adverse_cooloff_s: 0.0,
+        breaker_calibration_limit: 0.0,
+        breaker_calibration_cooloff_s: 0.0,
+        risk_rules: Vec::new(),
+        exec_latency_ms: 250.0,
+        exec_min_fill_prob: 0.0,
         oracle_beta: 0.0,
         oracle_delta_s: 2.0,
+        oracle_cadence_s: 1.0,
         ewma_lambda: 0.94,
         sigma_floor_annual: 0.30,
         max_portfolio_delta: 0.0,
         max_portfolio_gamma_neg: 0.0,
-        strategy_latency_arb: true,
-        strategy_certainty_capture: true,
-        strategy_convexity_fade: true,
-        strategy_strike_misalign: true,
-        strategy_lp_extreme: true,
-        strategy_cross_timeframe: false,
+        max_dollar_delta: 0.0,
+        max_var_frac: 0.0,
+        var_confidence: 0.99,
+        var_paths: 2000,
+        var_assets: Vec::new(),
+        var_sample_secs: 5,
+        var_corr_window: 720,
+        kelly_fraction: 0.5,
+        kelly_fractions: HashMap::new(),
+        strategy_params: HashMap::new(),
+        strategy_toggles: HashMap::new(),
+        shadow_strategies: HashSet::new(),
+        ensemble: false,
+        deconflict_policy: String::new(),
+        deconflict_groups: Vec::new(),
+        max_direction_flips: 1,
+        house_side_min_confidence: 0.7,
+        hedge: false,
+        hedge_venue: polymarket_crypto::config::HedgeVenue::Perp,
+        hedge_band_usd: 500.0,
+        hedge_step: 0.001,
+        hedge_fee_bps: 5.0,
+        hedge_dry_run: true,
+        hedge_rest_url: None,
+        binance_api_key: None,
+        binance_api_secret: None,
         dry_run: true,
         polymarket_private_key: None,
         polymarket_funder_address: None,
         polymarket_signature_type: 0,
+        polygon_rpc_url: String::new(),
     }
 }
@@ -1,3 +1,7 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::strategies::{params, registry};
+
 /// Trading interval.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Interval {
@@ -114,6 +118,43 @@ impl Interval {
     }
 }
 
+/// Binance market the delta hedge trades.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HedgeVenue {
+    Spot,
+    /// USDⓈ-M perpetual futures.
+    Perp,
+}
+
+impl HedgeVenue {
+    /// `HEDGE_VENUE`: "spot", else perps.
+    pub fn parse(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "spot" => HedgeVenue::Spot,
+            _ => HedgeVenue::Perp,
+        }
+    }
+}
+
+/// How the risk bankroll follows the wallet's equity.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BankrollMode {
+    /// `BANKROLL`, capped at equity once the wallet has synced.
+    Fixed,
+    /// Wallet equity: wins compound, losses shrink the stakes.
+    Compound,
+}
+
+impl BankrollMode {
+    /// `BANKROLL_MODE`: "compound", else fixed.
+    pub fn parse(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "compound" | "compounding" => BankrollMode::Compound,
+            _ => BankrollMode::Fixed,
+        }
+    }
+}
+
 /// Configuration loaded from environment variables.
 #[derive(Clone)]
 pub struct Config {
@@ -141,13 +182,52 @@ pub struct Config {
 
     // Bankroll & portfolio risk
     pub bankroll: f64,
+    pub bankroll_mode: BankrollMode,
+    /// Seconds between wallet reconciliations against chain + Data API (live only).
+    pub wallet_sync_secs: u64,
     pub max_total_exposure_frac: f64,
     pub daily_loss_halt_frac: f64,
     pub weekly_loss_halt_frac: f64,
 
+    // Intramarket mark-to-market stops (fractions of bankroll, 0.0 = disabled)
+    /// Market MTM drawdown from its peak that stops all strategies' new buys.
+    pub mtm_stop_loss_frac: f64,
+    /// Market MTM gain that stops all strategies' new buys.
+    pub mtm_take_profit_frac: f64,
+    /// Per-strategy MTM drawdown from its peak that stops the strategy.
+    pub strategy_stop_loss_frac: f64,
+    /// Per-strategy MTM gain that stops the strategy.
+    pub strategy_take_profit_frac: f64,
+    /// On a stop, also sell the stopped strategies' open inventory at the bid.
+    pub mtm_exit: bool,
+
+    // Circuit breakers (limit 0 = off; each trip halts buys for its cool-off)
+    /// Consecutive rejected / timed-out order acks.
+    pub breaker_max_rejects: u32,
+    pub breaker_reject_cooloff_s: f64,
+    /// p95 submit→ack latency of recent fills (ms).
+    pub breaker_latency_p95_ms: f64,
+    pub breaker_latency_cooloff_s: f64,
+    /// Markout horizon for adverse selection.
+    pub breaker_adverse_window_s: f64,
+    /// Mean mid move against our fills over the horizon (probability points).
+    pub breaker_adverse_limit: f64,
+    pub breaker_adverse_cooloff_s: f64,
+    /// |win rate − mean fair value| of recent settled fills.
+    pub breaker_calibration_limit: f64,
+    pub breaker_calibration_cooloff_s: f64,
+    /// Pre-trade risk rules to run, in order (empty = the default chain).
+    pub risk_rules: Vec<String>,
+    /// Decision-to-match latency the execution model lets the touch deplete over (ms).
+    pub exec_latency_ms: f64,
+    /// Post-only orders less likely than this to fill are dropped (0 = off).
+    pub exec_min_fill_prob: f64,
+
     // Oracle model
     pub oracle_beta: f64,
     pub oracle_delta_s: f64,
+    /// Seconds between oracle price updates (staleness of the settling print).
+    pub oracle_cadence_s: f64,
 
     // EWMA
     pub ewma_lambda: f64,
@@ -158,14 +238,72 @@ pub struct Config {
     // Portfolio Greeks limits (0.0 = disabled)
     pub max_portfolio_delta: f64,
     pub max_portfolio_gamma_neg: f64,
-
-    // Strategy toggles — set to false to disable individual strategies
-    pub strategy_latency_arb: bool,
-    pub strategy_certainty_capture: bool,
-    pub strategy_convexity_fade: bool,
-    pub strategy_strike_misalign: bool,
-    pub strategy_lp_extreme: bool,
-    pub strategy_cross_timeframe: bool,
+    /// Portfolio |delta × S| limit: USD notional of the underlying.
+    pub max_dollar_delta: f64,
+
+    // Correlated portfolio VaR
+    /// Monte Carlo VaR limit on every open binary in the wallet, as a
+    /// fraction of bankroll (0.0 = disabled).
+    pub max_var_frac: f64,
+    /// VaR quantile (0.99 = loss exceeded on 1% of paths).
+    pub var_confidence: f64,
+    pub var_paths: usize,
+    /// Assets whose spot returns are correlated (own asset always included).
+    pub var_assets: Vec<String>,
+    /// Seconds between spot samples of the correlation window.
+    pub var_sample_secs: u64,
+    /// Spot samples in the rolling correlation window.
+    pub var_corr_window: usize,
+
+    // Kelly sizing
+    /// Default fractional-Kelly multiplier (0.5 = half-Kelly).
+    pub kelly_fraction: f64,
+    /// Per-strategy fractional-Kelly overrides, e.g. `KELLY_FRACTIONS=lp_extreme=0.25,latency_arb=0.4`.
+    pub kelly_fractions: HashMap<String, f64>,
+
+    // Strategy parameters
+    /// Threshold overrides keyed `strategy.param`, read from `STRATEGY_PARAMS_FILE`
+    /// (the file the `optimize` binary writes). Empty = compiled defaults.
+    pub strategy_params: HashMap<String, f64>,
+
+    // Strategy toggles — one entry per registered strategy, read from its `env_toggle`.
+    // Missing names fall back to the registry default (see `is_strategy_enabled`).
+    pub strategy_toggles: HashMap<String, bool>,
+    /// Strategies whose toggle is `shadow`: evaluated and paper-traded, never dispatched.
+    pub shadow_strategies: HashSet<String>,
+
+    // Signal ensemble
+    /// Pool agreeing directional signals into one calibration-weighted order.
+    pub ensemble: bool,
+
+    // House side / deconfliction
+    /// `first_wins`, `net_exposure`, `allow_hedged` or `strategy_groups`.
+    pub deconflict_policy: String,
+    /// Strategy groups with their own house side, e.g.
+    /// `DECONFLICT_GROUPS=latency_arb,certainty_capture;market_maker,lp_extreme`.
+    pub deconflict_groups: Vec<Vec<String>>,
+    /// House-side flips allowed per market (first-wins and groups).
+    pub max_direction_flips: u32,
+    /// Confidence an active order needs to set the house side.
+    pub house_side_min_confidence: f64,
+
+    // Delta hedging on Binance
+    /// Hedge binary dollar delta outside `hedge_band_usd` with Binance orders.
+    pub hedge: bool,
+    pub hedge_venue: HedgeVenue,
+    /// Net dollar delta (binaries + hedge) tolerated before re-hedging to flat.
+    pub hedge_band_usd: f64,
+    /// Order quantity step of the hedge symbol (base asset).
+    pub hedge_step: f64,
+    /// Taker fee charged on dry-run hedge fills, in basis points.
+    pub hedge_fee_bps: f64,
+    /// Fill hedges locally at the Binance price instead of sending them.
+    /// Always on when `dry_run` is.
+    pub hedge_dry_run: bool,
+    /// REST base URL override (testnet, or a mock server).
+    pub hedge_rest_url: Option<String>,
+    pub binance_api_key: Option<String>,
+    pub binance_api_secret: Option<String>,
 
     // Mode
     pub dry_run: bool,
@@ -175,6 +313,8 @@ pub struct Config {
     pub polymarket_funder_address: Option<String>,
     /// Signature type: 0=EOA, 1=Poly Proxy, 2=Gnosis Safe
     pub polymarket_signature_type: u8,
+    /// Polygon RPC for on-chain CTF calls (merging parity pairs).
+    pub polygon_rpc_url: String,
 }
 
 impl Config {
@@ -225,6 +365,11 @@ impl Config {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(1000.0),
+            bankroll_mode: BankrollMode::parse(&std::env::var("BANKROLL_MODE").unwrap_or_default()),
+            wallet_sync_secs: std::env::var("WALLET_SYNC_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(30),
             max_total_exposure_frac: std::env::var("MAX_EXPOSURE_FRAC")
                 .ok()
                 .and_then(|s| s.parse().ok())
@@ -237,6 +382,72 @@ impl Config {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(-0.08),
+            mtm_stop_loss_frac: std::env::var("MTM_STOP_LOSS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0.0),
+            mtm_take_profit_frac: std::env::var("MTM_TAKE_PROFIT")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0.0),
+            strategy_stop_loss_frac: std::env::var("STRATEGY_STOP_LOSS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0.0),
+            strategy_take_profit_frac: std::env::var("STRATEGY_TAKE_PROFIT")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0.0),
+            mtm_exit: std::env::var("MTM_EXIT")
+                .map(|v| v == "1" || v.to_lowercase() == "true")
+                .unwrap_or(false),
+            breaker_max_rejects: std::env::var("BREAKER_MAX_REJECTS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(5),
+            breaker_reject_cooloff_s: std::env::var("BREAKER_REJECT_COOLOFF_S")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(60.0),
+            breaker_latency_p95_ms: std::env::var("BREAKER_LATENCY_P95_MS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(2000.0),
+            breaker_latency_cooloff_s: std::env::var("BREAKER_LATENCY_COOLOFF_S")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(120.0),
+            breaker_adverse_window_s: std::env::var("BREAKER_ADVERSE_WINDOW_S")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(10.0),
+            breaker_adverse_limit: std::env::var("BREAKER_ADVERSE_LIMIT")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0.0),
+            breaker_adverse_cooloff_s: std::env::var("BREAKER_ADVERSE_COOLOFF_S")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(300.0),
+            breaker_calibration_limit: std::env::var("BREAKER_CALIBRATION_LIMIT")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0.0),
+            breaker_calibration_cooloff_s: std::env::var("BREAKER_CALIBRATION_COOLOFF_S")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(1800.0),
+            risk_rules: std::env::var("RISK_RULES")
+                .map(|s| parse_names(&s))
+                .unwrap_or_default(),
+            exec_latency_ms: std::env::var("EXEC_LATENCY_MS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(250.0),
+            exec_min_fill_prob: std::env::var("EXEC_MIN_FILL_PROB")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0.0),
             oracle_beta: std::env::var("ORACLE_BETA")
                 .ok()
                 .and_then(|s| s.parse().ok())
@@ -245,6 +456,10 @@ impl Config {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(2.0),
+            oracle_cadence_s: std::env::var("ORACLE_CADENCE_S")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(1.0),
             ewma_lambda: std::env::var("EWMA_LAMBDA")
                 .ok()
                 .and_then(|s| s.parse().ok())
@@ -261,24 +476,103 @@ impl Config {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(0.0),
-            strategy_latency_arb: std::env::var("STRAT_LATENCY_ARB")
-                .map(|v| v != "0" && v.to_lowercase() != "false")
-                .unwrap_or(true),
-            strategy_certainty_capture: std::env::var("STRAT_CERTAINTY_CAPTURE")
-                .map(|v| v != "0" && v.to_lowercase() != "false")
-                .unwrap_or(true),
-            strategy_convexity_fade: std::env::var("STRAT_CONVEXITY_FADE")
-                .map(|v| v != "0" && v.to_lowercase() != "false")
-                .unwrap_or(true),
-            strategy_strike_misalign: std::env::var("STRAT_STRIKE_MISALIGN")
-                .map(|v| v != "0" && v.to_lowercase() != "false")
-                .unwrap_or(true),
-            strategy_lp_extreme: std::env::var("STRAT_LP_EXTREME")
-                .map(|v| v != "0" && v.to_lowercase() != "false")
-                .unwrap_or(true),
-            strategy_cross_timeframe: std::env::var("STRAT_CROSS_TF")
+            max_dollar_delta: std::env::var("MAX_DOLLAR_DELTA")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0.0),
+            max_var_frac: std::env::var("MAX_VAR_FRAC")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0.0),
+            var_confidence: std::env::var("VAR_CONFIDENCE")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0.99),
+            var_paths: std::env::var("VAR_PATHS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(2000),
+            var_assets: parse_names(
+                &std::env::var("VAR_ASSETS").unwrap_or_else(|_| "btc,eth,sol,xrp".into()).to_lowercase(),
+            ),
+            var_sample_secs: std::env::var("VAR_SAMPLE_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(5),
+            var_corr_window: std::env::var("VAR_CORR_WINDOW")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(720),
+            kelly_fraction: std::env::var("KELLY_FRACTION")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0.5),
+            kelly_fractions: std::env::var("KELLY_FRACTIONS")
+                .map(|v| parse_kelly_fractions(&v))
+                .unwrap_or_default(),
+            strategy_params: std::env::var("STRATEGY_PARAMS_FILE")
+                .ok()
+                .and_then(|path| match std::fs::read_to_string(&path) {
+                    Ok(text) => Some(params::parse(&text)),
+                    Err(e) => {
+                        eprintln!("[CONFIG] Cannot read STRATEGY_PARAMS_FILE {}: {}", path, e);
+                        None
+                    }
+                })
+                .unwrap_or_default(),
+            strategy_toggles: registry::STRATEGIES
+                .iter()
+                .map(|spec| {
+                    let on = std::env::var(spec.env_toggle)
+                        .map(|v| parse_toggle(&v, spec.enabled_by_default))
+                        .unwrap_or(spec.enabled_by_default);
+                    (spec.name.to_string(), on)
+                })
+                .collect(),
+            shadow_strategies: registry::STRATEGIES
+                .iter()
+                .filter(|spec| std::env::var(spec.env_toggle).is_ok_and(|v| is_shadow_toggle(&v)))
+                .map(|spec| spec.name.to_string())
+                .collect(),
+            ensemble: std::env::var("ENSEMBLE")
+                .map(|v| v == "1" || v.to_lowercase() == "true")
+                .unwrap_or(false),
+            deconflict_policy: std::env::var("DECONFLICT_POLICY")
+                .map(|s| s.trim().to_lowercase())
+                .unwrap_or_else(|_| "first_wins".into()),
+            deconflict_groups: std::env::var("DECONFLICT_GROUPS")
+                .map(|s| parse_groups(&s))
+                .unwrap_or_default(),
+            max_direction_flips: std::env::var("MAX_DIRECTION_FLIPS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(1),
+            house_side_min_confidence: std::env::var("HOUSE_SIDE_MIN_CONFIDENCE")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0.7),
+            hedge: std::env::var("HEDGE")
                 .map(|v| v == "1" || v.to_lowercase() == "true")
                 .unwrap_or(false),
+            hedge_venue: HedgeVenue::parse(&std::env::var("HEDGE_VENUE").unwrap_or_default()),
+            hedge_band_usd: std::env::var("HEDGE_BAND_USD")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(500.0),
+            hedge_step: std::env::var("HEDGE_STEP")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0.001),
+            hedge_fee_bps: std::env::var("HEDGE_FEE_BPS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(5.0),
+            hedge_dry_run: std::env::var("HEDGE_DRY_RUN")
+                .map(|v| v == "1" || v.to_lowercase() == "true")
+                .unwrap_or(true),
+            hedge_rest_url: std::env::var("HEDGE_REST_URL").ok(),
+            binance_api_key: std::env::var("BINANCE_API_KEY").ok(),
+            binance_api_secret: std::env::var("BINANCE_API_SECRET").ok(),
             dry_run: std::env::var("DRY_RUN")
                 .map(|v| v == "1" || v.to_lowercase() == "true")
                 .unwrap_or(true),
@@ -288,6 +582,8 @@ impl Config {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(0),
+            polygon_rpc_url: std::env::var("POLYGON_RPC_URL")
+                .unwrap_or_else(|_| "https://polygon-bor-rpc.publicnode.com".into()),
         }
     }
 
@@ -300,6 +596,62 @@ impl Config {
     pub fn slug_prefix(&self) -> String {
         format!("{}-updown-{}-", self.asset, self.interval.label())
     }
+
+    /// Whether a strategy is switched on. Unset names use the registry default;
+    /// unregistered names are always off.
+    pub fn is_strategy_enabled(&self, name: &str) -> bool {
+        match self.strategy_toggles.get(name) {
+            Some(&on) => on,
+            None => registry::spec(name).is_some_and(|s| s.enabled_by_default),
+        }
+    }
+
+    /// Whether an enabled strategy trades on paper only (toggle value `shadow`).
+    pub fn is_strategy_shadow(&self, name: &str) -> bool {
+        self.shadow_strategies.contains(name) && self.is_strategy_enabled(name)
+    }
+}
+
+/// Parse a strategy toggle value. Default-on strategies are only disabled by an
+/// explicit `0`/`false`; default-off strategies are only enabled by `1`/`true`
+/// or `shadow`.
+fn parse_toggle(v: &str, default: bool) -> bool {
+    if default {
+        v != "0" && v.to_lowercase() != "false"
+    } else {
+        v == "1" || v.to_lowercase() == "true" || is_shadow_toggle(v)
+    }
+}
+
+/// `STRAT_<NAME>=shadow`: run the strategy, but paper-trade its orders.
+fn is_shadow_toggle(v: &str) -> bool {
+    v.eq_ignore_ascii_case("shadow")
+}
+
+/// Parse `name=frac,name=frac` into per-strategy Kelly fractions.
+/// Malformed entries are skipped.
+fn parse_kelly_fractions(s: &str) -> HashMap<String, f64> {
+    s.split(',')
+        .filter_map(|kv| {
+            let (k, v) = kv.split_once('=')?;
+            let f: f64 = v.trim().parse().ok()?;
+            Some((k.trim().to_string(), f))
+        })
+        .collect()
+}
+
+/// Parse a comma-separated name list, dropping empty entries.
+fn parse_names(s: &str) -> Vec<String> {
+    s.split(',')
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Parse `;`-separated groups of comma-separated names, dropping empty groups.
+fn parse_groups(s: &str) -> Vec<Vec<String>> {
+    s.split(';').map(parse_names).filter(|g| !g.is_empty()).collect()
 }
 
 /// Known Polymarket series IDs by asset + interval.
@@ -324,24 +676,24 @@ fn default_series_id(asset: &str, interval: &Interval) -> &'static str {
 mod tests {
     use super::*;
 
-    /// Scenario: make_config() helper creates a Config with all 5 active strategies enabled.
+    /// Scenario: make_config() helper leaves strategy_toggles empty, so registry defaults apply.
     /// Expected: All active strategies default to true, cross_timeframe to false.
     #[test]
     fn test_strategy_toggles_default_true() {
         let config = crate::strategies::test_helpers::make_config();
-        assert!(config.strategy_latency_arb, "latency_arb should default to enabled");
-        assert!(config.strategy_certainty_capture, "certainty_capture should default to enabled");
-        assert!(config.strategy_convexity_fade, "convexity_fade should default to enabled");
-        assert!(config.strategy_strike_misalign, "strike_misalign should default to enabled");
-        assert!(config.strategy_lp_extreme, "lp_extreme should default to enabled");
+        assert!(config.is_strategy_enabled("latency_arb"), "latency_arb should default to enabled");
+        assert!(config.is_strategy_enabled("certainty_capture"), "certainty_capture should default to enabled");
+        assert!(config.is_strategy_enabled("convexity_fade"), "convexity_fade should default to enabled");
+        assert!(config.is_strategy_enabled("strike_misalign"), "strike_misalign should default to enabled");
+        assert!(config.is_strategy_enabled("lp_extreme"), "lp_extreme should default to enabled");
     }
 
     /// Scenario: cross_timeframe is disabled by default since no cross-market feed exists.
-    /// Expected: strategy_cross_timeframe is false in the default config.
+    /// Expected: is_strategy_enabled("cross_timeframe") is false in the default config.
     #[test]
     fn test_cross_timeframe_default_false() {
         let config = crate::strategies::test_helpers::make_config();
-        assert!(!config.strategy_cross_timeframe, "cross_timeframe should default to disabled");
+        assert!(!config.is_strategy_enabled("cross_timeframe"), "cross_timeframe should default to disabled");
     }
 
     /// Scenario: A strategy toggle can be set to false to disable it.
@@ -349,19 +701,57 @@ mod tests {
     #[test]
     fn test_disable_single_strategy() {
         let mut config = crate::strategies::test_helpers::make_config();
-        config.strategy_latency_arb = false;
-        assert!(!config.strategy_latency_arb, "latency_arb should be disabled");
-        assert!(config.strategy_certainty_capture, "other strategies should stay enabled");
-        assert!(config.strategy_convexity_fade, "other strategies should stay enabled");
+        config.strategy_toggles.insert("latency_arb".into(), false);
+        assert!(!config.is_strategy_enabled("latency_arb"), "latency_arb should be disabled");
+        assert!(config.is_strategy_enabled("certainty_capture"), "other strategies should stay enabled");
+        assert!(config.is_strategy_enabled("convexity_fade"), "other strategies should stay enabled");
     }
 
     /// Scenario: cross_timeframe toggle set to true to enable the disabled strategy.
-    /// Expected: strategy_cross_timeframe becomes true.
+    /// Expected: is_strategy_enabled("cross_timeframe") becomes true.
     #[test]
     fn test_enable_cross_timeframe() {
         let mut config = crate::strategies::test_helpers::make_config();
-        config.strategy_cross_timeframe = true;
-        assert!(config.strategy_cross_timeframe, "cross_timeframe should be enabled when set to true");
+        config.strategy_toggles.insert("cross_timeframe".into(), true);
+        assert!(config.is_strategy_enabled("cross_timeframe"), "cross_timeframe should be enabled when set to true");
+    }
+
+    /// Scenario: Toggle parsing for default-on and default-off strategies, plus an unregistered name.
+    /// Expected: Default-on needs an explicit 0/false to turn off, default-off needs 1/true
+    ///           to turn on; unknown strategies are never enabled.
+    #[test]
+    fn test_parse_toggle_and_unknown_strategy() {
+        assert!(parse_toggle("yes", true));
+        assert!(!parse_toggle("FALSE", true));
+        assert!(!parse_toggle("0", true));
+        assert!(!parse_toggle("yes", false));
+        assert!(parse_toggle("True", false));
+        assert!(parse_toggle("1", false));
+
+        let config = crate::strategies::test_helpers::make_config();
+        assert!(!config.is_strategy_enabled("not_a_strategy"));
+    }
+
+    /// Scenario: `shadow` toggle on a default-off and a default-on strategy; a shadow
+    ///           entry for a strategy that is toggled off.
+    /// Expected: Both shadow strategies are enabled and in shadow mode; the disabled one is neither.
+    #[test]
+    fn test_shadow_toggle() {
+        assert!(parse_toggle("shadow", false));
+        assert!(parse_toggle("SHADOW", true));
+        assert!(is_shadow_toggle("Shadow"));
+        assert!(!is_shadow_toggle("true"));
+
+        let mut config = crate::strategies::test_helpers::make_config();
+        config.strategy_toggles.insert("pin_risk".into(), true);
+        config.strategy_toggles.insert("lp_extreme".into(), false);
+        for name in ["pin_risk", "latency_arb", "lp_extreme"] {
+            config.shadow_strategies.insert(name.into());
+        }
+        assert!(config.is_strategy_shadow("pin_risk"));
+        assert!(config.is_strategy_shadow("latency_arb"));
+        assert!(!config.is_strategy_shadow("lp_extreme"));
+        assert!(!config.is_strategy_shadow("certainty_capture"));
     }
 
     /// Scenario: Interval parsing with known and unknown interval strings.
@@ -385,6 +775,27 @@ mod tests {
         assert_eq!(Interval::H4.window_ms(), 14_400_000);
     }
 
+    /// Scenario: KELLY_FRACTIONS string with two valid entries, whitespace and one malformed entry.
+    /// Expected: Valid entries parsed and trimmed; malformed entry skipped.
+    #[test]
+    fn test_parse_kelly_fractions() {
+        let m = parse_kelly_fractions("lp_extreme=0.25, latency_arb = 0.4,bogus");
+        assert_eq!(m.len(), 2);
+        assert_eq!(m.get("lp_extreme"), Some(&0.25));
+        assert_eq!(m.get("latency_arb"), Some(&0.4));
+    }
+
+    /// Scenario: DECONFLICT_GROUPS with two groups, whitespace and an empty group.
+    /// Expected: Two groups in order, names trimmed; the empty group dropped.
+    #[test]
+    fn test_parse_groups() {
+        let g = parse_groups("latency_arb, certainty_capture;; market_maker,lp_extreme;");
+        assert_eq!(g, vec![
+            vec!["latency_arb".to_string(), "certainty_capture".to_string()],
+            vec!["market_maker".to_string(), "lp_extreme".to_string()],
+        ]);
+    }
+
     /// Scenario: Known asset+interval combos return specific series IDs.
     /// Expected: btc/5m → "10684", eth/15m → "10191", unknown → "10684" fallback.
     #[test]
@@ -0,0 +1,390 @@
+//! Circuit breakers on execution quality and model error.
+//!
+//! Four breakers watch for patterns no single pre-trade check sees:
+//!
+//! | Breaker             | Trips when                                                   |
+//! |---------------------|--------------------------------------------------------------|
+//! | `rejections`        | `BREAKER_MAX_REJECTS` consecutive rejected / timed-out acks  |
+//! | `fill_latency`      | p95 submit→ack latency of the last fills > `BREAKER_LATENCY_P95_MS` |
+//! | `adverse_selection` | mean mid move against our fills `BREAKER_ADVERSE_WINDOW_S` later > `BREAKER_ADVERSE_LIMIT` |
+//! | `calibration`       | settled fills' win rate vs mean `fair_value` differ by > `BREAKER_CALIBRATION_LIMIT` |
+//!
+//! A trip halts new buys for that breaker's cool-off (the risk chain's `halt`
+//! rule via `trigger_halt`) and clears the breaker's samples, so it re-arms on
+//! fresh evidence. `CircuitBreakers` lives across markets: a cool-off longer
+//! than the market carries into the next one. A limit of 0 disables a breaker.
+
+use std::collections::VecDeque;
+
+use crate::config::Config;
+use crate::types::{OrderAction, OrderStatus, Side};
+
+/// Fills in the latency window.
+const LATENCY_WINDOW: usize = 20;
+/// Markouts in the adverse-selection window.
+const ADVERSE_WINDOW: usize = 20;
+/// Settled fills in the calibration window.
+const CALIBRATION_WINDOW: usize = 100;
+/// Samples required before the latency / adverse / calibration breakers can trip.
+const MIN_LATENCY_SAMPLES: usize = 10;
+const MIN_ADVERSE_SAMPLES: usize = 10;
+const MIN_CALIBRATION_SAMPLES: usize = 30;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Breaker {
+    Rejections,
+    FillLatency,
+    AdverseSelection,
+    Calibration,
+}
+
+impl Breaker {
+    pub fn name(self) -> &'static str {
+        match self {
+            Breaker::Rejections => "rejections",
+            Breaker::FillLatency => "fill_latency",
+            Breaker::AdverseSelection => "adverse_selection",
+            Breaker::Calibration => "calibration",
+        }
+    }
+}
+
+/// A breaker that fired: what it measured against which limit, and until when buys halt.
+#[derive(Clone, Copy, Debug)]
+pub struct Trip {
+    pub breaker: Breaker,
+    pub value: f64,
+    pub limit: f64,
+    pub until_ms: i64,
+}
+
+/// Thresholds and cool-offs (0 limit = breaker off).
+#[derive(Clone, Copy, Debug)]
+pub struct BreakerLimits {
+    pub max_rejects: u32,
+    pub reject_cooloff_ms: i64,
+    pub latency_p95_ms: f64,
+    pub latency_cooloff_ms: i64,
+    pub adverse_window_ms: i64,
+    /// Mean adverse mid move per fill (probability points).
+    pub adverse_limit: f64,
+    pub adverse_cooloff_ms: i64,
+    /// |win rate − mean fair value| of settled fills.
+    pub calibration_limit: f64,
+    pub calibration_cooloff_ms: i64,
+}
+
+impl BreakerLimits {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            max_rejects: config.breaker_max_rejects,
+            reject_cooloff_ms: (config.breaker_reject_cooloff_s * 1000.0) as i64,
+            latency_p95_ms: config.breaker_latency_p95_ms,
+            latency_cooloff_ms: (config.breaker_latency_cooloff_s * 1000.0) as i64,
+            adverse_window_ms: (config.breaker_adverse_window_s * 1000.0) as i64,
+            adverse_limit: config.breaker_adverse_limit,
+            adverse_cooloff_ms: (config.breaker_adverse_cooloff_s * 1000.0) as i64,
+            calibration_limit: config.breaker_calibration_limit,
+            calibration_cooloff_ms: (config.breaker_calibration_cooloff_s * 1000.0) as i64,
+        }
+    }
+}
+
+/// A fill waiting for its markout.
+struct PendingMarkout {
+    due_ms: i64,
+    side: Side,
+    /// +1 buy, −1 sell: the direction the mid has to move for us.
+    sign: f64,
+    mid_at_fill: f64,
+}
+
+pub struct CircuitBreakers {
+    limits: BreakerLimits,
+    consecutive_rejects: u32,
+    latencies: VecDeque<f64>,
+    pending_markouts: Vec<PendingMarkout>,
+    /// Adverse move per resolved markout (positive = against us).
+    markouts: VecDeque<f64>,
+    /// (fair value at order, won) per settled fill.
+    settled: VecDeque<(f64, bool)>,
+    halted_until_ms: i64,
+    trips: Vec<Trip>,
+}
+
+impl CircuitBreakers {
+    pub fn new(limits: BreakerLimits) -> Self {
+        Self {
+            limits,
+            consecutive_rejects: 0,
+            latencies: VecDeque::with_capacity(LATENCY_WINDOW),
+            pending_markouts: Vec::new(),
+            markouts: VecDeque::with_capacity(ADVERSE_WINDOW),
+            settled: VecDeque::with_capacity(CALIBRATION_WINDOW),
+            halted_until_ms: 0,
+            trips: Vec::new(),
+        }
+    }
+
+    /// End of the longest cool-off tripped so far.
+    pub fn halted_until_ms(&self) -> i64 {
+        self.halted_until_ms
+    }
+
+    /// Order ack: rejection streak and fill latency.
+    pub fn on_ack(&mut self, status: &OrderStatus, latency_ms: f64, now_ms: i64) {
+        match status {
+            OrderStatus::Rejected(_) | OrderStatus::Timeout => {
+                self.consecutive_rejects += 1;
+                let max = self.limits.max_rejects;
+                if max > 0 && self.consecutive_rejects >= max {
+                    self.consecutive_rejects = 0;
+                    self.trip(Breaker::Rejections, max as f64, max as f64, self.limits.reject_cooloff_ms, now_ms);
+                }
+            }
+            OrderStatus::Filled | OrderStatus::PartialFill | OrderStatus::Live => {
+                self.consecutive_rejects = 0;
+            }
+            _ => {}
+        }
+        if !matches!(status, OrderStatus::Filled | OrderStatus::PartialFill) || self.limits.latency_p95_ms <= 0.0 {
+            return;
+        }
+        if self.latencies.len() == LATENCY_WINDOW {
+            self.latencies.pop_front();
+        }
+        self.latencies.push_back(latency_ms);
+        if self.latencies.len() >= MIN_LATENCY_SAMPLES {
+            let p95 = percentile(self.latencies.iter().copied().collect(), 0.95);
+            if p95 > self.limits.latency_p95_ms {
+                self.latencies.clear();
+                self.trip(Breaker::FillLatency, p95, self.limits.latency_p95_ms, self.limits.latency_cooloff_ms, now_ms);
+            }
+        }
+    }
+
+    /// A fill on this market's `side` token with its mid at fill time; marked
+    /// out `adverse_window_ms` later by `on_tick`.
+    pub fn on_fill(&mut self, side: Side, action: OrderAction, mid: f64, now_ms: i64) {
+        if self.limits.adverse_limit <= 0.0 || mid <= 0.0 {
+            return;
+        }
+        self.pending_markouts.push(PendingMarkout {
+            due_ms: now_ms + self.limits.adverse_window_ms,
+            side,
+            sign: match action {
+                OrderAction::Buy => 1.0,
+                OrderAction::Sell => -1.0,
+            },
+            mid_at_fill: mid,
+        });
+    }
+
+    /// Resolve markouts that are due against the current mids.
+    pub fn on_tick(&mut self, up_mid: f64, down_mid: f64, now_ms: i64) {
+        let mut resolved = Vec::new();
+        self.pending_markouts.retain(|m| {
+            if now_ms < m.due_ms {
+                return true;
+            }
+            let mid = match m.side {
+                Side::Up => up_mid,
+                Side::Down => down_mid,
+            };
+            if mid > 0.0 {
+                resolved.push(m.sign * (m.mid_at_fill - mid));
+            }
+            false
+        });
+        for adverse in resolved {
+            if self.markouts.len() == ADVERSE_WINDOW {
+                self.markouts.pop_front();
+            }
+            self.markouts.push_back(adverse);
+        }
+        if self.markouts.len() >= MIN_ADVERSE_SAMPLES {
+            let mean = self.markouts.iter().sum::<f64>() / self.markouts.len() as f64;
+            if mean > self.limits.adverse_limit {
+                self.markouts.clear();
+                self.trip(Breaker::AdverseSelection, mean, self.limits.adverse_limit, self.limits.adverse_cooloff_ms, now_ms);
+            }
+        }
+    }
+
+    /// Market over: quotes for unresolved markouts are gone.
+    pub fn on_market_end(&mut self) {
+        self.pending_markouts.clear();
+    }
+
+    /// A settled buy: the model's fair value for its side when ordered, and whether it won.
+    pub fn on_settled(&mut self, fair_value: f64, won: bool) {
+        if self.limits.calibration_limit <= 0.0 {
+            return;
+        }
+        if self.settled.len() == CALIBRATION_WINDOW {
+            self.settled.pop_front();
+        }
+        self.settled.push_back((fair_value, won));
+    }
+
+    /// Check calibration once a market's fills have settled.
+    pub fn check_calibration(&mut self, now_ms: i64) {
+        if self.settled.len() < MIN_CALIBRATION_SAMPLES {
+            return;
+        }
+        let n = self.settled.len() as f64;
+        let win_rate = self.settled.iter().filter(|(_, won)| *won).count() as f64 / n;
+        let mean_fair = self.settled.iter().map(|(f, _)| f).sum::<f64>() / n;
+        let error = (win_rate - mean_fair).abs();
+        if error > self.limits.calibration_limit {
+            self.settled.clear();
+            self.trip(Breaker::Calibration, error, self.limits.calibration_limit, self.limits.calibration_cooloff_ms, now_ms);
+        }
+    }
+
+    fn trip(&mut self, breaker: Breaker, value: f64, limit: f64, cooloff_ms: i64, now_ms: i64) {
+        let until_ms = now_ms + cooloff_ms;
+        self.halted_until_ms = self.halted_until_ms.max(until_ms);
+        self.trips.push(Trip { breaker, value, limit, until_ms });
+    }
+
+    /// Trips since the last drain, for halt, telemetry and alerts.
+    pub fn drain_trips(&mut self) -> std::vec::Drain<'_, Trip> {
+        self.trips.drain(..)
+    }
+}
+
+/// Nearest-rank percentile.
+fn percentile(mut xs: Vec<f64>, q: f64) -> f64 {
+    xs.sort_by(|a, b| a.total_cmp(b));
+    let rank = ((q * xs.len() as f64).ceil() as usize).clamp(1, xs.len());
+    xs[rank - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits() -> BreakerLimits {
+        BreakerLimits {
+            max_rejects: 3,
+            reject_cooloff_ms: 60_000,
+            latency_p95_ms: 1_000.0,
+            latency_cooloff_ms: 120_000,
+            adverse_window_ms: 10_000,
+            adverse_limit: 0.03,
+            adverse_cooloff_ms: 300_000,
+            calibration_limit: 0.15,
+            calibration_cooloff_ms: 1_800_000,
+        }
+    }
+
+    fn tripped(b: &mut CircuitBreakers) -> Vec<Trip> {
+        b.drain_trips().collect()
+    }
+
+    // ── Rejections ──
+
+    /// Scenario: Two rejections, a fill, then three rejections (limit 3).
+    /// Expected: The fill resets the streak; the third consecutive rejection trips for 60s.
+    #[test]
+    fn test_consecutive_rejections_trip() {
+        let mut b = CircuitBreakers::new(limits());
+        let rejected = OrderStatus::Rejected("x".into());
+        b.on_ack(&rejected, 50.0, 1_000);
+        b.on_ack(&rejected, 50.0, 2_000);
+        b.on_ack(&OrderStatus::Filled, 50.0, 3_000);
+        b.on_ack(&rejected, 50.0, 4_000);
+        b.on_ack(&OrderStatus::Timeout, 50.0, 5_000);
+        assert!(tripped(&mut b).is_empty());
+        b.on_ack(&rejected, 50.0, 6_000);
+        let trips = tripped(&mut b);
+        assert_eq!(trips.len(), 1);
+        assert_eq!(trips[0].breaker, Breaker::Rejections);
+        assert_eq!(b.halted_until_ms(), 66_000);
+    }
+
+    // ── Latency ──
+
+    /// Scenario: 10 fast fills (the minimum sample), then an 11th slow one (nearest-rank
+    ///           p95 of 11 = the max).
+    /// Expected: No trip while fast; the slow fill trips with p95 = 2,500ms.
+    #[test]
+    fn test_latency_p95_trips() {
+        let mut b = CircuitBreakers::new(limits());
+        for i in 0..10 {
+            b.on_ack(&OrderStatus::Filled, 100.0, i);
+        }
+        assert!(tripped(&mut b).is_empty());
+        b.on_ack(&OrderStatus::Filled, 2_500.0, 20);
+        let trips = tripped(&mut b);
+        assert_eq!(trips.len(), 1);
+        assert_eq!(trips[0].breaker, Breaker::FillLatency);
+        assert_eq!(trips[0].value, 2_500.0);
+    }
+
+    /// Scenario: Rejection acks with high latency.
+    /// Expected: Only fills feed the latency window.
+    #[test]
+    fn test_latency_ignores_non_fills() {
+        let mut b = CircuitBreakers::new(BreakerLimits { max_rejects: 0, ..limits() });
+        for i in 0..20 {
+            b.on_ack(&OrderStatus::Rejected("x".into()), 5_000.0, i);
+        }
+        assert!(tripped(&mut b).is_empty());
+    }
+
+    // ── Adverse selection ──
+
+    /// Scenario: 10 UP buys at mid 0.60; 10s later UP mid is 0.55 (5 points against us).
+    /// Expected: Nothing before the window; then mean adverse 0.05 > 0.03 trips.
+    #[test]
+    fn test_adverse_selection_trips() {
+        let mut b = CircuitBreakers::new(limits());
+        for i in 0..10 {
+            b.on_fill(Side::Up, OrderAction::Buy, 0.60, i);
+        }
+        b.on_tick(0.55, 0.45, 5_000);
+        assert!(tripped(&mut b).is_empty(), "not yet due");
+        b.on_tick(0.55, 0.45, 10_010);
+        let trips = tripped(&mut b);
+        assert_eq!(trips.len(), 1);
+        assert_eq!(trips[0].breaker, Breaker::AdverseSelection);
+        assert!((trips[0].value - 0.05).abs() < 1e-9);
+    }
+
+    /// Scenario: 10 DOWN sells at mid 0.40; 10s later DOWN mid is 0.35 (moved our way).
+    /// Expected: Negative adverse move, no trip.
+    #[test]
+    fn test_favourable_markout_no_trip() {
+        let mut b = CircuitBreakers::new(limits());
+        for i in 0..10 {
+            b.on_fill(Side::Down, OrderAction::Sell, 0.40, i);
+        }
+        b.on_tick(0.65, 0.35, 10_010);
+        assert!(tripped(&mut b).is_empty());
+    }
+
+    // ── Calibration ──
+
+    /// Scenario: 30 settled fills at fair 0.70 of which 12 won (40%), then a well-calibrated set.
+    /// Expected: |0.40 − 0.70| = 0.30 > 0.15 trips; 21/30 wins at 0.70 does not.
+    #[test]
+    fn test_calibration_error_trips() {
+        let mut b = CircuitBreakers::new(limits());
+        for i in 0..30 {
+            b.on_settled(0.70, i < 12);
+        }
+        b.check_calibration(1_000);
+        let trips = tripped(&mut b);
+        assert_eq!(trips.len(), 1);
+        assert_eq!(trips[0].breaker, Breaker::Calibration);
+        assert!((trips[0].value - 0.30).abs() < 1e-9);
+
+        for i in 0..30 {
+            b.on_settled(0.70, i < 21);
+        }
+        b.check_calibration(2_000);
+        assert!(tripped(&mut b).is_empty());
+    }
+}
@@ -0,0 +1,448 @@
+//! Signal ensemble: pools agreeing strategy views into one order before risk.
+//!
+//! Without it, three strategies buying UP in the same batch send three orders,
+//! each sized as if it were the only view. With the ensemble enabled, active
+//! same-side buys on this market are replaced by one consolidated signal:
+//!
+//!   fair = Σ wᵢ·fairᵢ / Σ wᵢ,   wᵢ = 1 / Brierᵢ
+//!
+//! where Brierᵢ is strategy i's settled calibration, shrunk toward a coin
+//! flip (0.25) until it has history. The order is risk-checked under the
+//! highest-weight member (the *lead*) and its fills are split back to the
+//! members in proportion to their weights, so per-strategy PnL stays intact.
+//!
+//! Calibration is scored once per strategy per market: the mean P(UP) of
+//! everything it emitted in the market against the outcome.
+
+use std::collections::HashMap;
+
+use crate::engine::risk::StrategyRiskManager;
+use crate::engine::sizing::Bet;
+use crate::engine::state::MarketState;
+use crate::math::fees::Liquidity;
+use crate::types::{Fill, Instrument, OrderAction, Side, Signal};
+
+/// Pseudo-markets of coin-flip calibration every strategy starts with.
+const PRIOR_MARKETS: f64 = 10.0;
+/// Brier score of always forecasting 0.5.
+const PRIOR_BRIER: f64 = 0.25;
+/// Floor on a Brier score so one lucky strategy can't take the whole pool.
+const MIN_BRIER: f64 = 0.01;
+
+/// Settled forecast record of one strategy.
+#[derive(Debug, Clone, Copy, Default)]
+struct Calibration {
+    sq_err: f64,
+    markets: u32,
+}
+
+/// This market's views of one strategy, awaiting the outcome.
+#[derive(Debug, Clone, Copy, Default)]
+struct Pending {
+    sum_p_up: f64,
+    n: u32,
+}
+
+/// One consolidated order's members and their fill shares (Σ = 1, lead first).
+#[derive(Debug, Clone)]
+pub struct Merged {
+    pub side: Side,
+    pub shares: Vec<(&'static str, f64)>,
+}
+
+impl Merged {
+    /// Strategy the consolidated signal and its order are attributed to.
+    pub fn lead(&self) -> &'static str {
+        self.shares[0].0
+    }
+
+    /// Is `sig` the consolidated signal this merge produced?
+    pub fn is(&self, sig: &Signal) -> bool {
+        sig.strategy == self.lead() && sig.side == self.side && eligible(sig)
+    }
+}
+
+/// Calibration-weighted signal pooling. Persists across markets.
+#[derive(Debug, Default)]
+pub struct Ensemble {
+    calibration: HashMap<&'static str, Calibration>,
+    pending: HashMap<&'static str, Pending>,
+}
+
+/// Directional taker buys on this market — the signals the ensemble pools.
+/// Quotes, pairs, passive/bid-side orders, sells and cross-market orders keep
+/// their own execution and are never merged.
+#[inline]
+pub fn eligible(sig: &Signal) -> bool {
+    !sig.is_passive
+        && !sig.use_bid
+        && !sig.quote
+        && !sig.pair
+        && sig.action == OrderAction::Buy
+        && sig.instrument == Instrument::Current
+}
+
+/// Split one fill of a consolidated order into per-member fills (size and fee
+/// pro rata). An empty `shares` leaves the fill whole.
+pub fn split_fill(fill: &Fill, shares: &[(&'static str, f64)]) -> Vec<Fill> {
+    if shares.is_empty() {
+        return vec![fill.clone()];
+    }
+    shares
+        .iter()
+        .map(|&(strategy, share)| Fill {
+            strategy,
+            size: fill.size * share,
+            fee: fill.fee * share,
+            ..*fill
+        })
+        .collect()
+}
+
+impl Ensemble {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Shrunk Brier score of `strategy`'s settled forecasts (0.25 with no history).
+    pub fn brier(&self, strategy: &str) -> f64 {
+        let c = self.calibration.get(strategy).copied().unwrap_or_default();
+        let brier = (PRIOR_MARKETS * PRIOR_BRIER + c.sq_err) / (PRIOR_MARKETS + c.markets as f64);
+        brier.max(MIN_BRIER)
+    }
+
+    /// Pooling weight of `strategy`: inverse Brier score.
+    pub fn weight(&self, strategy: &str) -> f64 {
+        1.0 / self.brier(strategy)
+    }
+
+    /// Markets `strategy` has been scored on.
+    pub fn markets_scored(&self, strategy: &str) -> u32 {
+        self.calibration.get(strategy).map_or(0, |c| c.markets)
+    }
+
+    /// Remember each eligible signal's view of P(UP) for this market's scoring.
+    pub fn observe(&mut self, signals: &[Signal]) {
+        for sig in signals.iter().filter(|s| eligible(s)) {
+            let p_up = match sig.side {
+                Side::Up => sig.fair_value,
+                Side::Down => 1.0 - sig.fair_value,
+            };
+            let p = self.pending.entry(sig.strategy).or_default();
+            p.sum_p_up += p_up.clamp(0.0, 1.0);
+            p.n += 1;
+        }
+    }
+
+    /// Score this market's views against the outcome and start a new market.
+    pub fn on_market_end(&mut self, outcome: Side) {
+        let won_up = if outcome == Side::Up { 1.0 } else { 0.0 };
+        for (strategy, p) in self.pending.drain() {
+            let forecast = p.sum_p_up / p.n as f64;
+            let c = self.calibration.entry(strategy).or_default();
+            c.sq_err += (forecast - won_up).powi(2);
+            c.markets += 1;
+        }
+    }
+
+    /// Replace each group of two or more same-side eligible signals with one
+    /// consolidated signal, returning the merges made.
+    ///
+    /// Members must each pass risk on their own (a blocked strategy can't join
+    /// through the lead's budget). The consolidated signal takes the worst
+    /// member price, the weighted fair value and confidence, and is re-sized
+    /// by Kelly under the lead's fraction. If the pooled fair shows no edge
+    /// over the all-in taker price, the whole group is dropped.
+    pub fn merge(
+        &self,
+        signals: &mut Vec<Signal>,
+        state: &MarketState,
+        risk: &StrategyRiskManager,
+        order_id: u64,
+        now_ms: i64,
+    ) -> Vec<Merged> {
+        let mut merges = Vec::new();
+        for side in [Side::Up, Side::Down] {
+            let members: Vec<usize> = (0..signals.len())
+                .filter(|&i| {
+                    let s = &signals[i];
+                    s.side == side
+                        && eligible(s)
+                        && risk.check_strategy(s, state, order_id, now_ms).is_some()
+                })
+                .collect();
+            if members.len() < 2 {
+                continue;
+            }
+
+            let (mut w_sum, mut fair, mut confidence, mut price) = (0.0, 0.0, 0.0, 0.0_f64);
+            let mut shares: Vec<(&'static str, f64)> = Vec::with_capacity(members.len());
+            for &i in &members {
+                let s = &signals[i];
+                let w = self.weight(s.strategy);
+                w_sum += w;
+                fair += w * s.fair_value;
+                confidence += w * s.confidence;
+                price = price.max(s.market_price);
+                shares.push((s.strategy, w));
+            }
+            fair /= w_sum;
+            confidence /= w_sum;
+            for share in shares.iter_mut() {
+                share.1 /= w_sum;
+            }
+            // Lead first: highest weight, ties to the stronger signal
+            let score = |name: &str| {
+                members.iter().map(|&i| &signals[i]).find(|s| s.strategy == name)
+                    .map_or(0.0, |s| s.edge * s.confidence)
+            };
+            shares.sort_by(|a, b| {
+                b.1.partial_cmp(&a.1)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then_with(|| score(b.0).partial_cmp(&score(a.0)).unwrap_or(std::cmp::Ordering::Equal))
+            });
+            let lead = shares[0].0;
+
+            let all_in_price = state.info.fees.effective_price(price, Liquidity::Taker);
+            let edge = fair - all_in_price;
+
+            let mut idx = 0;
+            signals.retain(|_| {
+                let keep = !members.contains(&idx);
+                idx += 1;
+                keep
+            });
+            if edge <= 0.0 {
+                continue;
+            }
+
+            let size_frac = state.sizing.size(
+                state,
+                &Bet { strategy: lead, side, fair, price: all_in_price },
+                now_ms,
+            );
+            signals.push(Signal {
+                strategy: lead,
+                side,
+                edge,
+                fair_value: fair,
+                market_price: price,
+                confidence,
+                size_frac,
+                is_passive: false,
+                use_bid: false,
+                action: OrderAction::Buy,
+                quote: false,
+                pair: false,
+                instrument: Instrument::Current,
+            });
+            merges.push(Merged { side, shares });
+        }
+        merges
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::test_helpers::*;
+
+    /// Score `markets` markets in which `strategy` forecast P(UP) = `p_up` and UP won.
+    fn score(ens: &mut Ensemble, strategy: &'static str, p_up: f64, markets: u32) {
+        for _ in 0..markets {
+            ens.observe(&[make_signal(strategy, Side::Up, p_up - 0.40, 0.8, 0.40)]);
+            ens.on_market_end(Side::Up);
+        }
+    }
+
+    // ── Calibration ──
+
+    /// Scenario: Strategy never scored.
+    /// Expected: Coin-flip Brier 0.25, weight 4.
+    #[test]
+    fn test_prior_weight() {
+        let ens = Ensemble::new();
+        assert!((ens.brier("latency_arb") - 0.25).abs() < 1e-12);
+        assert!((ens.weight("latency_arb") - 4.0).abs() < 1e-12);
+        assert_eq!(ens.markets_scored("latency_arb"), 0);
+    }
+
+    /// Scenario: Over 20 UP markets, A forecast 0.9 and B forecast 0.3.
+    /// Expected: A's Brier falls below the prior, B's rises; A outweighs B.
+    #[test]
+    fn test_calibration_weights_favour_accurate_strategy() {
+        let mut ens = Ensemble::new();
+        score(&mut ens, "latency_arb", 0.9, 20);
+        score(&mut ens, "convexity_fade", 0.3, 20);
+        assert_eq!(ens.markets_scored("latency_arb"), 20);
+        // (10·0.25 + 20·0.01) / 30 = 0.09
+        assert!((ens.brier("latency_arb") - 0.09).abs() < 1e-9);
+        assert!(ens.brier("convexity_fade") > 0.25);
+        assert!(ens.weight("latency_arb") > 2.0 * ens.weight("convexity_fade"));
+    }
+
+    /// Scenario: One market with a DOWN view at fair 0.8 followed by 0.6, outcome DOWN.
+    /// Expected: Scored once on mean P(UP) = 0.3 → squared error 0.09.
+    #[test]
+    fn test_market_scored_once_on_mean_view() {
+        let mut ens = Ensemble::new();
+        ens.observe(&[make_signal("latency_arb", Side::Down, 0.30, 0.8, 0.5)]);
+        ens.observe(&[make_signal("latency_arb", Side::Down, 0.10, 0.8, 0.5)]);
+        ens.on_market_end(Side::Down);
+        assert_eq!(ens.markets_scored("latency_arb"), 1);
+        let expected = (10.0 * 0.25 + 0.09) / 11.0;
+        assert!((ens.brier("latency_arb") - expected).abs() < 1e-9);
+    }
+
+    /// Scenario: A resting quote and a pair leg are observed.
+    /// Expected: Neither is scored -- only directional taker views count.
+    #[test]
+    fn test_observe_skips_ineligible() {
+        let mut ens = Ensemble::new();
+        let mut quote = make_signal("market_maker", Side::Up, 0.10, 0.5, 0.5);
+        quote.quote = true;
+        let mut leg = make_signal("parity_arb", Side::Up, 0.10, 0.5, 0.5);
+        leg.pair = true;
+        ens.observe(&[quote, leg]);
+        ens.on_market_end(Side::Up);
+        assert_eq!(ens.markets_scored("market_maker"), 0);
+        assert_eq!(ens.markets_scored("parity_arb"), 0);
+    }
+
+    // ── Merge ──
+
+    /// Scenario: Three strategies buy UP at 0.50 with fairs 0.60/0.62/0.64, equal weights.
+    /// Expected: One consolidated UP signal at fair 0.62, three equal shares summing to 1.
+    #[test]
+    fn test_three_agreeing_signals_merge_into_one() {
+        let config = make_config();
+        let risk = StrategyRiskManager::new(&config);
+        let (state, now) = make_state(95_000.0, 95_100.0, 0.001, 120.0, 0.50, 0.50);
+        let ens = Ensemble::new();
+        let mut signals = vec![
+            make_signal("latency_arb", Side::Up, 0.10, 0.8, 0.50),
+            make_signal("convexity_fade", Side::Up, 0.12, 0.6, 0.50),
+            make_signal("certainty_capture", Side::Up, 0.14, 0.9, 0.50),
+        ];
+        let merges = ens.merge(&mut signals, &state, &risk, 1, now);
+
+        assert_eq!(signals.len(), 1);
+        assert_eq!(merges.len(), 1);
+        let sig = &signals[0];
+        assert_eq!(sig.side, Side::Up);
+        assert!((sig.fair_value - 0.62).abs() < 1e-9);
+        assert!(sig.edge > 0.0 && sig.edge < 0.12, "edge net of fee: {}", sig.edge);
+        assert!(sig.size_frac > 0.0);
+        assert!(merges[0].is(sig));
+        let total: f64 = merges[0].shares.iter().map(|s| s.1).sum();
+        assert!((total - 1.0).abs() < 1e-12);
+        assert_eq!(merges[0].shares.len(), 3);
+        // Equal weights: the strongest edge·confidence leads
+        assert_eq!(merges[0].lead(), "certainty_capture");
+    }
+
+    /// Scenario: A is well calibrated (weight ≈ 11), B is poorly calibrated.
+    /// Expected: Pooled fair sits near A's view; A leads with the larger share.
+    #[test]
+    fn test_weights_pull_fair_toward_calibrated_strategy() {
+        let config = make_config();
+        let risk = StrategyRiskManager::new(&config);
+        let (state, now) = make_state(95_000.0, 95_100.0, 0.001, 120.0, 0.50, 0.50);
+        let mut ens = Ensemble::new();
+        score(&mut ens, "latency_arb", 0.9, 20);
+        score(&mut ens, "convexity_fade", 0.3, 20);
+
+        let mut signals = vec![
+            make_signal("latency_arb", Side::Up, 0.20, 0.8, 0.50),
+            make_signal("convexity_fade", Side::Up, 0.06, 0.8, 0.50),
+        ];
+        let merges = ens.merge(&mut signals, &state, &risk, 1, now);
+        let sig = &signals[0];
+        assert!(sig.fair_value > 0.66, "fair = {}", sig.fair_value);
+        assert_eq!(merges[0].lead(), "latency_arb");
+        assert!(merges[0].shares[0].1 > 0.7);
+    }
+
+    /// Scenario: One UP signal, one DOWN signal, one passive UP signal.
+    /// Expected: Nothing merges -- no side has two eligible members.
+    #[test]
+    fn test_single_member_sides_untouched() {
+        let config = make_config();
+        let risk = StrategyRiskManager::new(&config);
+        let (state, now) = make_state(95_000.0, 95_100.0, 0.001, 120.0, 0.50, 0.50);
+        let mut passive = make_signal("lp_extreme", Side::Up, 0.10, 0.5, 0.50);
+        passive.is_passive = true;
+        let mut signals = vec![
+            make_signal("latency_arb", Side::Up, 0.10, 0.8, 0.50),
+            make_signal("convexity_fade", Side::Down, 0.10, 0.8, 0.50),
+            passive,
+        ];
+        let merges = Ensemble::new().merge(&mut signals, &state, &risk, 1, now);
+        assert!(merges.is_empty());
+        assert_eq!(signals.len(), 3);
+    }
+
+    /// Scenario: Two UP views at 0.503 and 0.507 against a 0.50 ask (≈0.8¢ taker fee).
+    /// Expected: Group dropped -- pooled fair doesn't clear the taker fee.
+    #[test]
+    fn test_group_dropped_without_pooled_edge() {
+        let config = make_config();
+        let risk = StrategyRiskManager::new(&config);
+        let (state, now) = make_state(95_000.0, 95_100.0, 0.001, 120.0, 0.50, 0.50);
+        let mut signals = vec![
+            make_signal("latency_arb", Side::Up, 0.003, 0.8, 0.50),
+            make_signal("convexity_fade", Side::Up, 0.007, 0.8, 0.50),
+        ];
+        let merges = Ensemble::new().merge(&mut signals, &state, &risk, 1, now);
+        assert!(merges.is_empty());
+        assert!(signals.is_empty());
+    }
+
+    /// Scenario: Three agreeing UP signals, one from a strategy on cooldown.
+    /// Expected: The blocked strategy stays out of the merge (and keeps its signal).
+    #[test]
+    fn test_risk_blocked_member_not_merged() {
+        let config = make_config();
+        let mut risk = StrategyRiskManager::new(&config);
+        let (state, now) = make_state(95_000.0, 95_100.0, 0.001, 120.0, 0.50, 0.50);
+        risk.on_order_sent("convexity_fade", now, 1.0);
+        let mut signals = vec![
+            make_signal("latency_arb", Side::Up, 0.12, 0.8, 0.50),
+            make_signal("convexity_fade", Side::Up, 0.12, 0.8, 0.50),
+            make_signal("certainty_capture", Side::Up, 0.12, 0.8, 0.50),
+        ];
+        let merges = Ensemble::new().merge(&mut signals, &state, &risk, 1, now);
+        assert_eq!(merges.len(), 1);
+        assert!(merges[0].shares.iter().all(|s| s.0 != "convexity_fade"));
+        assert_eq!(signals.len(), 2);
+        assert!(signals.iter().any(|s| s.strategy == "convexity_fade"));
+    }
+
+    // ── Fill attribution ──
+
+    /// Scenario: A 30-share fill with $0.30 fee split 0.5/0.3/0.2.
+    /// Expected: Member fills of 15/9/6 shares and $0.15/0.09/0.06 fees, same price.
+    #[test]
+    fn test_split_fill_pro_rata() {
+        let fill = Fill { order_id: 7, strategy: "latency_arb", side: Side::Up, price: 0.5, size: 30.0, fee: 0.30 };
+        let parts = split_fill(&fill, &[("latency_arb", 0.5), ("convexity_fade", 0.3), ("certainty_capture", 0.2)]);
+        assert_eq!(parts.len(), 3);
+        let sizes: Vec<f64> = parts.iter().map(|f| f.size).collect();
+        assert!((sizes[0] - 15.0).abs() < 1e-9 && (sizes[1] - 9.0).abs() < 1e-9 && (sizes[2] - 6.0).abs() < 1e-9);
+        assert!((parts[1].fee - 0.09).abs() < 1e-12);
+        assert_eq!(parts[2].strategy, "certainty_capture");
+        assert!(parts.iter().all(|f| f.order_id == 7 && f.price == 0.5));
+        let pnl: f64 = parts.iter().map(|f| f.pnl(Side::Up)).sum();
+        assert!((pnl - fill.pnl(Side::Up)).abs() < 1e-9);
+    }
+
+    /// Scenario: Fill of an ordinary (unmerged) order.
+    /// Expected: Passed through whole.
+    #[test]
+    fn test_split_fill_unmerged_passthrough() {
+        let fill = Fill { order_id: 1, strategy: "latency_arb", side: Side::Down, price: 0.4, size: 10.0, fee: 0.1 };
+        let parts = split_fill(&fill, &[]);
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].size, 10.0);
+    }
+}
@@ -0,0 +1,344 @@
+//! Pre-trade execution-cost model, shared by live risk and the backtester.
+//!
+//! Estimates what an order will actually get from the current book: its
+//! average fill price, the size expected to fill and the probability of a fill.
+//!
+//! - **Takers** (FOK, GTD/GTC not post-only) walk the contra side at or better
+//!   than their limit. Before the order lands, the touch keeps losing size at
+//!   the rate recently observed ([`BookFlow`]) for `EXEC_LATENCY_MS`. A FOK
+//!   fills whole or not at all; other takers fill what is there and rest the
+//!   remainder.
+//! - **Makers** (post-only) join the back of their price level. Size leaving
+//!   our touch over the order's life (GTD expiry, else `MAKER_HORIZON_MS`)
+//!   first clears the queue ahead, then fills us.
+//!
+//! The `book_depth` risk rule uses the estimate to shrink FOK orders to the
+//! depth that can take them and to drop post-only orders unlikely to fill; the
+//! backtester and replay fill takers at the estimated price and size. Book
+//! sizes are shares, order sizes USDC notional at the limit price, as
+//! everywhere else in the engine.
+
+use crate::config::Config;
+use crate::engine::queue_sim::PRICE_EPS;
+use crate::engine::state::{MarketState, OrderBook};
+use crate::types::{Instrument, OrderAction, OrderType, Side, Signal};
+
+/// Time constant of the touch-flow rate estimate (ms).
+const FLOW_TAU_MS: f64 = 5_000.0;
+
+/// Horizon over which a GTC post-only order is expected to fill (ms).
+const MAKER_HORIZON_MS: i64 = 30_000;
+
+// ─── Book dynamics ──────────────────────────────────────────────────────────
+
+/// Rate at which size leaves one token's touch, from successive snapshots.
+///
+/// Snapshots don't say whether size left by trading or cancelling, so this is
+/// an upper bound on traded flow: fine for depth decay, optimistic for queues.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BookFlow {
+    /// Shares/s leaving the best ask (taken by buyers or pulled).
+    ask_rate: f64,
+    /// Shares/s leaving the best bid.
+    bid_rate: f64,
+    last_ms: i64,
+}
+
+impl BookFlow {
+    /// Fold in the move from `old` to the snapshot `(bids, asks)` at `ts_ms`.
+    pub fn on_snapshot(&mut self, old: &OrderBook, bids: &[(f64, f64)], asks: &[(f64, f64)], ts_ms: i64) {
+        let ask_out = touch_outflow(old.asks.first(), asks, |new, old| new > old);
+        let bid_out = touch_outflow(old.bids.first(), bids, |new, old| new < old);
+        if self.last_ms > 0 {
+            let decay = (-((ts_ms - self.last_ms).max(0) as f64) / FLOW_TAU_MS).exp();
+            self.ask_rate *= decay;
+            self.bid_rate *= decay;
+        }
+        self.ask_rate += ask_out / (FLOW_TAU_MS / 1000.0);
+        self.bid_rate += bid_out / (FLOW_TAU_MS / 1000.0);
+        self.last_ms = ts_ms;
+    }
+
+    /// Shares/s leaving `book_side`'s touch, decayed to `now_ms`.
+    pub fn rate(&self, book_side: BookSide, now_ms: i64) -> f64 {
+        let r = match book_side {
+            BookSide::Asks => self.ask_rate,
+            BookSide::Bids => self.bid_rate,
+        };
+        r * (-((now_ms - self.last_ms).max(0) as f64) / FLOW_TAU_MS).exp()
+    }
+}
+
+/// Shares that left the old touch: all of it if the touch moved away
+/// (`moved_away(new, old)`), the drop in size if it stayed, none if improved.
+fn touch_outflow(old: Option<&(f64, f64)>, new: &[(f64, f64)], moved_away: fn(f64, f64) -> bool) -> f64 {
+    let Some(&(p0, s0)) = old else { return 0.0 };
+    match new.first() {
+        None => s0,
+        Some(&(p, _)) if moved_away(p, p0) && (p - p0).abs() > PRICE_EPS => s0,
+        Some(&(p, s)) if (p - p0).abs() <= PRICE_EPS => (s0 - s).max(0.0),
+        Some(_) => 0.0,
+    }
+}
+
+/// One side of an order book.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BookSide {
+    Bids,
+    Asks,
+}
+
+// ─── Estimates ──────────────────────────────────────────────────────────────
+
+/// How an order meets the book.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Execution {
+    /// Crosses the spread. A FOK fills whole or not at all; otherwise the
+    /// unfilled remainder rests at the limit.
+    Taker { fok: bool },
+    /// Post-only: queues at its price for `ttl_ms`.
+    Maker { ttl_ms: i64 },
+}
+
+impl Execution {
+    pub fn of(order_type: OrderType, post_only: bool, expiration_ms: Option<i64>, now_ms: i64) -> Self {
+        if post_only {
+            let ttl_ms = expiration_ms.map_or(MAKER_HORIZON_MS, |e| (e - now_ms).max(0));
+            Execution::Maker { ttl_ms }
+        } else {
+            Execution::Taker { fok: order_type == OrderType::FOK }
+        }
+    }
+}
+
+/// What an order is expected to get from the book.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FillEstimate {
+    /// Average fill price (the limit for makers).
+    pub price: f64,
+    /// USDC (at the limit) expected to fill: immediately for takers, over the
+    /// order's life for makers.
+    pub filled: f64,
+    /// Shares expected to fill (`filled / limit`); at `price` they cost
+    /// `filled_shares * price`.
+    pub filled_shares: f64,
+    /// Probability the order fills whole (takers) or in expectation (makers).
+    pub fill_prob: f64,
+    /// Takers: USDC (at the limit) the book can take at or better than the
+    /// limit once the order lands. Makers: 0.
+    pub depth: f64,
+}
+
+/// Execution-cost model for one market: settings plus each token's book flow.
+#[derive(Clone, Debug)]
+pub struct ExecutionModel {
+    /// Decision-to-match latency over which the touch keeps depleting (ms).
+    pub latency_ms: f64,
+    /// `book_depth` drops post-only orders less likely than this to fill.
+    pub min_fill_prob: f64,
+    pub up_flow: BookFlow,
+    pub down_flow: BookFlow,
+}
+
+impl Default for ExecutionModel {
+    fn default() -> Self {
+        Self {
+            latency_ms: 250.0,
+            min_fill_prob: 0.0,
+            up_flow: BookFlow::default(),
+            down_flow: BookFlow::default(),
+        }
+    }
+}
+
+impl ExecutionModel {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            latency_ms: config.exec_latency_ms,
+            min_fill_prob: config.exec_min_fill_prob,
+            ..Self::default()
+        }
+    }
+
+    /// Record a book snapshot for the UP or DOWN token, before it replaces `old`.
+    pub fn on_book(&mut self, is_up: bool, old: &OrderBook, bids: &[(f64, f64)], asks: &[(f64, f64)], ts_ms: i64) {
+        let flow = if is_up { &mut self.up_flow } else { &mut self.down_flow };
+        flow.on_snapshot(old, bids, asks, ts_ms);
+    }
+
+    /// Estimate `size` USDC of `sig` executed as `exec` against the current
+    /// book. `None` without a recorded book (or for cross-market orders, whose
+    /// books the engine doesn't keep).
+    pub fn estimate(&self, state: &MarketState, sig: &Signal, size: f64, exec: Execution, now_ms: i64) -> Option<FillEstimate> {
+        if sig.instrument != Instrument::Current || sig.market_price <= 0.0 || size <= 0.0 {
+            return None;
+        }
+        let (book, flow) = match sig.side {
+            Side::Up => (&state.up_book, &self.up_flow),
+            Side::Down => (&state.down_book, &self.down_flow),
+        };
+        if book.bids.is_empty() && book.asks.is_empty() {
+            return None;
+        }
+        let limit = sig.market_price;
+        let shares = size / limit;
+        match exec {
+            Execution::Taker { fok } => {
+                let contra = match sig.action {
+                    OrderAction::Buy => BookSide::Asks,
+                    OrderAction::Sell => BookSide::Bids,
+                };
+                let decayed = flow.rate(contra, now_ms) * self.latency_ms / 1000.0;
+                let (vwap, taken, available) = walk(levels(book, contra), contra, limit, decayed, shares);
+                let filled_shares = if fok && available + PRICE_EPS < shares { 0.0 } else { taken };
+                Some(FillEstimate {
+                    price: if filled_shares > 0.0 { vwap } else { limit },
+                    filled: filled_shares * limit,
+                    filled_shares,
+                    fill_prob: filled_shares / shares,
+                    depth: available * limit,
+                })
+            }
+            Execution::Maker { ttl_ms } => {
+                let own = match sig.action {
+                    OrderAction::Buy => BookSide::Bids,
+                    OrderAction::Sell => BookSide::Asks,
+                };
+                let queue_ahead = levels(book, own)
+                    .iter()
+                    .find(|(p, _)| (p - limit).abs() <= PRICE_EPS)
+                    .map_or(0.0, |&(_, s)| s);
+                let through = flow.rate(own, now_ms) * ttl_ms as f64 / 1000.0;
+                let filled_shares = (through - queue_ahead).clamp(0.0, shares);
+                Some(FillEstimate {
+                    price: limit,
+                    filled: filled_shares * limit,
+                    filled_shares,
+                    fill_prob: filled_shares / shares,
+                    depth: 0.0,
+                })
+            }
+        }
+    }
+}
+
+fn levels(book: &OrderBook, side: BookSide) -> &[(f64, f64)] {
+    match side {
+        BookSide::Asks => &book.asks,
+        BookSide::Bids => &book.bids,
+    }
+}
+
+/// Walk `levels` at or better than `limit` for up to `shares`, after
+/// `decayed` shares have left the front of the book. Returns (VWAP of the
+/// shares taken, shares taken, all shares available at or better than the limit).
+fn walk(levels: &[(f64, f64)], side: BookSide, limit: f64, mut decayed: f64, shares: f64) -> (f64, f64, f64) {
+    let (mut cost, mut filled, mut available) = (0.0, 0.0, 0.0);
+    for &(price, size) in levels {
+        let within = match side {
+            BookSide::Asks => price <= limit + PRICE_EPS,
+            BookSide::Bids => price >= limit - PRICE_EPS,
+        };
+        if !within {
+            break;
+        }
+        let gone = decayed.min(size);
+        decayed -= gone;
+        let left = size - gone;
+        let take = left.min(shares - filled);
+        cost += take * price;
+        filled += take;
+        available += left;
+    }
+    let vwap = if filled > 0.0 { cost / filled } else { limit };
+    (vwap, filled, available)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::test_helpers::*;
+
+    /// UP book (bids, asks) applied through the model, as `on_book_update` does.
+    fn apply(state: &mut MarketState, bids: Vec<(f64, f64)>, asks: Vec<(f64, f64)>, ts_ms: i64) {
+        let old = state.up_book.clone();
+        state.execution.on_book(true, &old, &bids, &asks, ts_ms);
+        inject_book(state, Side::Up, bids, asks);
+    }
+
+    // ── Takers ──
+
+    /// Scenario: Asks 100 @ 0.50, 100 @ 0.52, 100 @ 0.60; FOK buy limit 0.52 of $78 (150 shares), no flow.
+    /// Expected: Walks two levels: VWAP (100×0.50 + 50×0.52)/150; fills whole; depth 200 shares × 0.52.
+    #[test]
+    fn test_taker_walks_to_limit() {
+        let (mut state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        inject_book(&mut state, Side::Up, vec![(0.48, 100.0)], vec![(0.50, 100.0), (0.52, 100.0), (0.60, 100.0)]);
+        let sig = make_signal("latency_arb", Side::Up, 0.05, 0.8, 0.52);
+        let est = state.execution.estimate(&state, &sig, 78.0, Execution::Taker { fok: true }, now).unwrap();
+        assert!((est.price - (50.0 + 26.0) / 150.0).abs() < 1e-9, "vwap {}", est.price);
+        assert!((est.filled - 78.0).abs() < 1e-9);
+        assert!((est.filled_shares - 150.0).abs() < 1e-9);
+        assert_eq!(est.fill_prob, 1.0);
+        assert!((est.depth - 200.0 * 0.52).abs() < 1e-9);
+    }
+
+    /// Scenario: Touch 100 @ 0.50 only; a $75 (150 share) order at 0.50, as FOK and as GTD.
+    /// Expected: FOK can't fill whole → nothing, probability 0; GTD fills the 100 shares now.
+    #[test]
+    fn test_fok_all_or_nothing() {
+        let (mut state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        inject_book(&mut state, Side::Up, vec![(0.48, 100.0)], vec![(0.50, 100.0), (0.55, 100.0)]);
+        let sig = make_signal("latency_arb", Side::Up, 0.05, 0.8, 0.50);
+        let fok = state.execution.estimate(&state, &sig, 75.0, Execution::Taker { fok: true }, now).unwrap();
+        assert_eq!((fok.filled, fok.fill_prob), (0.0, 0.0));
+        assert!((fok.depth - 50.0).abs() < 1e-9, "still reports the depth to shrink to");
+        let gtd = state.execution.estimate(&state, &sig, 75.0, Execution::Taker { fok: false }, now).unwrap();
+        assert!((gtd.filled - 50.0).abs() < 1e-9);
+        assert!((gtd.fill_prob - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    /// Scenario: The best ask is taken down 100 → 20 shares in one second; 250ms latency.
+    /// Expected: Flow ≈ 80 / 5s = 16 shares/s → ~4 shares gone before the order lands.
+    #[test]
+    fn test_depletion_shrinks_depth() {
+        let (mut state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        apply(&mut state, vec![(0.48, 100.0)], vec![(0.50, 100.0)], now - 1_000);
+        apply(&mut state, vec![(0.48, 100.0)], vec![(0.50, 20.0)], now);
+        let sig = make_signal("latency_arb", Side::Up, 0.05, 0.8, 0.50);
+        let est = state.execution.estimate(&state, &sig, 5.0, Execution::Taker { fok: true }, now).unwrap();
+        assert!((est.depth / 0.50 - 16.0).abs() < 1e-6, "depth {} shares", est.depth / 0.50);
+    }
+
+    /// Scenario: No recorded book; and a cross-market order.
+    /// Expected: No estimate either way (callers fall back).
+    #[test]
+    fn test_no_book_no_estimate() {
+        let (state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        let sig = make_signal("latency_arb", Side::Up, 0.05, 0.8, 0.50);
+        assert!(state.execution.estimate(&state, &sig, 10.0, Execution::Taker { fok: true }, now).is_none());
+        let cross = Signal { instrument: Instrument::Cross(crate::config::Interval::H1), ..sig };
+        assert!(state.execution.estimate(&state, &cross, 10.0, Execution::Maker { ttl_ms: 10_000 }, now).is_none());
+    }
+
+    // ── Makers ──
+
+    /// Scenario: Bid touch 0.48 loses 50 shares a second for two seconds; a post-only buy
+    ///           joins 100 shares of queue at 0.48 for 10s (GTD), then one with only 1s left.
+    /// Expected: ~10s × flow clears the queue and fills the order; with 1s left it can't
+    ///           get through the queue.
+    #[test]
+    fn test_maker_queue_fill_probability() {
+        let (mut state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        apply(&mut state, vec![(0.48, 200.0)], vec![(0.52, 100.0)], now - 2_000);
+        apply(&mut state, vec![(0.48, 150.0)], vec![(0.52, 100.0)], now - 1_000);
+        apply(&mut state, vec![(0.48, 100.0)], vec![(0.52, 100.0)], now);
+        let sig = make_signal("latency_arb", Side::Up, 0.05, 0.8, 0.48);
+        let exec = Execution::of(OrderType::GTD, true, Some(now + 10_000), now);
+        let est = state.execution.estimate(&state, &sig, 9.6, exec, now).unwrap();
+        assert_eq!(est.fill_prob, 1.0, "{:?}", est);
+        assert_eq!(est.price, 0.48);
+        let late = Execution::of(OrderType::GTD, true, Some(now + 1_000), now);
+        assert_eq!(state.execution.estimate(&state, &sig, 9.6, late, now).unwrap().fill_prob, 0.0);
+    }
+}
@@ -0,0 +1,273 @@
+//! Delta hedging of the binary book on Binance.
+//!
+//! The binaries' dollar delta (`PortfolioGreeks::dollar_delta`) plus the hedge
+//! position's (`qty × S`) is the book's net dollar delta. When it leaves
+//! `±HEDGE_BAND_USD`, the [`Hedger`] sends one market order that takes the net
+//! delta back to flat, rounded to the symbol's quantity step; as delta drifts
+//! toward expiry it re-hedges the same way. At market end the settled
+//! instrument's delta is gone, so the final re-hedge unwinds what it covered.
+//!
+//! Live hedges go through `gateway::binance::hedge_gateway`; in dry-run they
+//! fill locally at the Binance price. Fees are charged at `HEDGE_FEE_BPS` of
+//! notional in both modes. The hedge is marked to the Binance price at each
+//! settlement and the change is that market's hedge PnL.
+
+use tokio::sync::mpsc;
+
+use crate::config::Config;
+use crate::engine::risk::PortfolioGreeks;
+use crate::types::{HedgeFill, HedgeOrder, HedgeRecord};
+
+/// Minimum gap between hedge orders (ms): lets a fill land and the Greeks move.
+const HEDGE_INTERVAL_MS: i64 = 1_000;
+
+/// Delta hedger. Lives across markets so an unwind sent at one market's end
+/// is booked when its fill arrives.
+pub struct Hedger {
+    band_usd: f64,
+    step: f64,
+    fee_rate: f64,
+    /// Live gateway channels; `None` in dry-run.
+    gateway: Option<(mpsc::Sender<HedgeOrder>, mpsc::Receiver<HedgeFill>)>,
+    next_id: u64,
+    /// The one order awaiting its fill (live only).
+    in_flight: Option<u64>,
+    last_order_ms: i64,
+    /// Open hedge position (base asset; negative = short).
+    pub qty: f64,
+    /// Quote cash from hedge trades, net of fees.
+    cash: f64,
+    /// `cash + qty × price` at the last settlement.
+    last_mark: f64,
+    /// Binary dollar delta at the last re-hedge check.
+    binary_dollar_delta: f64,
+    /// Fills not yet sent to telemetry.
+    records: Vec<HedgeRecord>,
+}
+
+impl Hedger {
+    pub fn new(config: &Config, gateway: Option<(mpsc::Sender<HedgeOrder>, mpsc::Receiver<HedgeFill>)>) -> Self {
+        Self {
+            band_usd: config.hedge_band_usd,
+            step: config.hedge_step,
+            fee_rate: config.hedge_fee_bps / 10_000.0,
+            gateway,
+            next_id: 1,
+            in_flight: None,
+            last_order_ms: 0,
+            qty: 0.0,
+            cash: 0.0,
+            last_mark: 0.0,
+            binary_dollar_delta: 0.0,
+            records: Vec::new(),
+        }
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.gateway.is_none()
+    }
+
+    /// Net dollar delta of binaries plus hedge at spot `s`.
+    pub fn net_dollar_delta(&self, greeks: &PortfolioGreeks, s: f64) -> f64 {
+        greeks.dollar_delta + self.qty * s
+    }
+
+    /// Book arrived fills, then re-hedge to flat if the net dollar delta is
+    /// outside the band. `force` skips the band and the order interval (market end).
+    pub fn rebalance(&mut self, greeks: &PortfolioGreeks, s: f64, now_ms: i64, force: bool) {
+        self.poll(now_ms);
+        self.binary_dollar_delta = greeks.dollar_delta;
+        if self.in_flight.is_some() || s <= 0.0 {
+            return;
+        }
+        if !force && now_ms - self.last_order_ms < HEDGE_INTERVAL_MS {
+            return;
+        }
+        if !force && self.net_dollar_delta(greeks, s).abs() <= self.band_usd {
+            return;
+        }
+        // Hedge delta is 1 per unit of base asset
+        let trade = (-(greeks.delta + self.qty) / self.step).round() * self.step;
+        if trade.abs() < self.step * 0.5 {
+            return;
+        }
+        self.last_order_ms = now_ms;
+        let id = self.next_id;
+        self.next_id += 1;
+        match &self.gateway {
+            None => self.apply_fill(trade, s, now_ms),
+            Some((tx, _)) => {
+                if tx.try_send(HedgeOrder { id, qty: trade }).is_ok() {
+                    self.in_flight = Some(id);
+                } else {
+                    eprintln!("[WARN] Hedge channel full, dropping hedge #{}", id);
+                }
+            }
+        }
+    }
+
+    /// Book fills returned by the live gateway.
+    fn poll(&mut self, now_ms: i64) {
+        let mut arrived = Vec::new();
+        if let Some((_, rx)) = &mut self.gateway {
+            while let Ok(fill) = rx.try_recv() {
+                arrived.push(fill);
+            }
+        }
+        for fill in arrived {
+            if self.in_flight == Some(fill.id) {
+                self.in_flight = None;
+            }
+            if let Some(e) = &fill.error {
+                eprintln!("[HEDGE] #{} failed: {}", fill.id, e);
+            }
+            if fill.qty != 0.0 {
+                self.apply_fill(fill.qty, fill.price, now_ms);
+            }
+        }
+    }
+
+    fn apply_fill(&mut self, qty: f64, price: f64, now_ms: i64) {
+        let fee = qty.abs() * price * self.fee_rate;
+        self.cash -= qty * price + fee;
+        self.qty += qty;
+        eprintln!(
+            "[HEDGE] {} {:.6} @ {:.2} fee=${:.2} position={:.6}{}",
+            if qty > 0.0 { "BUY" } else { "SELL" }, qty.abs(), price, fee, self.qty,
+            if self.is_dry_run() { " (dry run)" } else { "" },
+        );
+        self.records.push(HedgeRecord {
+            ts_ms: now_ms,
+            qty,
+            price,
+            fee,
+            position: self.qty,
+            binary_dollar_delta: self.binary_dollar_delta,
+            dry_run: self.is_dry_run(),
+        });
+    }
+
+    /// Hedge PnL since the last settlement, marking the position at `price`.
+    pub fn settle(&mut self, price: f64) -> f64 {
+        let mark = self.cash + self.qty * price;
+        let pnl = mark - self.last_mark;
+        self.last_mark = mark;
+        pnl
+    }
+
+    /// Fills since the last drain, for `hedges.csv`.
+    pub fn drain_records(&mut self) -> std::vec::Drain<'_, HedgeRecord> {
+        self.records.drain(..)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::test_helpers::make_config;
+
+    const S: f64 = 100_000.0;
+
+    fn hedger() -> Hedger {
+        let mut config = make_config();
+        config.hedge_band_usd = 500.0;
+        config.hedge_step = 0.001;
+        config.hedge_fee_bps = 5.0;
+        Hedger::new(&config, None)
+    }
+
+    /// Binary book with `delta` units of underlying exposure at spot S.
+    fn book(delta: f64) -> PortfolioGreeks {
+        PortfolioGreeks { delta, dollar_delta: delta * S, ..Default::default() }
+    }
+
+    // ── Band ──
+
+    /// Scenario: Binary dollar delta $400 with a $500 band.
+    /// Expected: No hedge.
+    #[test]
+    fn test_inside_band_no_hedge() {
+        let mut h = hedger();
+        h.rebalance(&book(0.004), S, 10_000, false);
+        assert_eq!(h.qty, 0.0);
+        assert_eq!(h.drain_records().count(), 0);
+    }
+
+    /// Scenario: Binary delta 0.0123 BTC ($1,230) with a $500 band, dry run.
+    /// Expected: Sells 0.012 (rounded to the 0.001 step) at S with a 5bp fee;
+    ///           net dollar delta is back inside one step.
+    #[test]
+    fn test_outside_band_hedges_to_flat() {
+        let mut h = hedger();
+        h.rebalance(&book(0.0123), S, 10_000, false);
+        assert!((h.qty + 0.012).abs() < 1e-12, "qty {}", h.qty);
+        let rec: Vec<HedgeRecord> = h.drain_records().collect();
+        assert_eq!(rec.len(), 1);
+        assert!((rec[0].fee - 0.012 * S * 0.0005).abs() < 1e-9);
+        assert!(rec[0].dry_run);
+        assert!(h.net_dollar_delta(&book(0.0123), S).abs() <= 0.001 * S);
+    }
+
+    /// Scenario: Delta drifts from 0.02 to 0.03 BTC; second check 500ms later, then 1s later.
+    /// Expected: The 500ms check is held by the order interval; the 1s check re-hedges to −0.03.
+    #[test]
+    fn test_rehedge_as_delta_drifts() {
+        let mut h = hedger();
+        h.rebalance(&book(0.02), S, 10_000, false);
+        assert!((h.qty + 0.02).abs() < 1e-12);
+        h.rebalance(&book(0.03), S, 10_500, false);
+        assert!((h.qty + 0.02).abs() < 1e-12, "held by interval");
+        h.rebalance(&book(0.03), S, 11_000, false);
+        assert!((h.qty + 0.03).abs() < 1e-12, "qty {}", h.qty);
+    }
+
+    /// Scenario: Market ends with the hedge short 0.02 and no binaries left (delta 0).
+    /// Expected: A forced re-hedge inside the band still buys the 0.02 back.
+    #[test]
+    fn test_force_unwinds_at_market_end() {
+        let mut h = hedger();
+        h.rebalance(&book(0.02), S, 10_000, false);
+        h.rebalance(&book(0.0), S, 10_100, true);
+        assert!(h.qty.abs() < 1e-12);
+    }
+
+    // ── Settlement ──
+
+    /// Scenario: Short 0.02 at 100,000; price falls to 99,000 and the market settles,
+    ///           then the next market settles at 99,500.
+    /// Expected: First PnL = +$20 less the entry fee; second = −$10 (mark change only).
+    #[test]
+    fn test_settle_marks_hedge() {
+        let mut h = hedger();
+        h.rebalance(&book(0.02), S, 10_000, false);
+        let fee = 0.02 * S * 0.0005;
+        assert!((h.settle(99_000.0) - (20.0 - fee)).abs() < 1e-9);
+        assert!((h.settle(99_500.0) + 10.0).abs() < 1e-9);
+    }
+
+    // ── Live gateway ──
+
+    /// Scenario: Live hedger; first order sent, a second check before the fill,
+    ///           then the fill arrives at 100,010.
+    /// Expected: One order in flight (no duplicate); the fill books at its own price.
+    #[test]
+    fn test_live_order_in_flight() {
+        let (order_tx, mut order_rx) = mpsc::channel(4);
+        let (fill_tx, fill_rx) = mpsc::channel(4);
+        let mut h = Hedger::new(&make_config(), Some((order_tx, fill_rx)));
+        assert!(!h.is_dry_run());
+
+        h.rebalance(&book(0.01), S, 10_000, false);
+        let order = order_rx.try_recv().unwrap();
+        assert!((order.qty + 0.01).abs() < 1e-12);
+        assert_eq!(h.qty, 0.0, "nothing booked before the fill");
+
+        h.rebalance(&book(0.01), S, 12_000, false);
+        assert!(order_rx.try_recv().is_err(), "no second order while one is in flight");
+
+        fill_tx.try_send(HedgeFill { id: order.id, qty: -0.01, price: 100_010.0, error: None }).unwrap();
+        h.rebalance(&book(0.01), S, 13_000, false);
+        assert!((h.qty + 0.01).abs() < 1e-12);
+        assert_eq!(h.drain_records().next().unwrap().price, 100_010.0);
+    }
+}
@@ -1,4 +1,14 @@
 pub mod state;
 pub mod risk;
+pub mod risk_rules;
 pub mod runner;
 pub mod pipeline;
+pub mod sizing;
+pub mod queue_sim;
+pub mod ensemble;
+pub mod shadow;
+pub mod mtm;
+pub mod hedge;
+pub mod breakers;
+pub mod execution;
+pub mod var;
@@ -0,0 +1,355 @@
+//! Intramarket mark-to-market stops.
+//!
+//! Risk gates only run at order entry and PnL is only booked at settlement, so
+//! a position that goes against us mid-market is invisible until expiry.
+//! [`MtmMonitor`] re-marks every strategy's open inventory on each engine tick
+//! (at the best bids and at the model fair value, via
+//! [`StrategyRiskManager::mark_to_market`]) and applies two rules, per strategy
+//! and for the market as a whole:
+//!
+//! - **Stop-loss**: liquidation-value PnL has fallen `stop_loss` below its peak.
+//! - **Take-profit**: liquidation-value PnL has reached `take_profit`.
+//!
+//! A triggered strategy is stopped in the risk manager (no new buys for the
+//! rest of the market); a triggered market stops every strategy. With `exit`
+//! set, [`MtmMonitor::dispatch_exits`] also sells the stopped strategies' open
+//! inventory at the bid (FOK), retrying while any is left.
+
+use std::collections::HashMap;
+
+use crate::config::Config;
+use crate::engine::pipeline::SignalSink;
+use crate::engine::risk::{mark_fair_up, ExposureSnapshot, StrategyRiskManager};
+use crate::engine::state::{MarketState, StrategyStats};
+use crate::types::{Instrument, OrderAction, Side, Signal};
+
+/// Minimum gap between exit attempts for one strategy and side.
+const EXIT_RETRY_MS: i64 = 2_000;
+
+/// Which rule stopped a strategy or the market.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StopKind {
+    StopLoss,
+    TakeProfit,
+}
+
+/// Stop thresholds in USDC (0.0 = disabled).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MtmLimits {
+    /// Market MTM drawdown from its peak that stops all strategies.
+    pub market_stop_loss: f64,
+    /// Market MTM gain that stops all strategies.
+    pub market_take_profit: f64,
+    /// Per-strategy MTM drawdown from its peak.
+    pub strategy_stop_loss: f64,
+    /// Per-strategy MTM gain.
+    pub strategy_take_profit: f64,
+    /// Sell stopped strategies' inventory instead of only halting new buys.
+    pub exit: bool,
+}
+
+impl MtmLimits {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            market_stop_loss: config.mtm_stop_loss_frac * config.bankroll,
+            market_take_profit: config.mtm_take_profit_frac * config.bankroll,
+            strategy_stop_loss: config.strategy_stop_loss_frac * config.bankroll,
+            strategy_take_profit: config.strategy_take_profit_frac * config.bankroll,
+            exit: config.mtm_exit,
+        }
+    }
+}
+
+/// One point of a market's MTM curve: everything the live strategies hold.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MtmPoint {
+    pub realized: f64,
+    /// Open inventory at the best bids.
+    pub unrealized: f64,
+    /// Open inventory at the model fair value.
+    pub unrealized_fair: f64,
+}
+
+/// Continuous mark-to-market of one market's live positions and its stop rules.
+pub struct MtmMonitor {
+    limits: MtmLimits,
+    /// Highest MTM PnL seen this market (drawdowns are measured from here).
+    market_peak: f64,
+    peaks: HashMap<&'static str, f64>,
+    market_stop: Option<StopKind>,
+    stops: HashMap<&'static str, StopKind>,
+    last_exit_ms: HashMap<(&'static str, Side), i64>,
+    snapshot: Vec<(&'static str, ExposureSnapshot)>,
+}
+
+/// Stop rule for a PnL path: drawdown from `peak` first, then the profit target.
+#[inline]
+fn trigger(pnl: f64, peak: f64, stop_loss: f64, take_profit: f64) -> Option<StopKind> {
+    if stop_loss > 0.0 && peak - pnl >= stop_loss {
+        Some(StopKind::StopLoss)
+    } else if take_profit > 0.0 && pnl >= take_profit {
+        Some(StopKind::TakeProfit)
+    } else {
+        None
+    }
+}
+
+impl MtmMonitor {
+    pub fn new(limits: MtmLimits) -> Self {
+        Self {
+            limits,
+            market_peak: 0.0,
+            peaks: HashMap::new(),
+            market_stop: None,
+            stops: HashMap::new(),
+            last_exit_ms: HashMap::new(),
+            snapshot: Vec::new(),
+        }
+    }
+
+    /// Latest per-strategy marks (strategies with anything open or realised).
+    pub fn snapshot(&self) -> &[(&'static str, ExposureSnapshot)] {
+        &self.snapshot
+    }
+
+    pub fn market_stop(&self) -> Option<StopKind> {
+        self.market_stop
+    }
+
+    pub fn strategy_stop(&self, strategy: &str) -> Option<StopKind> {
+        self.stops.get(strategy).copied()
+    }
+
+    /// Re-mark every position, apply the stop rules and return the market's curve point.
+    pub fn on_tick(&mut self, risk: &mut StrategyRiskManager, state: &MarketState, now_ms: i64) -> MtmPoint {
+        self.snapshot = risk.mark_to_market(state, now_ms);
+        let mut point = MtmPoint::default();
+        for &(name, e) in &self.snapshot {
+            point.realized += e.realized_pnl;
+            point.unrealized += e.unrealized_pnl;
+            point.unrealized_fair += e.unrealized_fair_pnl;
+
+            let pnl = e.mtm_pnl();
+            let peak = self.peaks.entry(name).or_insert(0.0);
+            *peak = peak.max(pnl);
+            if self.stops.contains_key(name) {
+                continue;
+            }
+            let l = &self.limits;
+            if let Some(kind) = trigger(pnl, *peak, l.strategy_stop_loss, l.strategy_take_profit) {
+                eprintln!("[RISK] MTM {:?} {}: pnl=${:.2} peak=${:.2}", kind, name, pnl, *peak);
+                self.stops.insert(name, kind);
+                risk.stop_strategy(name);
+            }
+        }
+
+        let pnl = point.realized + point.unrealized;
+        self.market_peak = self.market_peak.max(pnl);
+        if self.market_stop.is_none() {
+            let l = &self.limits;
+            if let Some(kind) = trigger(pnl, self.market_peak, l.market_stop_loss, l.market_take_profit) {
+                eprintln!("[RISK] MTM {:?} market: pnl=${:.2} peak=${:.2}, all strategies stopped", kind, pnl, self.market_peak);
+                self.market_stop = Some(kind);
+                risk.stop_all();
+            }
+        }
+        point
+    }
+
+    /// With `exit` set: FOK-sell each stopped strategy's open inventory at the bid.
+    /// Exits skip the signal pipeline (house side, cooldowns and order budgets
+    /// don't apply to reducing risk) but still pass the halt and stale-feed gates.
+    pub fn dispatch_exits(
+        &mut self,
+        risk: &mut StrategyRiskManager,
+        state: &mut MarketState,
+        next_order_id: &mut u64,
+        now_ms: i64,
+        sink: &mut dyn SignalSink,
+    ) {
+        if !self.limits.exit {
+            return;
+        }
+        let fair_up = mark_fair_up(state, now_ms);
+        for i in 0..self.snapshot.len() {
+            let name = self.snapshot[i].0;
+            if !risk.is_stopped(name) {
+                continue;
+            }
+            for (side, bid, fair) in [(Side::Up, state.up_bid, fair_up), (Side::Down, state.down_bid, 1.0 - fair_up)] {
+                let shares = risk.open_shares(name, side);
+                if shares <= 0.0 || bid <= 0.0 {
+                    continue;
+                }
+                if self.last_exit_ms.get(&(name, side)).is_some_and(|&t| now_ms - t < EXIT_RETRY_MS) {
+                    continue;
+                }
+                let sig = Signal {
+                    strategy: name,
+                    side,
+                    edge: bid - fair,
+                    fair_value: fair,
+                    market_price: bid,
+                    confidence: 1.0,
+                    size_frac: 0.0,
+                    is_passive: false,
+                    use_bid: true,
+                    action: OrderAction::Sell,
+                    quote: false,
+                    pair: false,
+                    instrument: Instrument::Current,
+                };
+                sink.on_signal(&sig, state, now_ms);
+                let Some(order) = risk.check_exit(&sig, shares, state, *next_order_id, now_ms) else { continue };
+                self.last_exit_ms.insert((name, side), now_ms);
+
+                state.total_signals += 1;
+                state.total_orders += 1;
+                let stats = state.strategy_stats.entry(name).or_insert_with(StrategyStats::new);
+                stats.signals += 1;
+                stats.orders += 1;
+                risk.on_order_sent(name, now_ms, 0.0);
+                state.position.on_order_sent();
+                sink.on_order(&sig, &order, state, now_ms);
+                *next_order_id += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::test_helpers::*;
+    use crate::types::{Fill, Order, OrderType};
+
+    fn fill(strategy: &'static str, side: Side, price: f64, size: f64) -> Fill {
+        Fill { order_id: 1, strategy, side, price, size, fee: 0.0 }
+    }
+
+    #[derive(Default)]
+    struct Orders(Vec<Order>);
+
+    impl SignalSink for Orders {
+        fn on_signal(&mut self, _sig: &Signal, _state: &MarketState, _now_ms: i64) {}
+        fn on_order(&mut self, _sig: &Signal, order: &Order, _state: &MarketState, _now_ms: i64) {
+            self.0.push(order.clone());
+        }
+    }
+
+    fn limits(strategy_stop_loss: f64, strategy_take_profit: f64, exit: bool) -> MtmLimits {
+        MtmLimits { strategy_stop_loss, strategy_take_profit, exit, ..MtmLimits::default() }
+    }
+
+    // ── Rules ──
+
+    /// Scenario: PnL rises to +$5 then falls back to +$1 with a $3 drawdown stop.
+    /// Expected: Stop-loss -- measured from the peak, not from zero.
+    #[test]
+    fn test_drawdown_measured_from_peak() {
+        assert_eq!(trigger(1.0, 5.0, 3.0, 0.0), Some(StopKind::StopLoss));
+        assert_eq!(trigger(3.0, 5.0, 3.0, 0.0), None);
+        assert_eq!(trigger(-3.0, 0.0, 3.0, 0.0), Some(StopKind::StopLoss));
+        assert_eq!(trigger(10.0, 10.0, 0.0, 8.0), Some(StopKind::TakeProfit));
+        assert_eq!(trigger(-100.0, 0.0, 0.0, 0.0), None, "both disabled");
+    }
+
+    /// Scenario: latency_arb holds 20 UP bought at 0.60 ($12); the UP bid falls to 0.40
+    ///           with a $3 per-strategy stop.
+    /// Expected: Marked -$4 at the bid → stopped; its next buy is blocked while
+    ///           another strategy still trades.
+    #[test]
+    fn test_strategy_stop_blocks_buys() {
+        let config = make_config();
+        let mut risk = StrategyRiskManager::new(&config);
+        let (mut state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        risk.on_order_sent("latency_arb", now, 12.0);
+        risk.on_fill(&fill("latency_arb", Side::Up, 0.60, 12.0));
+        state.up_bid = 0.40;
+
+        let mut mtm = MtmMonitor::new(limits(3.0, 0.0, false));
+        let point = mtm.on_tick(&mut risk, &state, now);
+        assert!((point.unrealized + 4.0).abs() < 1e-9, "unrealised {}", point.unrealized);
+        assert!(point.unrealized_fair > point.unrealized, "model still values UP above the bid");
+        assert_eq!(mtm.strategy_stop("latency_arb"), Some(StopKind::StopLoss));
+        assert!(mtm.market_stop().is_none());
+
+        let check = now + 61_000;
+        state.bn.binance_ts = check;
+        state.pm_last_ts = check;
+        let buy = |strategy| Signal {
+            strategy,
+            side: Side::Up,
+            edge: 0.05,
+            fair_value: 0.55,
+            market_price: 0.50,
+            confidence: 0.8,
+            size_frac: 0.01,
+            is_passive: false,
+            use_bid: false,
+            action: OrderAction::Buy,
+            quote: false,
+            pair: false,
+            instrument: Instrument::Current,
+        };
+        assert!(risk.check_strategy(&buy("latency_arb"), &state, 2, check).is_none());
+        assert!(risk.check_strategy(&buy("certainty_capture"), &state, 3, check).is_some());
+    }
+
+    /// Scenario: Two strategies each hold 10 UP bought at 0.40 ($4), bid 0.70: each up $3
+    ///           at the bids, with a $4 strategy and $5 market take-profit.
+    /// Expected: Neither strategy rule fires, the market one does and stops both.
+    #[test]
+    fn test_market_take_profit_stops_all() {
+        let config = make_config();
+        let mut risk = StrategyRiskManager::new(&config);
+        let (mut state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        for name in ["latency_arb", "certainty_capture"] {
+            risk.on_order_sent(name, now, 4.0);
+            risk.on_fill(&fill(name, Side::Up, 0.40, 4.0));
+        }
+        state.up_bid = 0.70;
+
+        let mut mtm = MtmMonitor::new(MtmLimits { market_take_profit: 5.0, ..limits(0.0, 4.0, false) });
+        mtm.on_tick(&mut risk, &state, now);
+        assert!(mtm.strategy_stop("latency_arb").is_none());
+        assert_eq!(mtm.market_stop(), Some(StopKind::TakeProfit));
+        assert!(risk.is_stopped("latency_arb") && risk.is_stopped("lp_extreme"));
+    }
+
+    // ── Exits ──
+
+    /// Scenario: A stopped strategy holds 20 UP bought at 0.60 ($12); exits on, UP bid 0.40.
+    /// Expected: One FOK sell of the 20 tokens held at 0.40 ($8 notional, not the $12 entry
+    ///           cost, which would ask for 30), not repeated within the retry gap; without
+    ///           `exit` nothing is sent.
+    #[test]
+    fn test_exit_sells_inventory_at_bid() {
+        let config = make_config();
+        let (mut state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        state.up_bid = 0.40;
+        for exit in [true, false] {
+            let mut risk = StrategyRiskManager::new(&config);
+            risk.on_order_sent("latency_arb", now, 12.0);
+            risk.on_fill(&fill("latency_arb", Side::Up, 0.60, 12.0));
+            let mut mtm = MtmMonitor::new(limits(3.0, 0.0, exit));
+            mtm.on_tick(&mut risk, &state, now);
+
+            let mut sink = Orders::default();
+            let mut next_id = 1;
+            mtm.dispatch_exits(&mut risk, &mut state, &mut next_id, now, &mut sink);
+            mtm.dispatch_exits(&mut risk, &mut state, &mut next_id, now + 500, &mut sink);
+            if !exit {
+                assert!(sink.0.is_empty());
+                continue;
+            }
+            assert_eq!(sink.0.len(), 1);
+            let o = &sink.0[0];
+            assert_eq!((o.action, o.side, o.order_type), (OrderAction::Sell, Side::Up, OrderType::FOK));
+            assert_eq!(o.price, 0.40);
+            assert!((o.size - 8.0).abs() < 1e-9, "notional {}", o.size);
+            assert!((o.size / o.price - 20.0).abs() < 1e-9, "sells the tokens held");
+            assert_eq!(next_id, 2);
+        }
+    }
+}
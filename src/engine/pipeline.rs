@@ -5,11 +5,20 @@
 //! sorting, risk checking, and house-side coherence.
 //!
 //! Engine-specific behavior (telemetry logging, order dispatch, fill
-//! recording) is abstracted via the [`SignalSink`] trait.
-
+//! recording) is abstracted via the [`SignalSink`] trait. Strategy
+//! evaluation and lifecycle hooks go through [`StrategyHost`], so the
+//! live runner, backtest engine and replay app drive strategies identically.
+
+use crate::config::Config;
+use crate::engine::ensemble::{Ensemble, Merged};
+use crate::engine::execution::Execution;
+use crate::engine::queue_sim::QueueFillSim;
 use crate::engine::risk::StrategyRiskManager;
+use crate::engine::risk_rules::{Rejection, RiskDecision};
+use crate::engine::sizing::Holdings;
 use crate::engine::state::{MarketState, StrategyStats};
-use crate::types::{Order, Side, Signal};
+use crate::strategies::StatefulStrategy;
+use crate::types::{EvalTrigger, Fill, Order, OrderAck, OrderAction, OrderStatus, Side, Signal};
 
 // ─── Sink trait ─────────────────────────────────────────────────────────────
 
@@ -23,87 +32,465 @@ pub trait SignalSink {
 
     /// Called when a signal passes risk and an order is produced.
     fn on_order(&mut self, sig: &Signal, order: &Order, state: &MarketState, now_ms: i64);
+
+    /// Called when both legs of a pair pass risk (UP leg first). The live
+    /// engine sends them as one `OrderRequest::Pair`; simulated engines, which
+    /// fill each leg at its limit anyway, can take them as two orders.
+    fn on_pair(&mut self, legs: [(&Signal, &Order); 2], state: &MarketState, now_ms: i64) {
+        for (sig, order) in legs {
+            self.on_order(sig, order, state, now_ms);
+        }
+    }
+
+    /// Called when an ensemble-consolidated order passes risk. `shares` lists
+    /// the member strategies and their fractions of every fill (lead first).
+    /// The live engine sends one order and splits its fills on ack; simulated
+    /// engines, which fill at dispatch, can take it as one order per member.
+    fn on_merged_order(
+        &mut self,
+        sig: &Signal,
+        order: &Order,
+        shares: &[(&'static str, f64)],
+        state: &MarketState,
+        now_ms: i64,
+    ) {
+        for &(strategy, share) in shares {
+            let member = Signal { strategy, ..*sig };
+            let mut part = order.clone();
+            part.strategy = strategy;
+            part.size *= share;
+            self.on_order(&member, &part, state, now_ms);
+        }
+    }
+
+    /// Called when the risk chain rejects a signal (a pair reports its UP leg).
+    fn on_rejection(&mut self, _sig: &Signal, _rejection: &Rejection, _state: &MarketState, _now_ms: i64) {}
 }
 
 // ─── Config ─────────────────────────────────────────────────────────────────
 
 /// Configuration knobs that differ between live and backtest.
 pub struct ProcessConfig {
-    /// Simulated slippage added to fill price (0 for live, 0.01 for backtest).
+    /// Simulated slippage added to fill price when no book is recorded
+    /// (0 for live, 0.01 for backtest).
     pub slippage_cents: f64,
+    /// Fill takers at the execution model's price and size (backtest only).
+    pub model_fills: bool,
+    /// How active signals are kept to one direction (`DECONFLICT_POLICY`).
+    pub deconfliction: DeconflictionPolicy,
 }
 
 impl ProcessConfig {
     pub fn live() -> Self {
-        Self { slippage_cents: 0.0 }
+        Self { slippage_cents: 0.0, model_fills: false, deconfliction: DeconflictionPolicy::default() }
     }
     pub fn backtest() -> Self {
-        Self { slippage_cents: 0.01 }
+        Self { slippage_cents: 0.01, model_fills: true, deconfliction: DeconflictionPolicy::default() }
+    }
+
+    pub fn with_deconfliction(self, deconfliction: DeconflictionPolicy) -> Self {
+        Self { deconfliction, ..self }
     }
 }
 
-// ─── Shared pipeline ────────────────────────────────────────────────────────
+// ─── Deconfliction ──────────────────────────────────────────────────────────
 
-/// Maximum number of directional flips allowed per market.
-/// A flip is when house_side changes from Some(Up) to Some(Down) or vice versa.
+/// Default number of directional flips allowed per market.
+/// A flip is when the house side changes from Some(Up) to Some(Down) or vice versa.
 /// Prevents churn from repeated thesis reversals mid-market.
 const MAX_DIRECTION_FLIPS: u32 = 1;
 
+/// Default confidence an active order needs to set the house side.
+const HOUSE_SIDE_MIN_CONFIDENCE: f64 = 0.7;
+
+/// Net filled USDC (UP cost − DOWN cost) below which `NetExposure` counts the
+/// market as flat.
+const NET_FLAT_USDC: f64 = 1.0;
+
+/// Names accepted by `DECONFLICT_POLICY`.
+pub const DECONFLICTION_POLICIES: &[&str] = &["first_wins", "net_exposure", "allow_hedged", "strategy_groups"];
+
+/// A market's house side: the direction active orders must agree with.
+/// Build a fresh one per market.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HouseSide {
+    /// House direction (under `StrategyGroups`, that of unlisted strategies).
+    pub side: Option<Side>,
+    /// Times `side` has flipped this market.
+    pub flips: u32,
+    /// `StrategyGroups` only: (side, flips) per configured group.
+    pub groups: Vec<(Option<Side>, u32)>,
+}
+
+/// How active signals are kept to one direction per market. Passive signals
+/// and pairs are exempt under every policy.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DeconflictionPolicy {
+    /// The first active order with `confidence >= min_confidence` sets the
+    /// house side; opposite active signals are dropped. Until it is set,
+    /// disagreeing signals in a batch keep the side with the larger
+    /// `sum(edge * confidence)`.
+    FirstWins { min_confidence: f64, max_flips: u32 },
+    /// The house side is the side of the market's net filled position (UP
+    /// cost − DOWN cost). Flat books deconflict by score, so once a position
+    /// is scratched the direction is free to change.
+    NetExposure,
+    /// No house side and no deconfliction: strategies may hold both sides.
+    AllowHedged,
+    /// First-wins, but each group of strategies keeps its own house side.
+    /// Strategies in no group share one more.
+    StrategyGroups { groups: Vec<Vec<String>>, min_confidence: f64, max_flips: u32 },
+}
+
+impl Default for DeconflictionPolicy {
+    fn default() -> Self {
+        DeconflictionPolicy::FirstWins { min_confidence: HOUSE_SIDE_MIN_CONFIDENCE, max_flips: MAX_DIRECTION_FLIPS }
+    }
+}
+
+impl DeconflictionPolicy {
+    /// Policy named by `DECONFLICT_POLICY`; unknown names fall back to first-wins.
+    pub fn from_config(config: &Config) -> Self {
+        let (min_confidence, max_flips) = (config.house_side_min_confidence, config.max_direction_flips);
+        match config.deconflict_policy.as_str() {
+            "net_exposure" => DeconflictionPolicy::NetExposure,
+            "allow_hedged" => DeconflictionPolicy::AllowHedged,
+            "strategy_groups" => DeconflictionPolicy::StrategyGroups {
+                groups: config.deconflict_groups.clone(),
+                min_confidence,
+                max_flips,
+            },
+            _ => DeconflictionPolicy::FirstWins { min_confidence, max_flips },
+        }
+    }
+
+    /// Index of the group `strategy` deconflicts in.
+    fn group_of(&self, strategy: &str) -> usize {
+        match self {
+            DeconflictionPolicy::StrategyGroups { groups, .. } => groups
+                .iter()
+                .position(|g| g.iter().any(|n| n == strategy))
+                .unwrap_or(groups.len()),
+            _ => 0,
+        }
+    }
+
+    fn group_count(&self) -> usize {
+        match self {
+            DeconflictionPolicy::StrategyGroups { groups, .. } => groups.len() + 1,
+            _ => 1,
+        }
+    }
+
+    /// House side and flip count of group `g`.
+    fn slot<'a>(&self, house: &'a mut HouseSide, g: usize) -> (&'a mut Option<Side>, &'a mut u32) {
+        if let DeconflictionPolicy::StrategyGroups { groups, .. } = self {
+            if g < groups.len() {
+                house.groups.resize(groups.len(), (None, 0));
+                let (side, flips) = &mut house.groups[g];
+                return (side, flips);
+            }
+        }
+        (&mut house.side, &mut house.flips)
+    }
+
+    /// Steps 1–2: drop active signals against their group's house side, then
+    /// deconflict groups with no house side yet.
+    fn filter(&self, signals: &mut Vec<Signal>, state: &MarketState, house: &mut HouseSide) {
+        match self {
+            DeconflictionPolicy::AllowHedged => return,
+            DeconflictionPolicy::NetExposure => {
+                let h = &state.position.holdings;
+                let net = h.up_cost - h.down_cost;
+                let side = if net.abs() < NET_FLAT_USDC {
+                    None
+                } else if net > 0.0 {
+                    Some(Side::Up)
+                } else {
+                    Some(Side::Down)
+                };
+                if matches!((house.side, side), (Some(prev), Some(now)) if prev != now) {
+                    house.flips += 1;
+                }
+                house.side = side;
+            }
+            _ => {}
+        }
+
+        for g in 0..self.group_count() {
+            let in_group = |s: &Signal| !s.is_passive && self.group_of(s.strategy) == g;
+            // ── Step 1: House-side filter ──
+            if let Some(hs) = *self.slot(house, g).0 {
+                signals.retain(|s| !in_group(s) || s.side == hs);
+                continue;
+            }
+            // ── Step 2: Deconflict when no house view yet ──
+            let (mut up_score, mut down_score, mut active) = (0.0_f64, 0.0_f64, 0);
+            for s in signals.iter().filter(|s| in_group(s)) {
+                active += 1;
+                match s.side {
+                    Side::Up => up_score += s.edge * s.confidence,
+                    Side::Down => down_score += s.edge * s.confidence,
+                }
+            }
+            if active > 1 && up_score > 0.0 && down_score > 0.0 {
+                let dominant = if up_score >= down_score { Side::Up } else { Side::Down };
+                signals.retain(|s| !in_group(s) || s.side == dominant);
+            }
+        }
+    }
+
+    /// Step 6: let an approved signal set (or flip) its group's house side.
+    fn on_dispatch(&self, sig: &Signal, house: &mut HouseSide) {
+        let (min_confidence, max_flips) = match self {
+            DeconflictionPolicy::FirstWins { min_confidence, max_flips }
+            | DeconflictionPolicy::StrategyGroups { min_confidence, max_flips, .. } => (*min_confidence, *max_flips),
+            // Net exposure follows fills; hedged books have no house side
+            DeconflictionPolicy::NetExposure | DeconflictionPolicy::AllowHedged => return,
+        };
+        // Only high-confidence active signals can set the house direction.
+        // Prevents low-conviction strategies (e.g. convexity_fade at 0.3-0.65)
+        // from locking the portfolio into a direction based on a weak signal.
+        // Once max_flips is reached, the house side is locked for the market.
+        if sig.is_passive || sig.confidence < min_confidence {
+            return;
+        }
+        let (side, flips) = self.slot(house, self.group_of(sig.strategy));
+        match *side {
+            None => {
+                *side = Some(sig.side);
+            }
+            Some(prev) if prev != sig.side && *flips < max_flips => {
+                *side = Some(sig.side);
+                *flips += 1;
+            }
+            _ => {} // same side or flips exhausted — no change
+        }
+    }
+}
+
+// ─── Strategy host ──────────────────────────────────────────────────────────
+
+/// Owns one market's strategy instances and drives their lifecycle hooks.
+///
+/// Strategies are selected per evaluation by their `trigger()`: `Both` runs on
+/// Binance trades and PM quotes/books alike, `MarketOpen` only when the engine
+/// evaluates the open window. Acks and fills are routed by strategy name to the
+/// strategy that originated the order. Build a fresh host per market.
+pub struct StrategyHost {
+    strategies: Vec<Box<dyn StatefulStrategy>>,
+}
+
+impl StrategyHost {
+    pub fn new(strategies: Vec<Box<dyn StatefulStrategy>>) -> Self {
+        Self { strategies }
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.strategies.iter().map(|s| s.name()).collect()
+    }
+
+    pub fn on_market_start(&mut self, state: &MarketState) {
+        for s in self.strategies.iter_mut() {
+            s.on_market_start(state);
+        }
+    }
+
+    /// Evaluate every strategy that fires on `event`, filling the (cleared) buffer.
+    #[inline]
+    pub fn evaluate(&mut self, event: EvalTrigger, state: &MarketState, now_ms: i64, buf: &mut Vec<Signal>) {
+        buf.clear();
+        for s in self.strategies.iter_mut() {
+            if fires_on(s.trigger(), event) {
+                s.evaluate(state, now_ms, buf);
+            }
+        }
+    }
+
+    /// Tell the originating strategy which engine order ID its signal became.
+    pub fn on_order_sent(&mut self, order: &Order, state: &MarketState) {
+        if let Some(s) = self.strategies.iter_mut().find(|s| s.name() == order.strategy) {
+            s.on_order_sent(order, state);
+        }
+    }
+
+    /// Collect cancel requests from every strategy (appended to `out`).
+    pub fn drain_cancels(&mut self, out: &mut Vec<u64>) {
+        for s in self.strategies.iter_mut() {
+            s.drain_cancels(out);
+        }
+    }
+
+    /// Route an order acknowledgement to the strategy that sent the order.
+    pub fn on_order_ack(&mut self, strategy: &str, ack: &OrderAck, state: &MarketState) {
+        if let Some(s) = self.strategies.iter_mut().find(|s| s.name() == strategy) {
+            s.on_order_ack(ack, state);
+        }
+    }
+
+    /// Route a fill to the strategy that sent the order.
+    pub fn on_fill(&mut self, fill: &Fill, state: &MarketState) {
+        if let Some(s) = self.strategies.iter_mut().find(|s| s.name() == fill.strategy) {
+            s.on_fill(fill, state);
+        }
+    }
+
+    /// Replay fills simulated at dispatch (backtest, replay) through the same
+    /// ack → fill sequence the live gateway produces.
+    pub fn on_simulated_fills(&mut self, fills: &[Fill], state: &MarketState) {
+        for fill in fills {
+            self.ack_fill(fill, OrderStatus::Filled, state);
+        }
+    }
+
+    /// Backtest/replay: acknowledge fills of resting orders from `QueueFillSim`.
+    /// Orders still resting in `sim` are acked as partial fills.
+    pub fn on_resting_fills(&mut self, fills: &[Fill], sim: &QueueFillSim, state: &MarketState) {
+        for fill in fills {
+            let status = if sim.is_resting(fill.order_id) {
+                OrderStatus::PartialFill
+            } else {
+                OrderStatus::Filled
+            };
+            self.ack_fill(fill, status, state);
+        }
+    }
+
+    fn ack_fill(&mut self, fill: &Fill, status: OrderStatus, state: &MarketState) {
+        let ack = OrderAck {
+            order_id: fill.order_id,
+            status,
+            filled_price: Some(fill.price),
+            filled_size: Some(fill.size.abs()),
+            latency_ms: 0.0,
+            clob_order_id: None,
+            raw_response: None,
+        };
+        self.on_order_ack(fill.strategy, &ack, state);
+        self.on_fill(fill, state);
+    }
+
+    pub fn on_market_end(&mut self, outcome: Side, state: &MarketState) {
+        for s in self.strategies.iter_mut() {
+            s.on_market_end(outcome, state);
+        }
+    }
+}
+
+/// Backtest/replay: cancel the quotes the strategies asked to pull from `sim`,
+/// release the unfilled exposure of cancelled buys and acknowledge the cancels.
+/// Orders no longer resting (already filled) are skipped, as the CLOB would.
+pub fn cancel_resting(
+    host: &mut StrategyHost,
+    sim: &mut QueueFillSim,
+    risk: &mut StrategyRiskManager,
+    state: &MarketState,
+    buf: &mut Vec<u64>,
+) {
+    host.drain_cancels(buf);
+    for id in buf.drain(..) {
+        let Some(o) = sim.cancel(id) else { continue };
+        if o.action == OrderAction::Buy {
+            risk.release_pending(o.strategy, o.remaining);
+        }
+        let ack = OrderAck {
+            order_id: id,
+            status: OrderStatus::Cancelled,
+            filled_price: None,
+            filled_size: None,
+            latency_ms: 0.0,
+            clob_order_id: None,
+            raw_response: None,
+        };
+        host.on_order_ack(o.strategy, &ack, state);
+    }
+}
+
+/// Does a strategy with trigger `strategy` run on an `event`-type evaluation?
+#[inline]
+fn fires_on(strategy: EvalTrigger, event: EvalTrigger) -> bool {
+    match strategy {
+        EvalTrigger::Both => matches!(event, EvalTrigger::BinanceTrade | EvalTrigger::PolymarketQuote),
+        _ => strategy == event,
+    }
+}
+
+// ─── Shared pipeline ────────────────────────────────────────────────────────
+
 /// Process a batch of signals through the full pipeline.
 ///
 /// Steps:
-/// 1. **House-side filter**: If `house_side` is set, drop active signals on the
-///    wrong side (passive signals are exempt). If `flip_count >= MAX_DIRECTION_FLIPS`,
-///    also block active signals that would flip direction.
-/// 2. **Deconfliction**: If `house_side` is `None` and active signals disagree,
-///    score each side by `sum(edge * confidence)`, keep the dominant side only.
+/// 0. **Pairs** (parity and nested-window packages) are taken out first and
+///    dispatched by [`dispatch_pair`]: they hold no direction, so house side,
+///    deconfliction and Kelly don't apply.
+/// 1. **House-side filter**: If the house side is set, drop active signals on
+///    the wrong side (passive signals are exempt). The
+///    [`DeconflictionPolicy`] decides what the house side is, and for which
+///    strategies.
+/// 2. **Deconfliction**: If there is no house side yet and active signals
+///    disagree, score each side by `sum(edge * confidence)`, keep the dominant
+///    side only. With an [`Ensemble`], agreeing directional buys are then pooled into one
+///    consolidated signal per side; its order's exposure, stats and fills are
+///    split across the members by their calibration weights.
 /// 3. **Sort** by `edge * confidence` descending so the best signals hit the
 ///    risk manager first (matters when budget is tight).
 /// 4. **Log** every signal via `sink.on_signal`.
-/// 5. **Risk check** each signal. On approval: apply slippage, update stats,
-///    set `house_side` (only if `confidence >= 0.7`), call `sink.on_order`.
+/// 5. **Re-size** each buy signal against orders already approved in this batch:
+///    all of them are bets on the same binary, so Kelly is solved jointly.
+///    Market-making quotes and sells keep the size their strategy chose.
+/// 6. **Risk check** each signal. On approval: with `model_fills`, price and
+///    size taker orders from the book ([`ExecutionModel`]; an order the book
+///    can't fill is rejected as `no_fill`), else apply flat slippage; update
+///    stats, let the policy set the house side, call `sink.on_order`.
+///
+/// [`ExecutionModel`]: crate::engine::execution::ExecutionModel
 ///
 /// Returns `true` if at least one order was dispatched.
 pub fn process_signals(
     signals: &mut Vec<Signal>,
     state: &mut MarketState,
     risk: &mut StrategyRiskManager,
-    house_side: &mut Option<Side>,
-    flip_count: &mut u32,
+    house: &mut HouseSide,
     next_order_id: &mut u64,
     now_ms: i64,
     config: &ProcessConfig,
+    mut ensemble: Option<&mut Ensemble>,
     sink: &mut dyn SignalSink,
 ) -> bool {
     if signals.is_empty() {
         return false;
     }
+    let mut any_dispatched = false;
+    if let Some(e) = ensemble.as_deref_mut() {
+        e.observe(signals);
+    }
 
-    // ── Step 1: House-side filter ──
-    if let Some(hs) = *house_side {
-        signals.retain(|s| s.is_passive || s.side == hs);
+    // ── Step 0: Pairs ──
+    if signals.iter().any(|s| s.pair) {
+        // Stable sort: pair legs move to the back, each pair still UP-then-DOWN
+        signals.sort_by_key(|s| s.pair);
+        let first_leg = signals.iter().position(|s| s.pair).unwrap_or(signals.len());
+        let legs = signals.split_off(first_leg);
+        for pair in legs.chunks_exact(2) {
+            any_dispatched |= dispatch_pair(&pair[0], &pair[1], state, risk, next_order_id, now_ms, sink);
+        }
         if signals.is_empty() {
-            return false;
+            return any_dispatched;
         }
     }
-    // ── Step 2: Deconflict when no house view yet ──
-    else {
-        let active: Vec<&Signal> = signals.iter().filter(|s| !s.is_passive).collect();
-        if active.len() > 1 {
-            let (mut up_score, mut down_score) = (0.0_f64, 0.0_f64);
-            for s in &active {
-                match s.side {
-                    Side::Up => up_score += s.edge * s.confidence,
-                    Side::Down => down_score += s.edge * s.confidence,
-                }
-            }
-            if up_score > 0.0 && down_score > 0.0 {
-                let dominant = if up_score >= down_score { Side::Up } else { Side::Down };
-                signals.retain(|s| s.is_passive || s.side == dominant);
-            }
-        }
+
+    // ── Step 1-2: House side + deconfliction ──
+    config.deconfliction.filter(signals, state, house);
+    if signals.is_empty() {
+        return any_dispatched;
     }
 
+    // ── Step 2b: Pool agreeing signals ──
+    let merges: Vec<Merged> = match ensemble {
+        Some(e) => e.merge(signals, state, risk, *next_order_id, now_ms),
+        None => Vec::new(),
+    };
+
     // ── Step 3: Sort by edge * confidence descending ──
     signals.sort_unstable_by(|a, b| {
         let score_a = a.edge * a.confidence;
@@ -116,66 +503,160 @@ pub fn process_signals(
         sink.on_signal(sig, state, now_ms);
     }
 
-    // ── Step 5: Risk check + dispatch ──
-    let mut any_dispatched = false;
+    // ── Step 5-6: Joint re-size, risk check + dispatch ──
+    let mut batch = Holdings::default();
+
+    for sig in signals.iter_mut() {
+        let kelly_sized = !sig.quote && sig.action == OrderAction::Buy;
+        if kelly_sized && !batch.is_empty() {
+            sig.size_frac *= state.sizing.batch_scale(state, sig, &batch, now_ms);
+        }
+
+        // A consolidated signal stands in for each of its members
+        let merge = merges.iter().find(|m| m.is(sig));
+        let solo = [(sig.strategy, 1.0)];
+        let members = merge.map_or(&solo[..], |m| &m.shares[..]);
 
-    for sig in signals.iter() {
         state.total_signals += 1;
-        state.strategy_stats
-            .entry(sig.strategy)
-            .or_insert_with(StrategyStats::new)
-            .signals += 1;
+        for &(name, _) in members {
+            state.strategy_stats
+                .entry(name)
+                .or_insert_with(StrategyStats::new)
+                .signals += 1;
+        }
 
-        if let Some(mut order) = risk.check_strategy(sig, state, *next_order_id, now_ms) {
-            // Apply slippage
-            if config.slippage_cents > 0.0 {
-                order.price = (order.price + config.slippage_cents).min(0.99);
+        let mut order = match risk.decide(sig, state, *next_order_id, now_ms) {
+            RiskDecision::Approved(order) => order,
+            RiskDecision::Rejected(rejection) => {
+                sink.on_rejection(sig, &rejection, state, now_ms);
+                continue;
             }
+        };
+
+        // Simulated fill: takers from the book when one is recorded, else flat
+        // slippage (resting quotes fill at their own limit price)
+        if !sig.quote {
+            let exec = Execution::of(order.order_type, order.post_only, order.expiration_ms, now_ms);
+            let estimate = match exec {
+                Execution::Taker { .. } if config.model_fills => {
+                    state.execution.estimate(state, sig, order.size, exec, now_ms)
+                }
+                _ => None,
+            };
+            match estimate {
+                Some(est) if est.filled <= 0.0 => {
+                    let rejection = Rejection { rule: "no_fill", value: est.depth, limit: order.size };
+                    sink.on_rejection(sig, &rejection, state, now_ms);
+                    continue;
+                }
+                Some(est) => {
+                    order.price = est.price;
+                    order.size = est.filled_shares * est.price;
+                }
+                None if config.slippage_cents > 0.0 => {
+                    order.price = (order.price + config.slippage_cents).min(0.99);
+                }
+                None => {}
+            }
+        }
 
-            state.total_orders += 1;
+        state.total_orders += 1;
+        for &(name, _) in members {
             let strat_stats = state
                 .strategy_stats
-                .entry(sig.strategy)
+                .entry(name)
                 .or_insert_with(StrategyStats::new);
             strat_stats.orders += 1;
             strat_stats.total_edge += sig.edge;
+        }
 
-            // Only high-confidence active signals can set the house direction.
-            // Prevents low-conviction strategies (e.g. convexity_fade at 0.3-0.65)
-            // from locking the portfolio into a direction based on a weak signal.
-            // Once MAX_DIRECTION_FLIPS is reached, house_side is locked for the market.
-            if !sig.is_passive && sig.confidence >= 0.7 {
-                match *house_side {
-                    None => {
-                        *house_side = Some(sig.side);
-                    }
-                    Some(prev) if prev != sig.side && *flip_count < MAX_DIRECTION_FLIPS => {
-                        *house_side = Some(sig.side);
-                        *flip_count += 1;
-                    }
-                    _ => {} // same side or flips exhausted — no change
-                }
-            }
-
-            risk.on_order_sent(sig.strategy, now_ms, order.size);
-            state.position.on_order_sent();
+        config.deconfliction.on_dispatch(sig, house);
 
-            sink.on_order(sig, &order, state, now_ms);
+        // Sells release inventory rather than add exposure
+        let exposure = match order.action {
+            OrderAction::Buy => order.size,
+            OrderAction::Sell => 0.0,
+        };
+        for &(name, share) in members {
+            risk.on_order_sent(name, now_ms, exposure * share);
+        }
+        state.position.on_order_sent();
+        if kelly_sized {
+            batch.add(sig.side, order.price, order.size);
+        }
 
-            *next_order_id += 1;
-            any_dispatched = true;
+        match merge {
+            Some(m) => sink.on_merged_order(sig, &order, &m.shares, state, now_ms),
+            None => sink.on_order(sig, &order, state, now_ms),
         }
+
+        *next_order_id += 1;
+        any_dispatched = true;
     }
 
     any_dispatched
 }
 
+/// Risk-check and dispatch one pair (UP leg, DOWN leg) both-or-neither.
+///
+/// Legs are sized to one share count by [`StrategyRiskManager::decide_pair`] and
+/// keep their limit prices: the strategy already priced them off the book, so no
+/// simulated slippage is added. Pairs never set the house side.
+fn dispatch_pair(
+    up: &Signal,
+    down: &Signal,
+    state: &mut MarketState,
+    risk: &mut StrategyRiskManager,
+    next_order_id: &mut u64,
+    now_ms: i64,
+    sink: &mut dyn SignalSink,
+) -> bool {
+    for sig in [up, down] {
+        sink.on_signal(sig, state, now_ms);
+        state.total_signals += 1;
+        state.strategy_stats
+            .entry(sig.strategy)
+            .or_insert_with(StrategyStats::new)
+            .signals += 1;
+    }
+
+    let (up_order, down_order) = match risk.decide_pair(up, down, state, *next_order_id, now_ms) {
+        Ok(legs) => legs,
+        Err(rejection) => {
+            sink.on_rejection(up, &rejection, state, now_ms);
+            return false;
+        }
+    };
+
+    for (sig, order) in [(up, &up_order), (down, &down_order)] {
+        state.total_orders += 1;
+        let strat_stats = state
+            .strategy_stats
+            .entry(sig.strategy)
+            .or_insert_with(StrategyStats::new);
+        strat_stats.orders += 1;
+        strat_stats.total_edge += sig.edge;
+
+        let exposure = match order.action {
+            OrderAction::Buy => order.size,
+            OrderAction::Sell => 0.0,
+        };
+        risk.on_order_sent(sig.strategy, now_ms, exposure);
+        state.position.on_order_sent();
+    }
+
+    sink.on_pair([(up, &up_order), (down, &down_order)], state, now_ms);
+    *next_order_id += 2;
+    true
+}
+
 // ─── Tests ──────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::strategies::test_helpers::*;
+    use crate::types::Instrument;
 
     /// Test sink that records calls for assertions.
     struct TestSink {
@@ -202,26 +683,6 @@ mod tests {
         }
     }
 
-    fn make_signal(
-        strategy: &'static str,
-        side: Side,
-        edge: f64,
-        confidence: f64,
-        price: f64,
-    ) -> Signal {
-        Signal {
-            strategy,
-            side,
-            edge,
-            fair_value: price + edge,
-            market_price: price,
-            confidence,
-            size_frac: 0.02,
-            is_passive: false,
-            use_bid: false,
-        }
-    }
-
     fn make_passive_signal(
         strategy: &'static str,
         side: Side,
@@ -238,6 +699,10 @@ mod tests {
             size_frac: 0.02,
             is_passive: true,
             use_bid: false,
+            action: OrderAction::Buy,
+            quote: false,
+            pair: false,
+            instrument: Instrument::Current,
         }
     }
 
@@ -253,15 +718,14 @@ mod tests {
             make_signal("latency_arb", Side::Up, 0.05, 0.8, 0.50),   // score = 0.04
             make_signal("convexity_fade", Side::Down, 0.03, 0.6, 0.50), // score = 0.018
         ];
-        let mut house_side = None;
-        let mut flip_count = 0u32;
+        let mut house = HouseSide::default();
         let mut next_id = 1;
         let conf = ProcessConfig::live();
         let mut sink = TestSink::new();
 
         process_signals(
             &mut signals, &mut state, &mut risk,
-            &mut house_side, &mut flip_count, &mut next_id, now, &conf, &mut sink,
+            &mut house, &mut next_id, now, &conf, None, &mut sink,
         );
 
         // All logged signals should be Up only (Down was deconflicted away)
@@ -281,15 +745,14 @@ mod tests {
         let mut signals = vec![
             make_signal("latency_arb", Side::Down, 0.05, 0.8, 0.50),
         ];
-        let mut house_side = Some(Side::Up);
-        let mut flip_count = 0u32;
+        let mut house = HouseSide { side: Some(Side::Up), ..Default::default() };
         let mut next_id = 1;
         let conf = ProcessConfig::live();
         let mut sink = TestSink::new();
 
         process_signals(
             &mut signals, &mut state, &mut risk,
-            &mut house_side, &mut flip_count, &mut next_id, now, &conf, &mut sink,
+            &mut house, &mut next_id, now, &conf, None, &mut sink,
         );
 
         assert!(sink.signals.is_empty(), "Down signal should be filtered by house=Up");
@@ -305,15 +768,14 @@ mod tests {
         let mut signals = vec![
             make_passive_signal("lp_extreme", Side::Down, 0.05, 0.10),
         ];
-        let mut house_side = Some(Side::Up);
-        let mut flip_count = 0u32;
+        let mut house = HouseSide { side: Some(Side::Up), ..Default::default() };
         let mut next_id = 1;
         let conf = ProcessConfig::live();
         let mut sink = TestSink::new();
 
         process_signals(
             &mut signals, &mut state, &mut risk,
-            &mut house_side, &mut flip_count, &mut next_id, now, &conf, &mut sink,
+            &mut house, &mut next_id, now, &conf, None, &mut sink,
         );
 
         assert_eq!(sink.signals.len(), 1, "Passive signal should survive house_side filter");
@@ -332,15 +794,14 @@ mod tests {
             make_signal("latency_arb", Side::Up, 0.05, 0.8, 0.50),     // score = 0.040
             make_signal("certainty_capture", Side::Up, 0.03, 0.9, 0.50), // score = 0.027
         ];
-        let mut house_side = None;
-        let mut flip_count = 0u32;
+        let mut house = HouseSide::default();
         let mut next_id = 1;
         let conf = ProcessConfig::live();
         let mut sink = TestSink::new();
 
         process_signals(
             &mut signals, &mut state, &mut risk,
-            &mut house_side, &mut flip_count, &mut next_id, now, &conf, &mut sink,
+            &mut house, &mut next_id, now, &conf, None, &mut sink,
         );
 
         // Signals logged in score order: latency_arb (0.040), certainty_capture (0.027), convexity_fade (0.008)
@@ -360,18 +821,17 @@ mod tests {
         let mut signals = vec![
             make_signal("convexity_fade", Side::Up, 0.05, 0.4, 0.50),
         ];
-        let mut house_side: Option<Side> = None;
-        let mut flip_count = 0u32;
+        let mut house = HouseSide::default();
         let mut next_id = 1;
         let conf = ProcessConfig::live();
         let mut sink = TestSink::new();
 
         process_signals(
             &mut signals, &mut state, &mut risk,
-            &mut house_side, &mut flip_count, &mut next_id, now, &conf, &mut sink,
+            &mut house, &mut next_id, now, &conf, None, &mut sink,
         );
 
-        assert!(house_side.is_none(), "Low-confidence signal should not set house_side");
+        assert!(house.side.is_none(), "Low-confidence signal should not set house_side");
     }
 
     /// High-confidence signal sets house_side.
@@ -384,20 +844,19 @@ mod tests {
         let mut signals = vec![
             make_signal("strike_misalign", Side::Down, 0.05, 0.9, 0.50),
         ];
-        let mut house_side: Option<Side> = None;
-        let mut flip_count = 0u32;
+        let mut house = HouseSide::default();
         let mut next_id = 1;
         let conf = ProcessConfig::live();
         let mut sink = TestSink::new();
 
         process_signals(
             &mut signals, &mut state, &mut risk,
-            &mut house_side, &mut flip_count, &mut next_id, now, &conf, &mut sink,
+            &mut house, &mut next_id, now, &conf, None, &mut sink,
         );
 
         // If the signal passed risk and filled, house_side should be set
         if !sink.orders.is_empty() {
-            assert_eq!(house_side, Some(Side::Down),
+            assert_eq!(house.side, Some(Side::Down),
                 "High-confidence signal should set house_side");
         }
     }
@@ -412,15 +871,14 @@ mod tests {
         let mut signals = vec![
             make_signal("latency_arb", Side::Up, 0.05, 0.8, 0.50),
         ];
-        let mut house_side = None;
-        let mut flip_count = 0u32;
+        let mut house = HouseSide::default();
         let mut next_id = 1;
         let conf = ProcessConfig::backtest();
         let mut sink = TestSink::new();
 
         process_signals(
             &mut signals, &mut state, &mut risk,
-            &mut house_side, &mut flip_count, &mut next_id, now, &conf, &mut sink,
+            &mut house, &mut next_id, now, &conf, None, &mut sink,
         );
 
         if let Some((_, _, price, _)) = sink.orders.first() {
@@ -441,15 +899,14 @@ mod tests {
         let mut signals = vec![
             make_signal("latency_arb", Side::Up, 0.05, 0.8, 0.50),
         ];
-        let mut house_side = None;
-        let mut flip_count = 0u32;
+        let mut house = HouseSide::default();
         let mut next_id = 1;
         let conf = ProcessConfig::live();
         let mut sink = TestSink::new();
 
         process_signals(
             &mut signals, &mut state, &mut risk,
-            &mut house_side, &mut flip_count, &mut next_id, now, &conf, &mut sink,
+            &mut house, &mut next_id, now, &conf, None, &mut sink,
         );
 
         if let Some((_, _, price, _)) = sink.orders.first() {
@@ -460,6 +917,113 @@ mod tests {
         }
     }
 
+    /// Resting quotes are posted at the quoted price even in backtest: they
+    /// provide liquidity, so no taker slippage applies.
+    #[test]
+    fn test_quote_skips_slippage_in_backtest() {
+        let config = make_config();
+        let mut risk = StrategyRiskManager::new(&config);
+        let (mut state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+
+        let mut quote = make_passive_signal("latency_arb", Side::Up, 0.02, 0.48);
+        quote.quote = true;
+        let mut signals = vec![quote];
+        let mut house = HouseSide::default();
+        let mut next_id = 1;
+        let conf = ProcessConfig::backtest();
+        let mut sink = TestSink::new();
+
+        process_signals(
+            &mut signals, &mut state, &mut risk,
+            &mut house, &mut next_id, now, &conf, None, &mut sink,
+        );
+
+        if let Some((_, _, price, _)) = sink.orders.first() {
+            assert!(
+                (*price - 0.48).abs() < 0.001,
+                "Quote should rest at its own price: got {}", price
+            );
+        }
+    }
+
+    /// With a recorded book, backtest fills a taker at the VWAP of the levels it
+    /// walks instead of limit + 1 cent, for exactly the shares the approved
+    /// order buys; a book with nothing at or under the limit fills nothing and
+    /// the order is dropped.
+    #[test]
+    fn test_backtest_models_taker_fill_from_book() {
+        let config = make_config();
+        let run = |conf: &ProcessConfig, asks: Vec<(f64, f64)>| {
+            let mut risk = StrategyRiskManager::new(&config);
+            let (mut state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+            inject_book(&mut state, Side::Up, vec![(0.47, 500.0)], asks);
+            let mut signals = vec![make_signal("latency_arb", Side::Up, 0.05, 0.8, 0.50)];
+            let mut house = HouseSide::default();
+            let mut next_id = 1;
+            let mut sink = TestSink::new();
+            process_signals(
+                &mut signals, &mut state, &mut risk,
+                &mut house, &mut next_id, now, conf, None, &mut sink,
+            );
+            sink.orders
+        };
+        let book = || vec![(0.48, 5.0), (0.50, 1000.0)];
+
+        // Unmodeled, the approved order is `approved` USDC at the 0.50 limit
+        let live = run(&ProcessConfig::live(), book());
+        assert_eq!(live.len(), 1);
+        let approved = live[0].3;
+        let shares = approved / 0.50;
+        assert!(shares > 5.0 && shares < 1005.0, "walks into the second level: {} shares", shares);
+
+        // Modeled: the same shares, 5 of them a cent cheaper
+        let orders = run(&ProcessConfig::backtest(), book());
+        assert_eq!(orders.len(), 1);
+        let (_, _, price, size) = orders[0];
+        let cost = 5.0 * 0.48 + (shares - 5.0) * 0.50;
+        assert!((price - cost / shares).abs() < 1e-9, "VWAP: got {}", price);
+        assert!((size / price - shares).abs() < 1e-9, "shares: got {}", size / price);
+        assert!((size - (approved - 0.10)).abs() < 1e-9, "notional: got {}", size);
+
+        assert!(run(&ProcessConfig::backtest(), vec![(0.55, 1000.0)]).is_empty(), "nothing at or under the limit");
+    }
+
+    /// A parity pair ignores house side and deconfliction, keeps its limit prices
+    /// in backtest and goes out as two equal-share orders with consecutive IDs.
+    #[test]
+    fn test_pair_dispatched_both_legs() {
+        let config = make_config();
+        let mut risk = StrategyRiskManager::new(&config);
+        let (mut state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+
+        let mut up = make_signal("parity_arb", Side::Up, 0.02, 1.0, 0.45);
+        let mut down = make_signal("parity_arb", Side::Down, 0.02, 1.0, 0.50);
+        up.pair = true;
+        down.pair = true;
+        let mut signals = vec![
+            up,
+            make_signal("latency_arb", Side::Up, 0.05, 0.8, 0.50),
+            down,
+        ];
+        let mut house = HouseSide { side: Some(Side::Up), ..Default::default() };
+        let mut next_id = 1;
+        let conf = ProcessConfig::backtest();
+        let mut sink = TestSink::new();
+
+        process_signals(
+            &mut signals, &mut state, &mut risk,
+            &mut house, &mut next_id, now, &conf, None, &mut sink,
+        );
+
+        let legs: Vec<_> = sink.orders.iter().filter(|o| o.0 == "parity_arb").collect();
+        assert_eq!(legs.len(), 2, "both legs dispatched despite house=Up");
+        assert_eq!((legs[0].1, legs[1].1), (Side::Up, Side::Down));
+        assert!((legs[0].2 - 0.45).abs() < 1e-9 && (legs[1].2 - 0.50).abs() < 1e-9);
+        assert!((legs[0].3 / 0.45 - legs[1].3 / 0.50).abs() < 1e-9, "equal shares");
+        assert_eq!(house.side, Some(Side::Up));
+        assert_eq!(next_id, 4, "pair took IDs 1-2, latency_arb 3");
+    }
+
     /// Strategy stats are incremented for signals and orders.
     #[test]
     fn test_strategy_stats_incremented() {
@@ -470,15 +1034,14 @@ mod tests {
         let mut signals = vec![
             make_signal("latency_arb", Side::Up, 0.05, 0.8, 0.50),
         ];
-        let mut house_side = None;
-        let mut flip_count = 0u32;
+        let mut house = HouseSide::default();
         let mut next_id = 1;
         let conf = ProcessConfig::live();
         let mut sink = TestSink::new();
 
         process_signals(
             &mut signals, &mut state, &mut risk,
-            &mut house_side, &mut flip_count, &mut next_id, now, &conf, &mut sink,
+            &mut house, &mut next_id, now, &conf, None, &mut sink,
         );
 
         assert!(state.total_signals >= 1, "total_signals should be incremented");
@@ -495,19 +1058,395 @@ mod tests {
         let (mut state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
 
         let mut signals: Vec<Signal> = vec![];
-        let mut house_side = None;
-        let mut flip_count = 0u32;
+        let mut house = HouseSide::default();
         let mut next_id = 1;
         let conf = ProcessConfig::live();
         let mut sink = TestSink::new();
 
         let result = process_signals(
             &mut signals, &mut state, &mut risk,
-            &mut house_side, &mut flip_count, &mut next_id, now, &conf, &mut sink,
+            &mut house, &mut next_id, now, &conf, None, &mut sink,
         );
 
         assert!(!result, "Empty signals should return false");
         assert!(sink.signals.is_empty());
         assert!(sink.orders.is_empty());
     }
+
+    /// Two same-side signals in one batch: the second is re-sized against the
+    /// first's approved order, since both are bets on the same binary outcome.
+    #[test]
+    fn test_batch_resizes_correlated_signals() {
+        let config = make_config();
+        let mut risk = StrategyRiskManager::new(&config);
+        let (mut state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        state.sizing = crate::engine::sizing::KellySizer::from_config(&config);
+
+        let mut signals = vec![
+            make_signal("latency_arb", Side::Up, 0.10, 0.9, 0.50),
+            make_signal("certainty_capture", Side::Up, 0.10, 0.8, 0.50),
+        ];
+        let mut house = HouseSide::default();
+        let mut next_id = 1;
+        let conf = ProcessConfig::live();
+        let mut sink = TestSink::new();
+
+        process_signals(
+            &mut signals, &mut state, &mut risk,
+            &mut house, &mut next_id, now, &conf, None, &mut sink,
+        );
+
+        assert_eq!(sink.orders.len(), 2);
+        assert_eq!(sink.orders[0].0, "latency_arb");
+        assert!((sink.orders[0].3 - 20.0).abs() < 1e-9, "First order unchanged: {}", sink.orders[0].3);
+        assert!(sink.orders[1].3 < 20.0, "Second order shrinks for correlated exposure: {}", sink.orders[1].3);
+    }
+
+    // ── Deconfliction policies ──
+
+    /// Run one batch under `policy`; returns the sink.
+    fn run_policy(
+        policy: DeconflictionPolicy,
+        mut signals: Vec<Signal>,
+        state: &mut MarketState,
+        house: &mut HouseSide,
+        now: i64,
+    ) -> TestSink {
+        let mut risk = StrategyRiskManager::new(&make_config());
+        let conf = ProcessConfig::live().with_deconfliction(policy);
+        let mut next_id = 1;
+        let mut sink = TestSink::new();
+        process_signals(&mut signals, state, &mut risk, house, &mut next_id, now, &conf, None, &mut sink);
+        sink
+    }
+
+    /// Scenario: First-wins with HOUSE_SIDE_MIN_CONFIDENCE lowered to 0.5; a 0.6-confidence UP order.
+    /// Expected: The order sets the house side (the 0.7 default would not).
+    #[test]
+    fn test_first_wins_min_confidence_configurable() {
+        let (mut state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        let mut house = HouseSide::default();
+        let policy = DeconflictionPolicy::FirstWins { min_confidence: 0.5, max_flips: 1 };
+        let sink = run_policy(policy, vec![make_signal("convexity_fade", Side::Up, 0.05, 0.6, 0.50)], &mut state, &mut house, now);
+        assert_eq!(sink.orders.len(), 1);
+        assert_eq!(house.side, Some(Side::Up));
+    }
+
+    /// Scenario: Allow-hedged; house side UP from earlier; UP and DOWN active signals from two strategies.
+    /// Expected: Both are logged and dispatched; the house side is left as it was.
+    #[test]
+    fn test_allow_hedged_keeps_both_sides() {
+        let (mut state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        let mut house = HouseSide { side: Some(Side::Up), ..Default::default() };
+        let signals = vec![
+            make_signal("latency_arb", Side::Up, 0.05, 0.8, 0.50),
+            make_signal("convexity_fade", Side::Down, 0.04, 0.9, 0.50),
+        ];
+        let sink = run_policy(DeconflictionPolicy::AllowHedged, signals, &mut state, &mut house, now);
+        assert_eq!(sink.signals.len(), 2, "no side dropped: {:?}", sink.signals);
+        let sides: Vec<Side> = sink.orders.iter().map(|o| o.1).collect();
+        assert!(sides.contains(&Side::Up) && sides.contains(&Side::Down), "orders {:?}", sink.orders);
+        assert_eq!(house, HouseSide { side: Some(Side::Up), ..Default::default() });
+    }
+
+    /// Scenario: Net-exposure; house side UP from the first order, but only $10 of DOWN filled.
+    ///           A stronger UP signal and a DOWN signal arrive.
+    /// Expected: House side follows the position to DOWN (one flip); only the DOWN signal survives.
+    #[test]
+    fn test_net_exposure_follows_position() {
+        let (mut state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        state.position.record_fill(Side::Down, 0.50, 20.0);
+        let mut house = HouseSide { side: Some(Side::Up), ..Default::default() };
+        let signals = vec![
+            make_signal("latency_arb", Side::Up, 0.08, 0.9, 0.50),
+            make_signal("convexity_fade", Side::Down, 0.03, 0.6, 0.50),
+        ];
+        let sink = run_policy(DeconflictionPolicy::NetExposure, signals, &mut state, &mut house, now);
+        assert_eq!(sink.signals, vec!["convexity_fade".to_string()]);
+        assert_eq!((house.side, house.flips), (Some(Side::Down), 1));
+    }
+
+    /// Scenario: Net-exposure; the DOWN position has been scratched (equal UP and DOWN cost),
+    ///           house side still DOWN; UP outscores DOWN in the batch.
+    /// Expected: Flat book → no house side; the batch deconflicts by score and UP is dispatched.
+    #[test]
+    fn test_net_exposure_flat_book_frees_direction() {
+        let (mut state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        state.position.record_fill(Side::Down, 0.50, 20.0);
+        state.position.record_fill(Side::Up, 0.50, 20.0);
+        let mut house = HouseSide { side: Some(Side::Down), ..Default::default() };
+        let signals = vec![
+            make_signal("latency_arb", Side::Up, 0.08, 0.9, 0.50),
+            make_signal("convexity_fade", Side::Down, 0.03, 0.6, 0.50),
+        ];
+        let sink = run_policy(DeconflictionPolicy::NetExposure, signals, &mut state, &mut house, now);
+        assert_eq!(sink.signals, vec!["latency_arb".to_string()]);
+        assert_eq!(sink.orders.len(), 1);
+        assert_eq!(house.side, None, "set by the next fill, not the order");
+    }
+
+    /// Scenario: Strategy groups [latency_arb] and the rest; latency_arb's group is UP,
+    ///           the default group DOWN. Each group sends one signal per side.
+    /// Expected: Each group keeps only its own house side: latency_arb UP and convexity_fade DOWN.
+    #[test]
+    fn test_strategy_groups_filter_independently() {
+        let (mut state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        let policy = DeconflictionPolicy::StrategyGroups {
+            groups: vec![vec!["latency_arb".into()]],
+            min_confidence: 0.7,
+            max_flips: 1,
+        };
+        let mut house = HouseSide { side: Some(Side::Down), flips: 0, groups: vec![(Some(Side::Up), 0)] };
+        let signals = vec![
+            make_signal("latency_arb", Side::Up, 0.05, 0.8, 0.50),
+            make_signal("latency_arb", Side::Down, 0.09, 0.9, 0.50),
+            make_signal("convexity_fade", Side::Down, 0.04, 0.8, 0.50),
+            make_signal("strike_misalign", Side::Up, 0.09, 0.9, 0.50),
+        ];
+        let sink = run_policy(policy, signals, &mut state, &mut house, now);
+        let mut logged = sink.signals.clone();
+        logged.sort();
+        assert_eq!(logged, vec!["convexity_fade".to_string(), "latency_arb".to_string()]);
+    }
+
+    /// Scenario: Strategy groups, no house sides yet; latency_arb (own group) goes UP and
+    ///           strike_misalign (default group) DOWN, both high confidence.
+    /// Expected: Not deconflicted against each other: both dispatched, each setting its own group's side.
+    #[test]
+    fn test_strategy_groups_set_own_house_side() {
+        let (mut state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        let policy = DeconflictionPolicy::StrategyGroups {
+            groups: vec![vec!["latency_arb".into()]],
+            min_confidence: 0.7,
+            max_flips: 1,
+        };
+        let mut house = HouseSide::default();
+        let signals = vec![
+            make_signal("latency_arb", Side::Up, 0.05, 0.8, 0.50),
+            make_signal("strike_misalign", Side::Down, 0.04, 0.9, 0.50),
+        ];
+        let sink = run_policy(policy, signals, &mut state, &mut house, now);
+        assert_eq!(sink.orders.len(), 2, "orders {:?}", sink.orders);
+        assert_eq!(house.groups, vec![(Some(Side::Up), 0)]);
+        assert_eq!(house.side, Some(Side::Down));
+    }
+
+    /// Scenario: DECONFLICT_POLICY=strategy_groups with groups and MAX_DIRECTION_FLIPS=2; then an unknown name.
+    /// Expected: Groups policy carrying the configured knobs; the unknown name falls back to first-wins.
+    #[test]
+    fn test_policy_from_config() {
+        let mut config = make_config();
+        config.deconflict_policy = "strategy_groups".into();
+        config.deconflict_groups = vec![vec!["market_maker".into(), "lp_extreme".into()]];
+        config.max_direction_flips = 2;
+        assert_eq!(
+            DeconflictionPolicy::from_config(&config),
+            DeconflictionPolicy::StrategyGroups {
+                groups: vec![vec!["market_maker".into(), "lp_extreme".into()]],
+                min_confidence: 0.7,
+                max_flips: 2,
+            }
+        );
+        config.deconflict_policy = "bogus".into();
+        assert_eq!(
+            DeconflictionPolicy::from_config(&config),
+            DeconflictionPolicy::FirstWins { min_confidence: 0.7, max_flips: 2 }
+        );
+    }
+
+    // ── Ensemble ──
+
+    /// With the ensemble on, three agreeing UP signals become one order: the
+    /// default sink hook splits it into per-member parts of one order ID, each
+    /// member's stats count the order and every member goes on cooldown.
+    #[test]
+    fn test_ensemble_merges_agreeing_signals() {
+        let config = make_config();
+        let mut risk = StrategyRiskManager::new(&config);
+        let (mut state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        state.sizing = crate::engine::sizing::KellySizer::from_config(&config);
+        let mut ensemble = Ensemble::new();
+
+        let mut signals = vec![
+            make_signal("latency_arb", Side::Up, 0.10, 0.9, 0.50),
+            make_signal("certainty_capture", Side::Up, 0.12, 0.8, 0.50),
+            make_signal("convexity_fade", Side::Up, 0.08, 0.6, 0.50),
+        ];
+        let mut house = HouseSide::default();
+        let mut next_id = 1;
+        let conf = ProcessConfig::live();
+        let mut sink = TestSink::new();
+
+        process_signals(
+            &mut signals, &mut state, &mut risk,
+            &mut house, &mut next_id, now, &conf, Some(&mut ensemble), &mut sink,
+        );
+
+        assert_eq!(next_id, 2, "One order ID for the consolidated order");
+        assert_eq!(state.total_orders, 1);
+        assert_eq!(sink.orders.len(), 3, "Split into one part per member");
+        let total: f64 = sink.orders.iter().map(|o| o.3).sum();
+        assert!((total - risk.total_exposure()).abs() < 1e-9);
+        for name in ["latency_arb", "certainty_capture", "convexity_fade"] {
+            assert_eq!(state.strategy_stats[name].orders, 1, "{} counts the order", name);
+            let again = make_signal(name, Side::Up, 0.10, 0.9, 0.50);
+            assert!(risk.check_strategy(&again, &state, next_id, now + 1).is_none(), "{} on cooldown", name);
+        }
+        assert_eq!(house.side, Some(Side::Up));
+    }
+
+    /// A lone directional signal with the ensemble on is dispatched as usual.
+    #[test]
+    fn test_ensemble_single_signal_unchanged() {
+        let config = make_config();
+        let mut risk = StrategyRiskManager::new(&config);
+        let (mut state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        let mut ensemble = Ensemble::new();
+
+        let mut signals = vec![make_signal("latency_arb", Side::Up, 0.05, 0.8, 0.50)];
+        let mut house = HouseSide::default();
+        let mut next_id = 1;
+        let conf = ProcessConfig::live();
+        let mut sink = TestSink::new();
+
+        process_signals(
+            &mut signals, &mut state, &mut risk,
+            &mut house, &mut next_id, now, &conf, Some(&mut ensemble), &mut sink,
+        );
+
+        assert_eq!(sink.orders.len(), 1);
+        assert_eq!(sink.orders[0].0, "latency_arb");
+        assert!((sink.orders[0].3 - risk.total_exposure()).abs() < 1e-9);
+    }
+
+    // ── StrategyHost ──
+
+    use crate::strategies::StatefulStrategy;
+    use crate::types::EvalTrigger;
+    use std::sync::{Arc, Mutex};
+
+    /// Stateful test strategy: fires once per market, logs every hook it receives.
+    struct OneShot {
+        name: &'static str,
+        trigger: EvalTrigger,
+        fired: bool,
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl OneShot {
+        fn boxed(name: &'static str, trigger: EvalTrigger, log: &Arc<Mutex<Vec<String>>>) -> Box<dyn StatefulStrategy> {
+            Box::new(Self { name, trigger, fired: false, log: Arc::clone(log) })
+        }
+        fn note(&self, hook: &str) {
+            self.log.lock().unwrap().push(format!("{}:{}", self.name, hook));
+        }
+    }
+
+    impl StatefulStrategy for OneShot {
+        fn name(&self) -> &'static str { self.name }
+        fn trigger(&self) -> EvalTrigger { self.trigger }
+        fn on_market_start(&mut self, _state: &MarketState) {
+            self.fired = false;
+            self.note("start");
+        }
+        fn evaluate(&mut self, _state: &MarketState, _now_ms: i64, out: &mut Vec<Signal>) {
+            if !self.fired {
+                self.fired = true;
+                out.push(make_signal(self.name, Side::Up, 0.05, 0.8, 0.50));
+            }
+        }
+        fn on_order_ack(&mut self, _ack: &OrderAck, _state: &MarketState) { self.note("ack"); }
+        fn on_fill(&mut self, _fill: &Fill, _state: &MarketState) { self.note("fill"); }
+        fn on_market_end(&mut self, outcome: Side, _state: &MarketState) {
+            self.note(&format!("end:{:?}", outcome));
+        }
+    }
+
+    /// Host evaluation selects by trigger: Both runs on Binance and PM events,
+    /// MarketOpen only on open-window evaluations.
+    #[test]
+    fn test_host_partitions_by_trigger() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut host = StrategyHost::new(vec![
+            OneShot::boxed("bn", EvalTrigger::BinanceTrade, &log),
+            OneShot::boxed("pm", EvalTrigger::PolymarketQuote, &log),
+            OneShot::boxed("both", EvalTrigger::Both, &log),
+            OneShot::boxed("open", EvalTrigger::MarketOpen, &log),
+        ]);
+        let (state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        let mut buf = Vec::new();
+
+        host.evaluate(EvalTrigger::BinanceTrade, &state, now, &mut buf);
+        let names: Vec<&str> = buf.iter().map(|s| s.strategy).collect();
+        assert_eq!(names, vec!["bn", "both"]);
+
+        host.evaluate(EvalTrigger::PolymarketQuote, &state, now, &mut buf);
+        let names: Vec<&str> = buf.iter().map(|s| s.strategy).collect();
+        assert_eq!(names, vec!["pm"], "both already fired; buffer cleared between calls");
+
+        host.evaluate(EvalTrigger::MarketOpen, &state, now, &mut buf);
+        let names: Vec<&str> = buf.iter().map(|s| s.strategy).collect();
+        assert_eq!(names, vec!["open"]);
+    }
+
+    /// Stateful memory persists across evaluations and is reset by on_market_start.
+    #[test]
+    fn test_host_state_persists_until_market_start() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut host = StrategyHost::new(vec![OneShot::boxed("pm", EvalTrigger::PolymarketQuote, &log)]);
+        let (state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        let mut buf = Vec::new();
+
+        host.on_market_start(&state);
+        host.evaluate(EvalTrigger::PolymarketQuote, &state, now, &mut buf);
+        assert_eq!(buf.len(), 1);
+        host.evaluate(EvalTrigger::PolymarketQuote, &state, now + 1, &mut buf);
+        assert!(buf.is_empty(), "&mut self evaluation remembers it already fired");
+
+        host.on_market_start(&state);
+        host.evaluate(EvalTrigger::PolymarketQuote, &state, now + 2, &mut buf);
+        assert_eq!(buf.len(), 1, "New market resets per-market memory");
+    }
+
+    /// Simulated fills (backtest/replay) drive ack → fill on the originating
+    /// strategy only, in the same order as live; market end reaches every strategy.
+    #[test]
+    fn test_host_routes_lifecycle_to_owner() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut host = StrategyHost::new(vec![
+            OneShot::boxed("a", EvalTrigger::PolymarketQuote, &log),
+            OneShot::boxed("b", EvalTrigger::PolymarketQuote, &log),
+        ]);
+        let (state, _) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+
+        host.on_market_start(&state);
+        let fill = Fill { order_id: 7, strategy: "b", side: Side::Up, price: 0.5, size: 10.0, fee: 0.0 };
+        host.on_simulated_fills(&[fill], &state);
+        host.on_market_end(Side::Down, &state);
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec!["a:start", "b:start", "b:ack", "b:fill", "a:end:Down", "b:end:Down"],
+        );
+    }
+
+    /// Stateless strategies run through the host unchanged via the blanket impl.
+    #[test]
+    fn test_host_drives_stateless_strategies() {
+        use crate::strategies::certainty_capture::CertaintyCapture;
+        use crate::strategies::Strategy;
+        let mut host = StrategyHost::new(vec![Box::new(CertaintyCapture)]);
+        assert_eq!(host.names(), vec!["certainty_capture"]);
+
+        let (state, now) = make_state(95_000.0, 97_000.0, 0.001, 60.0, 0.90, 0.50);
+        let mut buf = Vec::new();
+        host.evaluate(EvalTrigger::PolymarketQuote, &state, now, &mut buf);
+        let direct = Strategy::evaluate(&CertaintyCapture, &state, now).unwrap();
+        assert_eq!(buf.len(), 1);
+        assert_eq!(buf[0].edge, direct.edge);
+        assert_eq!(buf[0].size_frac, direct.size_frac);
+
+        host.evaluate(EvalTrigger::BinanceTrade, &state, now, &mut buf);
+        assert!(buf.is_empty(), "PM-triggered strategy does not run on Binance trades");
+    }
 }
@@ -0,0 +1,279 @@
+//! Queue-aware fill simulation for resting (post-only) orders.
+//!
+//! Used by the backtester and replay for market-making quotes, which cannot be
+//! assumed to fill at their limit price the moment they are posted. Each resting
+//! order joins the back of its price level; recorded book updates then move it
+//! forward and eventually fill it:
+//!
+//! - **Cross**: the contra side trades through our price (best ask ≤ our bid, or
+//!   best bid ≥ our ask) → everything remaining fills at our price.
+//! - **Depletion at the touch**: while our price is the best on our side, size
+//!   that leaves our level first consumes the queue ahead of us; anything beyond
+//!   that fills us. Levels away from the touch only shrink the queue ahead
+//!   (cancellations), never fill.
+//!
+//! Improving the touch (no recorded size at our price) therefore fills only on a
+//! cross. Book sizes are in shares; order sizes are USDC notional at the limit
+//! price, as everywhere else in the engine.
+
+use crate::engine::state::MarketState;
+use crate::math::fees::Liquidity;
+use crate::types::{Fill, Order, OrderAction, Side};
+
+/// Price equality tolerance (well below the smallest 0.001 tick).
+pub(crate) const PRICE_EPS: f64 = 1e-9;
+
+/// Orders smaller than this after a partial fill are treated as done.
+const DUST: f64 = 1e-6;
+
+/// One simulated resting order.
+#[derive(Clone, Debug)]
+pub struct RestingOrder {
+    pub order_id: u64,
+    pub strategy: &'static str,
+    pub side: Side,
+    pub action: OrderAction,
+    pub price: f64,
+    /// Unfilled size (USDC notional at `price`).
+    pub remaining: f64,
+    /// Shares queued ahead of us at `price`.
+    pub queue_ahead: f64,
+    /// Shares at our level in the last book seen.
+    level_seen: f64,
+}
+
+/// Resting orders for one market and the fills they receive.
+pub struct QueueFillSim {
+    orders: Vec<RestingOrder>,
+}
+
+impl QueueFillSim {
+    pub fn new() -> Self {
+        Self { orders: Vec::with_capacity(8) }
+    }
+
+    /// Rest `order` at the back of its price level in the current book.
+    pub fn post(&mut self, order: &Order, state: &MarketState) {
+        let level = level_size(state, order.side, order.action, order.price);
+        self.orders.push(RestingOrder {
+            order_id: order.id,
+            strategy: order.strategy,
+            side: order.side,
+            action: order.action,
+            price: order.price,
+            remaining: order.size,
+            queue_ahead: level,
+            level_seen: level,
+        });
+    }
+
+    /// Remove a resting order. `None` if it already filled or was never posted.
+    pub fn cancel(&mut self, order_id: u64) -> Option<RestingOrder> {
+        let idx = self.orders.iter().position(|o| o.order_id == order_id)?;
+        Some(self.orders.swap_remove(idx))
+    }
+
+    pub fn resting(&self) -> &[RestingOrder] {
+        &self.orders
+    }
+
+    pub fn is_resting(&self, order_id: u64) -> bool {
+        self.orders.iter().any(|o| o.order_id == order_id)
+    }
+
+    /// Advance every resting order against the current book/quotes, appending
+    /// maker fills to `out` (sells with negative size). Fully filled orders are removed.
+    pub fn on_market_update(&mut self, state: &MarketState, out: &mut Vec<Fill>) {
+        self.orders.retain_mut(|o| {
+            let filled = advance(o, state);
+            if filled > 0.0 {
+                o.remaining -= filled;
+                let fee = state.info.fees.fill_fee(o.price, filled, Liquidity::Maker);
+                out.push(Fill {
+                    order_id: o.order_id,
+                    strategy: o.strategy,
+                    side: o.side,
+                    price: o.price,
+                    size: match o.action {
+                        OrderAction::Buy => filled,
+                        OrderAction::Sell => -filled,
+                    },
+                    fee,
+                });
+            }
+            o.remaining > DUST
+        });
+    }
+
+    /// Drop every resting order (market end).
+    pub fn clear(&mut self) {
+        self.orders.clear();
+    }
+}
+
+impl Default for QueueFillSim {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Size (USDC) of `o` filled by the latest market update.
+fn advance(o: &mut RestingOrder, state: &MarketState) -> f64 {
+    let (best_own, best_contra) = touch(state, o.side, o.action);
+
+    let crossed = best_contra > 0.0
+        && match o.action {
+            OrderAction::Buy => best_contra <= o.price + PRICE_EPS,
+            OrderAction::Sell => best_contra >= o.price - PRICE_EPS,
+        };
+    if crossed {
+        return o.remaining;
+    }
+
+    let level = level_size(state, o.side, o.action, o.price);
+    let at_touch = best_own <= 0.0
+        || match o.action {
+            OrderAction::Buy => o.price >= best_own - PRICE_EPS,
+            OrderAction::Sell => o.price <= best_own + PRICE_EPS,
+        };
+    let left = (o.level_seen - level).max(0.0);
+    o.level_seen = level;
+
+    if !at_touch {
+        // Away from the touch shrinkage is cancellation; whatever remains can
+        // be at most what is still ahead of us.
+        o.queue_ahead = o.queue_ahead.min(level);
+        return 0.0;
+    }
+
+    if left <= o.queue_ahead {
+        o.queue_ahead -= left;
+        return 0.0;
+    }
+    let excess_shares = left - o.queue_ahead;
+    o.queue_ahead = 0.0;
+    (excess_shares * o.price).min(o.remaining)
+}
+
+/// (best price on our side, best price on the contra side) for a token; 0 = unknown.
+fn touch(state: &MarketState, side: Side, action: OrderAction) -> (f64, f64) {
+    let (bid, ask) = match side {
+        Side::Up => (state.up_bid, state.up_ask),
+        Side::Down => (state.down_bid, state.down_ask),
+    };
+    match action {
+        OrderAction::Buy => (bid, ask),
+        OrderAction::Sell => (ask, bid),
+    }
+}
+
+/// Recorded shares at exactly `price` on our side of the token's book.
+fn level_size(state: &MarketState, side: Side, action: OrderAction, price: f64) -> f64 {
+    let book = match side {
+        Side::Up => &state.up_book,
+        Side::Down => &state.down_book,
+    };
+    let levels = match action {
+        OrderAction::Buy => &book.bids,
+        OrderAction::Sell => &book.asks,
+    };
+    levels
+        .iter()
+        .find(|(p, _)| (p - price).abs() < PRICE_EPS)
+        .map_or(0.0, |(_, s)| *s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::test_helpers::*;
+    use crate::types::OrderType;
+
+    /// A market-making quote: GTC post-only.
+    fn quote(id: u64, side: Side, action: OrderAction, price: f64, size: f64) -> Order {
+        Order {
+            strategy: "market_maker",
+            is_passive: true,
+            order_type: OrderType::GTC,
+            post_only: true,
+            ..make_order(id, side, action, price, size)
+        }
+    }
+
+    /// Scenario: Bid posted at 0.48 behind 100 shares; the level shrinks to 60, empties,
+    ///           refills with 30 shares behind us, then drops to 10.
+    /// Expected: The first 100 shares only walk us to the front; the final 20-share drop
+    ///           fills 20 shares ($9.60 at 0.48) of ours as a maker.
+    #[test]
+    fn test_fill_after_queue_ahead_depletes() {
+        let (mut state, _) = make_state(95_000.0, 95_000.0, 0.001, 120.0, 0.50, 0.50);
+        inject_book(&mut state, Side::Up, vec![(0.48, 100.0)], vec![(0.52, 100.0)]);
+        let mut sim = QueueFillSim::new();
+        sim.post(&quote(1, Side::Up, OrderAction::Buy, 0.48, 20.0), &state);
+        let mut fills = Vec::new();
+
+        inject_book(&mut state, Side::Up, vec![(0.48, 60.0)], vec![(0.52, 100.0)]);
+        sim.on_market_update(&state, &mut fills);
+        assert!(fills.is_empty());
+        assert!((sim.resting()[0].queue_ahead - 60.0).abs() < 1e-9);
+
+        inject_book(&mut state, Side::Up, vec![(0.47, 50.0)], vec![(0.52, 100.0)]);
+        sim.on_market_update(&state, &mut fills);
+        assert!(fills.is_empty(), "Level vanished while we were still queued: 60 ahead consumed exactly");
+
+        inject_book(&mut state, Side::Up, vec![(0.48, 30.0)], vec![(0.52, 100.0)]);
+        sim.on_market_update(&state, &mut fills);
+        inject_book(&mut state, Side::Up, vec![(0.48, 10.0)], vec![(0.52, 100.0)]);
+        sim.on_market_update(&state, &mut fills);
+        assert_eq!(fills.len(), 1);
+        assert!((fills[0].size - 20.0 * 0.48).abs() < 1e-9, "20 shares at 0.48: {}", fills[0].size);
+        assert!(fills[0].fee <= 0.0, "Resting fills earn the maker rebate");
+        assert!((sim.resting()[0].remaining - (20.0 - 9.6)).abs() < 1e-9);
+    }
+
+    /// Scenario: Resting ask at 0.55 on Down; the Down best bid jumps to 0.56.
+    /// Expected: Full fill at our 0.55, reported with negative size, order removed.
+    #[test]
+    fn test_cross_fills_everything() {
+        let (mut state, _) = make_state(95_000.0, 95_000.0, 0.001, 120.0, 0.50, 0.50);
+        inject_book(&mut state, Side::Down, vec![(0.50, 100.0)], vec![(0.55, 100.0)]);
+        let mut sim = QueueFillSim::new();
+        sim.post(&quote(2, Side::Down, OrderAction::Sell, 0.55, 10.0), &state);
+
+        let mut fills = Vec::new();
+        inject_book(&mut state, Side::Down, vec![(0.56, 100.0)], vec![(0.58, 100.0)]);
+        sim.on_market_update(&state, &mut fills);
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].price, 0.55);
+        assert!((fills[0].size + 10.0).abs() < 1e-9, "Sell fills are negative: {}", fills[0].size);
+        assert!(sim.resting().is_empty());
+    }
+
+    /// Scenario: Bid at 0.45 below a 0.48 best bid; its level shrinks from 100 to 30.
+    /// Expected: No fill away from the touch; queue ahead is clamped to the 30 left.
+    #[test]
+    fn test_away_from_touch_never_fills() {
+        let (mut state, _) = make_state(95_000.0, 95_000.0, 0.001, 120.0, 0.50, 0.50);
+        inject_book(&mut state, Side::Up, vec![(0.48, 50.0), (0.45, 100.0)], vec![(0.52, 100.0)]);
+        let mut sim = QueueFillSim::new();
+        sim.post(&quote(3, Side::Up, OrderAction::Buy, 0.45, 10.0), &state);
+
+        let mut fills = Vec::new();
+        inject_book(&mut state, Side::Up, vec![(0.48, 50.0), (0.45, 30.0)], vec![(0.52, 100.0)]);
+        sim.on_market_update(&state, &mut fills);
+        assert!(fills.is_empty());
+        assert!((sim.resting()[0].queue_ahead - 30.0).abs() < 1e-9);
+    }
+
+    /// Scenario: Cancel a resting order, then cancel it again.
+    /// Expected: First cancel returns it with its remaining size; second returns None.
+    #[test]
+    fn test_cancel_removes_order() {
+        let (state, _) = make_state(95_000.0, 95_000.0, 0.001, 120.0, 0.50, 0.50);
+        let mut sim = QueueFillSim::new();
+        sim.post(&quote(4, Side::Up, OrderAction::Buy, 0.40, 5.0), &state);
+        let o = sim.cancel(4).expect("resting");
+        assert_eq!(o.remaining, 5.0);
+        assert!(sim.cancel(4).is_none());
+    }
+}
@@ -2,9 +2,14 @@ use std::collections::HashMap;
 use std::time::Instant;
 
 use crate::config::Config;
+use crate::engine::risk_rules::{
+    self, PortfolioView, Rejection, RiskDecision, RiskRule, RuleContext, StrategyView, MIN_ORDER_USDC,
+};
 use crate::engine::state::MarketState;
-use crate::math::pricing::{delta_bin, gamma_bin};
-use crate::types::{Fill, Order, OrderAck, OrderType, Side, Signal};
+use crate::math::oracle::OracleBasis;
+use crate::math::pricing::{delta_bin_batch, gamma_bin_batch, p_fair, theta_bin, vega_bin};
+use crate::strategies::registry;
+use crate::types::{Fill, Instrument, Order, OrderAction, OrderType, Side, Signal};
 
 #[derive(Clone)]
 pub struct StrategyLimits {
@@ -15,45 +20,172 @@ pub struct StrategyLimits {
 }
 
 struct StrategyRiskState {
-    exposure: f64,
+    /// Sent and not yet filled, cancelled, rejected or expired.
+    pending: f64,
+    /// Filled and still held: buys add, sells unwind.
+    filled: f64,
     orders_this_market: u32,
     last_order_ms: i64,
+    inventory: Inventory,
+    /// Stopped by a mark-to-market rule: no new buys this market.
+    stopped: bool,
 }
 
 impl StrategyRiskState {
     fn new() -> Self {
         Self {
-            exposure: 0.0,
+            pending: 0.0,
+            filled: 0.0,
             orders_this_market: 0,
             last_order_ms: 0,
+            inventory: Inventory::default(),
+            stopped: false,
         }
     }
+
+    /// What the strategy's caps bind: pending plus filled.
+    #[inline]
+    fn exposure(&self) -> f64 {
+        self.pending + self.filled
+    }
+}
+
+// ─── Inventory & mark-to-market ───────────────────────────────────────────────
+
+/// One side's open position: outcome tokens held and the USDC paid for them.
+/// `Fill::size` is USDC notional at the fill price, so a fill is
+/// `size / price` tokens.
+#[derive(Clone, Copy, Debug, Default)]
+struct Lot {
+    shares: f64,
+    cost: f64,
+}
+
+/// A strategy's open inventory in the current market and the PnL it has
+/// realised by unwinding (net of every fee paid, including on open lots).
+#[derive(Clone, Copy, Debug, Default)]
+struct Inventory {
+    up: Lot,
+    down: Lot,
+    realized: f64,
+}
+
+impl Inventory {
+    /// Apply a fill; returns the cost (USDC) a sell took off the open lot.
+    fn on_fill(&mut self, fill: &Fill) -> f64 {
+        self.realized -= fill.fee;
+        if fill.price <= 0.0 {
+            return 0.0;
+        }
+        let lot = match fill.side {
+            Side::Up => &mut self.up,
+            Side::Down => &mut self.down,
+        };
+        if fill.size >= 0.0 {
+            lot.shares += fill.size / fill.price;
+            lot.cost += fill.size;
+            return 0.0;
+        }
+        let sold = (-fill.size / fill.price).min(lot.shares);
+        if sold <= 0.0 {
+            return 0.0;
+        }
+        let released = lot.cost * sold / lot.shares;
+        self.realized += fill.price * sold - released;
+        lot.cost -= released;
+        lot.shares -= sold;
+        released
+    }
+
+    /// Open lots valued at the given marks, less their cost.
+    fn unrealized(&self, up_mark: f64, down_mark: f64) -> f64 {
+        self.up.shares * up_mark - self.up.cost + self.down.shares * down_mark - self.down.cost
+    }
+
+    fn is_flat(&self) -> bool {
+        self.up.shares == 0.0 && self.down.shares == 0.0
+    }
+}
+
+/// Exposure and mark-to-market PnL of one strategy in the current market.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ExposureSnapshot {
+    /// Sent orders not yet filled or released.
+    pub pending: f64,
+    /// Cost (USDC) of the filled inventory still held.
+    pub filled: f64,
+    /// PnL locked in by unwinding inventory, net of all fees paid so far.
+    pub realized_pnl: f64,
+    /// Open inventory marked at the best bids (what it would sell for now).
+    pub unrealized_pnl: f64,
+    /// Open inventory marked at the model fair value (what it is worth held to expiry).
+    pub unrealized_fair_pnl: f64,
+}
+
+impl ExposureSnapshot {
+    /// Liquidation-value PnL: realised plus open inventory at the bids.
+    #[inline]
+    pub fn mtm_pnl(&self) -> f64 {
+        self.realized_pnl + self.unrealized_pnl
+    }
+}
+
+/// Model P(UP) for marking: Φ(d2) before expiry, the strike test after.
+/// Falls back to `up_bid` without a spot price.
+pub fn mark_fair_up(state: &MarketState, now_ms: i64) -> f64 {
+    let (s, k) = (state.s_est(), state.info.strike);
+    if s <= 0.0 || k <= 0.0 {
+        return state.up_bid.max(0.0);
+    }
+    let tau = state.tau_eff_s(now_ms);
+    if tau <= 0.0 {
+        return if s >= k { 1.0 } else { 0.0 };
+    }
+    p_fair(s, k, state.sigma_real(), tau)
 }
 
 // ─── Portfolio Greeks ─────────────────────────────────────────────────────────
 
-/// Per-fill record for Greeks recomputation as market conditions change.
-#[derive(Clone)]
-struct FillGreeksRecord {
-    side: Side,
-    size: f64,
+/// Greeks of one binary instrument (a market's strike and expiry), scaled by
+/// the net position: UP size minus DOWN size.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct InstrumentGreeks {
+    pub strike: f64,
+    pub end_ms: i64,
+    pub net_size: f64,
+    pub delta: f64,
+    pub gamma: f64,
+    pub vega: f64,
+    /// Per second of calendar time.
+    pub theta: f64,
 }
 
-/// Aggregate portfolio Greeks snapshot.
+/// Aggregate portfolio Greeks snapshot over every open instrument.
 #[derive(Clone, Copy, Debug, Default)]
 pub struct PortfolioGreeks {
     pub delta: f64,
     pub gamma: f64,
+    pub vega: f64,
+    pub theta: f64,
+    /// `delta × S`: the USD notional of the underlying with the same exposure.
+    pub dollar_delta: f64,
+    /// Fills across the open instruments.
     pub n_positions: u32,
 }
 
-/// Tracks fills and recomputes aggregate Greeks on demand.
+/// Tracks fills per instrument and recomputes aggregate Greeks on demand.
 ///
-/// All fills in a market share the same (S, K, sigma, tau) since it's one binary
-/// option. `delta_bin` and `gamma_bin` are computed once per recompute, then scaled
-/// by `sign * size` per fill. Cost: 2 function calls + N multiply-accumulates.
+/// Positions in one instrument share (S, K, sigma, tau), so each instrument's
+/// unit Greeks are computed once per recompute and scaled by its net size.
+/// Instruments are dropped once expired; there are only ever a few open (the
+/// current market plus any cross markets traded), so they live in a Vec.
 pub struct GreeksTracker {
-    positions: Vec<FillGreeksRecord>,
+    /// Each open instrument and its fill count.
+    instruments: Vec<(InstrumentGreeks, u32)>,
+    /// Spot price at the last recompute.
+    spot: f64,
+    /// Batch-kernel inputs/outputs (S, K, tau, delta, gamma), reused across recomputes.
+    scratch: Vec<f64>,
     /// Cached snapshot — recomputed on `recompute()`.
     pub snapshot: PortfolioGreeks,
 }
@@ -61,45 +193,93 @@ pub struct GreeksTracker {
 impl GreeksTracker {
     pub fn new() -> Self {
         Self {
-            positions: Vec::with_capacity(16),
+            instruments: Vec::with_capacity(4),
+            spot: 0.0,
+            scratch: Vec::new(),
             snapshot: PortfolioGreeks::default(),
         }
     }
 
-    /// Record a new fill. Call `recompute()` after to update the snapshot.
-    pub fn on_fill(&mut self, side: Side, size: f64) {
-        self.positions.push(FillGreeksRecord { side, size });
-        self.snapshot.n_positions = self.positions.len() as u32;
+    /// Record a fill on the instrument (`strike`, `end_ms`). Call `recompute()`
+    /// after to update the snapshot.
+    pub fn on_fill(&mut self, strike: f64, end_ms: i64, side: Side, size: f64) {
+        let signed = match side {
+            Side::Up => size,
+            Side::Down => -size,
+        };
+        match self.instruments.iter_mut().find(|(g, _)| g.strike == strike && g.end_ms == end_ms) {
+            Some((g, n)) => {
+                g.net_size += signed;
+                *n += 1;
+            }
+            None => {
+                let g = InstrumentGreeks { strike, end_ms, net_size: signed, ..Default::default() };
+                self.instruments.push((g, 1));
+            }
+        }
+        self.snapshot.n_positions += 1;
     }
 
-    /// Recompute aggregate Greeks at current market conditions.
+    /// Recompute every open instrument's Greeks at spot `s` and vol `sigma`,
+    /// with each instrument's effective time to expiry from `oracle`.
     /// Called on every Binance trade (when positions exist) and after every fill.
-    pub fn recompute(&mut self, s: f64, k: f64, sigma: f64, tau: f64) {
-        let unit_delta = delta_bin(s, k, sigma, tau);
-        let unit_gamma = gamma_bin(s, k, sigma, tau);
-
-        let mut total_delta = 0.0;
-        let mut total_gamma = 0.0;
-
-        for pos in &self.positions {
-            let sign = match pos.side {
-                Side::Up => 1.0,
-                Side::Down => -1.0,
-            };
-            total_delta += sign * pos.size * unit_delta;
-            total_gamma += sign * pos.size * unit_gamma;
+    pub fn recompute(&mut self, s: f64, sigma: f64, now_ms: i64, oracle: &OracleBasis) {
+        self.spot = s;
+        self.expire(now_ms);
+        let n = self.instruments.len();
+        self.scratch.clear();
+        self.scratch.resize(5 * n, 0.0);
+        let (spot, rest) = self.scratch.split_at_mut(n);
+        let (strike, rest) = rest.split_at_mut(n);
+        let (tau, rest) = rest.split_at_mut(n);
+        let (delta, gamma) = rest.split_at_mut(n);
+        for (i, (g, _)) in self.instruments.iter().enumerate() {
+            spot[i] = s;
+            strike[i] = g.strike;
+            tau[i] = oracle.tau_eff((g.end_ms - now_ms) as f64 / 1000.0);
+        }
+        delta_bin_batch(spot, strike, tau, sigma, delta);
+        gamma_bin_batch(spot, strike, tau, sigma, gamma);
+        for (i, (g, _)) in self.instruments.iter_mut().enumerate() {
+            let (k, t, n) = (g.strike, tau[i], g.net_size);
+            g.delta = n * delta[i];
+            g.gamma = n * gamma[i];
+            g.vega = n * vega_bin(s, k, sigma, t);
+            g.theta = n * theta_bin(s, k, sigma, t);
         }
+        self.aggregate();
+    }
 
-        self.snapshot = PortfolioGreeks {
-            delta: total_delta,
-            gamma: total_gamma,
-            n_positions: self.positions.len() as u32,
-        };
+    /// Drop instruments expired by `now_ms`: they have settled and carry no Greeks.
+    pub fn expire(&mut self, now_ms: i64) {
+        let before = self.instruments.len();
+        self.instruments.retain(|(g, _)| g.end_ms > now_ms);
+        if self.instruments.len() != before {
+            self.aggregate();
+        }
+    }
+
+    /// Per-instrument Greeks as of the last recompute.
+    pub fn instruments(&self) -> impl Iterator<Item = &InstrumentGreeks> {
+        self.instruments.iter().map(|(g, _)| g)
+    }
+
+    fn aggregate(&mut self) {
+        let mut p = PortfolioGreeks::default();
+        for (g, n) in &self.instruments {
+            p.delta += g.delta;
+            p.gamma += g.gamma;
+            p.vega += g.vega;
+            p.theta += g.theta;
+            p.n_positions += n;
+        }
+        p.dollar_delta = p.delta * self.spot;
+        self.snapshot = p;
     }
 
-    /// Reset at market end.
+    /// Drop every instrument.
     pub fn reset(&mut self) {
-        self.positions.clear();
+        self.instruments.clear();
         self.snapshot = PortfolioGreeks::default();
     }
 }
@@ -111,8 +291,9 @@ pub struct StrategyRiskManager {
     limits: HashMap<&'static str, StrategyLimits>,
     state: HashMap<&'static str, StrategyRiskState>,
 
-    // Portfolio-level
-    pub total_exposure: f64,
+    // Portfolio-level (`total_exposure()` = pending + filled)
+    pub pending_exposure: f64,
+    pub filled_exposure: f64,
     max_total_exposure_frac: f64,
     pub daily_pnl: f64,
     daily_loss_halt_frac: f64,
@@ -124,70 +305,36 @@ pub struct StrategyRiskManager {
     pub greeks: GreeksTracker,
     max_portfolio_delta: f64,
     max_portfolio_gamma_neg: f64,
+    max_dollar_delta: f64,
+
+    /// Pre-trade gates, in order (`RISK_RULES`).
+    rules: Vec<Box<dyn RiskRule>>,
+}
+
+/// Order type, post-only flag and expiry a signal is sent with:
+/// - lp_extreme (is_passive), market-making quotes: GTC post_only
+/// - convexity_fade, strike_misalign (use_bid): GTD at bid, post_only, 10s TTL
+/// - latency_arb, flow_momentum, pin_risk, pair legs: FOK (race / closing seconds / no resting leg risk)
+/// - certainty_capture, cross_timeframe (others): GTD at ask, 10s TTL
+pub fn order_params(signal: &Signal, now_ms: i64) -> (OrderType, bool, Option<i64>) {
+    if signal.is_passive || signal.quote {
+        (OrderType::GTC, true, None)
+    } else if signal.use_bid {
+        (OrderType::GTD, true, Some(now_ms + 10_000))
+    } else if signal.pair || matches!(signal.strategy, "latency_arb" | "flow_momentum" | "pin_risk") {
+        (OrderType::FOK, false, None)
+    } else {
+        (OrderType::GTD, false, Some(now_ms + 10_000))
+    }
 }
 
 impl StrategyRiskManager {
     pub fn new(config: &Config) -> Self {
-        let mut limits = HashMap::new();
-
-        // Cooldowns tuned for 300s (5-min) markets.
-        // Target: 4-6 total orders per market. Each strategy gets 1-2 shots.
-        // Portfolio cap (15% = $150) binds before individual caps sum.
-
-        limits.insert(
-            "latency_arb",
-            StrategyLimits {
-                max_per_trade_frac: 0.02,   // $20 per trade
-                max_total_frac: 0.04,       // $40 total (2 orders)
-                cooldown_ms: 60_000,        // 60s between orders
-                max_orders_per_market: 2,
-            },
-        );
-        limits.insert(
-            "certainty_capture",
-            StrategyLimits {
-                max_per_trade_frac: 0.03,   // $30 per trade
-                max_total_frac: 0.03,       // $30 total (1 order)
-                cooldown_ms: 120_000,       // 120s — fires once, late in market
-                max_orders_per_market: 1,
-            },
-        );
-        limits.insert(
-            "convexity_fade",
-            StrategyLimits {
-                max_per_trade_frac: 0.01,   // $10 per trade
-                max_total_frac: 0.02,       // $20 total (2 orders)
-                cooldown_ms: 60_000,        // 60s between orders
-                max_orders_per_market: 2,
-            },
-        );
-        limits.insert(
-            "cross_timeframe",
-            StrategyLimits {
-                max_per_trade_frac: 0.005,
-                max_total_frac: 0.02,
-                cooldown_ms: 120_000,
-                max_orders_per_market: 1,
-            },
-        );
-        limits.insert(
-            "strike_misalign",
-            StrategyLimits {
-                max_per_trade_frac: 0.02,   // $20 per trade
-                max_total_frac: 0.04,       // $40 total (2 orders)
-                cooldown_ms: 30_000,        // 30s — allows re-entry if edge persists
-                max_orders_per_market: 2,
-            },
-        );
-        limits.insert(
-            "lp_extreme",
-            StrategyLimits {
-                max_per_trade_frac: 0.02,   // $20 per trade
-                max_total_frac: 0.02,       // $20 total (1 order)
-                cooldown_ms: 120_000,       // 120s — one tail risk shot
-                max_orders_per_market: 1,
-            },
-        );
+        // Default limits come from the strategy registry; unregistered names are blocked.
+        let limits: HashMap<&'static str, StrategyLimits> = registry::STRATEGIES
+            .iter()
+            .map(|spec| (spec.name, spec.limits.clone()))
+            .collect();
 
         let mut state = HashMap::new();
         for &name in limits.keys() {
@@ -198,7 +345,8 @@ impl StrategyRiskManager {
             bankroll: config.bankroll,
             limits,
             state,
-            total_exposure: 0.0,
+            pending_exposure: 0.0,
+            filled_exposure: 0.0,
             max_total_exposure_frac: config.max_total_exposure_frac,
             daily_pnl: 0.0,
             daily_loss_halt_frac: config.daily_loss_halt_frac,
@@ -208,6 +356,8 @@ impl StrategyRiskManager {
             greeks: GreeksTracker::new(),
             max_portfolio_delta: config.max_portfolio_delta,
             max_portfolio_gamma_neg: config.max_portfolio_gamma_neg,
+            max_dollar_delta: config.max_dollar_delta,
+            rules: risk_rules::build_chain(&config.risk_rules),
         }
     }
 
@@ -219,154 +369,300 @@ impl StrategyRiskManager {
         order_id: u64,
         now_ms: i64,
     ) -> Option<Order> {
-        // 1. Portfolio halt check
-        if now_ms < self.halted_until_ms {
-            return None;
-        }
+        self.decide(signal, state, order_id, now_ms).order()
+    }
 
-        // 2. Kill switch: daily loss
-        if self.daily_pnl < self.daily_loss_halt_frac * self.bankroll {
-            return None;
-        }
+    /// Run a signal through the risk rule chain: the order it gets, or the
+    /// rule that rejected it and the numbers that failed.
+    pub fn decide(
+        &self,
+        signal: &Signal,
+        state: &MarketState,
+        order_id: u64,
+        now_ms: i64,
+    ) -> RiskDecision {
+        // Unregistered strategies have no limits to check against.
+        let (Some(limits), Some(strat_state)) =
+            (self.limits.get(signal.strategy), self.state.get(signal.strategy))
+        else {
+            return RiskDecision::Rejected(Rejection { rule: "unknown_strategy", value: 0.0, limit: 0.0 });
+        };
 
-        // 3. Kill switch: weekly loss
-        if self.weekly_pnl < self.weekly_loss_halt_frac * self.bankroll {
-            return None;
-        }
+        let ctx = RuleContext {
+            signal,
+            state,
+            now_ms,
+            bankroll: self.bankroll,
+            limits,
+            strategy: StrategyView {
+                exposure: strat_state.exposure(),
+                orders_this_market: strat_state.orders_this_market,
+                last_order_ms: strat_state.last_order_ms,
+                stopped: strat_state.stopped,
+            },
+            portfolio: self.portfolio_view(),
+        };
+        let size = match risk_rules::run(&self.rules, &ctx, signal.size_frac * self.bankroll) {
+            Ok(size) => size,
+            Err(rejection) => return RiskDecision::Rejected(rejection),
+        };
 
-        // 4. Kill switch: stale feeds
-        if state.is_stale(now_ms) {
-            return None;
-        }
+        let (order_type, post_only, expiration_ms) = order_params(signal, now_ms);
 
-        // 5. Portfolio-level exposure check
-        let max_portfolio = self.max_total_exposure_frac * self.bankroll;
-        if self.total_exposure >= max_portfolio {
-            return None;
+        RiskDecision::Approved(Order {
+            id: order_id,
+            side: signal.side,
+            price: signal.market_price,
+            size,
+            strategy: signal.strategy,
+            signal_edge: signal.edge,
+            is_passive: signal.is_passive,
+            created_at: Instant::now(),
+            order_type,
+            post_only,
+            expiration_ms,
+            token_id: String::new(), // set by LiveSink::on_order from MarketInfo
+            action: signal.action,
+            instrument: signal.instrument,
+        })
+    }
+
+    /// Portfolio-level inputs to the rule chain, limits in USDC.
+    fn portfolio_view(&self) -> PortfolioView {
+        PortfolioView {
+            exposure: self.total_exposure(),
+            max_exposure: self.max_total_exposure_frac * self.bankroll,
+            daily_pnl: self.daily_pnl,
+            daily_loss_halt: self.daily_loss_halt_frac * self.bankroll,
+            weekly_pnl: self.weekly_pnl,
+            weekly_loss_halt: self.weekly_loss_halt_frac * self.bankroll,
+            halted_until_ms: self.halted_until_ms,
+            greeks: self.greeks.snapshot,
+            max_delta: self.max_portfolio_delta,
+            max_gamma_neg: self.max_portfolio_gamma_neg,
+            max_dollar_delta: self.max_dollar_delta,
         }
+    }
 
-        // 5b. Portfolio delta limit (0.0 = disabled)
-        if self.max_portfolio_delta > 0.0
-            && self.greeks.snapshot.delta.abs() > self.max_portfolio_delta
-        {
+    /// Exit order for `shares` of a strategy's open inventory: FOK sell at the
+    /// signal's price (the bid), for `shares × bid` USDC so the gateway sells
+    /// exactly the tokens held. Exits reduce risk, so only the halt and
+    /// stale-feed gates apply.
+    pub fn check_exit(
+        &self,
+        signal: &Signal,
+        shares: f64,
+        state: &MarketState,
+        order_id: u64,
+        now_ms: i64,
+    ) -> Option<Order> {
+        if now_ms < self.halted_until_ms || state.is_stale(now_ms) {
             return None;
         }
-
-        // 5c. Portfolio negative gamma limit (0.0 = disabled)
-        if self.max_portfolio_gamma_neg > 0.0
-            && self.greeks.snapshot.gamma < -self.max_portfolio_gamma_neg
-        {
+        if shares <= 0.0 || signal.market_price <= 0.0 {
             return None;
         }
+        Some(Order {
+            id: order_id,
+            side: signal.side,
+            price: signal.market_price,
+            size: shares * signal.market_price,
+            strategy: signal.strategy,
+            signal_edge: signal.edge,
+            is_passive: false,
+            created_at: Instant::now(),
+            order_type: OrderType::FOK,
+            post_only: false,
+            expiration_ms: None,
+            token_id: String::new(),
+            action: OrderAction::Sell,
+            instrument: Instrument::Current,
+        })
+    }
 
-        // 6. Per-strategy checks
-        let limits = self.limits.get(signal.strategy)?;
-        let strat_state = self.state.get(signal.strategy)?;
+    /// Check both legs of a two-leg package (UP, DOWN) and size them to one share count.
+    /// The pair is approved whole or not at all: each leg passes `check_strategy`,
+    /// and a bought pair's combined notional must fit the room left in the
+    /// strategy's and the portfolio's exposure caps. Legs get IDs `order_id`, `order_id + 1`.
+    pub fn check_pair(
+        &self,
+        up: &Signal,
+        down: &Signal,
+        state: &MarketState,
+        order_id: u64,
+        now_ms: i64,
+    ) -> Option<(Order, Order)> {
+        self.decide_pair(up, down, state, order_id, now_ms).ok()
+    }
 
-        // Per-strategy cooldown
-        if strat_state.last_order_ms > 0
-            && now_ms - strat_state.last_order_ms < limits.cooldown_ms
-        {
-            return None;
+    /// `check_pair` with the reason on rejection: the first leg's rule, or
+    /// `pair_size` when the legs fit on their own but not at one share count.
+    pub fn decide_pair(
+        &self,
+        up: &Signal,
+        down: &Signal,
+        state: &MarketState,
+        order_id: u64,
+        now_ms: i64,
+    ) -> Result<(Order, Order), Rejection> {
+        let decide = |sig: &Signal, id: u64| match self.decide(sig, state, id, now_ms) {
+            RiskDecision::Approved(order) => Ok(order),
+            RiskDecision::Rejected(rejection) => Err(rejection),
+        };
+        let mut up_order = decide(up, order_id)?;
+        let mut down_order = decide(down, order_id + 1)?;
+
+        let mut shares = (up_order.size / up_order.price).min(down_order.size / down_order.price);
+        if up_order.action == OrderAction::Buy {
+            // Both legs passed `decide`, so the strategy is registered.
+            let limits = &self.limits[up.strategy];
+            let strat_exposure = self.state[up.strategy].exposure();
+            let room = (limits.max_total_frac * self.bankroll - strat_exposure)
+                .min(self.max_total_exposure_frac * self.bankroll - self.total_exposure());
+            shares = shares.min(room / (up_order.price + down_order.price));
         }
+        let shares = shares.floor();
+        up_order.size = shares * up_order.price;
+        down_order.size = shares * down_order.price;
 
-        // Per-strategy max orders
-        if strat_state.orders_this_market >= limits.max_orders_per_market {
-            return None;
+        let smaller = up_order.size.min(down_order.size);
+        if smaller < MIN_ORDER_USDC {
+            return Err(Rejection { rule: "pair_size", value: smaller, limit: MIN_ORDER_USDC });
         }
+        Ok((up_order, down_order))
+    }
 
-        // Per-strategy exposure limit
-        let max_strat_exposure = limits.max_total_frac * self.bankroll;
-        if strat_state.exposure >= max_strat_exposure {
-            return None;
+    /// Block a strategy's new buys for the rest of the market (sells still pass).
+    pub fn stop_strategy(&mut self, strategy: &str) {
+        if let Some(s) = self.state.get_mut(strategy) {
+            s.stopped = true;
         }
+    }
 
-        // 7. Compute size
-        let kelly_size = signal.size_frac * self.bankroll;
-        let per_trade_cap = limits.max_per_trade_frac * self.bankroll;
-        let strat_room = max_strat_exposure - strat_state.exposure;
-        let portfolio_room = max_portfolio - self.total_exposure;
-
-        let size = kelly_size
-            .min(per_trade_cap)
-            .min(strat_room)
-            .min(portfolio_room);
-
-        if size < 1.0 {
-            return None;
+    /// Stop every strategy (market-level mark-to-market rule).
+    pub fn stop_all(&mut self) {
+        for s in self.state.values_mut() {
+            s.stopped = true;
         }
+    }
 
-        // Determine order type and execution parameters:
-        // - lp_extreme (is_passive): GTC post_only (unchanged)
-        // - convexity_fade, strike_misalign (use_bid): GTD at bid, post_only, 10s TTL
-        // - latency_arb: FOK (latency race, needs instant fill-or-kill)
-        // - certainty_capture, cross_timeframe (others): GTD at ask, 10s TTL
-        let (order_type, post_only, expiration_ms) = if signal.is_passive {
-            (OrderType::GTC, true, None)
-        } else if signal.use_bid {
-            (OrderType::GTD, true, Some(now_ms + 10_000))
-        } else if signal.strategy == "latency_arb" {
-            (OrderType::FOK, false, None)
-        } else {
-            (OrderType::GTD, false, Some(now_ms + 10_000))
-        };
+    pub fn is_stopped(&self, strategy: &str) -> bool {
+        self.state.get(strategy).is_some_and(|s| s.stopped)
+    }
 
-        Some(Order {
-            id: order_id,
-            side: signal.side,
-            price: signal.market_price,
-            size,
-            strategy: signal.strategy,
-            signal_edge: signal.edge,
-            is_passive: signal.is_passive,
-            created_at: Instant::now(),
-            order_type,
-            post_only,
-            expiration_ms,
-            token_id: String::new(), // set by LiveSink::on_order from MarketInfo
+    /// Outcome tokens a strategy holds on `side` in the current market.
+    pub fn open_shares(&self, strategy: &str, side: Side) -> f64 {
+        self.state.get(strategy).map_or(0.0, |s| match side {
+            Side::Up => s.inventory.up.shares,
+            Side::Down => s.inventory.down.shares,
         })
     }
 
+    /// Portfolio exposure the caps bind: pending orders plus filled inventory.
+    #[inline]
+    pub fn total_exposure(&self) -> f64 {
+        self.pending_exposure + self.filled_exposure
+    }
+
+    /// Reserve a sent order's `size` as pending exposure.
     pub fn on_order_sent(&mut self, strategy: &'static str, now_ms: i64, size: f64) {
         if let Some(s) = self.state.get_mut(strategy) {
             s.last_order_ms = now_ms;
             s.orders_this_market += 1;
-            s.exposure += size;
+            s.pending += size;
+        }
+        self.pending_exposure += size;
+    }
+
+    /// Release the pending exposure of an order's unfilled `size` once it can no
+    /// longer fill: cancelled, rejected, unmatched, timed out or expired.
+    /// The order still counts toward the strategy's per-market order budget.
+    pub fn release_pending(&mut self, strategy: &str, size: f64) {
+        if let Some(s) = self.state.get_mut(strategy) {
+            s.pending = (s.pending - size).max(0.0);
         }
-        self.total_exposure += size;
+        self.pending_exposure = (self.pending_exposure - size).max(0.0);
     }
 
-    pub fn on_fill(&mut self, _strategy: &str, _ack: &OrderAck) {
-        // PnL is computed at settlement — not at fill time.
-        // Fills are tracked in runner.rs and settled with settle_market().
+    /// Book a current-market fill: a buy moves its size from pending to filled
+    /// exposure, a sell unwinds filled exposure and realises PnL at average cost.
+    pub fn on_fill(&mut self, fill: &Fill) {
+        let Some(s) = self.state.get_mut(fill.strategy) else { return };
+        let sold = s.inventory.on_fill(fill);
+        self.move_filled(fill.strategy, fill.size.max(0.0), sold);
     }
 
-    /// Settle PnL at market end. Called once per market with the known outcome.
+    /// Book a cross-market fill's exposure only: it trades another market's
+    /// token, so it is marked and settled by the `CrossLedger`, not here.
+    pub fn on_cross_fill(&mut self, fill: &Fill) {
+        let sold = (-fill.size).max(0.0);
+        self.move_filled(fill.strategy, fill.size.max(0.0), sold);
+    }
+
+    fn move_filled(&mut self, strategy: &str, bought: f64, sold: f64) {
+        let Some(s) = self.state.get_mut(strategy) else { return };
+        let from_pending = bought.min(s.pending);
+        let unwound = sold.min(s.filled);
+        s.pending -= from_pending;
+        s.filled += bought - unwound;
+        self.pending_exposure = (self.pending_exposure - from_pending).max(0.0);
+        self.filled_exposure = (self.filled_exposure + bought - unwound).max(0.0);
+    }
+
+    /// Exposure and PnL of every strategy with open orders, inventory or realised
+    /// PnL this market, sorted by name. Inventory is marked at the best bids and
+    /// at the model fair value.
+    pub fn mark_to_market(&self, state: &MarketState, now_ms: i64) -> Vec<(&'static str, ExposureSnapshot)> {
+        let fair_up = mark_fair_up(state, now_ms);
+        let mut out: Vec<(&'static str, ExposureSnapshot)> = self
+            .state
+            .iter()
+            .filter(|(_, s)| s.pending > 0.0 || s.filled > 0.0 || !s.inventory.is_flat() || s.inventory.realized != 0.0)
+            .map(|(&name, s)| {
+                (name, ExposureSnapshot {
+                    pending: s.pending,
+                    filled: s.filled,
+                    realized_pnl: s.inventory.realized,
+                    unrealized_pnl: s.inventory.unrealized(state.up_bid.max(0.0), state.down_bid.max(0.0)),
+                    unrealized_fair_pnl: s.inventory.unrealized(fair_up, 1.0 - fair_up),
+                })
+            })
+            .collect();
+        out.sort_by_key(|&(name, _)| name);
+        out
+    }
+
+    /// Settle PnL at market end, net of each fill's fee or rebate.
+    /// Called once per market with the known outcome.
     pub fn settle_market(&mut self, outcome: Side, fills: &[Fill]) {
         let mut market_pnl = 0.0;
         for fill in fills {
-            let pnl = if fill.side == outcome {
-                (1.0 - fill.price) * fill.size
-            } else {
-                -(fill.price * fill.size)
-            };
-            market_pnl += pnl;
+            market_pnl += fill.pnl(outcome);
         }
-        self.daily_pnl += market_pnl;
-        self.weekly_pnl += market_pnl;
+        self.book_pnl(market_pnl);
         // Reset per-market exposure for next market
-        self.total_exposure = 0.0;
-        self.greeks.reset();
+        self.pending_exposure = 0.0;
+        self.filled_exposure = 0.0;
+        // Greeks are per instrument: the caller expires this market's with
+        // `greeks.expire(end_ms)`, leaving any still-open instrument tracked.
         for s in self.state.values_mut() {
             *s = StrategyRiskState::new();
         }
     }
 
+    /// Add settled PnL to the daily and weekly kill-switch totals. Used directly
+    /// for cross-market fills, which settle on their own market's outcome.
+    pub fn book_pnl(&mut self, pnl: f64) {
+        self.daily_pnl += pnl;
+        self.weekly_pnl += pnl;
+    }
+
+    /// Halt new buys for `duration_ms` (never shortens a halt already running).
     pub fn trigger_halt(&mut self, now_ms: i64, duration_ms: i64) {
-        self.halted_until_ms = now_ms + duration_ms;
+        self.halted_until_ms = self.halted_until_ms.max(now_ms + duration_ms);
         eprintln!(
             "[RISK] HALT triggered until +{}ms (total_exp=${:.0}, daily_pnl=${:.2})",
-            duration_ms, self.total_exposure, self.daily_pnl
+            duration_ms, self.total_exposure(), self.daily_pnl
         );
     }
 }
@@ -374,7 +670,9 @@ impl StrategyRiskManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::math::pricing::delta_bin;
     use crate::strategies::test_helpers::*;
+    use crate::types::Instrument;
 
     fn make_signal(strategy: &'static str, edge: f64, price: f64, size_frac: f64) -> Signal {
         Signal {
@@ -387,6 +685,10 @@ mod tests {
             size_frac,
             is_passive: false,
             use_bid: false,
+            action: OrderAction::Buy,
+            quote: false,
+            pair: false,
+            instrument: Instrument::Current,
         }
     }
 
@@ -481,7 +783,7 @@ mod tests {
         let mut risk = StrategyRiskManager::new(&config);
         let (state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
         // max_total_exposure_frac = 0.15, bankroll = 1000 → cap = 150
-        risk.total_exposure = 150.0;
+        risk.filled_exposure = 150.0;
         let signal = make_signal("latency_arb", 0.05, 0.50, 0.01);
         assert!(risk.check_strategy(&signal, &state, 1, now).is_none());
     }
@@ -526,6 +828,7 @@ mod tests {
                 side: Side::Up,
                 price: 0.60,
                 size: 10.0,
+                fee: 0.0,
             },
             Fill {
                 order_id: 2,
@@ -533,6 +836,7 @@ mod tests {
                 side: Side::Down,
                 price: 0.40,
                 size: 10.0,
+                fee: 0.0,
             },
         ];
 
@@ -544,7 +848,42 @@ mod tests {
         // Total = 0.0
         assert!((risk.daily_pnl - 0.0).abs() < 1e-10, "Daily PnL: {}", risk.daily_pnl);
         assert!((risk.weekly_pnl - 0.0).abs() < 1e-10, "Weekly PnL: {}", risk.weekly_pnl);
-        assert_eq!(risk.total_exposure, 0.0, "Exposure should be reset after settle");
+        assert_eq!(risk.total_exposure(), 0.0, "Exposure should be reset after settle");
+    }
+
+    /// Scenario: One taker fill and one maker fill, both winning at p = 0.50.
+    /// Expected: Settled PnL is gross minus the taker fee plus the maker rebate.
+    #[test]
+    fn test_settle_market_net_of_fees() {
+        use crate::math::fees::{FeeSchedule, Liquidity};
+        let config = make_config();
+        let mut risk = StrategyRiskManager::new(&config);
+        let fees = FeeSchedule::default();
+
+        let fills = vec![
+            Fill {
+                order_id: 1,
+                strategy: "latency_arb",
+                side: Side::Up,
+                price: 0.50,
+                size: 10.0,
+                fee: fees.fill_fee(0.50, 10.0, Liquidity::Taker),
+            },
+            Fill {
+                order_id: 2,
+                strategy: "convexity_fade",
+                side: Side::Up,
+                price: 0.50,
+                size: 10.0,
+                fee: fees.fill_fee(0.50, 10.0, Liquidity::Maker),
+            },
+        ];
+        risk.settle_market(Side::Up, &fills);
+
+        // Gross = 5 + 5. Taker pays 0.0078125 * 10, maker earns 20% of that back.
+        let taker_fee = 0.078125;
+        let expected = 10.0 - taker_fee + 0.2 * taker_fee;
+        assert!((risk.daily_pnl - expected).abs() < 1e-10, "Net PnL: {}", risk.daily_pnl);
     }
 
     // ── Max orders per market gate ──
@@ -622,11 +961,11 @@ mod tests {
         let config = make_config();
         let mut risk = StrategyRiskManager::new(&config);
         risk.on_order_sent("latency_arb", 0, 10.0);
-        assert_eq!(risk.total_exposure, 10.0);
+        assert_eq!(risk.total_exposure(), 10.0);
 
         risk.settle_market(Side::Up, &[]);
         assert_eq!(risk.daily_pnl, 0.0, "Empty fills → zero PnL");
-        assert_eq!(risk.total_exposure, 0.0, "Exposure should reset");
+        assert_eq!(risk.total_exposure(), 0.0, "Exposure should reset");
     }
 
     // ── Settle PnL accumulation across multiple markets ──
@@ -645,6 +984,7 @@ mod tests {
             side: Side::Up,
             price: 0.40,
             size: 10.0,
+            fee: 0.0,
         }];
         risk.settle_market(Side::Up, &fills1);
         // PnL = (1 - 0.40) * 10 = 6.0
@@ -657,6 +997,7 @@ mod tests {
             side: Side::Up,
             price: 0.60,
             size: 10.0,
+            fee: 0.0,
         }];
         risk.settle_market(Side::Down, &fills2);
         // PnL = -(0.60 * 10) = -6.0 → cumulative = 6.0 + (-6.0) = 0.0
@@ -673,8 +1014,8 @@ mod tests {
         let mut risk = StrategyRiskManager::new(&config);
 
         let fills = vec![
-            Fill { order_id: 1, strategy: "latency_arb", side: Side::Up, price: 0.30, size: 20.0 },
-            Fill { order_id: 2, strategy: "certainty_capture", side: Side::Up, price: 0.50, size: 10.0 },
+            Fill { order_id: 1, strategy: "latency_arb", side: Side::Up, price: 0.30, size: 20.0, fee: 0.0 },
+            Fill { order_id: 2, strategy: "certainty_capture", side: Side::Up, price: 0.50, size: 10.0, fee: 0.0 },
         ];
         risk.settle_market(Side::Up, &fills);
 
@@ -695,8 +1036,8 @@ mod tests {
         let mut risk = StrategyRiskManager::new(&config);
 
         let fills = vec![
-            Fill { order_id: 1, strategy: "latency_arb", side: Side::Up, price: 0.60, size: 15.0 },
-            Fill { order_id: 2, strategy: "certainty_capture", side: Side::Up, price: 0.40, size: 10.0 },
+            Fill { order_id: 1, strategy: "latency_arb", side: Side::Up, price: 0.60, size: 15.0, fee: 0.0 },
+            Fill { order_id: 2, strategy: "certainty_capture", side: Side::Up, price: 0.40, size: 10.0, fee: 0.0 },
         ];
         risk.settle_market(Side::Down, &fills);
 
@@ -717,7 +1058,7 @@ mod tests {
         let (state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
 
         // Portfolio cap = 0.15 * 1000 = 150. Set exposure to 145 → only $5 room
-        risk.total_exposure = 145.0;
+        risk.filled_exposure = 145.0;
 
         let signal = make_signal("latency_arb", 0.05, 0.50, 0.02); // wants 0.02 * 1000 = $20
         let order = risk.check_strategy(&signal, &state, 1, now);
@@ -774,10 +1115,273 @@ mod tests {
         let mut risk = StrategyRiskManager::new(&config);
 
         risk.on_order_sent("latency_arb", 1000, 15.0);
-        assert_eq!(risk.total_exposure, 15.0);
+        assert_eq!(risk.total_exposure(), 15.0);
 
         risk.on_order_sent("certainty_capture", 2000, 25.0);
-        assert_eq!(risk.total_exposure, 40.0);
+        assert_eq!(risk.total_exposure(), 40.0);
+    }
+
+    // ── Market-making quotes, sells and cancels ──
+
+    /// Scenario: market_maker quote while the portfolio sits at its $150 cap, as a buy and as a sell.
+    /// Expected: The buy is blocked (gate 5); the sell unwinds inventory and passes.
+    #[test]
+    fn test_sell_passes_at_portfolio_cap() {
+        let config = make_config();
+        let mut risk = StrategyRiskManager::new(&config);
+        let (state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        risk.filled_exposure = 150.0;
+
+        let mut signal = make_signal("market_maker", 0.01, 0.50, 0.005);
+        signal.quote = true;
+        assert!(risk.check_strategy(&signal, &state, 1, now).is_none());
+
+        signal.action = OrderAction::Sell;
+        let order = risk.check_strategy(&signal, &state, 2, now).expect("sell passes");
+        assert_eq!(order.action, OrderAction::Sell);
+        assert!((order.size - 5.0).abs() < 1e-9);
+    }
+
+    /// Scenario: market_maker quote signal.
+    /// Expected: Rests GTC post-only with no expiry.
+    #[test]
+    fn test_quote_rests_gtc_post_only() {
+        let config = make_config();
+        let risk = StrategyRiskManager::new(&config);
+        let (state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        let mut signal = make_signal("market_maker", 0.01, 0.48, 0.005);
+        signal.quote = true;
+        let order = risk.check_strategy(&signal, &state, 1, now).expect("approved");
+        assert_eq!(order.order_type, OrderType::GTC);
+        assert!(order.post_only);
+        assert!(order.expiration_ms.is_none());
+    }
+
+    /// Scenario: Aggressive signals from flow_momentum and certainty_capture at the ask.
+    /// Expected: flow_momentum crosses FOK like latency_arb (a momentum chase must not
+    ///           rest behind the move); certainty_capture rests GTD with a 10s TTL.
+    #[test]
+    fn test_momentum_taker_is_fok() {
+        let config = make_config();
+        let risk = StrategyRiskManager::new(&config);
+        let (state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        let fm = risk.check_strategy(&make_signal("flow_momentum", 0.05, 0.50, 0.01), &state, 1, now)
+            .expect("approved");
+        assert_eq!(fm.order_type, OrderType::FOK);
+        assert!(fm.expiration_ms.is_none());
+        let cc = risk.check_strategy(&make_signal("certainty_capture", 0.05, 0.50, 0.01), &state, 2, now)
+            .expect("approved");
+        assert_eq!(cc.order_type, OrderType::GTD);
+        assert_eq!(cc.expiration_ms, Some(now + 10_000));
+    }
+
+    /// Scenario: $5 quote sent, then cancelled unfilled.
+    /// Expected: Strategy and portfolio exposure return to zero; the order still
+    ///           counts toward the per-market order budget.
+    #[test]
+    fn test_cancel_releases_exposure() {
+        let config = make_config();
+        let mut risk = StrategyRiskManager::new(&config);
+        risk.on_order_sent("market_maker", 1000, 5.0);
+        risk.release_pending("market_maker", 5.0);
+        assert_eq!(risk.total_exposure(), 0.0);
+        assert_eq!(risk.state["market_maker"].exposure(), 0.0);
+        assert_eq!(risk.state["market_maker"].orders_this_market, 1);
+    }
+
+    // ── Fill-aware exposure ──
+
+    fn fill(strategy: &'static str, side: Side, price: f64, size: f64) -> Fill {
+        Fill { order_id: 1, strategy, side, price, size, fee: 0.0 }
+    }
+
+    /// Scenario: latency_arb sends $40 (its whole cap), the order is rejected.
+    /// Expected: The pending $40 is released and the next signal is approved.
+    #[test]
+    fn test_rejected_order_releases_cap() {
+        let config = make_config();
+        let mut risk = StrategyRiskManager::new(&config);
+        let (mut state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        risk.on_order_sent("latency_arb", now, 40.0);
+        risk.release_pending("latency_arb", 40.0);
+
+        let check_time = now + 61_000;
+        state.bn.binance_ts = check_time;
+        state.pm_last_ts = check_time;
+        let signal = make_signal("latency_arb", 0.05, 0.50, 0.01);
+        assert!(risk.check_strategy(&signal, &state, 2, check_time).is_some());
+    }
+
+    /// Scenario: $20 buy sent, $12 fills, the remaining $8 is cancelled.
+    /// Expected: $12 stays as filled exposure (still binding the caps), nothing pending.
+    #[test]
+    fn test_partial_fill_then_cancel() {
+        let config = make_config();
+        let mut risk = StrategyRiskManager::new(&config);
+        risk.on_order_sent("certainty_capture", 1000, 20.0);
+        assert_eq!((risk.pending_exposure, risk.filled_exposure), (20.0, 0.0));
+
+        risk.on_fill(&fill("certainty_capture", Side::Up, 0.60, 12.0));
+        assert_eq!((risk.pending_exposure, risk.filled_exposure), (8.0, 12.0));
+        assert_eq!(risk.total_exposure(), 20.0);
+
+        risk.release_pending("certainty_capture", 8.0);
+        assert_eq!((risk.pending_exposure, risk.filled_exposure), (0.0, 12.0));
+        assert_eq!(risk.state["certainty_capture"].exposure(), 12.0);
+    }
+
+    /// Scenario: market_maker buys 10 UP at 0.40 ($4) and 10 at 0.50 ($5), sells 10 at
+    ///           0.55 ($5.50); the UP bid is 0.48.
+    /// Expected: Realised = (0.55 − 0.45) × 10 = $1.00 at average cost; the other 10
+    ///           are marked at the bid: (0.48 − 0.45) × 10 = $0.30 unrealised; filled
+    ///           exposure drops to their $4.50 cost.
+    #[test]
+    fn test_unwind_realises_at_average_cost() {
+        let config = make_config();
+        let mut risk = StrategyRiskManager::new(&config);
+        let (mut state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        state.up_bid = 0.48;
+        risk.on_order_sent("market_maker", 1000, 9.0);
+        risk.on_fill(&fill("market_maker", Side::Up, 0.40, 4.0));
+        risk.on_fill(&fill("market_maker", Side::Up, 0.50, 5.0));
+        risk.on_fill(&Fill { fee: 0.02, ..fill("market_maker", Side::Up, 0.55, -5.5) });
+
+        let mtm = risk.mark_to_market(&state, now);
+        assert_eq!(mtm.len(), 1);
+        let (name, e) = mtm[0];
+        assert_eq!(name, "market_maker");
+        assert_eq!(e.pending, 0.0);
+        assert!((e.filled - 4.5).abs() < 1e-9, "filled: {}", e.filled);
+        assert!((e.realized_pnl - 0.98).abs() < 1e-9, "realised net of fee: {}", e.realized_pnl);
+        assert!((e.unrealized_pnl - 0.30).abs() < 1e-9, "unrealised: {}", e.unrealized_pnl);
+        assert!((risk.open_shares("market_maker", Side::Up) - 10.0).abs() < 1e-9);
+        assert!((risk.total_exposure() - 4.5).abs() < 1e-9);
+    }
+
+    /// Scenario: nested_arb's $10 cross-market buy fills.
+    /// Expected: Exposure moves to filled, but no inventory is marked against this
+    ///           market's book (the CrossLedger marks it).
+    #[test]
+    fn test_cross_fill_books_exposure_only() {
+        let config = make_config();
+        let mut risk = StrategyRiskManager::new(&config);
+        let (state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        risk.on_order_sent("nested_arb", 1000, 10.0);
+        risk.on_cross_fill(&fill("nested_arb", Side::Up, 0.30, 10.0));
+        let mtm = risk.mark_to_market(&state, now);
+        assert_eq!(mtm[0].1, ExposureSnapshot { pending: 0.0, filled: 10.0, realized_pnl: 0.0, unrealized_pnl: 0.0, unrealized_fair_pnl: 0.0 });
+    }
+
+    /// Scenario: Buys filled and a quote pending, then the market settles.
+    /// Expected: Pending, filled and inventory all reset; nothing left to mark.
+    #[test]
+    fn test_settle_clears_inventory() {
+        let config = make_config();
+        let mut risk = StrategyRiskManager::new(&config);
+        let (state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        risk.on_order_sent("market_maker", 1000, 15.0);
+        let f = fill("market_maker", Side::Up, 0.50, 10.0);
+        risk.on_fill(&f);
+        risk.settle_market(Side::Up, &[f]);
+        assert_eq!(risk.total_exposure(), 0.0);
+        assert!(risk.mark_to_market(&state, now).is_empty());
+    }
+
+    // ── Parity pairs ──
+
+    fn pair_leg(side: Side, price: f64, size_frac: f64) -> Signal {
+        let mut sig = make_signal("parity_arb", 0.02, price, size_frac);
+        sig.side = side;
+        sig.pair = true;
+        sig
+    }
+
+    /// Scenario: UP leg asks $100 at 0.45 (capped to $50 per trade = 111 shares),
+    ///           DOWN leg asks $30 at 0.50 (60 shares).
+    /// Expected: Both legs FOK, IDs 1 and 2, cut to the smaller 60 shares
+    ///           ($27 UP, $30 DOWN).
+    #[test]
+    fn test_pair_legs_sized_to_equal_shares() {
+        let config = make_config();
+        let risk = StrategyRiskManager::new(&config);
+        let (state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        let (up, down) = risk
+            .check_pair(&pair_leg(Side::Up, 0.45, 0.10), &pair_leg(Side::Down, 0.50, 0.03), &state, 1, now)
+            .expect("pair approved");
+
+        assert_eq!((up.id, down.id), (1, 2));
+        assert_eq!(up.order_type, OrderType::FOK);
+        assert_eq!(down.order_type, OrderType::FOK);
+        assert!((up.size - 27.0).abs() < 1e-9, "up = {}", up.size);
+        assert!((down.size - 30.0).abs() < 1e-9, "down = {}", down.size);
+    }
+
+    /// Scenario: Portfolio at $120 of its $150 cap; a pair at 0.45 + 0.50.
+    /// Expected: Combined notional fits the $30 left: floor(30 / 0.95) = 31 shares.
+    #[test]
+    fn test_pair_fits_remaining_room() {
+        let config = make_config();
+        let mut risk = StrategyRiskManager::new(&config);
+        let (state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        risk.filled_exposure = 120.0;
+        let (up, down) = risk
+            .check_pair(&pair_leg(Side::Up, 0.45, 0.05), &pair_leg(Side::Down, 0.50, 0.05), &state, 1, now)
+            .expect("pair approved");
+
+        assert!((up.size / 0.45 - 31.0).abs() < 1e-9);
+        assert!((down.size / 0.50 - 31.0).abs() < 1e-9);
+        assert!(up.size + down.size <= 30.0);
+    }
+
+    /// Scenario: Portfolio delta above MAX_PORTFOLIO_DELTA.
+    /// Expected: A directional buy is blocked (gate 5b); a delta-neutral pair passes.
+    #[test]
+    fn test_pair_skips_greeks_gates() {
+        let config = make_config();
+        let mut risk = StrategyRiskManager::new(&config);
+        let (state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        risk.max_portfolio_delta = 10.0;
+        risk.greeks.snapshot.delta = 50.0;
+
+        let mut directional = pair_leg(Side::Up, 0.45, 0.02);
+        directional.pair = false;
+        assert!(risk.check_strategy(&directional, &state, 1, now).is_none());
+        assert!(risk
+            .check_pair(&pair_leg(Side::Up, 0.45, 0.02), &pair_leg(Side::Down, 0.50, 0.02), &state, 2, now)
+            .is_some());
+    }
+
+    /// Scenario: DOWN leg too small to clear the $1 order floor.
+    /// Expected: The whole pair is rejected, not just the leg.
+    #[test]
+    fn test_pair_rejected_whole() {
+        let config = make_config();
+        let risk = StrategyRiskManager::new(&config);
+        let (state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        assert!(risk
+            .check_pair(&pair_leg(Side::Up, 0.45, 0.02), &pair_leg(Side::Down, 0.50, 0.0005), &state, 1, now)
+            .is_none());
+    }
+
+    /// Scenario: Portfolio delta above the limit; a buy routed to the 5m cross market,
+    ///           then the same buy with the delta back under the limit.
+    /// Expected: Blocked by the delta gate (Greeks cover every open market), then
+    ///           approved with the order carrying its instrument for routing.
+    #[test]
+    fn test_cross_market_leg_gated_by_portfolio_greeks() {
+        let config = make_config();
+        let mut risk = StrategyRiskManager::new(&config);
+        let (state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        risk.max_portfolio_delta = 10.0;
+        risk.greeks.snapshot.delta = 50.0;
+
+        let mut sig = make_signal("latency_arb", 0.05, 0.45, 0.02);
+        sig.instrument = Instrument::Cross(crate::config::Interval::M5);
+        assert!(risk.check_strategy(&sig, &state, 1, now).is_none());
+
+        risk.greeks.snapshot.delta = 5.0;
+        let order = risk.check_strategy(&sig, &state, 1, now).expect("under the delta limit");
+        assert_eq!(order.instrument, Instrument::Cross(crate::config::Interval::M5));
     }
 
     // ── Independent strategy limits ──
@@ -803,8 +1407,59 @@ mod tests {
             "Certainty capture should not be blocked by latency_arb cap");
     }
 
+    // ── Rule chain decisions ──
+
+    /// Scenario: latency_arb sends $40 (its cap); a second signal arrives at once,
+    ///           under the default chain and under `RISK_RULES=strategy_exposure,min_size`.
+    /// Expected: Default: rejected by `cooldown`. Reordered: rejected by
+    ///           `strategy_exposure` reporting $40 against the $40 cap.
+    #[test]
+    fn test_decide_reports_rejecting_rule() {
+        let mut config = make_config();
+        let (state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        let signal = make_signal("latency_arb", 0.05, 0.50, 0.01);
+
+        let mut risk = StrategyRiskManager::new(&config);
+        risk.on_order_sent("latency_arb", now, 40.0);
+        match risk.decide(&signal, &state, 2, now) {
+            RiskDecision::Rejected(r) => assert_eq!(r.rule, "cooldown"),
+            RiskDecision::Approved(_) => panic!("should be on cooldown"),
+        }
+
+        config.risk_rules = vec!["strategy_exposure".to_string(), "min_size".to_string()];
+        let mut risk = StrategyRiskManager::new(&config);
+        risk.on_order_sent("latency_arb", now, 40.0);
+        match risk.decide(&signal, &state, 2, now) {
+            RiskDecision::Rejected(r) => {
+                assert_eq!(r, Rejection { rule: "strategy_exposure", value: 40.0, limit: 40.0 })
+            }
+            RiskDecision::Approved(_) => panic!("should be at the strategy cap"),
+        }
+    }
+
+    /// Scenario: Signal from a strategy the registry does not know.
+    /// Expected: Rejected as `unknown_strategy`.
+    #[test]
+    fn test_decide_unknown_strategy() {
+        let risk = StrategyRiskManager::new(&make_config());
+        let (state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        let signal = make_signal("nonexistent", 0.05, 0.50, 0.01);
+        let rejection = match risk.decide(&signal, &state, 1, now) {
+            RiskDecision::Rejected(r) => r,
+            RiskDecision::Approved(_) => panic!("unknown strategy approved"),
+        };
+        assert_eq!(rejection.rule, "unknown_strategy");
+    }
+
     // ── Portfolio Greeks tests ──
 
+    const T0: i64 = 1_700_000_000_000;
+
+    /// Oracle with no delay, so each instrument's tau is its plain time to expiry.
+    fn no_delay() -> OracleBasis {
+        OracleBasis::new(0.0, 0.0, 1.0)
+    }
+
     /// Scenario: Fresh GreeksTracker with no fills.
     /// Expected: Snapshot is all zeros.
     #[test]
@@ -820,9 +1475,9 @@ mod tests {
     #[test]
     fn test_greeks_tracker_single_up_fill() {
         let mut tracker = GreeksTracker::new();
-        tracker.on_fill(Side::Up, 10.0);
+        tracker.on_fill(100_000.0, T0 + 300_000, Side::Up, 10.0);
         // ATM with sigma=0.001/s, tau=300s
-        tracker.recompute(100_000.0, 100_000.0, 0.001, 300.0);
+        tracker.recompute(100_000.0, 0.001, T0, &no_delay());
         assert!(tracker.snapshot.delta > 0.0,
             "UP fill should produce positive delta: {}", tracker.snapshot.delta);
         assert_eq!(tracker.snapshot.n_positions, 1);
@@ -833,9 +1488,9 @@ mod tests {
     #[test]
     fn test_greeks_tracker_opposing_fills_cancel() {
         let mut tracker = GreeksTracker::new();
-        tracker.on_fill(Side::Up, 10.0);
-        tracker.on_fill(Side::Down, 10.0);
-        tracker.recompute(100_000.0, 100_000.0, 0.001, 300.0);
+        tracker.on_fill(100_000.0, T0 + 300_000, Side::Up, 10.0);
+        tracker.on_fill(100_000.0, T0 + 300_000, Side::Down, 10.0);
+        tracker.recompute(100_000.0, 0.001, T0, &no_delay());
         assert!(tracker.snapshot.delta.abs() < 1e-12,
             "Opposing fills should cancel delta: {}", tracker.snapshot.delta);
         assert!(tracker.snapshot.gamma.abs() < 1e-12,
@@ -848,12 +1503,12 @@ mod tests {
     #[test]
     fn test_greeks_tracker_recompute_varies_with_s() {
         let mut tracker = GreeksTracker::new();
-        tracker.on_fill(Side::Up, 10.0);
+        tracker.on_fill(100_000.0, T0 + 300_000, Side::Up, 10.0);
 
-        tracker.recompute(100_000.0, 100_000.0, 0.001, 300.0);
+        tracker.recompute(100_000.0, 0.001, T0, &no_delay());
         let delta_at_atm = tracker.snapshot.delta;
 
-        tracker.recompute(100_500.0, 100_000.0, 0.001, 300.0);
+        tracker.recompute(100_500.0, 0.001, T0, &no_delay());
         let delta_itm = tracker.snapshot.delta;
 
         assert!((delta_at_atm - delta_itm).abs() > 1e-10,
@@ -861,18 +1516,70 @@ mod tests {
     }
 
     /// Scenario: Add a fill, recompute, then reset.
-    /// Expected: After reset, snapshot is all zeros and positions vec is empty.
+    /// Expected: After reset, snapshot is all zeros and no instrument is tracked.
     #[test]
     fn test_greeks_tracker_reset_clears() {
         let mut tracker = GreeksTracker::new();
-        tracker.on_fill(Side::Up, 10.0);
-        tracker.recompute(100_000.0, 100_000.0, 0.001, 300.0);
+        tracker.on_fill(100_000.0, T0 + 300_000, Side::Up, 10.0);
+        tracker.recompute(100_000.0, 0.001, T0, &no_delay());
         assert!(tracker.snapshot.delta != 0.0, "Should have nonzero delta before reset");
 
         tracker.reset();
         assert_eq!(tracker.snapshot.delta, 0.0);
         assert_eq!(tracker.snapshot.gamma, 0.0);
         assert_eq!(tracker.snapshot.n_positions, 0);
+        assert_eq!(tracker.instruments().count(), 0);
+    }
+
+    /// Scenario: UP $10 on a 5m market (K=100000) and UP $10 on a 15m market
+    ///           (K=99800), recomputed at S=100000.
+    /// Expected: Two instruments, each priced at its own strike and expiry; the
+    ///           portfolio is their sum, and dollar delta = delta × S.
+    #[test]
+    fn test_greeks_tracker_aggregates_instruments() {
+        let mut tracker = GreeksTracker::new();
+        tracker.on_fill(100_000.0, T0 + 300_000, Side::Up, 10.0);
+        tracker.on_fill(99_800.0, T0 + 900_000, Side::Up, 10.0);
+        tracker.recompute(100_000.0, 0.001, T0, &no_delay());
+
+        let by_strike: Vec<InstrumentGreeks> = tracker.instruments().copied().collect();
+        assert_eq!(by_strike.len(), 2);
+        let expect_5m = 10.0 * delta_bin(100_000.0, 100_000.0, 0.001, 300.0);
+        let expect_15m = 10.0 * delta_bin(100_000.0, 99_800.0, 0.001, 900.0);
+        assert!((by_strike[0].delta - expect_5m).abs() < 1e-12);
+        assert!((by_strike[1].delta - expect_15m).abs() < 1e-12);
+        assert!((by_strike[1].vega - 10.0 * vega_bin(100_000.0, 99_800.0, 0.001, 900.0)).abs() < 1e-12);
+        assert!(by_strike[1].theta > 0.0, "ITM binary gains with time: {}", by_strike[1].theta);
+
+        let p = tracker.snapshot;
+        assert!((p.delta - (expect_5m + expect_15m)).abs() < 1e-12);
+        assert!((p.dollar_delta - p.delta * 100_000.0).abs() < 1e-6);
+        assert_eq!(p.n_positions, 2);
+    }
+
+    /// Scenario: Positions on a 5m and a 15m market; the 5m market expires and settles.
+    /// Expected: settle_market + expire drop only the 5m instrument; the 15m
+    ///           position's Greeks carry into the next market.
+    #[test]
+    fn test_settle_keeps_open_instruments() {
+        let config = make_config();
+        let mut risk = StrategyRiskManager::new(&config);
+        risk.greeks.on_fill(100_000.0, T0 + 300_000, Side::Up, 10.0);
+        risk.greeks.on_fill(99_800.0, T0 + 900_000, Side::Down, 10.0);
+        risk.greeks.recompute(100_000.0, 0.001, T0, &no_delay());
+        assert_eq!(risk.greeks.snapshot.n_positions, 2);
+
+        risk.settle_market(Side::Up, &[]);
+        risk.greeks.expire(T0 + 300_000);
+        assert_eq!(risk.greeks.snapshot.n_positions, 1, "15m position still open");
+        let open: Vec<_> = risk.greeks.instruments().collect();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].end_ms, T0 + 900_000);
+        assert!(risk.greeks.snapshot.delta < 0.0, "short the 15m binary: {}", risk.greeks.snapshot.delta);
+
+        risk.greeks.recompute(100_000.0, 0.001, T0 + 900_000, &no_delay());
+        assert_eq!(risk.greeks.snapshot.n_positions, 0);
+        assert_eq!(risk.greeks.snapshot.delta, 0.0);
     }
 
     /// Scenario: max_portfolio_delta set to a tiny value; delta pushed past it via greeks.recompute.
@@ -885,8 +1592,8 @@ mod tests {
         let (state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
 
         // Simulate a fill that pushes delta past the limit
-        risk.greeks.on_fill(Side::Up, 100.0);
-        risk.greeks.recompute(95_500.0, 95_000.0, 0.001, 120.0);
+        risk.greeks.on_fill(state.info.strike, state.info.end_ms, Side::Up, 100.0);
+        risk.greeks.recompute(95_500.0, 0.001, now, &state.oracle);
         assert!(risk.greeks.snapshot.delta.abs() > 0.0001,
             "Delta should exceed limit: {}", risk.greeks.snapshot.delta);
 
@@ -895,6 +1602,31 @@ mod tests {
             "Should be blocked by portfolio delta limit");
     }
 
+    /// Scenario: MAX_DOLLAR_DELTA=$50; $100 UP near the strike gives |delta × S| above it.
+    /// Expected: Rejected by `portfolio_dollar_delta` reporting the dollar delta and the $50 limit.
+    #[test]
+    fn test_dollar_delta_limit_blocks_order() {
+        let mut config = make_config();
+        config.max_dollar_delta = 50.0;
+        let mut risk = StrategyRiskManager::new(&config);
+        let (state, now) = make_state(95_000.0, 95_100.0, 0.001, 120.0, 0.50, 0.50);
+
+        risk.greeks.on_fill(state.info.strike, state.info.end_ms, Side::Up, 100.0);
+        risk.greeks.recompute(95_100.0, 0.001, now, &state.oracle);
+        let dollar_delta = risk.greeks.snapshot.dollar_delta;
+        assert!(dollar_delta > 50.0, "dollar delta: {}", dollar_delta);
+
+        let signal = make_signal("latency_arb", 0.05, 0.50, 0.01);
+        match risk.decide(&signal, &state, 1, now) {
+            RiskDecision::Rejected(r) => {
+                assert_eq!(r.rule, "portfolio_dollar_delta");
+                assert!((r.value - dollar_delta).abs() < 1e-9);
+                assert_eq!(r.limit, 50.0);
+            }
+            RiskDecision::Approved(_) => panic!("should be over the dollar-delta limit"),
+        }
+    }
+
     /// Scenario: max_portfolio_gamma_neg set to a small value; gamma made sufficiently negative.
     /// Expected: check_strategy returns None (gate 5c blocks).
     #[test]
@@ -907,8 +1639,8 @@ mod tests {
         // Deep ITM: gamma_bin is negative. UP fill with positive sign * negative gamma = negative.
         // Actually, for S > K (ITM), gamma_bin is negative.
         // UP fill: sign=+1, so total_gamma = +1 * size * gamma_bin (negative) = negative.
-        risk.greeks.on_fill(Side::Up, 100.0);
-        risk.greeks.recompute(100_000.0, 95_000.0, 0.001, 120.0);
+        risk.greeks.on_fill(state.info.strike, state.info.end_ms, Side::Up, 100.0);
+        risk.greeks.recompute(100_000.0, 0.001, now, &state.oracle);
         assert!(risk.greeks.snapshot.gamma < 0.0,
             "ITM UP fill should produce negative portfolio gamma: {}", risk.greeks.snapshot.gamma);
 
@@ -926,8 +1658,8 @@ mod tests {
         let (state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
 
         // Add a fill with nonzero Greeks
-        risk.greeks.on_fill(Side::Up, 100.0);
-        risk.greeks.recompute(95_500.0, 95_000.0, 0.001, 120.0);
+        risk.greeks.on_fill(state.info.strike, state.info.end_ms, Side::Up, 100.0);
+        risk.greeks.recompute(95_500.0, 0.001, now, &state.oracle);
         assert!(risk.greeks.snapshot.delta.abs() > 0.0, "Should have nonzero delta");
 
         // Default limits are 0.0 = disabled, so order should pass
@@ -935,21 +1667,4 @@ mod tests {
         assert!(risk.check_strategy(&signal, &state, 1, now).is_some(),
             "Order should pass when Greeks limits are disabled (0.0)");
     }
-
-    /// Scenario: settle_market resets Greeks tracker.
-    /// Expected: After settlement, greeks snapshot is zeroed out.
-    #[test]
-    fn test_settle_resets_greeks() {
-        let config = make_config();
-        let mut risk = StrategyRiskManager::new(&config);
-
-        risk.greeks.on_fill(Side::Up, 10.0);
-        risk.greeks.recompute(100_000.0, 100_000.0, 0.001, 300.0);
-        assert!(risk.greeks.snapshot.n_positions > 0);
-
-        risk.settle_market(Side::Up, &[]);
-        assert_eq!(risk.greeks.snapshot.n_positions, 0, "Greeks should reset after settle");
-        assert_eq!(risk.greeks.snapshot.delta, 0.0);
-        assert_eq!(risk.greeks.snapshot.gamma, 0.0);
-    }
 }
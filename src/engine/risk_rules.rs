@@ -0,0 +1,676 @@
+//! Pre-trade risk rule chain.
+//!
+//! `StrategyRiskManager::decide` runs a signal through an ordered list of
+//! [`RiskRule`]s. Each rule sees a [`RuleContext`] (the signal, market state and
+//! a snapshot of the portfolio and strategy risk state) and either rejects the
+//! signal or caps the USDC size its order will get; the order starts at the
+//! signal's Kelly size. The first rejection ends the chain and is returned as
+//! a [`Rejection`] naming the rule and the numbers that failed it.
+//!
+//! The chain is [`DEFAULT_CHAIN`] unless `RISK_RULES` lists rule names, in
+//! which case only those run, in that order. Size caps must run before
+//! `min_size` for the floor to see the final size.
+
+use std::fmt;
+
+use crate::engine::execution::Execution;
+use crate::engine::risk::{mark_fair_up, order_params, PortfolioGreeks, StrategyLimits};
+use crate::engine::state::MarketState;
+use crate::types::{Instrument, Order, OrderAction, Side, Signal};
+
+/// Smallest order the CLOB accepts (USDC).
+pub const MIN_ORDER_USDC: f64 = 1.0;
+
+/// Feed age beyond which quotes are too old to trade on (ms).
+const STALE_MS: i64 = 1000;
+
+// ─── Decisions ──────────────────────────────────────────────────────────────
+
+/// Why a signal did not become an order: the rule, the value it measured and
+/// the limit that value failed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rejection {
+    pub rule: &'static str,
+    pub value: f64,
+    pub limit: f64,
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({:.4} vs limit {:.4})", self.rule, self.value, self.limit)
+    }
+}
+
+/// Outcome of running a signal through the chain.
+pub enum RiskDecision {
+    Approved(Order),
+    Rejected(Rejection),
+}
+
+impl RiskDecision {
+    pub fn order(self) -> Option<Order> {
+        match self {
+            RiskDecision::Approved(order) => Some(order),
+            RiskDecision::Rejected(_) => None,
+        }
+    }
+}
+
+#[inline]
+fn reject(rule: &'static str, value: f64, limit: f64) -> Result<(), Rejection> {
+    Err(Rejection { rule, value, limit })
+}
+
+// ─── Context ────────────────────────────────────────────────────────────────
+
+/// Portfolio-level risk state at decision time (USDC limits, not fractions).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PortfolioView {
+    pub exposure: f64,
+    pub max_exposure: f64,
+    pub daily_pnl: f64,
+    pub daily_loss_halt: f64,
+    pub weekly_pnl: f64,
+    pub weekly_loss_halt: f64,
+    pub halted_until_ms: i64,
+    pub greeks: PortfolioGreeks,
+    /// 0.0 = disabled.
+    pub max_delta: f64,
+    /// 0.0 = disabled.
+    pub max_gamma_neg: f64,
+    /// USD notional of the underlying; 0.0 = disabled.
+    pub max_dollar_delta: f64,
+}
+
+/// The signal's strategy's risk state at decision time.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StrategyView {
+    /// Pending plus filled.
+    pub exposure: f64,
+    pub orders_this_market: u32,
+    pub last_order_ms: i64,
+    /// Stopped by a mark-to-market rule.
+    pub stopped: bool,
+}
+
+/// Everything a rule may look at.
+pub struct RuleContext<'a> {
+    pub signal: &'a Signal,
+    pub state: &'a MarketState,
+    pub now_ms: i64,
+    pub bankroll: f64,
+    pub limits: &'a StrategyLimits,
+    pub strategy: StrategyView,
+    pub portfolio: PortfolioView,
+}
+
+impl<'a> RuleContext<'a> {
+    /// Sells unwind inventory: exposure and Greeks gates only bind buys.
+    #[inline]
+    pub fn is_buy(&self) -> bool {
+        self.signal.action == OrderAction::Buy
+    }
+
+    /// A parity pair is delta/gamma-neutral, so its legs skip the Greeks gates.
+    /// Cross-market buys are gated too: the Greeks cover every open instrument.
+    #[inline]
+    pub fn adds_greeks(&self) -> bool {
+        self.is_buy() && !self.signal.pair
+    }
+}
+
+// ─── Rules ──────────────────────────────────────────────────────────────────
+
+/// One gate of the pre-trade risk chain.
+pub trait RiskRule: Send + Sync {
+    /// Config name (`RISK_RULES`) and `Rejection::rule`.
+    fn name(&self) -> &'static str;
+
+    /// Reject the signal, or lower `size` (USDC) to what the rule allows.
+    fn check(&self, ctx: &RuleContext, size: &mut f64) -> Result<(), Rejection>;
+}
+
+/// Portfolio halt (`trigger_halt`) still running.
+pub struct Halt;
+
+impl RiskRule for Halt {
+    fn name(&self) -> &'static str {
+        "halt"
+    }
+
+    fn check(&self, ctx: &RuleContext, _size: &mut f64) -> Result<(), Rejection> {
+        let until = ctx.portfolio.halted_until_ms;
+        if ctx.now_ms < until {
+            return reject(self.name(), ctx.now_ms as f64, until as f64);
+        }
+        Ok(())
+    }
+}
+
+/// Kill switch: daily PnL below its halt level.
+pub struct DailyLoss;
+
+impl RiskRule for DailyLoss {
+    fn name(&self) -> &'static str {
+        "daily_loss"
+    }
+
+    fn check(&self, ctx: &RuleContext, _size: &mut f64) -> Result<(), Rejection> {
+        let p = &ctx.portfolio;
+        if p.daily_pnl < p.daily_loss_halt {
+            return reject(self.name(), p.daily_pnl, p.daily_loss_halt);
+        }
+        Ok(())
+    }
+}
+
+/// Kill switch: weekly PnL below its halt level.
+pub struct WeeklyLoss;
+
+impl RiskRule for WeeklyLoss {
+    fn name(&self) -> &'static str {
+        "weekly_loss"
+    }
+
+    fn check(&self, ctx: &RuleContext, _size: &mut f64) -> Result<(), Rejection> {
+        let p = &ctx.portfolio;
+        if p.weekly_pnl < p.weekly_loss_halt {
+            return reject(self.name(), p.weekly_pnl, p.weekly_loss_halt);
+        }
+        Ok(())
+    }
+}
+
+/// Kill switch: Binance or Polymarket feed older than 1s.
+pub struct StaleFeed;
+
+impl RiskRule for StaleFeed {
+    fn name(&self) -> &'static str {
+        "stale_feed"
+    }
+
+    fn check(&self, ctx: &RuleContext, _size: &mut f64) -> Result<(), Rejection> {
+        if ctx.state.is_stale(ctx.now_ms) {
+            let age = |ts: i64| if ts > 0 { ctx.now_ms - ts } else { 0 };
+            let oldest = age(ctx.state.bn.binance_ts).max(age(ctx.state.pm_last_ts));
+            return reject(self.name(), oldest as f64, STALE_MS as f64);
+        }
+        Ok(())
+    }
+}
+
+/// Portfolio exposure cap: blocks buys at the cap, caps them to the room left.
+pub struct PortfolioExposure;
+
+impl RiskRule for PortfolioExposure {
+    fn name(&self) -> &'static str {
+        "portfolio_exposure"
+    }
+
+    fn check(&self, ctx: &RuleContext, size: &mut f64) -> Result<(), Rejection> {
+        if !ctx.is_buy() {
+            return Ok(());
+        }
+        let p = &ctx.portfolio;
+        if p.exposure >= p.max_exposure {
+            return reject(self.name(), p.exposure, p.max_exposure);
+        }
+        *size = size.min(p.max_exposure - p.exposure);
+        Ok(())
+    }
+}
+
+/// Portfolio |delta| limit for Greeks-adding buys (0.0 = disabled).
+pub struct PortfolioDelta;
+
+impl RiskRule for PortfolioDelta {
+    fn name(&self) -> &'static str {
+        "portfolio_delta"
+    }
+
+    fn check(&self, ctx: &RuleContext, _size: &mut f64) -> Result<(), Rejection> {
+        let p = &ctx.portfolio;
+        if ctx.adds_greeks() && p.max_delta > 0.0 && p.greeks.delta.abs() > p.max_delta {
+            return reject(self.name(), p.greeks.delta.abs(), p.max_delta);
+        }
+        Ok(())
+    }
+}
+
+/// Portfolio |dollar delta| limit for Greeks-adding buys (0.0 = disabled).
+/// Unlike `portfolio_delta`, comparable across assets and price levels.
+pub struct PortfolioDollarDelta;
+
+impl RiskRule for PortfolioDollarDelta {
+    fn name(&self) -> &'static str {
+        "portfolio_dollar_delta"
+    }
+
+    fn check(&self, ctx: &RuleContext, _size: &mut f64) -> Result<(), Rejection> {
+        let p = &ctx.portfolio;
+        let dollar_delta = p.greeks.dollar_delta.abs();
+        if ctx.adds_greeks() && p.max_dollar_delta > 0.0 && dollar_delta > p.max_dollar_delta {
+            return reject(self.name(), dollar_delta, p.max_dollar_delta);
+        }
+        Ok(())
+    }
+}
+
+/// Portfolio negative-gamma limit for Greeks-adding buys (0.0 = disabled).
+pub struct PortfolioGamma;
+
+impl RiskRule for PortfolioGamma {
+    fn name(&self) -> &'static str {
+        "portfolio_gamma"
+    }
+
+    fn check(&self, ctx: &RuleContext, _size: &mut f64) -> Result<(), Rejection> {
+        let p = &ctx.portfolio;
+        if ctx.adds_greeks() && p.max_gamma_neg > 0.0 && p.greeks.gamma < -p.max_gamma_neg {
+            return reject(self.name(), p.greeks.gamma, -p.max_gamma_neg);
+        }
+        Ok(())
+    }
+}
+
+/// Per-strategy minimum gap between orders.
+pub struct Cooldown;
+
+impl RiskRule for Cooldown {
+    fn name(&self) -> &'static str {
+        "cooldown"
+    }
+
+    fn check(&self, ctx: &RuleContext, _size: &mut f64) -> Result<(), Rejection> {
+        let last = ctx.strategy.last_order_ms;
+        let since = ctx.now_ms - last;
+        if last > 0 && since < ctx.limits.cooldown_ms {
+            return reject(self.name(), since as f64, ctx.limits.cooldown_ms as f64);
+        }
+        Ok(())
+    }
+}
+
+/// Per-strategy mark-to-market stop: exits only.
+pub struct MtmStop;
+
+impl RiskRule for MtmStop {
+    fn name(&self) -> &'static str {
+        "mtm_stop"
+    }
+
+    fn check(&self, ctx: &RuleContext, _size: &mut f64) -> Result<(), Rejection> {
+        if ctx.is_buy() && ctx.strategy.stopped {
+            return reject(self.name(), 1.0, 0.0);
+        }
+        Ok(())
+    }
+}
+
+/// Per-strategy order budget for the market.
+pub struct MaxOrders;
+
+impl RiskRule for MaxOrders {
+    fn name(&self) -> &'static str {
+        "max_orders"
+    }
+
+    fn check(&self, ctx: &RuleContext, _size: &mut f64) -> Result<(), Rejection> {
+        let (n, max) = (ctx.strategy.orders_this_market, ctx.limits.max_orders_per_market);
+        if n >= max {
+            return reject(self.name(), n as f64, max as f64);
+        }
+        Ok(())
+    }
+}
+
+/// Per-strategy exposure cap: blocks buys at the cap, caps them to the room left.
+pub struct StrategyExposure;
+
+impl RiskRule for StrategyExposure {
+    fn name(&self) -> &'static str {
+        "strategy_exposure"
+    }
+
+    fn check(&self, ctx: &RuleContext, size: &mut f64) -> Result<(), Rejection> {
+        if !ctx.is_buy() {
+            return Ok(());
+        }
+        let max = ctx.limits.max_total_frac * ctx.bankroll;
+        if ctx.strategy.exposure >= max {
+            return reject(self.name(), ctx.strategy.exposure, max);
+        }
+        *size = size.min(max - ctx.strategy.exposure);
+        Ok(())
+    }
+}
+
+/// Per-strategy per-trade cap (buys and sells).
+pub struct PerTradeCap;
+
+impl RiskRule for PerTradeCap {
+    fn name(&self) -> &'static str {
+        "per_trade_cap"
+    }
+
+    fn check(&self, ctx: &RuleContext, size: &mut f64) -> Result<(), Rejection> {
+        *size = size.min(ctx.limits.max_per_trade_frac * ctx.bankroll);
+        Ok(())
+    }
+}
+
+/// Execution cost: a FOK is shrunk to the depth the book can give it at or
+/// better than its limit (it would otherwise walk past it and be killed); a
+/// post-only order less likely than `EXEC_MIN_FILL_PROB` to fill is dropped.
+/// Quotes, pairs and books not yet recorded pass.
+pub struct BookDepth;
+
+impl RiskRule for BookDepth {
+    fn name(&self) -> &'static str {
+        "book_depth"
+    }
+
+    fn check(&self, ctx: &RuleContext, size: &mut f64) -> Result<(), Rejection> {
+        if ctx.signal.quote || ctx.signal.pair {
+            return Ok(());
+        }
+        let (order_type, post_only, expiration_ms) = order_params(ctx.signal, ctx.now_ms);
+        let exec = Execution::of(order_type, post_only, expiration_ms, ctx.now_ms);
+        let model = &ctx.state.execution;
+        let Some(est) = model.estimate(ctx.state, ctx.signal, *size, exec, ctx.now_ms) else {
+            return Ok(());
+        };
+        match exec {
+            Execution::Taker { fok: true } => *size = size.min(est.depth),
+            Execution::Maker { .. } if est.fill_prob < model.min_fill_prob => {
+                return reject(self.name(), est.fill_prob, model.min_fill_prob);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Correlated portfolio VaR limit (`MAX_VAR_FRAC` × bankroll, 0.0 = disabled):
+/// rejects a current-market buy that would lift the Monte Carlo VaR of every
+/// open wallet position above the limit. Buys that lower VaR (the other side
+/// of a held market) pass even above it. Runs on the final size; pairs and
+/// cross-market buys are not gated.
+pub struct PortfolioVar;
+
+impl RiskRule for PortfolioVar {
+    fn name(&self) -> &'static str {
+        "portfolio_var"
+    }
+
+    fn check(&self, ctx: &RuleContext, size: &mut f64) -> Result<(), Rejection> {
+        let var = &ctx.state.var;
+        let sig = ctx.signal;
+        if !var.enabled() || !ctx.adds_greeks() || sig.instrument != Instrument::Current || sig.market_price <= 0.0 {
+            return Ok(());
+        }
+        let shares = *size / sig.market_price;
+        let (net_up, cash) = match sig.side {
+            Side::Up => (shares, -*size),
+            Side::Down => (-shares, shares - *size),
+        };
+        let Some(after) = var.var_with(mark_fair_up(ctx.state, ctx.now_ms), net_up, cash) else {
+            return Ok(());
+        };
+        let limit = var.max_var_frac * ctx.bankroll;
+        if after > limit && after > var.var {
+            return reject(self.name(), after, limit);
+        }
+        Ok(())
+    }
+}
+
+/// Order floor: the capped size must reach the CLOB minimum.
+pub struct MinSize;
+
+impl RiskRule for MinSize {
+    fn name(&self) -> &'static str {
+        "min_size"
+    }
+
+    fn check(&self, _ctx: &RuleContext, size: &mut f64) -> Result<(), Rejection> {
+        if *size < MIN_ORDER_USDC {
+            return reject(self.name(), *size, MIN_ORDER_USDC);
+        }
+        Ok(())
+    }
+}
+
+// ─── Chain ──────────────────────────────────────────────────────────────────
+
+/// Every rule, in the order the chain runs when `RISK_RULES` is unset.
+pub const DEFAULT_CHAIN: &[&str] = &[
+    "halt",
+    "daily_loss",
+    "weekly_loss",
+    "stale_feed",
+    "portfolio_exposure",
+    "portfolio_delta",
+    "portfolio_dollar_delta",
+    "portfolio_gamma",
+    "cooldown",
+    "mtm_stop",
+    "max_orders",
+    "strategy_exposure",
+    "per_trade_cap",
+    "book_depth",
+    "portfolio_var",
+    "min_size",
+];
+
+/// Construct a rule by its config name.
+pub fn build(name: &str) -> Option<Box<dyn RiskRule>> {
+    let rule: Box<dyn RiskRule> = match name {
+        "halt" => Box::new(Halt),
+        "daily_loss" => Box::new(DailyLoss),
+        "weekly_loss" => Box::new(WeeklyLoss),
+        "stale_feed" => Box::new(StaleFeed),
+        "portfolio_exposure" => Box::new(PortfolioExposure),
+        "portfolio_delta" => Box::new(PortfolioDelta),
+        "portfolio_dollar_delta" => Box::new(PortfolioDollarDelta),
+        "portfolio_gamma" => Box::new(PortfolioGamma),
+        "cooldown" => Box::new(Cooldown),
+        "mtm_stop" => Box::new(MtmStop),
+        "max_orders" => Box::new(MaxOrders),
+        "strategy_exposure" => Box::new(StrategyExposure),
+        "per_trade_cap" => Box::new(PerTradeCap),
+        "book_depth" => Box::new(BookDepth),
+        "portfolio_var" => Box::new(PortfolioVar),
+        "min_size" => Box::new(MinSize),
+        _ => return None,
+    };
+    Some(rule)
+}
+
+/// The configured chain: `names` in order (unknown names skipped), or
+/// [`DEFAULT_CHAIN`] when `names` is empty.
+pub fn build_chain(names: &[String]) -> Vec<Box<dyn RiskRule>> {
+    if names.is_empty() {
+        return DEFAULT_CHAIN.iter().filter_map(|n| build(n)).collect();
+    }
+    names.iter().filter_map(|n| build(n)).collect()
+}
+
+/// Names in `names` that are not rules (reported at startup).
+pub fn unknown(names: &[String]) -> Vec<&str> {
+    names.iter().map(String::as_str).filter(|n| build(n).is_none()).collect()
+}
+
+/// Run `chain` over `ctx`, starting from `size`. Returns the final size or
+/// the first rejection.
+pub fn run(chain: &[Box<dyn RiskRule>], ctx: &RuleContext, mut size: f64) -> Result<f64, Rejection> {
+    for rule in chain {
+        rule.check(ctx, &mut size)?;
+    }
+    Ok(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::test_helpers::*;
+    use crate::engine::var::{Correlation, VarPosition};
+    use crate::types::Side;
+
+    const LIMITS: StrategyLimits = StrategyLimits {
+        max_per_trade_frac: 0.02,
+        max_total_frac: 0.04,
+        cooldown_ms: 60_000,
+        max_orders_per_market: 2,
+    };
+
+    fn ctx<'a>(sig: &'a Signal, state: &'a MarketState, now_ms: i64) -> RuleContext<'a> {
+        RuleContext {
+            signal: sig,
+            state,
+            now_ms,
+            bankroll: 1000.0,
+            limits: &LIMITS,
+            strategy: StrategyView::default(),
+            portfolio: PortfolioView {
+                max_exposure: 150.0,
+                daily_loss_halt: -30.0,
+                weekly_loss_halt: -80.0,
+                ..PortfolioView::default()
+            },
+        }
+    }
+
+    // ── Rules ──
+
+    /// Scenario: Strategy ordered 10s ago with a 60s cooldown.
+    /// Expected: Rejected by `cooldown` with the elapsed time and the limit.
+    #[test]
+    fn test_cooldown_reports_numbers() {
+        let (state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        let sig = make_signal("latency_arb", Side::Up, 0.05, 0.8, 0.50);
+        let mut c = ctx(&sig, &state, now);
+        c.strategy.last_order_ms = now - 10_000;
+        let r = Cooldown.check(&c, &mut 20.0).unwrap_err();
+        assert_eq!(r, Rejection { rule: "cooldown", value: 10_000.0, limit: 60_000.0 });
+    }
+
+    /// Scenario: $30 of the $40 strategy cap used; a $20 buy and a $20 sell.
+    /// Expected: The buy is capped to the $10 left; the sell is not capped.
+    #[test]
+    fn test_strategy_exposure_caps_buys_only() {
+        let (state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        for (action, expected) in [(OrderAction::Buy, 10.0), (OrderAction::Sell, 20.0)] {
+            let sig = Signal { action, ..make_signal("latency_arb", Side::Up, 0.05, 0.8, 0.50) };
+            let mut c = ctx(&sig, &state, now);
+            c.strategy.exposure = 30.0;
+            let mut size = 20.0;
+            StrategyExposure.check(&c, &mut size).unwrap();
+            assert!((size - expected).abs() < 1e-9, "{:?}: {}", action, size);
+        }
+    }
+
+    /// Scenario: Daily PnL -$31 against a -$30 halt.
+    /// Expected: `daily_loss` rejects, reporting the PnL and the halt level.
+    #[test]
+    fn test_daily_loss_rejects() {
+        let (state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        let sig = make_signal("latency_arb", Side::Up, 0.05, 0.8, 0.50);
+        let mut c = ctx(&sig, &state, now);
+        c.portfolio.daily_pnl = -31.0;
+        assert_eq!(DailyLoss.check(&c, &mut 20.0).unwrap_err().limit, -30.0);
+    }
+
+    /// Scenario: 30 shares ask at the 0.50 limit, 100 more at 0.53; a $20 latency_arb FOK buy,
+    ///           then a GTD taker buy of the same size, then with no book at all.
+    /// Expected: The FOK shrinks to the $15 the limit can take; the GTD (remainder rests)
+    ///           and the book-less FOK keep $20.
+    #[test]
+    fn test_book_depth_shrinks_fok() {
+        let (mut state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        let sig = make_signal("latency_arb", Side::Up, 0.05, 0.8, 0.50);
+        let (fresh, _) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        let mut size = 20.0;
+        BookDepth.check(&ctx(&sig, &fresh, now), &mut size).unwrap();
+        assert_eq!(size, 20.0, "no book recorded");
+
+        inject_book(&mut state, Side::Up, vec![(0.48, 100.0)], vec![(0.50, 30.0), (0.53, 100.0)]);
+        let mut size = 20.0;
+        BookDepth.check(&ctx(&sig, &state, now), &mut size).unwrap();
+        assert!((size - 15.0).abs() < 1e-9, "size {}", size);
+
+        let gtd = Signal { strategy: "certainty_capture", ..sig };
+        let mut size = 20.0;
+        BookDepth.check(&ctx(&gtd, &state, now), &mut size).unwrap();
+        assert_eq!(size, 20.0);
+    }
+
+    /// Scenario: EXEC_MIN_FILL_PROB=0.5; a post-only bid joins 100 shares of queue with no
+    ///           flow observed.
+    /// Expected: Fill probability 0 → rejected by `book_depth`.
+    #[test]
+    fn test_book_depth_drops_unlikely_maker() {
+        let (mut state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        inject_book(&mut state, Side::Up, vec![(0.48, 100.0)], vec![(0.52, 100.0)]);
+        state.execution.min_fill_prob = 0.5;
+        let sig = Signal { strategy: "strike_misalign", use_bid: true, market_price: 0.48, ..make_signal("latency_arb", Side::Up, 0.05, 0.8, 0.50) };
+        let r = BookDepth.check(&ctx(&sig, &state, now), &mut 20.0).unwrap_err();
+        assert_eq!(r, Rejection { rule: "book_depth", value: 0.0, limit: 0.5 });
+    }
+
+    /// Scenario: MAX_VAR_FRAC=0.05 ($50); this market's UP held worth $40 (99% VaR $40).
+    ///           A $20 UP buy at 0.50, a $20 DOWN buy at 0.50, then the UP buy with the limit off.
+    /// Expected: UP lifts VaR to $60 → rejected; DOWN hedges it → passes; disabled → passes.
+    #[test]
+    fn test_portfolio_var_blocks_adding_risk() {
+        let (mut state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        let p_up = mark_fair_up(&state, now);
+        let held = VarPosition { asset: "btc".into(), end_ms: state.info.end_ms, net_up: 40.0 / p_up, p_up };
+        state.var.max_var_frac = 0.05;
+        state.var.rebuild(&Correlation::default(), &[held], "btc", state.info.end_ms, now);
+        assert!((state.var.var - 40.0).abs() < 1e-9, "base VaR {}", state.var.var);
+
+        let up = make_signal("latency_arb", Side::Up, 0.05, 0.8, 0.50);
+        let r = PortfolioVar.check(&ctx(&up, &state, now), &mut 20.0).unwrap_err();
+        assert_eq!((r.rule, r.limit), ("portfolio_var", 50.0));
+        assert!((r.value - 60.0).abs() < 1e-9);
+
+        let down = Signal { side: Side::Down, ..make_signal("latency_arb", Side::Up, 0.05, 0.8, 0.50) };
+        PortfolioVar.check(&ctx(&down, &state, now), &mut 20.0).unwrap();
+
+        state.var.max_var_frac = 0.0;
+        PortfolioVar.check(&ctx(&up, &state, now), &mut 20.0).unwrap();
+    }
+
+    // ── Chain ──
+
+    /// Scenario: A $20 buy through the default chain, then with `min_size` moved
+    ///           before the caps and the strategy $39.50 into its $40 cap.
+    /// Expected: Default: rejected by `min_size` ($0.50 left). Reordered: the floor
+    ///           sees the uncapped $20 and passes, so the order is $0.50.
+    #[test]
+    fn test_chain_order_matters() {
+        let (state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        let sig = make_signal("latency_arb", Side::Up, 0.05, 0.8, 0.50);
+        let mut c = ctx(&sig, &state, now);
+        c.strategy.exposure = 39.5;
+
+        let default = build_chain(&[]);
+        assert_eq!(default.len(), DEFAULT_CHAIN.len());
+        assert_eq!(run(&default, &c, 20.0).unwrap_err().rule, "min_size");
+
+        let names: Vec<String> = ["min_size", "strategy_exposure"].iter().map(|s| s.to_string()).collect();
+        let size = run(&build_chain(&names), &c, 20.0).unwrap();
+        assert!((size - 0.5).abs() < 1e-9);
+    }
+
+    /// Scenario: `RISK_RULES=halt,bogus,cooldown`.
+    /// Expected: Two rules built in order; `bogus` reported as unknown.
+    #[test]
+    fn test_unknown_rules_skipped() {
+        let names: Vec<String> = ["halt", "bogus", "cooldown"].iter().map(|s| s.to_string()).collect();
+        let chain = build_chain(&names);
+        assert_eq!(chain.iter().map(|r| r.name()).collect::<Vec<_>>(), ["halt", "cooldown"]);
+        assert_eq!(unknown(&names), ["bogus"]);
+    }
+}
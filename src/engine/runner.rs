@@ -3,28 +3,65 @@ use std::time::Instant;
 use tokio::sync::mpsc;
 
 use crate::config::Config;
-use crate::engine::pipeline::{self, ProcessConfig, SignalSink};
+use crate::engine::breakers::CircuitBreakers;
+use crate::engine::ensemble::{self, Ensemble};
+use crate::engine::execution::ExecutionModel;
+use crate::engine::hedge::Hedger;
+use crate::engine::pipeline::{self, DeconflictionPolicy, HouseSide, ProcessConfig, SignalSink, StrategyHost};
+use crate::engine::mtm::{MtmLimits, MtmMonitor};
 use crate::engine::risk::{PortfolioGreeks, StrategyRiskManager};
+use crate::engine::risk_rules::Rejection;
+use crate::engine::shadow::Shadow;
+use crate::engine::sizing::KellySizer;
 use crate::engine::state::{BinanceState, MarketState};
+use crate::engine::var::{self, PortfolioRisk, VarScenarios};
+use crate::math::fees::Liquidity;
 use crate::math::oracle::OracleBasis;
 use crate::math::pricing::{delta_bin, gamma_bin, z_score};
-use crate::math::regime::Regime;
-use crate::strategies::evaluate_filtered;
-use crate::strategies::latency_arb::LatencyArb;
-use crate::strategies::certainty_capture::CertaintyCapture;
-use crate::strategies::convexity_fade::ConvexityFade;
-use crate::strategies::strike_misalign::StrikeMisalign;
-use crate::strategies::lp_extreme::LpExtreme;
+use crate::math::regime::RegimeModel;
+use crate::strategies::params::StrategyParams;
+use crate::strategies::registry;
 use crate::types::*;
 
 // ─── LiveSink ──────────────────────────────────────────────────────────────
 
+/// What the engine remembers about a dispatched order until its terminal ack.
+struct LiveOrder {
+    strategy: &'static str,
+    side: Side,
+    action: OrderAction,
+    liquidity: Liquidity,
+    /// Unfilled size; released from pending exposure once the order can no longer fill.
+    remaining: f64,
+    /// GTD expiry: the CLOB drops the order silently, so the engine releases it.
+    expires_ms: Option<i64>,
+    /// (strike, end_ms) of the cross market the order trades; `None` for this market.
+    cross: Option<(f64, i64)>,
+    /// Member strategies and fill shares of an ensemble order; empty otherwise.
+    shares: Vec<(&'static str, f64)>,
+    /// Model fair value of the order's side at submit (calibration breaker).
+    fair_value: f64,
+}
+
+impl LiveOrder {
+    /// Strategies this order is attributed to, with their share of each fill.
+    fn members(&self) -> Vec<(&'static str, f64)> {
+        if self.shares.is_empty() {
+            vec![(self.strategy, 1.0)]
+        } else {
+            self.shares.clone()
+        }
+    }
+}
+
 /// SignalSink implementation for the live engine.
 /// Wraps async channels for order dispatch and telemetry, plus order attribution map.
 struct LiveSink<'a> {
-    order_tx: &'a mpsc::Sender<Order>,
+    order_tx: &'a mpsc::Sender<OrderRequest>,
     telem_tx: &'a mpsc::Sender<TelemetryEvent>,
-    order_strategies: &'a mut HashMap<u64, (&'static str, Side)>,
+    orders: &'a mut HashMap<u64, LiveOrder>,
+    /// Told the engine ID of every dispatched order (resting-quote tracking).
+    host: &'a mut StrategyHost,
     /// Per-batch eval latency (for telemetry records).
     eval_us: u64,
     dispatched: bool,
@@ -34,21 +71,161 @@ struct LiveSink<'a> {
 
 impl<'a> LiveSink<'a> {
     fn new(
-        order_tx: &'a mpsc::Sender<Order>,
+        order_tx: &'a mpsc::Sender<OrderRequest>,
         telem_tx: &'a mpsc::Sender<TelemetryEvent>,
-        order_strategies: &'a mut HashMap<u64, (&'static str, Side)>,
+        orders: &'a mut HashMap<u64, LiveOrder>,
+        host: &'a mut StrategyHost,
         eval_us: u64,
         portfolio_greeks: PortfolioGreeks,
     ) -> Self {
         Self {
             order_tx,
             telem_tx,
-            order_strategies,
+            orders,
+            host,
             eval_us,
             dispatched: false,
             portfolio_greeks,
         }
     }
+
+    /// Remember, report and log an approved order; returns it ready for the
+    /// gateway (token ID set from MarketInfo, or from the cross market it trades).
+    fn register(&mut self, sig: &Signal, order: &Order, state: &MarketState, now_ms: i64) -> Order {
+        let time_left_s = state.time_left_s(now_ms);
+        let cross = match order.instrument {
+            Instrument::Current => None,
+            Instrument::Cross(interval) => state.cross_markets.get(&interval),
+        };
+
+        self.orders.insert(
+            order.id,
+            LiveOrder {
+                strategy: sig.strategy,
+                side: sig.side,
+                action: order.action,
+                liquidity: Liquidity::of(order.order_type, order.post_only),
+                remaining: order.size,
+                expires_ms: order.expiration_ms,
+                cross: cross.map(|cm| (cm.strike, cm.end_ms)),
+                shares: Vec::new(),
+                fair_value: sig.fair_value,
+            },
+        );
+        self.host.on_order_sent(order, state);
+
+        let _ = self.telem_tx.try_send(TelemetryEvent::OrderSent(OrderRecord {
+            ts_ms: now_ms,
+            order_id: order.id,
+            side: order.side,
+            price: order.price,
+            size: order.size,
+            strategy: order.strategy.to_string(),
+            edge_at_submit: sig.edge,
+            binance_price: state.bn.binance_price,
+            time_left_s,
+        }));
+
+        eprintln!(
+            "[SIG] {} {:?} {:?} edge={:.3} fair={:.3} mkt={:.3} sz=${:.1} {} {:?} post_only={}",
+            sig.strategy, order.action, sig.side, sig.edge, sig.fair_value,
+            sig.market_price, order.size,
+            if sig.is_passive { "PASSIVE" } else { "ACTIVE" },
+            order.order_type, order.post_only,
+        );
+
+        let mut order = order.clone();
+        order.token_id = match (cross, order.side) {
+            (None, Side::Up) => state.info.up_token_id.clone(),
+            (None, Side::Down) => state.info.down_token_id.clone(),
+            (Some(cm), Side::Up) => cm.up_token_id.clone(),
+            (Some(cm), Side::Down) => cm.down_token_id.clone(),
+        };
+        order
+    }
+}
+
+/// Mid of one token's book; 0 while either side is empty.
+fn token_mid(bid: f64, ask: f64) -> f64 {
+    if bid > 0.0 && ask > 0.0 { (bid + ask) / 2.0 } else { 0.0 }
+}
+
+/// Halt buys for every breaker that tripped, and report it.
+fn apply_trips(
+    breakers: &mut CircuitBreakers,
+    risk: &mut StrategyRiskManager,
+    telem_tx: &mpsc::Sender<TelemetryEvent>,
+    now_ms: i64,
+) {
+    for trip in breakers.drain_trips() {
+        eprintln!(
+            "[BREAKER] {} tripped: {:.4} vs limit {:.4}",
+            trip.breaker.name(), trip.value, trip.limit,
+        );
+        risk.trigger_halt(now_ms, trip.until_ms - now_ms);
+        let _ = telem_tx.try_send(TelemetryEvent::Breaker(BreakerRecord {
+            ts_ms: now_ms,
+            breaker: trip.breaker.name(),
+            value: trip.value,
+            limit: trip.limit,
+            until_ms: trip.until_ms,
+        }));
+    }
+}
+
+/// Grace after a GTD's expiry before it is assumed gone: a fill matched just
+/// before expiry may still be on its way back from the gateway.
+const GTD_EXPIRY_GRACE_MS: i64 = 2_000;
+
+/// Drop expired GTD orders and release their unfilled buy exposure. The CLOB
+/// expires them without an ack, so nothing else would give the budget back.
+fn expire_gtd(orders: &mut HashMap<u64, LiveOrder>, risk: &mut StrategyRiskManager, now_ms: i64) {
+    orders.retain(|&id, o| {
+        let Some(expires_ms) = o.expires_ms else { return true };
+        if now_ms < expires_ms + GTD_EXPIRY_GRACE_MS {
+            return true;
+        }
+        if o.action == OrderAction::Buy && o.remaining > 0.0 {
+            for (name, share) in o.members() {
+                risk.release_pending(name, o.remaining * share);
+            }
+        }
+        eprintln!("[RISK] #{} [{}] GTD expired, released ${:.2}", id, o.strategy, o.remaining);
+        false
+    });
+}
+
+/// Re-simulate portfolio VaR: this market's and cross markets' positions from
+/// our own fills, every other open position from the last portfolio feed.
+fn rebuild_var(state: &mut MarketState, risk: &StrategyRiskManager, portfolio: &PortfolioRisk, asset: &str, now_ms: i64) {
+    if !state.var.enabled() {
+        return;
+    }
+    let own = var::own_positions(&risk.greeks, asset, state.s_est(), state.sigma_real(), now_ms, &state.oracle);
+    let positions = var::merge_positions(own, &portfolio.positions);
+    let end_ms = state.info.end_ms;
+    state.var.rebuild(&portfolio.correlation, &positions, asset, end_ms, now_ms);
+}
+
+/// Forward the strategies' pending cancel requests to the gateway.
+/// Exposure is released when the gateway acknowledges the cancel.
+/// Paper orders are cancelled on the shadow book instead.
+fn send_cancels(
+    host: &mut StrategyHost,
+    shadow: &mut Shadow,
+    state: &MarketState,
+    order_tx: &mpsc::Sender<OrderRequest>,
+    buf: &mut Vec<u64>,
+) {
+    host.drain_cancels(buf);
+    for id in buf.drain(..) {
+        if shadow.cancel(id, host, state) {
+            continue;
+        }
+        if order_tx.try_send(OrderRequest::Cancel(id)).is_err() {
+            eprintln!("[WARN] Order channel full, dropping cancel #{}", id);
+        }
+    }
 }
 
 impl<'a> SignalSink for LiveSink<'a> {
@@ -83,114 +260,143 @@ impl<'a> SignalSink for LiveSink<'a> {
     }
 
     fn on_order(&mut self, sig: &Signal, order: &Order, state: &MarketState, now_ms: i64) {
-        let time_left_s = state.time_left_s(now_ms);
-
-        self.order_strategies.insert(order.id, (sig.strategy, sig.side));
-
-        let _ = self.telem_tx.try_send(TelemetryEvent::OrderSent(OrderRecord {
-            ts_ms: now_ms,
-            order_id: order.id,
-            side: order.side,
-            price: order.price,
-            size: order.size,
-            strategy: order.strategy.to_string(),
-            edge_at_submit: sig.edge,
-            binance_price: state.bn.binance_price,
-            time_left_s,
-        }));
+        let order = self.register(sig, order, state, now_ms);
+        let id = order.id;
+        if self.order_tx.try_send(OrderRequest::Place(order)).is_err() {
+            eprintln!("[WARN] Order channel full, dropping order #{}", id);
+        }
+        self.dispatched = true;
+    }
 
+    fn on_merged_order(
+        &mut self,
+        sig: &Signal,
+        order: &Order,
+        shares: &[(&'static str, f64)],
+        state: &MarketState,
+        now_ms: i64,
+    ) {
+        let order = self.register(sig, order, state, now_ms);
+        if let Some(o) = self.orders.get_mut(&order.id) {
+            o.shares = shares.to_vec();
+        }
         eprintln!(
-            "[SIG] {} {:?} edge={:.3} fair={:.3} mkt={:.3} sz=${:.1} {} {:?} post_only={}",
-            sig.strategy, sig.side, sig.edge, sig.fair_value,
-            sig.market_price, order.size,
-            if sig.is_passive { "PASSIVE" } else { "ACTIVE" },
-            order.order_type, order.post_only,
+            "[ENS] #{} pooled {}",
+            order.id,
+            shares.iter().map(|(n, w)| format!("{}={:.2}", n, w)).collect::<Vec<_>>().join(" "),
         );
+        let id = order.id;
+        if self.order_tx.try_send(OrderRequest::Place(order)).is_err() {
+            eprintln!("[WARN] Order channel full, dropping order #{}", id);
+        }
+        self.dispatched = true;
+    }
 
-        // Set token_id from MarketInfo before dispatch
-        let mut order = order.clone();
-        order.token_id = match order.side {
-            Side::Up => state.info.up_token_id.clone(),
-            Side::Down => state.info.down_token_id.clone(),
-        };
-
-        if let Err(e) = self.order_tx.try_send(order) {
-            eprintln!("[WARN] Order channel full, dropping order #{}", e.into_inner().id);
+    fn on_pair(&mut self, legs: [(&Signal, &Order); 2], state: &MarketState, now_ms: i64) {
+        let [(up_sig, up), (down_sig, down)] = legs;
+        let up = self.register(up_sig, up, state, now_ms);
+        let down = self.register(down_sig, down, state, now_ms);
+        let ids = (up.id, down.id);
+        if self.order_tx.try_send(OrderRequest::Pair(up, down)).is_err() {
+            eprintln!("[WARN] Order channel full, dropping pair #{}+#{}", ids.0, ids.1);
         }
         self.dispatched = true;
     }
+
+    fn on_rejection(&mut self, sig: &Signal, rejection: &Rejection, _state: &MarketState, now_ms: i64) {
+        let _ = self.telem_tx.try_send(TelemetryEvent::RiskRejection(RiskRejectionRecord {
+            ts_ms: now_ms,
+            strategy: sig.strategy.to_string(),
+            side: sig.side,
+            action: sig.action,
+            edge: sig.edge,
+            rule: rejection.rule,
+            value: rejection.value,
+            limit: rejection.limit,
+        }));
+    }
 }
 
 /// Core engine event loop. Single task, owns all state.
 ///
 /// Accepts BinanceState (persistent across markets) and returns it at market end.
 ///
-/// Side coherence: first dispatched order sets `house_side`. All subsequent
-/// ACTIVE orders must agree. Passive signals (lp_extreme) are exempt —
-/// they intentionally take the opposite side for LP purposes.
+/// Side coherence: under the default `DECONFLICT_POLICY` (first-wins) the first
+/// dispatched order sets the house side. All subsequent ACTIVE orders must
+/// agree. Passive signals (lp_extreme) are exempt — they intentionally take
+/// the opposite side for LP purposes.
 ///
 /// PnL: fills are recorded, settled at market end when outcome is known.
+///
+/// `ensemble` (persistent across markets, `None` when disabled) pools agreeing
+/// signals; it is scored against each market's outcome at settlement.
+///
+/// `hedger` (persistent across markets, `None` when `HEDGE` is off) keeps the
+/// binaries' dollar delta inside the hedge band; its PnL is marked at settlement.
+///
+/// `breakers` (persistent across markets) watch acks, fills and settlements
+/// and halt new buys for their cool-off when one trips.
+#[allow(clippy::too_many_arguments)]
 pub async fn run_engine(
     market: MarketInfo,
     binance_state: BinanceState,
     mut feed_rx: mpsc::Receiver<FeedEvent>,
-    order_tx: mpsc::Sender<Order>,
+    order_tx: mpsc::Sender<OrderRequest>,
     telem_tx: mpsc::Sender<TelemetryEvent>,
     config: &Config,
+    mut ensemble: Option<&mut Ensemble>,
+    mut hedger: Option<&mut Hedger>,
+    breakers: &mut CircuitBreakers,
 ) -> BinanceState {
-    let oracle = OracleBasis::new(config.oracle_beta, config.oracle_delta_s);
+    let oracle = OracleBasis::new(config.oracle_beta, config.oracle_delta_s, config.oracle_cadence_s);
     let mut state = MarketState::new(market, binance_state, oracle);
+    state.sizing = KellySizer::from_config(config);
+    state.execution = ExecutionModel::from_config(config);
+    state.var = VarScenarios::from_config(config);
+    state.params = StrategyParams::from_config(config);
     let mut risk = StrategyRiskManager::new(config);
-
-    // ── Instantiate strategies (only those enabled in config) ──
-    let latency_arb = LatencyArb;
-    let certainty_capture = CertaintyCapture;
-    let convexity_fade = ConvexityFade;
-    let strike_misalign = StrikeMisalign;
-    let lp_extreme = LpExtreme;
-
-    // ── Partition strategies by trigger type, respecting config toggles ──
-    let mut binance_strategies: Vec<&dyn crate::strategies::Strategy> = Vec::with_capacity(3);
-    if config.strategy_latency_arb { binance_strategies.push(&latency_arb); }
-    if config.strategy_lp_extreme  { binance_strategies.push(&lp_extreme); }
-
-    let mut pm_strategies: Vec<&dyn crate::strategies::Strategy> = Vec::with_capacity(4);
-    if config.strategy_certainty_capture { pm_strategies.push(&certainty_capture); }
-    if config.strategy_convexity_fade    { pm_strategies.push(&convexity_fade); }
-    if config.strategy_lp_extreme        { pm_strategies.push(&lp_extreme); }
-
-    let mut open_strategies: Vec<&dyn crate::strategies::Strategy> = Vec::with_capacity(2);
-    if config.strategy_strike_misalign { open_strategies.push(&strike_misalign); }
-
-    {
-        let enabled: Vec<&str> = [
-            (config.strategy_latency_arb, "latency_arb"),
-            (config.strategy_certainty_capture, "certainty_capture"),
-            (config.strategy_convexity_fade, "convexity_fade"),
-            (config.strategy_strike_misalign, "strike_misalign"),
-            (config.strategy_lp_extreme, "lp_extreme"),
-            (config.strategy_cross_timeframe, "cross_timeframe"),
-        ].iter().filter(|(on, _)| *on).map(|(_, name)| *name).collect();
-        eprintln!("[ENGINE] Strategies enabled: {:?}", enabled);
+    // A breaker cool-off from an earlier market still applies
+    risk.halted_until_ms = breakers.halted_until_ms();
+    let mut mtm = MtmMonitor::new(MtmLimits::from_config(config));
+
+    // ── Instantiate strategies (registry entries enabled in config) ──
+    // The host partitions them by trigger type on every evaluation.
+    let mut strategies = StrategyHost::new(registry::build_enabled(config));
+    eprintln!("[ENGINE] Strategies enabled: {:?}", strategies.names());
+
+    // Shadow strategies run the same pipeline against a paper book
+    let mut shadow = Shadow::new(config);
+    if !shadow.names().is_empty() {
+        eprintln!("[ENGINE] Shadow (paper) strategies: {:?}", shadow.names());
     }
 
     let mut signals_buf: Vec<Signal> = Vec::with_capacity(8);
     let mut open_buf: Vec<Signal> = Vec::with_capacity(2);
     let mut next_order_id: u64 = 1;
 
-    // Map order_id → (strategy_name, side) for fill attribution + settlement
-    let mut order_strategies: HashMap<u64, (&'static str, Side)> = HashMap::new();
+    // Map order_id → strategy, side, action for fill attribution + settlement.
+    // Entries live until the order's terminal ack (resting orders fill over time).
+    let mut orders: HashMap<u64, LiveOrder> = HashMap::new();
+    let mut cancel_buf: Vec<u64> = Vec::with_capacity(4);
 
     // Fill tracking for settlement PnL
     let mut fills: Vec<Fill> = Vec::with_capacity(64);
+    // (side, fair value at submit) per buy fill on this market, for the calibration breaker
+    let mut calibration: Vec<(Side, f64)> = Vec::with_capacity(64);
 
-    // Side coherence: first ACTIVE order sets the house view for this market
-    // Passive signals (lp_extreme) are exempt from house_side filtering
-    let mut house_side: Option<Side> = None;
-    let mut flip_count: u32 = 0;
+    // Side coherence: the deconfliction policy keeps ACTIVE orders to the house view
+    // Passive signals (lp_extreme) are exempt from house-side filtering
+    let process_config = ProcessConfig::live().with_deconfliction(DeconflictionPolicy::from_config(config));
+    let mut house = HouseSide::default();
 
     // Diagnostic: periodic strategy health log (every 10s)
     let mut last_diag_ms: i64 = 0;
+    // Exposure / MTM curve telemetry (every second while anything is open)
+    let mut last_exposure_ms: i64 = 0;
+    // Latest spot correlation and wallet positions, for portfolio VaR; built
+    // now so a first order is priced even before the first portfolio sample
+    let mut portfolio = PortfolioRisk::default();
+    rebuild_var(&mut state, &risk, &portfolio, &config.asset, chrono::Utc::now().timestamp_millis());
 
     // Log market start
     let _ = telem_tx.try_send(TelemetryEvent::MarketStart(MarketStartRecord {
@@ -200,6 +406,7 @@ pub async fn run_engine(
         start_ms: state.info.start_ms,
         end_ms: state.info.end_ms,
     }));
+    strategies.on_market_start(&state);
 
     let mut warmup_done = false;
 
@@ -229,14 +436,11 @@ pub async fn run_engine(
                 let recv_at = t.recv_at;
                 let recv_latency_us = recv_at.elapsed().as_micros() as u64;
                 state.on_binance_trade(t);
+                shadow.on_binance_trade(&state);
 
                 // Recompute portfolio Greeks at new spot price
                 if risk.greeks.snapshot.n_positions > 0 {
-                    let s = state.s_est();
-                    let k = state.info.strike;
-                    let sigma = state.sigma_real();
-                    let tau = state.tau_eff_s(now_ms);
-                    risk.greeks.recompute(s, k, sigma, tau);
+                    risk.greeks.recompute(state.s_est(), state.sigma_real(), now_ms, &state.oracle);
                 }
 
                 let _ = telem_tx.try_send(TelemetryEvent::Latency(LatencyRecord {
@@ -262,15 +466,15 @@ pub async fn run_engine(
                 let in_open_window = elapsed_ms >= 0 && elapsed_ms <= config.interval.open_window_ms();
                 if in_open_window {
                     let eval_start = Instant::now();
-                    evaluate_filtered(&open_strategies, &state, now_ms, &mut open_buf);
+                    strategies.evaluate(EvalTrigger::MarketOpen, &state, now_ms, &mut open_buf);
                     if !open_buf.is_empty() {
                         let eval_us = eval_start.elapsed().as_micros() as u64;
-                        let config = ProcessConfig::live();
-                        let mut sink = LiveSink::new(&order_tx, &telem_tx, &mut order_strategies, eval_us, risk.greeks.snapshot);
+                        shadow.process(&mut open_buf, &mut state, &mut strategies, &mut next_order_id, now_ms);
+                        let mut sink = LiveSink::new(&order_tx, &telem_tx, &mut orders, &mut strategies, eval_us, risk.greeks.snapshot);
                         pipeline::process_signals(
                             &mut open_buf, &mut state, &mut risk,
-                            &mut house_side, &mut flip_count, &mut next_order_id, now_ms,
-                            &config, &mut sink,
+                            &mut house, &mut next_order_id, now_ms,
+                            &process_config, ensemble.as_deref_mut(), &mut sink,
                         );
                         if sink.dispatched {
                             let e2e_us = recv_at.elapsed().as_micros() as u64;
@@ -301,12 +505,12 @@ pub async fn run_engine(
                 // ── Periodic diagnostic log (every 10s) ──
                 if now_ms - last_diag_ms >= 10_000 {
                     last_diag_ms = now_ms;
-                    log_strategy_diagnostics(&state, now_ms, &house_side, &risk.greeks.snapshot);
+                    log_strategy_diagnostics(&state, now_ms, &house.side, &risk.greeks.snapshot);
                 }
 
                 // ── Evaluate Binance-triggered strategies ──
                 let eval_start = Instant::now();
-                evaluate_filtered(&binance_strategies, &state, now_ms, &mut signals_buf);
+                strategies.evaluate(EvalTrigger::BinanceTrade, &state, now_ms, &mut signals_buf);
 
                 let eval_us = eval_start.elapsed().as_micros() as u64;
                 let _ = telem_tx.try_send(TelemetryEvent::Latency(LatencyRecord {
@@ -316,12 +520,12 @@ pub async fn run_engine(
                 }));
 
                 {
-                    let config = ProcessConfig::live();
-                    let mut sink = LiveSink::new(&order_tx, &telem_tx, &mut order_strategies, eval_us, risk.greeks.snapshot);
+                    shadow.process(&mut signals_buf, &mut state, &mut strategies, &mut next_order_id, now_ms);
+                    let mut sink = LiveSink::new(&order_tx, &telem_tx, &mut orders, &mut strategies, eval_us, risk.greeks.snapshot);
                     pipeline::process_signals(
                         &mut signals_buf, &mut state, &mut risk,
-                        &mut house_side, &mut flip_count, &mut next_order_id, now_ms,
-                        &config, &mut sink,
+                        &mut house, &mut next_order_id, now_ms,
+                        &process_config, ensemble.as_deref_mut(), &mut sink,
                     );
                     if sink.dispatched {
                         let e2e_us = recv_at.elapsed().as_micros() as u64;
@@ -330,12 +534,14 @@ pub async fn run_engine(
                         }));
                     }
                 }
+                send_cancels(&mut strategies, &mut shadow, &state, &order_tx, &mut cancel_buf);
             }
 
             FeedEvent::PolymarketQuote(q) => {
                 let recv_at = q.recv_at;
                 let pm_recv_us = recv_at.elapsed().as_micros() as u64;
                 state.on_polymarket_quote(q);
+                shadow.on_market_update(&mut strategies, &mut state);
 
                 let _ = telem_tx.try_send(TelemetryEvent::Latency(LatencyRecord {
                     ts_ms: now_ms,
@@ -352,15 +558,15 @@ pub async fn run_engine(
                 if !warmup_done && state.bn.ewma_vol.is_valid() {
                     let elapsed_ms = now_ms - state.info.start_ms;
                     if elapsed_ms >= 0 && elapsed_ms <= config.interval.open_window_ms() {
-                        evaluate_filtered(&open_strategies, &state, now_ms, &mut open_buf);
+                        strategies.evaluate(EvalTrigger::MarketOpen, &state, now_ms, &mut open_buf);
                         if !open_buf.is_empty() {
                             let eval_us = 0u64;
-                            let config = ProcessConfig::live();
-                            let mut sink = LiveSink::new(&order_tx, &telem_tx, &mut order_strategies, eval_us, risk.greeks.snapshot);
+                            shadow.process(&mut open_buf, &mut state, &mut strategies, &mut next_order_id, now_ms);
+                            let mut sink = LiveSink::new(&order_tx, &telem_tx, &mut orders, &mut strategies, eval_us, risk.greeks.snapshot);
                             pipeline::process_signals(
                                 &mut open_buf, &mut state, &mut risk,
-                                &mut house_side, &mut flip_count, &mut next_order_id, now_ms,
-                                &config, &mut sink,
+                                &mut house, &mut next_order_id, now_ms,
+                                &process_config, ensemble.as_deref_mut(), &mut sink,
                             );
                         }
                     }
@@ -372,11 +578,11 @@ pub async fn run_engine(
                 }
 
                 let eval_start = Instant::now();
-                evaluate_filtered(&pm_strategies, &state, now_ms, &mut signals_buf);
+                strategies.evaluate(EvalTrigger::PolymarketQuote, &state, now_ms, &mut signals_buf);
 
                 let elapsed_ms = now_ms - state.info.start_ms;
                 if elapsed_ms >= 0 && elapsed_ms <= config.interval.open_window_ms() {
-                    evaluate_filtered(&open_strategies, &state, now_ms, &mut open_buf);
+                    strategies.evaluate(EvalTrigger::MarketOpen, &state, now_ms, &mut open_buf);
                     signals_buf.extend(open_buf.drain(..));
                 }
 
@@ -388,12 +594,12 @@ pub async fn run_engine(
                 }));
 
                 {
-                    let config = ProcessConfig::live();
-                    let mut sink = LiveSink::new(&order_tx, &telem_tx, &mut order_strategies, eval_us, risk.greeks.snapshot);
+                    shadow.process(&mut signals_buf, &mut state, &mut strategies, &mut next_order_id, now_ms);
+                    let mut sink = LiveSink::new(&order_tx, &telem_tx, &mut orders, &mut strategies, eval_us, risk.greeks.snapshot);
                     pipeline::process_signals(
                         &mut signals_buf, &mut state, &mut risk,
-                        &mut house_side, &mut flip_count, &mut next_order_id, now_ms,
-                        &config, &mut sink,
+                        &mut house, &mut next_order_id, now_ms,
+                        &process_config, ensemble.as_deref_mut(), &mut sink,
                     );
                     if sink.dispatched {
                         let e2e_us = recv_at.elapsed().as_micros() as u64;
@@ -402,11 +608,13 @@ pub async fn run_engine(
                         }));
                     }
                 }
+                send_cancels(&mut strategies, &mut shadow, &state, &order_tx, &mut cancel_buf);
             }
 
             FeedEvent::PolymarketBook(book) => {
                 let recv_at = book.recv_at;
                 state.on_book_update(book);
+                shadow.on_market_update(&mut strategies, &mut state);
 
                 if !state.has_data() {
                     continue;
@@ -416,15 +624,15 @@ pub async fn run_engine(
                 if !warmup_done && state.bn.ewma_vol.is_valid() {
                     let elapsed_ms = now_ms - state.info.start_ms;
                     if elapsed_ms >= 0 && elapsed_ms <= config.interval.open_window_ms() {
-                        evaluate_filtered(&open_strategies, &state, now_ms, &mut open_buf);
+                        strategies.evaluate(EvalTrigger::MarketOpen, &state, now_ms, &mut open_buf);
                         if !open_buf.is_empty() {
                             let eval_us = 0u64;
-                            let config = ProcessConfig::live();
-                            let mut sink = LiveSink::new(&order_tx, &telem_tx, &mut order_strategies, eval_us, risk.greeks.snapshot);
+                            shadow.process(&mut open_buf, &mut state, &mut strategies, &mut next_order_id, now_ms);
+                            let mut sink = LiveSink::new(&order_tx, &telem_tx, &mut orders, &mut strategies, eval_us, risk.greeks.snapshot);
                             pipeline::process_signals(
                                 &mut open_buf, &mut state, &mut risk,
-                                &mut house_side, &mut flip_count, &mut next_order_id, now_ms,
-                                &config, &mut sink,
+                                &mut house, &mut next_order_id, now_ms,
+                                &process_config, ensemble.as_deref_mut(), &mut sink,
                             );
                         }
                     }
@@ -436,11 +644,11 @@ pub async fn run_engine(
                 }
 
                 let eval_start = Instant::now();
-                evaluate_filtered(&pm_strategies, &state, now_ms, &mut signals_buf);
+                strategies.evaluate(EvalTrigger::PolymarketQuote, &state, now_ms, &mut signals_buf);
 
                 let elapsed_ms = now_ms - state.info.start_ms;
                 if elapsed_ms >= 0 && elapsed_ms <= config.interval.open_window_ms() {
-                    evaluate_filtered(&open_strategies, &state, now_ms, &mut open_buf);
+                    strategies.evaluate(EvalTrigger::MarketOpen, &state, now_ms, &mut open_buf);
                     signals_buf.extend(open_buf.drain(..));
                 }
 
@@ -452,12 +660,12 @@ pub async fn run_engine(
                 }));
 
                 {
-                    let config = ProcessConfig::live();
-                    let mut sink = LiveSink::new(&order_tx, &telem_tx, &mut order_strategies, eval_us, risk.greeks.snapshot);
+                    shadow.process(&mut signals_buf, &mut state, &mut strategies, &mut next_order_id, now_ms);
+                    let mut sink = LiveSink::new(&order_tx, &telem_tx, &mut orders, &mut strategies, eval_us, risk.greeks.snapshot);
                     pipeline::process_signals(
                         &mut signals_buf, &mut state, &mut risk,
-                        &mut house_side, &mut flip_count, &mut next_order_id, now_ms,
-                        &config, &mut sink,
+                        &mut house, &mut next_order_id, now_ms,
+                        &process_config, ensemble.as_deref_mut(), &mut sink,
                     );
                     if sink.dispatched {
                         let e2e_us = recv_at.elapsed().as_micros() as u64;
@@ -466,16 +674,31 @@ pub async fn run_engine(
                         }));
                     }
                 }
+                send_cancels(&mut strategies, &mut shadow, &state, &order_tx, &mut cancel_buf);
             }
 
             FeedEvent::CrossMarketQuote(cm) => {
                 state.on_cross_market_quote(cm);
             }
 
+            FeedEvent::Portfolio(p) => {
+                portfolio = p;
+                rebuild_var(&mut state, &risk, &portfolio, &config.asset, now_ms);
+            }
+
             FeedEvent::OrderAck(ack) => {
-                let (strat_name, order_side) = order_strategies.remove(&ack.order_id)
-                    .unwrap_or(("unknown", Side::Up));
+                let (strat_name, order_side, action, liquidity, cross) = orders
+                    .get(&ack.order_id)
+                    .map(|o| (o.strategy, o.side, o.action, o.liquidity, o.cross))
+                    .unwrap_or(("unknown", Side::Up, OrderAction::Buy, Liquidity::Taker, None));
+                let (shares, members) = orders
+                    .get(&ack.order_id)
+                    .map(|o| (o.shares.clone(), o.members()))
+                    .unwrap_or_else(|| (Vec::new(), vec![(strat_name, 1.0)]));
+                let fair_value = orders.get(&ack.order_id).map_or(0.0, |o| o.fair_value);
                 let strategy = strat_name.to_string();
+                strategies.on_order_ack(strat_name, &ack, &state);
+                breakers.on_ack(&ack.status, ack.latency_ms, now_ms);
 
                 let pnl_if_correct = ack
                     .filled_price
@@ -498,32 +721,62 @@ pub async fn run_engine(
                     OrderStatus::Filled | OrderStatus::PartialFill => {
                         state.total_filled += 1;
 
-                        if let (Some(price), Some(size)) = (ack.filled_price, ack.filled_size) {
-                            fills.push(Fill {
+                        if let (Some(price), Some(filled)) = (ack.filled_price, ack.filled_size) {
+                            if let Some(o) = orders.get_mut(&ack.order_id) {
+                                o.remaining = (o.remaining - filled).max(0.0);
+                            }
+                            // Sells are recorded with negative size (unwinds holdings and Greeks)
+                            let size = match action {
+                                OrderAction::Buy => filled,
+                                OrderAction::Sell => -filled,
+                            };
+                            let fill = Fill {
                                 order_id: ack.order_id,
                                 strategy: strat_name,
                                 side: order_side,
                                 price,
                                 size,
-                            });
-
-                            // Update portfolio Greeks
-                            risk.greeks.on_fill(order_side, size);
-                            risk.greeks.recompute(
-                                state.s_est(), state.info.strike,
-                                state.sigma_real(), state.tau_eff_s(now_ms),
-                            );
+                                fee: state.info.fees.fill_fee(price, filled, liquidity),
+                            };
+                            // Ensemble orders: one fill per member, pro rata
+                            for fill in ensemble::split_fill(&fill, &shares) {
+                                strategies.on_fill(&fill, &state);
+
+                                if let Some((strike, end_ms)) = cross {
+                                    // Another market's token: settles on that market's outcome,
+                                    // outside this market's position; Greeks at its own strike
+                                    risk.on_cross_fill(&fill);
+                                    risk.greeks.on_fill(strike, end_ms, order_side, fill.size);
+                                    state.cross_ledger.record(fill, strike, end_ms);
+                                } else {
+                                    risk.on_fill(&fill);
+                                    state.position.record_fill(order_side, price, fill.size);
+                                    risk.greeks.on_fill(state.info.strike, state.info.end_ms, order_side, fill.size);
+                                    fills.push(fill);
+                                }
+                            }
+                            // Update portfolio Greeks and VaR
+                            risk.greeks.recompute(state.s_est(), state.sigma_real(), now_ms, &state.oracle);
+                            rebuild_var(&mut state, &risk, &portfolio, &config.asset, now_ms);
+
+                            if cross.is_none() {
+                                let mid = match order_side {
+                                    Side::Up => token_mid(state.up_bid, state.up_ask),
+                                    Side::Down => token_mid(state.down_bid, state.down_ask),
+                                };
+                                breakers.on_fill(order_side, action, mid, now_ms);
+                                if action == OrderAction::Buy {
+                                    calibration.push((order_side, fair_value));
+                                }
+                            }
                         }
 
-                        for (&key, stats) in state.strategy_stats.iter_mut() {
-                            if key == strat_name {
+                        for (name, _) in &members {
+                            if let Some(stats) = state.strategy_stats.get_mut(name) {
                                 stats.filled += 1;
-                                break;
                             }
                         }
 
-                        risk.on_fill(&strategy, &ack);
-
                         let side_str = match order_side {
                             Side::Up => "Up",
                             Side::Down => "Down",
@@ -534,16 +787,114 @@ pub async fn run_engine(
                             ack.filled_price, ack.filled_size, ack.latency_ms
                         );
                     }
+                    OrderStatus::Unwound { exit_price } => {
+                        // Broken pair: the leg filled and the gateway reversed it.
+                        // Record both trades; the position is flat again.
+                        if let (Some(price), Some(filled)) = (ack.filled_price, ack.filled_size) {
+                            if let Some(o) = orders.get_mut(&ack.order_id) {
+                                o.remaining = (o.remaining - filled).max(0.0);
+                            }
+                            let sign = match action {
+                                OrderAction::Buy => 1.0,
+                                OrderAction::Sell => -1.0,
+                            };
+                            for (px, size) in [(price, sign * filled), (exit_price, -sign * filled)] {
+                                let fill = Fill {
+                                    order_id: ack.order_id,
+                                    strategy: strat_name,
+                                    side: order_side,
+                                    price: px,
+                                    size,
+                                    fee: state.info.fees.fill_fee(px, filled, Liquidity::Taker),
+                                };
+                                strategies.on_fill(&fill, &state);
+                                if let Some((strike, end_ms)) = cross {
+                                    risk.on_cross_fill(&fill);
+                                    risk.greeks.on_fill(strike, end_ms, order_side, size);
+                                    state.cross_ledger.record(fill, strike, end_ms);
+                                    continue;
+                                }
+                                risk.on_fill(&fill);
+                                state.position.record_fill(order_side, px, size);
+                                risk.greeks.on_fill(state.info.strike, state.info.end_ms, order_side, size);
+                                fills.push(fill);
+                            }
+                            risk.greeks.recompute(state.s_est(), state.sigma_real(), now_ms, &state.oracle);
+                            rebuild_var(&mut state, &risk, &portfolio, &config.asset, now_ms);
+                        }
+                        eprintln!(
+                            "[FILL] #{} [{}] Unwound entry={:?} exit={:.3} size={:?}",
+                            ack.order_id, strategy, ack.filled_price, exit_price, ack.filled_size
+                        );
+                    }
                     _ => {
-                        risk.on_fill(&strategy, &ack);
                         eprintln!("[FILL] #{} [{}] {:?}", ack.order_id, strategy, ack.status);
                     }
                 }
 
                 state.position.on_fill(&ack);
+                if ack.status.is_terminal() {
+                    // Whatever a buy left unfilled (cancelled, rejected, unmatched) is no longer pending
+                    if let Some(o) = orders.remove(&ack.order_id) {
+                        if action == OrderAction::Buy {
+                            for &(name, share) in &members {
+                                risk.release_pending(name, o.remaining * share);
+                            }
+                        }
+                    }
+                }
+                apply_trips(breakers, &mut risk, &telem_tx, now_ms);
+            }
+
+            FeedEvent::PairsMerged(pairs) => {
+                state.position.on_merge(pairs);
             }
 
             FeedEvent::Tick => {
+                expire_gtd(&mut orders, &mut risk, now_ms);
+
+                // Mark open positions, apply MTM stops, sell stopped inventory
+                let point = mtm.on_tick(&mut risk, &state, now_ms);
+                {
+                    let mut sink = LiveSink::new(
+                        &order_tx, &telem_tx, &mut orders, &mut strategies, 0, risk.greeks.snapshot,
+                    );
+                    mtm.dispatch_exits(&mut risk, &mut state, &mut next_order_id, now_ms, &mut sink);
+                }
+                if !mtm.snapshot().is_empty() && now_ms - last_exposure_ms >= 1_000 {
+                    last_exposure_ms = now_ms;
+                    for &(name, e) in mtm.snapshot() {
+                        let _ = telem_tx.try_send(TelemetryEvent::Exposure(ExposureRecord {
+                            ts_ms: now_ms,
+                            strategy: name.to_string(),
+                            pending: e.pending,
+                            filled: e.filled,
+                            realized_pnl: e.realized_pnl,
+                            unrealized_pnl: e.unrealized_pnl,
+                            unrealized_fair_pnl: e.unrealized_fair_pnl,
+                        }));
+                    }
+                    let _ = telem_tx.try_send(TelemetryEvent::Mtm(MtmRecord {
+                        ts_ms: now_ms,
+                        time_left_s: state.time_left_s(now_ms),
+                        realized_pnl: point.realized,
+                        unrealized_pnl: point.unrealized,
+                        unrealized_fair_pnl: point.unrealized_fair,
+                        stopped: mtm.market_stop().is_some(),
+                    }));
+                }
+                breakers.on_tick(
+                    token_mid(state.up_bid, state.up_ask),
+                    token_mid(state.down_bid, state.down_ask),
+                    now_ms,
+                );
+                apply_trips(breakers, &mut risk, &telem_tx, now_ms);
+                if let Some(h) = hedger.as_deref_mut() {
+                    h.rebalance(&risk.greeks.snapshot, state.bn.binance_price, now_ms, false);
+                    for record in h.drain_records() {
+                        let _ = telem_tx.try_send(TelemetryEvent::Hedge(record));
+                    }
+                }
                 if state.is_stale(now_ms) {
                     eprintln!(
                         "[WARN] Stale: bn_age={}ms pm_age={}ms",
@@ -563,18 +914,31 @@ pub async fn run_engine(
     // ── Settlement ──
     let final_distance = state.distance();
     let outcome = if final_distance >= 0.0 { Side::Up } else { Side::Down };
+    strategies.on_market_end(outcome, &state);
+    if let Some(e) = ensemble {
+        e.on_market_end(outcome);
+        for &name in &strategies.names() {
+            if e.markets_scored(name) > 0 {
+                eprintln!("[ENS]   {}: brier={:.3} weight={:.1} markets={}", name, e.brier(name), e.weight(name), e.markets_scored(name));
+            }
+        }
+    }
 
     let mut realized_pnl = 0.0_f64;
     let mut per_strat_pnl: HashMap<&str, f64> = HashMap::new();
     for fill in &fills {
-        let pnl = if fill.side == outcome {
-            (1.0 - fill.price) * fill.size
-        } else {
-            -(fill.price * fill.size)
-        };
+        let pnl = fill.pnl(outcome);
         realized_pnl += pnl;
         *per_strat_pnl.entry(fill.strategy).or_insert(0.0) += pnl;
     }
+    // Cross-market fills settle on their own market's strike and expiry price
+    let cross_fills = state.cross_ledger.settle(state.bn.binance_price);
+    for (cross_outcome, fill) in &cross_fills {
+        let pnl = fill.pnl(*cross_outcome);
+        realized_pnl += pnl;
+        *per_strat_pnl.entry(fill.strategy).or_insert(0.0) += pnl;
+        risk.book_pnl(pnl);
+    }
     state.gross_pnl = realized_pnl;
 
     for (&name, stats) in state.strategy_stats.iter_mut() {
@@ -584,6 +948,38 @@ pub async fn run_engine(
     }
 
     risk.settle_market(outcome, &fills);
+    risk.greeks.expire(state.info.end_ms);
+
+    // Model error: settled buys' win rate against the fair values they were bought at
+    for &(side, fair_value) in &calibration {
+        breakers.on_settled(fair_value, side == outcome);
+    }
+    breakers.on_market_end();
+    let settle_ms = chrono::Utc::now().timestamp_millis();
+    breakers.check_calibration(settle_ms);
+    apply_trips(breakers, &mut risk, &telem_tx, settle_ms);
+
+    // Re-hedge to what is still open (unwinds this market's share) and mark the hedge
+    let hedge_pnl = hedger.map(|h| {
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        h.rebalance(&risk.greeks.snapshot, state.bn.binance_price, now_ms, true);
+        for record in h.drain_records() {
+            let _ = telem_tx.try_send(TelemetryEvent::Hedge(record));
+        }
+        let pnl = h.settle(state.bn.binance_price);
+        state.gross_pnl += pnl;
+        risk.book_pnl(pnl);
+        eprintln!("[HEDGE] Market PnL ${:.2} position={:.6}", pnl, h.qty);
+        pnl
+    });
+
+    // Paper fills settle on their own, outside the market's PnL and risk
+    let shadow_pnl = shadow.settle(outcome, state.bn.binance_price);
+    for (&name, &pnl) in &shadow_pnl {
+        if let Some(stats) = state.strategy_stats.get_mut(name) {
+            stats.gross_pnl = pnl;
+        }
+    }
 
     let per_strategy: Vec<PerStrategyEnd> = state.strategy_stats.iter()
         .map(|(&name, stats)| PerStrategyEnd {
@@ -593,6 +989,7 @@ pub async fn run_engine(
             filled: stats.filled,
             gross_pnl: stats.gross_pnl,
             avg_edge: stats.avg_edge(),
+            shadow: shadow.is_shadow(name),
         })
         .collect();
 
@@ -606,19 +1003,24 @@ pub async fn run_engine(
         total_orders: state.total_orders,
         total_filled: state.total_filled,
         gross_pnl: state.gross_pnl,
+        shadow_pnl: shadow_pnl.values().sum(),
+        hedge_pnl,
         per_strategy,
     }));
 
     eprintln!(
-        "[ENGINE] Market {} ended | outcome={:?} | house={:?} | flips={} | sig={} ord={} fill={} pnl=${:.2} ({}fills settled)",
-        state.info.slug, outcome, house_side, flip_count, state.total_signals, state.total_orders,
-        state.total_filled, state.gross_pnl, fills.len(),
+        "[ENGINE] Market {} ended | outcome={:?} | house={:?} | flips={} | sig={} ord={} fill={} pnl=${:.2} ({}fills settled, {} cross)",
+        state.info.slug, outcome, house.side, house.flips, state.total_signals, state.total_orders,
+        state.total_filled, state.gross_pnl, fills.len(), cross_fills.len(),
     );
     for (&name, stats) in &state.strategy_stats {
-        let strat_pnl = per_strat_pnl.get(name).copied().unwrap_or(0.0);
+        let (strat_pnl, tag) = match shadow_pnl.get(name) {
+            Some(&pnl) => (pnl, " (shadow)"),
+            None => (per_strat_pnl.get(name).copied().unwrap_or(0.0), ""),
+        };
         eprintln!(
-            "[ENGINE]   {}: sig={} ord={} fill={} pnl=${:.2} avg_edge={:.3}",
-            name, stats.signals, stats.orders, stats.filled, strat_pnl, stats.avg_edge()
+            "[ENGINE]   {}: sig={} ord={} fill={} pnl=${:.2} avg_edge={:.3}{}",
+            name, stats.signals, stats.orders, stats.filled, strat_pnl, stats.avg_edge(), tag
         );
     }
 
@@ -634,6 +1036,7 @@ fn log_strategy_diagnostics(state: &MarketState, now_ms: i64, house_side: &Optio
     let dist = state.distance();
     let dist_frac = state.distance_frac().abs();
     let regime = state.bn.regime.classify();
+    let trend_prob = state.bn.trend_prob();
 
     let z = if sigma > 0.0 && tau > 0.0 && s > 0.0 && k > 0.0 {
         z_score(s, k, sigma, tau)
@@ -642,13 +1045,30 @@ fn log_strategy_diagnostics(state: &MarketState, now_ms: i64, house_side: &Optio
     };
 
     eprintln!(
-        "[DIAG] t_left={:.0}s σ={:.8} z={:.2} dist=${:.0} dist_frac={:.5} regime={:?}({:.0}%/{}) house={:?} \
-         up_ask={:.3} down_ask={:.3} S={:.2} K={:.0} port_Δ={:.4} port_Γ={:.6} n_pos={}",
+        "[DIAG] t_left={:.0}s σ={:.8} z={:.2} dist=${:.0} dist_frac={:.5} regime={:?}({:.0}%/{}) trend_p={:.2} H={:.2} house={:?} \
+         up_ask={:.3} down_ask={:.3} S={:.2} K={:.0} port_Δ={:.4} port_$Δ={:.0} port_Γ={:.6} port_ν={:.4} port_Θ={:.6}/s n_pos={}",
         tau, sigma, z, dist, dist_frac, regime,
         state.bn.regime.dominant_frac() * 100.0, state.bn.regime.total_ticks(),
+        trend_prob, state.bn.vr_regime.hurst(),
         house_side,
         state.up_ask, state.down_ask, s, k,
-        greeks.delta, greeks.gamma, greeks.n_positions,
+        greeks.delta, greeks.dollar_delta, greeks.gamma, greeks.vega, greeks.theta, greeks.n_positions,
+    );
+
+    eprintln!(
+        "[DIAG]   regime_models: VR={:.2} n={} dir={} | HMM p={:.2} post={:.2} μ₁={:+.2e} n={}",
+        state.bn.vr_regime.ratio(), state.bn.vr_regime.n_returns(),
+        if state.bn.vr_regime.trend_direction_up() { "UP" } else { "DN" },
+        state.bn.hmm_regime.trend_prob(), state.bn.hmm_regime.posterior(),
+        state.bn.hmm_regime.trend_mean(), state.bn.hmm_regime.n_returns(),
+    );
+
+    let fmt_opt = |v: Option<f64>, prec: usize| v.map_or("-".to_string(), |x| format!("{:.*}", prec, x));
+    eprintln!(
+        "[DIAG]   vol_surface: atm_iv={} rv={:.8} iv_rv={} iv_rv_avg={}/{} term_b={} n_pts={}",
+        fmt_opt(state.atm_iv(), 8), sigma, fmt_opt(state.iv_rv_spread(), 8),
+        fmt_opt(state.vol_surface.mean_iv_rv_spread(), 8), state.vol_surface.history().len(),
+        fmt_opt(state.iv_term_slope(), 3), state.vol_surface.points().len(),
     );
 
     // Per-strategy gate analysis
@@ -670,16 +1090,16 @@ fn log_strategy_diagnostics(state: &MarketState, now_ms: i64, house_side: &Optio
         z_abs, cc_fair, cc_ask, cc_edge, cc_gate,
     );
 
-    // convexity_fade: needs regime=Range|Ambiguous, dist_frac <= 0.003, edge >= 0.02
-    let cf_gate = if regime == Regime::Trend { "regime=Trend" }
+    // convexity_fade: needs trend_prob <= 0.85, dist_frac <= 0.003, edge >= 0.02
+    let cf_gate = if trend_prob > 0.85 { "trend_p>0.85" }
         else if dist_frac > 0.003 { "dist>0.3%" }
         else { "PASS(regime+dist)" };
     eprintln!(
-        "[DIAG]   convexity_fade: regime={:?} dist_frac={:.5} → {}",
-        regime, dist_frac, cf_gate,
+        "[DIAG]   convexity_fade: trend_p={:.2} scale={:.2} dist_frac={:.5} → {}",
+        trend_prob, 1.0 - trend_prob, dist_frac, cf_gate,
     );
 
-    // lp_extreme: needs |z| >= 1.5, losing_ask < 0.25, !Trend
+    // lp_extreme: needs |z| >= 1.5, losing_ask < 0.25, trend_prob <= 0.85
     let (lp_ask, lp_side) = if z > 0.0 {
         (state.down_ask, "Down")
     } else {
@@ -688,14 +1108,14 @@ fn log_strategy_diagnostics(state: &MarketState, now_ms: i64, house_side: &Optio
     let market_dur_s = (state.info.end_ms - state.info.start_ms) as f64 / 1000.0;
     let lp_min_tau = (market_dur_s * 0.20).max(60.0);
     let lp_tau_msg = format!("tau<{:.0}", lp_min_tau);
-    let lp_gate = if regime == Regime::Trend { "regime=Trend" }
+    let lp_gate = if trend_prob > 0.85 { "trend_p>0.85" }
         else if tau < lp_min_tau { &lp_tau_msg }
         else if z_abs < 1.5 { "z<1.5" }
         else if lp_ask <= 0.0 || lp_ask >= 0.25 { "ask>=0.25" }
         else { "PASS" };
     eprintln!(
-        "[DIAG]   lp_extreme: z_abs={:.2} losing_side={} ask={:.3} regime={:?} → {}",
-        z_abs, lp_side, lp_ask, regime, lp_gate,
+        "[DIAG]   lp_extreme: z_abs={:.2} losing_side={} ask={:.3} trend_p={:.2} → {}",
+        z_abs, lp_side, lp_ask, trend_prob, lp_gate,
     );
 
     // strike_misalign: only in open window (interval-dependent)
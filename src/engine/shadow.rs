@@ -0,0 +1,580 @@
+//! Shadow mode: paper-trade strategies live without dispatching orders.
+//!
+//! Strategies toggled `STRAT_<NAME>=shadow` are evaluated with the rest, but
+//! [`Shadow::process`] takes their signals out of each batch and runs them
+//! through `process_signals` on a separate book: its own risk manager, house
+//! view and flip count, so paper trading never consumes live limits or steers
+//! live coherence. Orders go to a [`PaperSink`] instead of the gateway:
+//!
+//! - **Takers** walk the live book up to their limit. FOK orders fill in full
+//!   or are rejected; GTD/GTC orders rest whatever the book can't take.
+//! - **Post-only** orders that would cross are `Unmatched`; the rest queue in a
+//!   [`QueueFillSim`] and fill as live book updates move through them.
+//! - **Pairs** fill only if both legs can fill in full on this market's book.
+//! - **Cross-market** orders fill at that market's top-of-book quote.
+//!
+//! Fills are acked back to the strategies like live ones and settled at market
+//! end on their own, never touching live position, Greeks or daily PnL.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::config::Config;
+use crate::engine::pipeline::{self, DeconflictionPolicy, HouseSide, ProcessConfig, SignalSink, StrategyHost};
+use crate::engine::queue_sim::{QueueFillSim, PRICE_EPS};
+use crate::engine::risk::StrategyRiskManager;
+use crate::engine::state::{CrossLedger, MarketState, StrategyStats};
+use crate::math::fees::Liquidity;
+use crate::strategies::registry;
+use crate::types::{Fill, Instrument, Order, OrderAck, OrderAction, OrderStatus, OrderType, Side, Signal};
+
+/// Fills smaller than this (USDC) are ignored.
+const DUST: f64 = 1e-6;
+
+// ─── Paper book ─────────────────────────────────────────────────────────────
+
+/// Simulated execution for shadow orders.
+#[derive(Default)]
+pub struct PaperBook {
+    sim: QueueFillSim,
+    /// Fills not yet acked to the strategies.
+    new_fills: Vec<Fill>,
+    /// Rejections not yet acked to the strategies: (strategy, ack).
+    new_acks: Vec<(&'static str, OrderAck)>,
+    /// Pending buy exposure of orders that will not fill: (strategy, size).
+    released: Vec<(&'static str, f64)>,
+    /// Current-market fills, settled at market end.
+    fills: Vec<Fill>,
+    /// Cross-market fills, settled on their own market's expiry.
+    cross: CrossLedger,
+    /// (strike, end_ms) of the cross market each cross order trades.
+    cross_terms: HashMap<u64, (f64, i64)>,
+    /// Every paper order ID placed this market.
+    ids: HashSet<u64>,
+}
+
+impl PaperBook {
+    /// Execute `order` against the live book.
+    pub fn place(&mut self, order: &Order, state: &MarketState) {
+        self.ids.insert(order.id);
+        if let Instrument::Cross(interval) = order.instrument {
+            let cm = state.cross_markets.get(&interval);
+            let quote = cm.map_or(0.0, |cm| match (order.side, order.action) {
+                (Side::Up, OrderAction::Buy) => cm.up_ask,
+                (Side::Down, OrderAction::Buy) => cm.down_ask,
+                (Side::Up, OrderAction::Sell) => cm.up_bid,
+                (Side::Down, OrderAction::Sell) => cm.down_bid,
+            });
+            if let Some(cm) = cm.filter(|_| quote > 0.0 && crosses(order.action, quote, order.price)) {
+                self.cross_terms.insert(order.id, (cm.strike, cm.end_ms));
+                self.fill(order, quote, order.size, state);
+            } else {
+                self.reject(order, "cross market not marketable");
+            }
+            return;
+        }
+
+        if order.post_only {
+            let (_, contra) = touch(state, order.side, order.action);
+            if contra > 0.0 && crosses(order.action, contra, order.price) {
+                self.release(order);
+                self.new_acks.push((order.strategy, ack(order.id, OrderStatus::Unmatched)));
+            } else {
+                self.sim.post(order, state);
+            }
+            return;
+        }
+
+        let (filled, avg_price) = take(state, order.side, order.action, order.price, order.size);
+        if order.order_type == OrderType::FOK {
+            if filled < order.size - DUST {
+                self.reject(order, "FOK not matched");
+            } else {
+                self.fill(order, avg_price, order.size, state);
+            }
+            return;
+        }
+
+        // GTD/GTC takers: the marketable part fills now, the rest rests at the limit
+        let remaining = order.size - filled;
+        if remaining > DUST {
+            let mut rest = order.clone();
+            rest.size = remaining;
+            self.sim.post(&rest, state);
+        }
+        if filled > DUST {
+            self.fill(order, avg_price, filled, state);
+        }
+    }
+
+    /// Both legs fill in full, or neither does.
+    pub fn place_pair(&mut self, legs: [&Order; 2], state: &MarketState) {
+        self.ids.extend(legs.iter().map(|o| o.id));
+        let takes = legs.map(|o| take(state, o.side, o.action, o.price, o.size));
+        let complete = legs
+            .iter()
+            .zip(&takes)
+            .all(|(o, (filled, _))| o.instrument == Instrument::Current && *filled >= o.size - DUST);
+        for (order, (_, avg_price)) in legs.into_iter().zip(takes) {
+            if complete {
+                self.fill(order, avg_price, order.size, state);
+            } else {
+                self.reject(order, "pair not matched");
+            }
+        }
+    }
+
+    /// Whether `order_id` was placed on paper.
+    pub fn owns(&self, order_id: u64) -> bool {
+        self.ids.contains(&order_id)
+    }
+
+    fn fill(&mut self, order: &Order, price: f64, size: f64, state: &MarketState) {
+        self.new_fills.push(Fill {
+            order_id: order.id,
+            strategy: order.strategy,
+            side: order.side,
+            price,
+            size: match order.action {
+                OrderAction::Buy => size,
+                OrderAction::Sell => -size,
+            },
+            fee: state.info.fees.fill_fee(price, size, Liquidity::Taker),
+        });
+    }
+
+    /// Give back the pending exposure of a buy that will not fill.
+    fn release(&mut self, order: &Order) {
+        if order.action == OrderAction::Buy {
+            self.released.push((order.strategy, order.size));
+        }
+    }
+
+    fn reject(&mut self, order: &Order, reason: &str) {
+        self.release(order);
+        let status = OrderStatus::Rejected(format!("paper: {}", reason));
+        self.new_acks.push((order.strategy, ack(order.id, status)));
+    }
+}
+
+fn ack(order_id: u64, status: OrderStatus) -> OrderAck {
+    OrderAck {
+        order_id,
+        status,
+        filled_price: None,
+        filled_size: None,
+        latency_ms: 0.0,
+        clob_order_id: None,
+        raw_response: None,
+    }
+}
+
+/// Does a contra price `contra` trade against our `limit`?
+#[inline]
+fn crosses(action: OrderAction, contra: f64, limit: f64) -> bool {
+    match action {
+        OrderAction::Buy => contra <= limit + PRICE_EPS,
+        OrderAction::Sell => contra >= limit - PRICE_EPS,
+    }
+}
+
+/// (best price on our side, best price on the contra side) for a token; 0 = unknown.
+fn touch(state: &MarketState, side: Side, action: OrderAction) -> (f64, f64) {
+    let (bid, ask) = match side {
+        Side::Up => (state.up_bid, state.up_ask),
+        Side::Down => (state.down_bid, state.down_ask),
+    };
+    match action {
+        OrderAction::Buy => (bid, ask),
+        OrderAction::Sell => (ask, bid),
+    }
+}
+
+/// Walk the contra side of the book up to `limit` for `notional` USDC.
+/// Returns (USDC filled, average price). Without book depth the top-of-book
+/// quote is taken as deep enough.
+fn take(state: &MarketState, side: Side, action: OrderAction, limit: f64, notional: f64) -> (f64, f64) {
+    let book = match side {
+        Side::Up => &state.up_book,
+        Side::Down => &state.down_book,
+    };
+    let levels = match action {
+        OrderAction::Buy => &book.asks,
+        OrderAction::Sell => &book.bids,
+    };
+    if levels.is_empty() {
+        let (_, contra) = touch(state, side, action);
+        return if contra > 0.0 && crosses(action, contra, limit) { (notional, contra) } else { (0.0, 0.0) };
+    }
+
+    let (mut usdc, mut shares) = (0.0, 0.0);
+    for &(price, size) in levels {
+        if usdc >= notional - DUST || !crosses(action, price, limit) {
+            break;
+        }
+        let part = (size * price).min(notional - usdc);
+        usdc += part;
+        shares += part / price;
+    }
+    if shares > 0.0 { (usdc, usdc / shares) } else { (0.0, 0.0) }
+}
+
+// ─── Sink ───────────────────────────────────────────────────────────────────
+
+/// `SignalSink` for shadow strategies: orders go to the [`PaperBook`].
+pub struct PaperSink<'a> {
+    pub book: &'a mut PaperBook,
+    pub host: &'a mut StrategyHost,
+}
+
+impl<'a> SignalSink for PaperSink<'a> {
+    fn on_signal(&mut self, _sig: &Signal, _state: &MarketState, _now_ms: i64) {}
+
+    fn on_order(&mut self, sig: &Signal, order: &Order, state: &MarketState, _now_ms: i64) {
+        self.host.on_order_sent(order, state);
+        eprintln!(
+            "[SHADOW] {} {:?} {:?} edge={:.3} fair={:.3} mkt={:.3} sz=${:.1} {:?} post_only={}",
+            sig.strategy, order.action, sig.side, sig.edge, sig.fair_value,
+            sig.market_price, order.size, order.order_type, order.post_only,
+        );
+        self.book.place(order, state);
+    }
+
+    fn on_pair(&mut self, legs: [(&Signal, &Order); 2], state: &MarketState, _now_ms: i64) {
+        let [(_, up), (_, down)] = legs;
+        self.host.on_order_sent(up, state);
+        self.host.on_order_sent(down, state);
+        eprintln!(
+            "[SHADOW] {} pair {:?} UP@{:.3} + DOWN@{:.3} sz=${:.1}",
+            up.strategy, up.action, up.price, down.price, up.size,
+        );
+        self.book.place_pair([up, down], state);
+    }
+}
+
+// ─── Shadow book ────────────────────────────────────────────────────────────
+
+/// Paper-trading book for one market's shadow strategies.
+pub struct Shadow {
+    /// Registry names of the strategies in shadow mode.
+    names: Vec<&'static str>,
+    risk: StrategyRiskManager,
+    house: HouseSide,
+    process_config: ProcessConfig,
+    book: PaperBook,
+    buf: Vec<Signal>,
+    fill_buf: Vec<Fill>,
+}
+
+impl Shadow {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            names: registry::STRATEGIES
+                .iter()
+                .filter(|s| config.is_strategy_shadow(s.name))
+                .map(|s| s.name)
+                .collect(),
+            risk: StrategyRiskManager::new(config),
+            house: HouseSide::default(),
+            process_config: ProcessConfig::live().with_deconfliction(DeconflictionPolicy::from_config(config)),
+            book: PaperBook::default(),
+            buf: Vec::with_capacity(4),
+            fill_buf: Vec::with_capacity(4),
+        }
+    }
+
+    pub fn names(&self) -> &[&'static str] {
+        &self.names
+    }
+
+    pub fn is_shadow(&self, strategy: &str) -> bool {
+        self.names.contains(&strategy)
+    }
+
+    /// Take the shadow strategies' signals out of `signals` and run them
+    /// through the pipeline against the paper book.
+    pub fn process(
+        &mut self,
+        signals: &mut Vec<Signal>,
+        state: &mut MarketState,
+        host: &mut StrategyHost,
+        next_order_id: &mut u64,
+        now_ms: i64,
+    ) {
+        if self.names.is_empty() {
+            return;
+        }
+        self.buf.clear();
+        let mut i = 0;
+        while i < signals.len() {
+            if self.is_shadow(signals[i].strategy) {
+                self.buf.push(signals.remove(i));
+            } else {
+                i += 1;
+            }
+        }
+        if self.buf.is_empty() {
+            return;
+        }
+
+        // Market totals and pending orders describe live trading only
+        let live = (state.total_signals, state.total_orders, state.position.pending_orders);
+        let mut sink = PaperSink { book: &mut self.book, host };
+        pipeline::process_signals(
+            &mut self.buf, state, &mut self.risk,
+            &mut self.house, next_order_id, now_ms,
+            &self.process_config, None, &mut sink,
+        );
+        (state.total_signals, state.total_orders, state.position.pending_orders) = live;
+
+        self.deliver(host, state);
+    }
+
+    /// Advance resting paper orders against the latest book and quotes.
+    pub fn on_market_update(&mut self, host: &mut StrategyHost, state: &mut MarketState) {
+        if self.book.sim.resting().is_empty() {
+            return;
+        }
+        self.book.sim.on_market_update(state, &mut self.book.new_fills);
+        self.deliver(host, state);
+    }
+
+    /// Fix cross-market settlement prices as their windows expire.
+    pub fn on_binance_trade(&mut self, state: &MarketState) {
+        if !self.book.cross.fills.is_empty() {
+            self.book.cross.mark(state.bn.binance_price, state.bn.binance_ts);
+        }
+    }
+
+    /// Cancel a paper order if it is still resting. `false` if `order_id` is
+    /// not a paper order (it belongs to the gateway).
+    pub fn cancel(&mut self, order_id: u64, host: &mut StrategyHost, state: &MarketState) -> bool {
+        if !self.book.owns(order_id) {
+            return false;
+        }
+        if let Some(o) = self.book.sim.cancel(order_id) {
+            if o.action == OrderAction::Buy {
+                self.risk.release_pending(o.strategy, o.remaining);
+            }
+            host.on_order_ack(o.strategy, &ack(order_id, OrderStatus::Cancelled), state);
+        }
+        true
+    }
+
+    /// Ack new paper fills and rejections to the strategies and book the fills.
+    fn deliver(&mut self, host: &mut StrategyHost, state: &mut MarketState) {
+        for (strategy, size) in self.book.released.drain(..) {
+            self.risk.release_pending(strategy, size);
+        }
+        for (strategy, ack) in self.book.new_acks.drain(..) {
+            host.on_order_ack(strategy, &ack, state);
+        }
+        if self.book.new_fills.is_empty() {
+            return;
+        }
+        std::mem::swap(&mut self.fill_buf, &mut self.book.new_fills);
+        host.on_resting_fills(&self.fill_buf, &self.book.sim, state);
+        for fill in self.fill_buf.drain(..) {
+            state.strategy_stats.entry(fill.strategy).or_insert_with(StrategyStats::new).filled += 1;
+            eprintln!(
+                "[SHADOW] #{} [{}] {:?} filled ${:.2} @ {:.3}",
+                fill.order_id, fill.strategy, fill.side, fill.size, fill.price,
+            );
+            match self.book.cross_terms.remove(&fill.order_id) {
+                Some((strike, end_ms)) => {
+                    self.risk.on_cross_fill(&fill);
+                    self.book.cross.record(fill, strike, end_ms);
+                }
+                None => {
+                    self.risk.on_fill(&fill);
+                    self.book.fills.push(fill);
+                }
+            }
+        }
+    }
+
+    /// Settle the market's paper fills: PnL per shadow strategy (0 without
+    /// fills). Resting paper orders are dropped.
+    pub fn settle(&mut self, outcome: Side, final_price: f64) -> HashMap<&'static str, f64> {
+        self.book.sim.clear();
+        self.book.ids.clear();
+        let mut pnl: HashMap<&'static str, f64> = self.names.iter().map(|&n| (n, 0.0)).collect();
+        for fill in self.book.fills.drain(..) {
+            *pnl.entry(fill.strategy).or_insert(0.0) += fill.pnl(outcome);
+        }
+        for (cross_outcome, fill) in self.book.cross.settle(final_price) {
+            *pnl.entry(fill.strategy).or_insert(0.0) += fill.pnl(cross_outcome);
+        }
+        pnl
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::test_helpers::*;
+
+    fn shadow_of(names: &[&str]) -> Shadow {
+        let mut config = make_config();
+        for &name in names {
+            config.strategy_toggles.insert(name.into(), true);
+            config.shadow_strategies.insert(name.into());
+        }
+        Shadow::new(&config)
+    }
+
+    /// A post-only GTC buy, as a resting paper order.
+    fn post_only(id: u64, price: f64, size: f64) -> Order {
+        Order {
+            is_passive: true,
+            order_type: OrderType::GTC,
+            post_only: true,
+            ..make_order(id, Side::Up, OrderAction::Buy, price, size)
+        }
+    }
+
+    // ── Routing ──
+
+    /// Scenario: One batch with a shadow latency_arb buy and a live certainty_capture buy,
+    ///           $50 offered at the UP ask.
+    /// Expected: Only the live signal stays in the batch; the shadow one is paper-filled,
+    ///           counted in its strategy stats but not in the market totals, and settles
+    ///           to a paper profit on an UP outcome.
+    #[test]
+    fn test_process_takes_only_shadow_signals() {
+        let (mut state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        inject_book(&mut state, Side::Up, vec![(0.48, 100.0)], vec![(0.50, 100.0)]);
+        let mut shadow = shadow_of(&["latency_arb"]);
+        let mut host = StrategyHost::new(Vec::new());
+        let mut next_id = 1;
+        let mut signals = vec![
+            make_signal("latency_arb", Side::Up, 0.05, 0.8, 0.50),
+            make_signal("certainty_capture", Side::Up, 0.05, 0.8, 0.50),
+        ];
+
+        shadow.process(&mut signals, &mut state, &mut host, &mut next_id, now);
+
+        assert_eq!(signals.len(), 1);
+        assert_eq!(signals[0].strategy, "certainty_capture");
+        assert_eq!((state.total_signals, state.total_orders), (0, 0), "live totals untouched");
+        assert_eq!(state.position.pending_orders, 0);
+        let stats = &state.strategy_stats["latency_arb"];
+        assert_eq!((stats.orders, stats.filled), (1, 1));
+        assert_eq!(next_id, 2, "paper orders share the engine's ID sequence");
+
+        let pnl = shadow.settle(Side::Up, 95_500.0);
+        assert!(pnl["latency_arb"] > 0.0, "pnl = {}", pnl["latency_arb"]);
+        assert!(state.position.holdings.is_empty(), "live position untouched");
+    }
+
+    /// Scenario: No strategy in shadow mode.
+    /// Expected: The batch is left as it is.
+    #[test]
+    fn test_process_noop_without_shadow_strategies() {
+        let (mut state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        let mut shadow = shadow_of(&[]);
+        let mut host = StrategyHost::new(Vec::new());
+        let mut next_id = 1;
+        let mut signals = vec![make_signal("latency_arb", Side::Up, 0.05, 0.8, 0.50)];
+        shadow.process(&mut signals, &mut state, &mut host, &mut next_id, now);
+        assert_eq!(signals.len(), 1);
+        assert_eq!(next_id, 1);
+    }
+
+    // ── Execution ──
+
+    /// Scenario: UP asks $10 at 0.50 and $52 at 0.52; FOK buys of $20 limited at 0.52 and at 0.50.
+    /// Expected: The first fills in full at the depth-weighted price between the levels;
+    ///           the second can only get $10 and is rejected.
+    #[test]
+    fn test_fok_walks_depth() {
+        let (mut state, _) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        inject_book(&mut state, Side::Up, vec![(0.48, 100.0)], vec![(0.50, 20.0), (0.52, 100.0)]);
+        let mut book = PaperBook::default();
+
+        book.place(&make_order(1, Side::Up, OrderAction::Buy, 0.52, 20.0), &state);
+        assert_eq!(book.new_fills.len(), 1);
+        let fill = &book.new_fills[0];
+        let expected = 20.0 / (20.0 + 10.0 / 0.52);
+        assert!((fill.price - expected).abs() < 1e-9, "avg price {}", fill.price);
+        assert_eq!(fill.size, 20.0);
+        assert!(fill.fee > 0.0, "taker fee charged");
+
+        book.place(&make_order(2, Side::Up, OrderAction::Buy, 0.50, 20.0), &state);
+        assert_eq!(book.new_fills.len(), 1);
+        assert!(matches!(book.new_acks[0].1.status, OrderStatus::Rejected(_)));
+    }
+
+    /// Scenario: GTD buy of $20 at 0.50 with $10 offered there.
+    /// Expected: $10 fills now, the other $10 rests at the limit.
+    #[test]
+    fn test_gtd_rests_remainder() {
+        let (mut state, _) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        inject_book(&mut state, Side::Up, vec![(0.48, 100.0)], vec![(0.50, 20.0), (0.52, 100.0)]);
+        let mut book = PaperBook::default();
+        book.place(&Order { order_type: OrderType::GTD, ..make_order(1, Side::Up, OrderAction::Buy, 0.50, 20.0) }, &state);
+        assert_eq!(book.new_fills.len(), 1);
+        assert!((book.new_fills[0].size - 10.0).abs() < 1e-9);
+        assert_eq!(book.sim.resting().len(), 1);
+        assert!((book.sim.resting()[0].remaining - 10.0).abs() < 1e-9);
+    }
+
+    /// Scenario: Post-only bids at the ask (would cross) and one tick below it; then the
+    ///           ask drops through the resting bid.
+    /// Expected: The crossing bid is `Unmatched`; the other rests and fills as a maker
+    ///           once the ask reaches it.
+    #[test]
+    fn test_post_only_rests_until_crossed() {
+        let (mut state, _) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        state.up_bid = 0.48;
+        inject_book(&mut state, Side::Up, vec![(0.48, 100.0)], vec![(0.50, 100.0)]);
+        let mut shadow = shadow_of(&["latency_arb"]);
+        let mut host = StrategyHost::new(Vec::new());
+
+        shadow.book.place(&post_only(1, 0.50, 10.0), &state);
+        shadow.book.place(&post_only(2, 0.49, 10.0), &state);
+        assert!(matches!(shadow.book.new_acks[0].1.status, OrderStatus::Unmatched));
+        assert_eq!(shadow.book.sim.resting().len(), 1);
+
+        shadow.deliver(&mut host, &mut state);
+        state.up_ask = 0.49;
+        inject_book(&mut state, Side::Up, vec![(0.48, 100.0)], vec![(0.49, 100.0)]);
+        shadow.on_market_update(&mut host, &mut state);
+        assert!(shadow.book.sim.resting().is_empty());
+        assert_eq!(shadow.book.fills.len(), 1);
+        assert!(shadow.book.fills[0].fee <= 0.0, "maker fill pays no taker fee");
+    }
+
+    /// Scenario: Pair whose DOWN leg has only $5 offered for a $10 leg.
+    /// Expected: Neither leg fills; both are rejected.
+    #[test]
+    fn test_pair_all_or_nothing() {
+        let (mut state, _) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.45, 0.50);
+        inject_book(&mut state, Side::Up, vec![(0.43, 100.0)], vec![(0.45, 100.0)]);
+        inject_book(&mut state, Side::Down, vec![(0.48, 100.0)], vec![(0.50, 10.0)]);
+        let mut book = PaperBook::default();
+        let up = make_order(1, Side::Up, OrderAction::Buy, 0.45, 10.0);
+        let down = make_order(2, Side::Down, OrderAction::Buy, 0.50, 10.0);
+        book.place_pair([&up, &down], &state);
+        assert!(book.new_fills.is_empty());
+        assert_eq!(book.new_acks.len(), 2);
+    }
+
+    /// Scenario: Cancel requests for a resting paper order and for an ID never placed on paper.
+    /// Expected: The paper order is pulled (and its exposure released); the other ID is
+    ///           left for the gateway.
+    #[test]
+    fn test_cancel_only_paper_orders() {
+        let (mut state, now) = make_state(95_000.0, 95_500.0, 0.001, 120.0, 0.50, 0.50);
+        state.up_bid = 0.48;
+        inject_book(&mut state, Side::Up, vec![(0.48, 100.0)], vec![(0.50, 100.0)]);
+        let mut shadow = shadow_of(&["latency_arb"]);
+        let mut host = StrategyHost::new(Vec::new());
+        let resting = post_only(7, 0.49, 10.0);
+        shadow.risk.on_order_sent("latency_arb", now, 10.0);
+        shadow.book.place(&resting, &state);
+
+        assert!(shadow.cancel(7, &mut host, &state));
+        assert!(shadow.book.sim.resting().is_empty());
+        assert!(!shadow.cancel(8, &mut host, &state));
+    }
+}
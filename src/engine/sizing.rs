@@ -0,0 +1,416 @@
+use std::collections::HashMap;
+
+use crate::config::Config;
+use crate::engine::state::MarketState;
+use crate::math::pricing::vega_bin;
+use crate::types::{Side, Signal};
+
+/// Hard cap on any single stake, as a fraction of bankroll.
+pub const MAX_KELLY_FRAC: f64 = 0.15;
+/// Default fractional-Kelly multiplier (half-Kelly).
+pub const DEFAULT_KELLY_FRACTION: f64 = 0.5;
+
+// ─── Holdings ────────────────────────────────────────────────────────────────
+
+/// Net exposure to one market's binary outcome, in settlement units
+/// (`price * size` at risk, `(1 - price) * size` won — same as `Fill::pnl`).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Holdings {
+    pub up_size: f64,
+    pub up_cost: f64,
+    pub down_size: f64,
+    pub down_cost: f64,
+    /// Tokens held per side: Σ size / price over fills (sells, with negative
+    /// size, take them back out). Each pays $1 if its side wins.
+    pub up_shares: f64,
+    pub down_shares: f64,
+}
+
+impl Holdings {
+    pub fn add(&mut self, side: Side, price: f64, size: f64) {
+        let shares = if price > 0.0 { size / price } else { 0.0 };
+        match side {
+            Side::Up => {
+                self.up_size += size;
+                self.up_cost += price * size;
+                self.up_shares += shares;
+            }
+            Side::Down => {
+                self.down_size += size;
+                self.down_cost += price * size;
+                self.down_shares += shares;
+            }
+        }
+    }
+
+    /// Take `pairs` UP+DOWN full sets out after they were merged into USDC.
+    /// Each side's size and cost shrink in proportion to the shares removed.
+    pub fn merge(&mut self, pairs: f64) {
+        fn take(size: &mut f64, cost: &mut f64, shares: &mut f64, n: f64) {
+            if *shares <= 0.0 {
+                return;
+            }
+            let keep = ((*shares - n) / *shares).max(0.0);
+            *size *= keep;
+            *cost *= keep;
+            *shares *= keep;
+        }
+        take(&mut self.up_size, &mut self.up_cost, &mut self.up_shares, pairs);
+        take(&mut self.down_size, &mut self.down_cost, &mut self.down_shares, pairs);
+    }
+
+    /// Tokens held on `side`, floored at zero.
+    #[inline]
+    pub fn shares(&self, side: Side) -> f64 {
+        match side {
+            Side::Up => self.up_shares.max(0.0),
+            Side::Down => self.down_shares.max(0.0),
+        }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.up_size == 0.0 && self.down_size == 0.0
+    }
+
+    /// Settlement payoff (gross of fees) if `outcome` wins.
+    #[inline]
+    pub fn payoff(&self, outcome: Side) -> f64 {
+        match outcome {
+            Side::Up => self.up_size - self.up_cost - self.down_cost,
+            Side::Down => self.down_size - self.down_cost - self.up_cost,
+        }
+    }
+
+    pub fn combined(&self, other: &Holdings) -> Holdings {
+        Holdings {
+            up_size: self.up_size + other.up_size,
+            up_cost: self.up_cost + other.up_cost,
+            down_size: self.down_size + other.down_size,
+            down_cost: self.down_cost + other.down_cost,
+            up_shares: self.up_shares + other.up_shares,
+            down_shares: self.down_shares + other.down_shares,
+        }
+    }
+}
+
+// ─── Kelly math ──────────────────────────────────────────────────────────────
+
+/// Fractional Kelly stake for one more bet on a binary that already carries
+/// exposure.
+///
+/// Every position in a market is a bet on the same outcome, so the bankroll
+/// after settlement is one of two numbers:
+///
+///   W_win  = 1 + a_win  + f·(1-c)/c
+///   W_lose = 1 + a_lose - f
+///
+/// where `a_*` are the existing payoffs as bankroll fractions. Maximising
+/// `p·ln W_win + (1-p)·ln W_lose` gives
+///
+///   f* = p·(1 + a_lose) - (1-p)·(1 + a_win)·c/(1-c)
+///
+/// which reduces to `(p - c)/(1 - c)` on a flat book. Fractional Kelly `k`
+/// targets `k` × the full-Kelly portfolio, so existing holdings are scaled by
+/// `1/k` before solving and the result is scaled back by `k`. A position
+/// already at its fractional target therefore adds nothing.
+///
+/// `p` is the win probability of the bet side, `price` the all-in cost per share.
+pub fn correlated_kelly(p: f64, price: f64, a_win: f64, a_lose: f64, k: f64, max_frac: f64) -> f64 {
+    if price <= 0.0 || price >= 1.0 || k <= 0.0 || p <= price {
+        return 0.0;
+    }
+    let (aw, al) = (a_win / k, a_lose / k);
+    let full = p * (1.0 + al) - (1.0 - p) * (1.0 + aw) * price / (1.0 - price);
+    (k * full).clamp(0.0, max_frac)
+}
+
+/// Shrinkage for uncertainty in the fair value (Baker-McHale style):
+/// scale the stake by edge² / (edge² + Var[fair]). Noisy fair values with
+/// thin edges get cut hardest; 1.0 when the variance is zero.
+#[inline]
+pub fn uncertainty_shrink(edge: f64, fair_var: f64) -> f64 {
+    if edge <= 0.0 {
+        return 0.0;
+    }
+    let e2 = edge * edge;
+    e2 / (e2 + fair_var.max(0.0))
+}
+
+/// Posterior variance of the model fair value from sigma estimation error.
+///
+/// EWMA sigma with `n_eff` effective samples has Var[σ̂] ≈ σ²/(2·n_eff);
+/// delta-method through binary vega gives Var[p] ≈ vega²·σ²/(2·n_eff).
+pub fn fair_variance(state: &MarketState, now_ms: i64) -> f64 {
+    let sigma = state.sigma_real();
+    let tau = state.tau_eff_s(now_ms);
+    let (s, k) = (state.s_est(), state.info.strike);
+    if sigma <= 0.0 || tau <= 0.0 || s <= 0.0 || k <= 0.0 {
+        return 0.0;
+    }
+    let vega = vega_bin(s, k, sigma, tau);
+    let n_eff = state.bn.ewma_vol.effective_samples().max(1.0);
+    vega * vega * sigma * sigma / (2.0 * n_eff)
+}
+
+// ─── Sizer ───────────────────────────────────────────────────────────────────
+
+/// One candidate bet for the sizer.
+pub struct Bet {
+    pub strategy: &'static str,
+    pub side: Side,
+    /// Model probability that `side` wins.
+    pub fair: f64,
+    /// All-in cost per share (quoted price ± fee), see `FeeSchedule::effective_price`.
+    pub price: f64,
+}
+
+/// Portfolio-aware, uncertainty-adjusted Kelly sizing with per-strategy
+/// fractional multipliers. Lives on `MarketState` so strategies size against
+/// the market's current holdings; the pipeline re-sizes each batch so
+/// simultaneous signals are solved as one correlated portfolio.
+#[derive(Clone)]
+pub struct KellySizer {
+    bankroll: f64,
+    default_fraction: f64,
+    fractions: HashMap<String, f64>,
+    max_frac: f64,
+}
+
+impl Default for KellySizer {
+    fn default() -> Self {
+        Self {
+            bankroll: 0.0,
+            default_fraction: DEFAULT_KELLY_FRACTION,
+            fractions: HashMap::new(),
+            max_frac: MAX_KELLY_FRAC,
+        }
+    }
+}
+
+impl KellySizer {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            bankroll: config.bankroll,
+            default_fraction: config.kelly_fraction,
+            fractions: config.kelly_fractions.clone(),
+            max_frac: MAX_KELLY_FRAC,
+        }
+    }
+
+    #[inline]
+    pub fn bankroll(&self) -> f64 {
+        self.bankroll
+    }
+
+    /// Fractional-Kelly multiplier for a strategy.
+    #[inline]
+    pub fn fraction(&self, strategy: &str) -> f64 {
+        self.fractions.get(strategy).copied().unwrap_or(self.default_fraction)
+    }
+
+    /// Stake (bankroll fraction) for `bet` given the market's filled holdings.
+    pub fn size(&self, state: &MarketState, bet: &Bet, now_ms: i64) -> f64 {
+        self.size_with(state, bet, &Holdings::default(), now_ms)
+    }
+
+    /// Stake for `bet` given filled holdings plus `pending` (orders approved
+    /// earlier in the same batch).
+    pub fn size_with(&self, state: &MarketState, bet: &Bet, pending: &Holdings, now_ms: i64) -> f64 {
+        let edge = bet.fair - bet.price;
+        let shrink = uncertainty_shrink(edge, fair_variance(state, now_ms));
+        let k = self.fraction(bet.strategy) * shrink;
+
+        let (a_win, a_lose) = if self.bankroll > 0.0 {
+            let h = state.position.holdings.combined(pending);
+            let other = match bet.side {
+                Side::Up => Side::Down,
+                Side::Down => Side::Up,
+            };
+            (h.payoff(bet.side) / self.bankroll, h.payoff(other) / self.bankroll)
+        } else {
+            (0.0, 0.0)
+        };
+        correlated_kelly(bet.fair, bet.price, a_win, a_lose, k, self.max_frac)
+    }
+
+    /// Multiplier that takes a signal's standalone size to its size given
+    /// `pending` batch orders. Strategy-specific scaling already folded into
+    /// `size_frac` (regime, queue depth) is preserved proportionally.
+    pub fn batch_scale(&self, state: &MarketState, sig: &Signal, pending: &Holdings, now_ms: i64) -> f64 {
+        let bet = Bet {
+            strategy: sig.strategy,
+            side: sig.side,
+            fair: sig.fair_value,
+            price: state.info.fees.effective_price(sig.market_price, sig.liquidity()),
+        };
+        let alone = self.size(state, &bet, now_ms);
+        if alone <= 0.0 {
+            return 1.0;
+        }
+        self.size_with(state, &bet, pending, now_ms) / alone
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::test_helpers::*;
+
+    fn flat(edge: f64, price: f64) -> f64 {
+        correlated_kelly(price + edge, price, 0.0, 0.0, DEFAULT_KELLY_FRACTION, MAX_KELLY_FRAC)
+    }
+
+    // ── Flat book (matches legacy half-Kelly) ──
+
+    /// Scenario: Positive edge (5%) at 50-cent price.
+    /// Expected: Half-Kelly produces a positive position size.
+    #[test]
+    fn test_kelly_positive_edge() {
+        let f = flat(0.05, 0.50);
+        assert!(f > 0.0, "Positive edge should produce positive sizing: {}", f);
+    }
+
+    /// Scenario: Zero edge at 50-cent price.
+    /// Expected: Kelly returns 0 -- no position without edge.
+    #[test]
+    fn test_kelly_zero_edge() {
+        assert_eq!(flat(0.0, 0.50), 0.0);
+    }
+
+    /// Scenario: Price equals 1.0 (certain outcome, no payout upside).
+    /// Expected: Kelly returns 0 -- denominator is zero, no sizing.
+    #[test]
+    fn test_kelly_price_at_one() {
+        assert_eq!(flat(0.05, 1.0), 0.0);
+    }
+
+    /// Scenario: Negative edge (-5%) at 50-cent price.
+    /// Expected: Kelly returns 0 -- never size into a negative-EV trade.
+    #[test]
+    fn test_kelly_negative_edge() {
+        assert_eq!(flat(-0.05, 0.50), 0.0);
+    }
+
+    /// Scenario: Price above 1.0 (impossible for valid binary, but edge case).
+    /// Expected: Kelly returns 0 -- invalid price triggers early return.
+    #[test]
+    fn test_kelly_price_above_one() {
+        assert_eq!(flat(0.10, 1.5), 0.0);
+    }
+
+    /// Scenario: Huge edge (90%) at near-zero price (1 cent) producing raw Kelly ~0.45.
+    /// Expected: Result is clamped to the 15% max position size cap.
+    #[test]
+    fn test_kelly_clamp_at_015() {
+        let f = flat(0.90, 0.01);
+        assert!((f - 0.15).abs() < 1e-10, "Should clamp to 0.15: {}", f);
+    }
+
+    /// Scenario: 10% edge at 40-cent price, within normal sizing range.
+    /// Expected: Result matches exact half-Kelly formula (0.10/0.60)*0.5 = 0.0833.
+    #[test]
+    fn test_kelly_exact_formula() {
+        let f = flat(0.10, 0.40);
+        let expected = (0.10 / 0.60) * 0.5;
+        assert!((f - expected).abs() < 1e-10, "kelly = {}, expected = {}", f, expected);
+    }
+
+    /// Scenario: 5% edge at price 0.99, denominator near zero producing raw Kelly ~2.5.
+    /// Expected: Result is clamped to the 15% max position size cap.
+    #[test]
+    fn test_kelly_price_near_one() {
+        let f = flat(0.05, 0.99);
+        assert!((f - 0.15).abs() < 1e-10, "Should clamp: {}", f);
+    }
+
+    // ── Correlated holdings ──
+
+    /// Scenario: Existing UP holding already equal to the half-Kelly target at the same price.
+    /// Expected: Next UP bet at the same fair/price sizes to zero.
+    #[test]
+    fn test_existing_position_at_target_adds_nothing() {
+        let (p, c) = (0.60, 0.50);
+        let f0 = correlated_kelly(p, c, 0.0, 0.0, 0.5, 1.0);
+        // Holding f0 of bankroll at c: win pays f0·(1-c)/c, loss costs f0
+        let f1 = correlated_kelly(p, c, f0 * (1.0 - c) / c, -f0, 0.5, 1.0);
+        assert!(f1.abs() < 1e-12, "f0={} f1={}", f0, f1);
+    }
+
+    /// Scenario: Existing DOWN position (hedge) when a new UP bet arrives.
+    /// Expected: UP stake is larger than on a flat book — the bets offset.
+    #[test]
+    fn test_opposite_position_increases_stake() {
+        let flat_f = correlated_kelly(0.60, 0.50, 0.0, 0.0, 0.5, 1.0);
+        // Holding DOWN: pays if UP loses, costs if UP wins
+        let hedged = correlated_kelly(0.60, 0.50, -0.05, 0.05, 0.5, 1.0);
+        assert!(hedged > flat_f, "hedged={} flat={}", hedged, flat_f);
+    }
+
+    /// Scenario: Holdings record UP and DOWN fills.
+    /// Expected: Payoffs net each side's cost against the other's winnings.
+    #[test]
+    fn test_holdings_payoff() {
+        let mut h = Holdings::default();
+        h.add(Side::Up, 0.40, 10.0);
+        h.add(Side::Down, 0.50, 4.0);
+        assert!((h.payoff(Side::Up) - (6.0 - 2.0)).abs() < 1e-12);
+        assert!((h.payoff(Side::Down) - (2.0 - 4.0)).abs() < 1e-12);
+    }
+
+    /// Scenario: 40 UP shares bought at 0.40 and 60 DOWN at 0.50; 30 pairs merged.
+    /// Expected: 10 UP / 30 DOWN shares remain, size and cost shrink pro rata.
+    #[test]
+    fn test_holdings_merge() {
+        let mut h = Holdings::default();
+        h.add(Side::Up, 0.40, 16.0);
+        h.add(Side::Down, 0.50, 30.0);
+        assert!((h.shares(Side::Up) - 40.0).abs() < 1e-12);
+        h.merge(30.0);
+        assert!((h.shares(Side::Up) - 10.0).abs() < 1e-12);
+        assert!((h.shares(Side::Down) - 30.0).abs() < 1e-12);
+        assert!((h.up_size - 4.0).abs() < 1e-12 && (h.down_size - 15.0).abs() < 1e-12);
+        assert!((h.up_cost - 1.6).abs() < 1e-12 && (h.down_cost - 7.5).abs() < 1e-12);
+    }
+
+    // ── Uncertainty shrinkage ──
+
+    /// Scenario: Same edge with zero and with large fair-value variance.
+    /// Expected: No shrink at zero variance; shrink to 0.5 when Var = edge².
+    #[test]
+    fn test_uncertainty_shrink() {
+        assert_eq!(uncertainty_shrink(0.05, 0.0), 1.0);
+        assert!((uncertainty_shrink(0.05, 0.0025) - 0.5).abs() < 1e-12);
+        assert_eq!(uncertainty_shrink(-0.01, 0.0), 0.0);
+    }
+
+    /// Scenario: Fair variance near ATM with sigma injected (no EWMA samples yet).
+    /// Expected: Variance is positive but small relative to typical edges.
+    #[test]
+    fn test_fair_variance_positive() {
+        let (state, now) = make_state(95_000.0, 95_200.0, 0.001, 60.0, 0.50, 0.50);
+        let v = fair_variance(&state, now);
+        assert!(v > 0.0 && v < 0.01, "var={}", v);
+    }
+
+    // ── Sizer ──
+
+    /// Scenario: Per-strategy override in config; UP fill already on the book.
+    /// Expected: Override changes the stake; a same-side fill shrinks the next stake.
+    #[test]
+    fn test_sizer_fraction_and_holdings() {
+        let mut config = make_config();
+        config.kelly_fractions.insert("latency_arb".to_string(), 0.25);
+        let (mut state, now) = make_state(95_000.0, 95_200.0, 0.001, 60.0, 0.50, 0.50);
+        state.sizing = KellySizer::from_config(&config);
+
+        let bet = |strategy| Bet { strategy, side: Side::Up, fair: 0.60, price: 0.50 };
+        let la = state.sizing.size(&state, &bet("latency_arb"), now);
+        let cc = state.sizing.size(&state, &bet("certainty_capture"), now);
+        assert!(la > 0.0 && (la / cc - 0.5).abs() < 1e-9, "la={} cc={}", la, cc);
+
+        state.position.record_fill(Side::Up, 0.50, cc * config.bankroll * 0.5);
+        let after = state.sizing.size(&state, &bet("certainty_capture"), now);
+        assert!(after < cc, "after={} before={}", after, cc);
+    }
+}
@@ -1,12 +1,17 @@
 use std::collections::{HashMap, VecDeque};
 
 use crate::config::Interval;
+use crate::engine::execution::ExecutionModel;
+use crate::engine::var::VarScenarios;
+use crate::engine::sizing::{Holdings, KellySizer};
+use crate::strategies::params::StrategyParams;
 use crate::math::ewma::SampledEwmaVol;
 use crate::math::oracle::OracleBasis;
-use crate::math::regime::RegimeClassifier;
+use crate::math::regime::{GaussianHmm, RegimeClassifier, RegimeModel, VarianceRatio};
+use crate::math::vol_surface::{SurfacePoint, VolSurface};
 use crate::math::vwap::VwapTracker;
 use crate::types::{
-    BinanceTrade, CrossMarketQuoteEvent, MarketInfo, OrderAck, OrderStatus, PolymarketBook,
+    BinanceTrade, CrossMarketQuoteEvent, Fill, MarketInfo, OrderAck, OrderStatus, PolymarketBook,
     PolymarketQuote, Side,
 };
 
@@ -162,7 +167,7 @@ impl OrderBook {
     }
 }
 
-/// Cross-market state for Edge 4 (cross-timeframe RV).
+/// Cross-market state for Edge 4 (cross-timeframe RV) and nested-window arbitrage.
 #[derive(Clone)]
 pub struct CrossMarketState {
     pub interval: Interval,
@@ -171,9 +176,71 @@ pub struct CrossMarketState {
     pub down_bid: f64,
     pub down_ask: f64,
     pub strike: f64,
+    pub start_ms: i64,
     pub end_ms: i64,
+    pub up_token_id: String,
+    pub down_token_id: String,
 }
 
+/// A fill on a cross market, settled against that market's own strike and expiry.
+#[derive(Clone)]
+pub struct CrossFill {
+    pub fill: Fill,
+    pub strike: f64,
+    pub end_ms: i64,
+    /// Binance price at the first trade at or after `end_ms`; `None` until then.
+    pub settle_price: Option<f64>,
+}
+
+/// Fills on cross markets. Only markets ending no later than the current one are
+/// traded, so every entry settles before this engine cycle does.
+#[derive(Clone, Default)]
+pub struct CrossLedger {
+    pub fills: Vec<CrossFill>,
+}
+
+impl CrossLedger {
+    pub fn record(&mut self, fill: Fill, strike: f64, end_ms: i64) {
+        self.fills.push(CrossFill { fill, strike, end_ms, settle_price: None });
+    }
+
+    /// Fix the settlement price of every fill whose market has expired by `ts_ms`.
+    pub fn mark(&mut self, price: f64, ts_ms: i64) {
+        for cf in &mut self.fills {
+            if cf.settle_price.is_none() && ts_ms >= cf.end_ms {
+                cf.settle_price = Some(price);
+            }
+        }
+    }
+
+    /// Drain the ledger into (outcome, fill) pairs. Fills not yet marked
+    /// (markets ending with the current one) settle at `final_price`.
+    pub fn settle(&mut self, final_price: f64) -> Vec<(Side, Fill)> {
+        self.fills
+            .drain(..)
+            .map(|cf| {
+                let price = cf.settle_price.unwrap_or(final_price);
+                let outcome = if price - cf.strike >= 0.0 { Side::Up } else { Side::Down };
+                (outcome, cf.fill)
+            })
+            .collect()
+    }
+}
+
+/// Window of 1s returns for the probabilistic regime models (2 minutes).
+const RETURN_WINDOW: usize = 120;
+/// Variance-ratio aggregation lag (seconds).
+const VR_LAG: usize = 5;
+/// HMM regime persistence: P(stay in state) per 1s step.
+const HMM_P_STAY: f64 = 0.95;
+/// Baum-Welch iterations per refit (once per second).
+const HMM_EM_ITERS: usize = 8;
+
+/// Minimum interval between vol-surface refreshes (IV inversion is off the hot path).
+const VOL_SURFACE_REFRESH_MS: i64 = 250;
+/// ATM IV vs RV history window.
+const VOL_SURFACE_HISTORY_MS: i64 = 300_000;
+
 /// Persistent Binance-derived state that survives across markets.
 /// Created once at startup, threaded through each market cycle.
 /// Market 1 warms in ~10s. Market 2+ starts instantly.
@@ -186,6 +253,10 @@ pub struct BinanceState {
     pub prev_binance_price: f64,
     pub vwap_tracker: VwapTracker,
     pub regime: RegimeClassifier,
+    /// Variance-ratio / Hurst trend model over 1s returns.
+    pub vr_regime: VarianceRatio,
+    /// Two-state Gaussian HMM trend model over 1s returns.
+    pub hmm_regime: GaussianHmm,
     /// Cached sigma_real (updated once per second when EWMA samples).
     pub sigma_real_cached: f64,
     /// Per-second sigma floor.
@@ -210,10 +281,46 @@ impl BinanceState {
             prev_binance_price: 0.0,
             vwap_tracker: VwapTracker::new(vwap_window_ms),
             regime: RegimeClassifier::new(regime_window_ms),
+            vr_regime: VarianceRatio::new(VR_LAG, RETURN_WINDOW),
+            hmm_regime: GaussianHmm::new(RETURN_WINDOW, HMM_P_STAY, HMM_EM_ITERS),
             sigma_real_cached: 0.0,
             sigma_floor_per_sec,
         }
     }
+
+    /// Feed one 1s sampled return into the probabilistic regime models.
+    pub fn on_sample_return(&mut self, r: f64) {
+        self.vr_regime.update(r);
+        self.hmm_regime.update(r);
+    }
+
+    /// Last Binance trade price at or before `ts_ms`, from the 30s trade buffer.
+    /// `None` if the buffer does not reach back that far.
+    #[inline]
+    pub fn price_at(&self, ts_ms: i64) -> Option<f64> {
+        let idx = self.trade_buffer.partition_point(|t| t.exchange_ts_ms <= ts_ms);
+        if idx == 0 {
+            return None;
+        }
+        Some(self.trade_buffer[idx - 1].price)
+    }
+
+    /// Probability that BTC is trending, in [0, 1].
+    /// Averages the 1s-return models once warmed up; until then falls back
+    /// to the tick-direction classifier's ramp.
+    #[inline]
+    pub fn trend_prob(&self) -> f64 {
+        let models: [&dyn RegimeModel; 2] = [&self.vr_regime, &self.hmm_regime];
+        let (sum, n) = models
+            .iter()
+            .filter(|m| m.is_ready())
+            .fold((0.0, 0u32), |(s, n), m| (s + m.trend_prob(), n + 1));
+        if n > 0 {
+            sum / n as f64
+        } else {
+            self.regime.trend_prob()
+        }
+    }
 }
 
 /// Owned by the engine task — no Arc, no RwLock, no shared references.
@@ -234,8 +341,20 @@ pub struct MarketState {
     pub oracle: OracleBasis,
     // Cross-timeframe markets (Edge 4)
     pub cross_markets: HashMap<Interval, CrossMarketState>,
+    // Fills on cross markets, settled on their own outcomes
+    pub cross_ledger: CrossLedger,
+    // Implied-vol surface (this market + cross markets)
+    pub vol_surface: VolSurface,
     // Position tracking
     pub position: PositionTracker,
+    // Kelly sizing (set from Config by the runner / backtest)
+    pub sizing: KellySizer,
+    // Strategy threshold overrides (set from Config like `sizing`)
+    pub params: StrategyParams,
+    // Execution-cost model and book flow (set from Config like `sizing`)
+    pub execution: ExecutionModel,
+    // Correlated portfolio VaR scenarios (live engine, rebuilt by the runner)
+    pub var: VarScenarios,
     // Stats (aggregate)
     pub total_signals: u32,
     pub total_orders: u32,
@@ -261,7 +380,13 @@ impl MarketState {
             down_book: OrderBook::new(),
             oracle,
             cross_markets: HashMap::new(),
+            cross_ledger: CrossLedger::default(),
+            vol_surface: VolSurface::new(VOL_SURFACE_HISTORY_MS, VOL_SURFACE_REFRESH_MS),
             position: PositionTracker::new(),
+            sizing: KellySizer::default(),
+            params: StrategyParams::default(),
+            execution: ExecutionModel::default(),
+            var: VarScenarios::default(),
             total_signals: 0,
             total_orders: 0,
             total_filled: 0,
@@ -289,6 +414,9 @@ impl MarketState {
             } else {
                 0.0
             };
+            // Same 1s sample drives the probabilistic regime models
+            let r = bn.ewma_vol.last_return();
+            bn.on_sample_return(r);
         }
 
         // Update VWAP tracker
@@ -301,6 +429,7 @@ impl MarketState {
                 .update(t.exchange_ts_ms, t.price > bn.prev_binance_price);
         }
         bn.prev_binance_price = t.price;
+        let ts = t.exchange_ts_ms;
 
         // Store trade in buffer
         bn.trade_buffer.push_back(t);
@@ -314,6 +443,11 @@ impl MarketState {
         {
             bn.trade_buffer.pop_front();
         }
+
+        // Cross markets that just expired settle on this price
+        if !self.cross_ledger.fills.is_empty() {
+            self.cross_ledger.mark(self.bn.binance_price, ts);
+        }
     }
 
     #[inline]
@@ -331,10 +465,14 @@ impl MarketState {
             self.down_ask = v;
         }
         self.pm_last_ts = q.server_ts_ms;
+        self.maybe_refresh_vol_surface(q.server_ts_ms);
     }
 
     #[inline]
     pub fn on_book_update(&mut self, book: PolymarketBook) {
+        let ts = self.last_event_ts();
+        let old = if book.is_up_token { &self.up_book } else { &self.down_book };
+        self.execution.on_book(book.is_up_token, old, &book.bids, &book.asks, ts);
         if book.is_up_token {
             self.up_book.apply_snapshot(book.bids, book.asks);
             self.up_bid = self.up_book.best_bid();
@@ -344,28 +482,111 @@ impl MarketState {
             self.down_bid = self.down_book.best_bid();
             self.down_ask = self.down_book.best_ask();
         }
+        self.maybe_refresh_vol_surface(self.last_event_ts());
     }
 
+    /// Latest quote per interval; a quote for the next window replaces the expired one.
     pub fn on_cross_market_quote(&mut self, e: CrossMarketQuoteEvent) {
-        self.cross_markets
-            .entry(e.interval)
-            .and_modify(|cm| {
-                cm.up_bid = e.up_bid;
-                cm.up_ask = e.up_ask;
-                cm.down_bid = e.down_bid;
-                cm.down_ask = e.down_ask;
-                cm.strike = e.strike;
-                cm.end_ms = e.end_ms;
-            })
-            .or_insert(CrossMarketState {
+        self.cross_markets.insert(
+            e.interval,
+            CrossMarketState {
                 interval: e.interval,
                 up_bid: e.up_bid,
                 up_ask: e.up_ask,
                 down_bid: e.down_bid,
                 down_ask: e.down_ask,
                 strike: e.strike,
+                start_ms: e.start_ms,
                 end_ms: e.end_ms,
-            });
+                up_token_id: e.up_token_id,
+                down_token_id: e.down_token_id,
+            },
+        );
+        self.maybe_refresh_vol_surface(self.last_event_ts());
+    }
+
+    /// Latest known event timestamp (book snapshots carry no server time).
+    #[inline]
+    fn last_event_ts(&self) -> i64 {
+        self.pm_last_ts.max(self.bn.binance_ts)
+    }
+
+    #[inline]
+    fn maybe_refresh_vol_surface(&mut self, now_ms: i64) {
+        if self.vol_surface.refresh_due(now_ms) {
+            self.refresh_vol_surface(now_ms);
+        }
+    }
+
+    /// Recompute bid/ask IVs for this market and every tracked cross market,
+    /// drop expired points, reprice them at sigma_real, and sample ATM IV vs
+    /// sigma_real (1s cadence).
+    pub fn refresh_vol_surface(&mut self, now_ms: i64) {
+        let s = self.s_est();
+        if s <= 0.0 || now_ms <= 0 {
+            return;
+        }
+        self.vol_surface.mark_refreshed(now_ms);
+
+        let tau = self.tau_eff_s(now_ms);
+        if tau > 0.0 && self.info.strike > 0.0 {
+            let own = SurfacePoint::compute(
+                s,
+                self.info.strike,
+                self.info.end_ms,
+                tau,
+                (self.up_bid, self.up_ask),
+                (self.down_bid, self.down_ask),
+            );
+            self.vol_surface.upsert(own, now_ms);
+        }
+
+        for cm in self.cross_markets.values() {
+            let cm_tau = (cm.end_ms - now_ms) as f64 / 1000.0;
+            if cm_tau <= 0.0 || cm.strike <= 0.0 {
+                continue;
+            }
+            let pt = SurfacePoint::compute(
+                s,
+                cm.strike,
+                cm.end_ms,
+                cm_tau,
+                (cm.up_bid, cm.up_ask),
+                (cm.down_bid, cm.down_ask),
+            );
+            self.vol_surface.upsert(pt, now_ms);
+        }
+        self.vol_surface.prune(now_ms);
+
+        let rv = self.sigma_real();
+        self.vol_surface.reprice(s, rv);
+        if let (Some(iv), true) = (self.atm_iv(), rv > 0.0) {
+            self.vol_surface.record(now_ms, iv, rv);
+        }
+    }
+
+    /// ATM implied vol (per-second): mid IV of this market's UP/DOWN quotes.
+    #[inline]
+    pub fn atm_iv(&self) -> Option<f64> {
+        self.vol_surface
+            .point(self.info.end_ms, self.info.strike)
+            .and_then(|p| p.mid_iv())
+    }
+
+    /// Current IV − RV spread (per-second). Positive = options rich vs realized.
+    #[inline]
+    pub fn iv_rv_spread(&self) -> Option<f64> {
+        let rv = self.sigma_real();
+        if rv <= 0.0 {
+            return None;
+        }
+        self.atm_iv().map(|iv| iv - rv)
+    }
+
+    /// IV term-structure exponent b in σ(τ) = a · τ^b across tracked expiries.
+    #[inline]
+    pub fn iv_term_slope(&self) -> Option<f64> {
+        self.vol_surface.term_slope()
     }
 
     #[inline]
@@ -422,6 +643,8 @@ pub struct PositionTracker {
     pub size: f64,
     pub avg_price: f64,
     pub pending_orders: u32,
+    /// Per-side filled exposure, used by the Kelly sizer.
+    pub holdings: Holdings,
 }
 
 impl PositionTracker {
@@ -431,9 +654,20 @@ impl PositionTracker {
             size: 0.0,
             avg_price: 0.0,
             pending_orders: 0,
+            holdings: Holdings::default(),
         }
     }
 
+    /// Record a filled order's side, price and size for sizing.
+    pub fn record_fill(&mut self, side: Side, price: f64, size: f64) {
+        self.holdings.add(side, price, size);
+    }
+
+    /// `pairs` UP+DOWN full sets of this market were merged back into USDC.
+    pub fn on_merge(&mut self, pairs: f64) {
+        self.holdings.merge(pairs);
+    }
+
     pub fn on_order_sent(&mut self) {
         self.pending_orders += 1;
     }
@@ -460,6 +694,7 @@ impl PositionTracker {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::math::fees::FeeSchedule;
 
     fn make_book(bids: Vec<(f64, f64)>, asks: Vec<(f64, f64)>) -> OrderBook {
         let mut ob = OrderBook::new();
@@ -729,8 +964,10 @@ mod tests {
             strike,
             tick_size: 0.01,
             neg_risk: false,
+            fees: FeeSchedule::default(),
+            condition_id: String::new(),
         };
-        let oracle = OracleBasis::new(0.0, 2.0);
+        let oracle = OracleBasis::new(0.0, 2.0, 1.0);
         let mut bn = BinanceState::new(0.94, 5, 0.30, 30_000, 60_000);
         bn.binance_price = binance_price;
         MarketState::new(info, bn, oracle)
@@ -790,8 +1027,10 @@ mod tests {
             strike: 95_000.0,
             tick_size: 0.01,
             neg_risk: false,
+            fees: FeeSchedule::default(),
+            condition_id: String::new(),
         };
-        let oracle = OracleBasis::new(15.0, 2.0);
+        let oracle = OracleBasis::new(15.0, 2.0, 1.0);
         let mut bn = BinanceState::new(0.94, 5, 0.30, 30_000, 60_000);
         bn.binance_price = 96_000.0;
         let state = MarketState::new(info, bn, oracle);
@@ -811,8 +1050,10 @@ mod tests {
             strike: 95_000.0,
             tick_size: 0.01,
             neg_risk: false,
+            fees: FeeSchedule::default(),
+            condition_id: String::new(),
         };
-        let oracle = OracleBasis::new(0.0, 3.0);
+        let oracle = OracleBasis::new(0.0, 3.0, 1.0);
         let bn = BinanceState::new(0.94, 5, 0.30, 30_000, 60_000);
         let state = MarketState::new(info, bn, oracle);
         // time_left_s(200_000) = 100.0, tau_eff = 100.0 + 3.0 = 103.0
@@ -906,4 +1147,234 @@ mod tests {
         });
         assert_eq!(pt.pending_orders, 0);
     }
+
+    // ── BinanceState::trend_prob ──
+
+    /// Scenario: Fresh BinanceState with 80% up ticks but no 1s returns yet.
+    /// Expected: trend_prob falls back to the tick classifier ramp (1.0 at 80%).
+    #[test]
+    fn test_trend_prob_falls_back_to_ticks() {
+        let mut bn = BinanceState::new(0.94, 5, 0.30, 30_000, 30_000);
+        for i in 0..20 {
+            bn.regime.update(i * 100, i % 5 != 0);
+        }
+        assert!(!bn.vr_regime.is_ready() && !bn.hmm_regime.is_ready());
+        assert_eq!(bn.trend_prob(), 1.0);
+    }
+
+    /// Scenario: 80% up ticks, but 120 alternating ±1bp 1s returns (range-bound in magnitude).
+    /// Expected: Warmed-up return models take over → trend_prob low despite the tick label.
+    #[test]
+    fn test_trend_prob_uses_return_models_when_ready() {
+        let mut bn = BinanceState::new(0.94, 5, 0.30, 30_000, 30_000);
+        for i in 0..20 {
+            bn.regime.update(i * 100, i % 5 != 0);
+        }
+        for i in 0..120 {
+            bn.on_sample_return(if i % 2 == 0 { 1e-4 } else { -1e-4 });
+        }
+        assert!(bn.trend_prob() < 0.1, "trend_prob = {}", bn.trend_prob());
+    }
+
+    // ── BinanceState::price_at ──
+
+    /// Scenario: Trades at t=1s ($100), 2s ($101), 4s ($103); look up before, on and between them.
+    /// Expected: None before the first trade; otherwise the last price at or before the time.
+    #[test]
+    fn test_price_at_reads_trade_path() {
+        let mut bn = BinanceState::new(0.94, 5, 0.30, 30_000, 30_000);
+        for (ts, price) in [(1000, 100.0), (2000, 101.0), (4000, 103.0)] {
+            bn.trade_buffer.push_back(BinanceTrade {
+                exchange_ts_ms: ts,
+                recv_at: std::time::Instant::now(),
+                price,
+                qty: 1.0,
+                is_buy: true,
+            });
+        }
+        assert_eq!(bn.price_at(999), None);
+        assert_eq!(bn.price_at(1000), Some(100.0));
+        assert_eq!(bn.price_at(3999), Some(101.0));
+        assert_eq!(bn.price_at(10_000), Some(103.0));
+    }
+
+    /// Scenario: Binance trades one second apart with steadily rising price.
+    /// Expected: on_binance_trade feeds each 1s sample into the regime models.
+    #[test]
+    fn test_binance_trades_feed_regime_models() {
+        let info = MarketInfo {
+            slug: "test".into(),
+            start_ms: 0,
+            end_ms: 300_000,
+            up_token_id: String::new(),
+            down_token_id: String::new(),
+            strike: 95_000.0,
+            tick_size: 0.01,
+            neg_risk: false,
+            fees: FeeSchedule::default(),
+            condition_id: String::new(),
+        };
+        let bn = BinanceState::new(0.94, 5, 0.30, 30_000, 30_000);
+        let mut state = MarketState::new(info, bn, OracleBasis::new(0.0, 2.0, 1.0));
+        let mut price = 95_000.0;
+        for i in 0..41 {
+            state.on_binance_trade(BinanceTrade {
+                exchange_ts_ms: i * 1000,
+                recv_at: std::time::Instant::now(),
+                price,
+                qty: 0.01,
+                is_buy: true,
+            });
+            price *= 1.0001;
+        }
+        // First trade seeds the EWMA; the next 40 produce samples
+        assert_eq!(state.bn.vr_regime.n_returns(), 40);
+        assert_eq!(state.bn.hmm_regime.n_returns(), 40);
+    }
+
+    // ── Vol surface features ──
+
+    fn surface_state(strike: f64, end_ms: i64, btc: f64, sigma: f64) -> MarketState {
+        let info = MarketInfo {
+            slug: "test".into(),
+            start_ms: 0,
+            end_ms,
+            up_token_id: String::new(),
+            down_token_id: String::new(),
+            strike,
+            tick_size: 0.01,
+            neg_risk: false,
+            fees: FeeSchedule::default(),
+            condition_id: String::new(),
+        };
+        let bn = BinanceState::new(0.94, 5, 0.30, 30_000, 30_000);
+        let mut state = MarketState::new(info, bn, OracleBasis::new(0.0, 0.0, 1.0));
+        state.bn.binance_price = btc;
+        state.bn.sigma_real_cached = sigma;
+        state
+    }
+
+    fn quote(ts: i64, up_bid: f64, up_ask: f64, down_bid: f64, down_ask: f64) -> PolymarketQuote {
+        PolymarketQuote {
+            server_ts_ms: ts,
+            recv_at: std::time::Instant::now(),
+            up_bid: Some(up_bid),
+            up_ask: Some(up_ask),
+            down_bid: Some(down_bid),
+            down_ask: Some(down_ask),
+        }
+    }
+
+    /// Scenario: Quotes priced at IV = 1.5e-4 while realized sigma is 1e-4.
+    /// Expected: atm_iv ≈ 1.5e-4, iv_rv_spread ≈ +0.5e-4, one IV/RV history sample.
+    #[test]
+    fn test_vol_surface_atm_iv_and_spread() {
+        use crate::math::pricing::p_fair;
+        let mut state = surface_state(100_000.0, 300_000, 100_050.0, 1e-4);
+        let p = p_fair(100_050.0, 100_000.0, 1.5e-4, 300.0);
+        state.on_polymarket_quote(quote(1, p - 0.005, p + 0.005, 1.0 - p - 0.005, 1.0 - p + 0.005));
+        let iv = state.atm_iv().expect("atm iv");
+        assert!((iv - 1.5e-4).abs() / 1.5e-4 < 0.05, "atm iv = {}", iv);
+        let spread = state.iv_rv_spread().unwrap();
+        assert!((spread - 0.5e-4).abs() < 1e-5, "spread = {}", spread);
+        assert_eq!(state.vol_surface.history().len(), 1);
+    }
+
+    /// Scenario: Two quote updates 100ms apart (refresh throttle 250ms).
+    /// Expected: Second update doesn't recompute — surface point timestamp unchanged.
+    #[test]
+    fn test_vol_surface_refresh_throttled() {
+        let mut state = surface_state(100_000.0, 300_000, 100_050.0, 1e-4);
+        state.on_polymarket_quote(quote(1_000, 0.55, 0.57, 0.43, 0.45));
+        state.on_polymarket_quote(quote(1_100, 0.60, 0.62, 0.38, 0.40));
+        let pt = state.vol_surface.point(300_000, 100_000.0).unwrap();
+        assert_eq!(pt.updated_ms, 1_000);
+    }
+
+    /// Scenario: Own 5m market plus a 1h cross market, both priced with σ(τ) rising in τ.
+    /// Expected: Surface holds both expiries and iv_term_slope is positive.
+    #[test]
+    fn test_vol_surface_term_structure_with_cross_market() {
+        use crate::math::pricing::p_fair;
+        let (s, k) = (100_050.0, 100_000.0);
+        let mut state = surface_state(k, 300_000, s, 1e-4);
+        state.bn.binance_ts = 0;
+        let p_cm = p_fair(s, k, 2e-4, 3_600.0);
+        state.on_cross_market_quote(CrossMarketQuoteEvent {
+            interval: Interval::H1,
+            up_bid: p_cm - 0.005,
+            up_ask: p_cm + 0.005,
+            down_bid: 1.0 - p_cm - 0.005,
+            down_ask: 1.0 - p_cm + 0.005,
+            strike: k,
+            start_ms: 0,
+            end_ms: 3_600_000,
+            up_token_id: String::new(),
+            down_token_id: String::new(),
+        });
+        let p = p_fair(s, k, 1e-4, 300.0);
+        state.on_polymarket_quote(quote(300, p - 0.005, p + 0.005, 1.0 - p - 0.005, 1.0 - p + 0.005));
+        assert_eq!(state.vol_surface.points().len(), 2);
+        let b = state.iv_term_slope().expect("two expiries");
+        assert!(b > 0.0, "term slope = {}", b);
+    }
+
+    // ── Cross-market settlement ──
+
+    fn cross_fill(order_id: u64, side: Side, price: f64) -> Fill {
+        Fill { order_id, strategy: "nested_arb", side, price, size: 10.0, fee: 0.0 }
+    }
+
+    /// Scenario: UP bought on a cross market (strike 95_000, ends at 60s) inside a 300s
+    /// market. BTC is 95_100 on the first trade after 60s, then falls to 94_800.
+    /// Expected: The cross fill settles UP on the price at its own expiry, not the
+    /// final price; a fill on a market ending with ours settles at the final price.
+    #[test]
+    fn test_cross_ledger_settles_on_own_expiry() {
+        let mut state = surface_state(95_000.0, 300_000, 95_000.0, 1e-4);
+        state.cross_ledger.record(cross_fill(1, Side::Up, 0.5), 95_000.0, 60_000);
+        state.cross_ledger.record(cross_fill(2, Side::Up, 0.5), 94_900.0, 300_000);
+
+        for (ts, price) in [(59_000, 94_990.0), (60_200, 95_100.0), (120_000, 94_800.0)] {
+            state.on_binance_trade(BinanceTrade {
+                exchange_ts_ms: ts,
+                recv_at: std::time::Instant::now(),
+                price,
+                qty: 0.01,
+                is_buy: true,
+            });
+        }
+        assert_eq!(state.cross_ledger.fills[0].settle_price, Some(95_100.0));
+        assert_eq!(state.cross_ledger.fills[1].settle_price, None);
+
+        let settled = state.cross_ledger.settle(94_800.0);
+        assert!(state.cross_ledger.fills.is_empty());
+        assert_eq!(settled[0].0, Side::Up);
+        assert_eq!(settled[1].0, Side::Down, "94_800 final < 94_900 strike");
+        assert!((settled[0].1.pnl(settled[0].0) - 5.0).abs() < 1e-9);
+    }
+
+    /// Scenario: A quote for the next 5m window arrives while the previous one is tracked.
+    /// Expected: It replaces the entry for that interval, token IDs and start included.
+    #[test]
+    fn test_cross_quote_rolls_to_next_window() {
+        let mut state = surface_state(95_000.0, 900_000, 95_000.0, 1e-4);
+        for (start_ms, strike, token) in [(0, 95_000.0, "a"), (300_000, 95_050.0, "b")] {
+            state.on_cross_market_quote(CrossMarketQuoteEvent {
+                interval: Interval::M5,
+                up_bid: 0.49,
+                up_ask: 0.51,
+                down_bid: 0.49,
+                down_ask: 0.51,
+                strike,
+                start_ms,
+                end_ms: start_ms + 300_000,
+                up_token_id: format!("{}-up", token),
+                down_token_id: format!("{}-down", token),
+            });
+        }
+        let cm = &state.cross_markets[&Interval::M5];
+        assert_eq!((cm.start_ms, cm.end_ms, cm.strike), (300_000, 600_000, 95_050.0));
+        assert_eq!(cm.up_token_id, "b-up");
+    }
 }
@@ -0,0 +1,523 @@
+//! Correlated portfolio VaR across assets and intervals.
+//!
+//! Each process trades one asset and interval, but BTC and ETH (or the 5m and
+//! 15m markets of one asset) move together, so per-process exposure caps
+//! understate the book's risk. The portfolio feed samples the spot price of
+//! every `VAR_ASSETS` asset into a rolling correlation of log returns
+//! ([`CorrelationTracker`]) and reads the open positions of every process
+//! sharing the wallet.
+//!
+//! [`VarScenarios::rebuild`] simulates correlated unit Brownian paths `B` of
+//! all assets out to each open position's expiry `τ`. A binary whose UP token
+//! is worth `p` settles UP on a path when `Φ⁻¹(p)·√τ + B(τ) > 0`: alone it
+//! wins with probability `p`, jointly with the assets' correlation (and a 5m
+//! and 15m market of one asset share the first five minutes of their path).
+//! Per path, each position's PnL at expiry against its current value is
+//! summed; VaR is the loss at `VAR_CONFIDENCE`. The paths are kept, so the
+//! `portfolio_var` risk rule prices a candidate order on the same scenarios
+//! in O(paths).
+
+use std::collections::VecDeque;
+
+use crate::config::{Config, Interval};
+use crate::engine::risk::GreeksTracker;
+use crate::gateway::wallet::OpenPosition;
+use crate::math::normal::inv_cdf;
+use crate::math::oracle::OracleBasis;
+use crate::math::pricing::p_fair;
+
+/// Samples needed before the measured correlation replaces the identity.
+const MIN_SAMPLES: usize = 30;
+
+/// Fixed seed: every rebuild draws the same shocks, so VaR moves with the
+/// positions rather than with simulation noise.
+const SEED: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// P(UP) is clamped this far inside (0, 1) so settled-looking prices keep a
+/// finite strike distance.
+const P_EPS: f64 = 1e-6;
+
+// ─── Correlation ────────────────────────────────────────────────────────────
+
+/// Correlation matrix of the tracked assets' spot returns.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Correlation {
+    pub assets: Vec<String>,
+    pub matrix: Vec<Vec<f64>>,
+}
+
+impl Correlation {
+    pub fn identity(assets: Vec<String>) -> Self {
+        let n = assets.len();
+        let matrix = (0..n).map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect()).collect();
+        Self { assets, matrix }
+    }
+
+    pub fn get(&self, a: &str, b: &str) -> Option<f64> {
+        let i = self.assets.iter().position(|x| x == a)?;
+        let j = self.assets.iter().position(|x| x == b)?;
+        Some(self.matrix[i][j])
+    }
+}
+
+/// Rolling correlation of spot log returns between tracked assets.
+pub struct CorrelationTracker {
+    assets: Vec<String>,
+    last: Vec<f64>,
+    /// One log return per asset per sample, oldest first.
+    returns: VecDeque<Vec<f64>>,
+    window: usize,
+}
+
+impl CorrelationTracker {
+    pub fn new(assets: Vec<String>, window: usize) -> Self {
+        let n = assets.len();
+        Self { assets, last: vec![0.0; n], returns: VecDeque::with_capacity(window), window: window.max(2) }
+    }
+
+    /// Record one spot price per asset, in `assets` order. A sample with any
+    /// price missing (≤ 0) is skipped and the next one spans both intervals.
+    pub fn on_prices(&mut self, prices: &[f64]) {
+        if prices.len() != self.assets.len() || prices.iter().any(|&p| p <= 0.0) {
+            return;
+        }
+        if self.last.iter().all(|&p| p > 0.0) {
+            if self.returns.len() == self.window {
+                self.returns.pop_front();
+            }
+            self.returns.push_back(prices.iter().zip(&self.last).map(|(p, l)| (p / l).ln()).collect());
+        }
+        self.last.copy_from_slice(prices);
+    }
+
+    /// Pearson correlation over the window; the identity until `MIN_SAMPLES`
+    /// returns are in. An asset that hasn't moved is uncorrelated with the rest.
+    pub fn correlation(&self) -> Correlation {
+        let mut corr = Correlation::identity(self.assets.clone());
+        let m = self.returns.len();
+        if m < MIN_SAMPLES {
+            return corr;
+        }
+        let n = self.assets.len();
+        let mean: Vec<f64> = (0..n).map(|a| self.returns.iter().map(|r| r[a]).sum::<f64>() / m as f64).collect();
+        let mut cov = vec![vec![0.0; n]; n];
+        for r in &self.returns {
+            for i in 0..n {
+                for j in 0..=i {
+                    cov[i][j] += (r[i] - mean[i]) * (r[j] - mean[j]);
+                }
+            }
+        }
+        for i in 0..n {
+            for j in 0..i {
+                let denom = (cov[i][i] * cov[j][j]).sqrt();
+                let rho = if denom > 0.0 { (cov[i][j] / denom).clamp(-1.0, 1.0) } else { 0.0 };
+                corr.matrix[i][j] = rho;
+                corr.matrix[j][i] = rho;
+            }
+        }
+        corr
+    }
+}
+
+/// Lower Cholesky factor of `m`. A singular or not-quite-PSD matrix (assets
+/// moving in lockstep) is shrunk toward the identity until it factors.
+fn cholesky(m: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let n = m.len();
+    for shrink in [0.0, 1e-6, 0.01, 0.1, 1.0] {
+        let mut l = vec![vec![0.0; n]; n];
+        let mut ok = true;
+        'rows: for i in 0..n {
+            for j in 0..=i {
+                let target = if i == j { 1.0 } else { (1.0 - shrink) * m[i][j] };
+                let s = target - (0..j).map(|k| l[i][k] * l[j][k]).sum::<f64>();
+                if i == j {
+                    if s <= 1e-12 {
+                        ok = false;
+                        break 'rows;
+                    }
+                    l[i][i] = s.sqrt();
+                } else {
+                    l[i][j] = s / l[j][j];
+                }
+            }
+        }
+        if ok {
+            return l;
+        }
+    }
+    unreachable!("the identity always factors")
+}
+
+// ─── Positions ──────────────────────────────────────────────────────────────
+
+/// One binary market's net position, as VaR sees it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VarPosition {
+    pub asset: String,
+    pub end_ms: i64,
+    /// UP tokens minus DOWN tokens: the PnL against current value is
+    /// `net_up × (1{UP} − p_up)` either way.
+    pub net_up: f64,
+    /// Current P(UP).
+    pub p_up: f64,
+}
+
+impl VarPosition {
+    /// From a wallet position. Needs a `{asset}-updown-{interval}-{start}`
+    /// slug for the expiry, so 1h markets (human-readable slugs) are skipped.
+    pub fn from_wallet(p: &OpenPosition) -> Option<Self> {
+        let mut parts = p.slug.rsplitn(4, '-');
+        let start_s: i64 = parts.next()?.parse().ok()?;
+        let label = parts.next()?;
+        let interval = [Interval::M5, Interval::M15, Interval::H1, Interval::H4]
+            .into_iter()
+            .find(|i| i.label() == label)?;
+        if parts.next()? != "updown" {
+            return None;
+        }
+        let asset = parts.next()?.to_string();
+        let (net_up, p_up) = if p.up { (p.size, p.price) } else { (-p.size, 1.0 - p.price) };
+        Some(Self { asset, end_ms: (start_s + interval.window_secs()) * 1000, net_up, p_up })
+    }
+}
+
+/// This process's own instruments (current market plus cross markets), at
+/// the model fair value the Greeks use. Instruments whose fills have netted
+/// flat are kept, so `merge_positions` still knows this process tracks them.
+pub fn own_positions(
+    greeks: &GreeksTracker,
+    asset: &str,
+    s: f64,
+    sigma: f64,
+    now_ms: i64,
+    oracle: &OracleBasis,
+) -> Vec<VarPosition> {
+    greeks
+        .instruments()
+        .filter(|g| g.end_ms > now_ms)
+        .map(|g| {
+            let tau = oracle.tau_eff((g.end_ms - now_ms) as f64 / 1000.0);
+            VarPosition { asset: asset.to_string(), end_ms: g.end_ms, net_up: g.net_size, p_up: p_fair(s, g.strike, sigma, tau) }
+        })
+        .collect()
+}
+
+/// Own positions plus the wallet's. The wallet also holds this process's
+/// markets but lags its fills, so its entries for any instrument tracked here
+/// are dropped, even one whose own fills have netted flat.
+pub fn merge_positions(mut own: Vec<VarPosition>, wallet: &[VarPosition]) -> Vec<VarPosition> {
+    let other: Vec<VarPosition> = wallet
+        .iter()
+        .filter(|w| !own.iter().any(|o| o.asset == w.asset && o.end_ms == w.end_ms))
+        .cloned()
+        .collect();
+    own.retain(|o| o.net_up != 0.0);
+    own.extend(other);
+    own
+}
+
+/// What the portfolio feed sends the engine every `VAR_SAMPLE_SECS`.
+#[derive(Clone, Debug, Default)]
+pub struct PortfolioRisk {
+    pub correlation: Correlation,
+    /// Every open wallet position VaR can place.
+    pub positions: Vec<VarPosition>,
+}
+
+// ─── Scenarios ──────────────────────────────────────────────────────────────
+
+/// Standard normal draws: xorshift64* uniforms through Box–Muller.
+struct Normals {
+    state: u64,
+    spare: Option<f64>,
+}
+
+impl Normals {
+    fn new(seed: u64) -> Self {
+        Self { state: seed.max(1), spare: None }
+    }
+
+    fn uniform(&mut self) -> f64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        let x = self.state.wrapping_mul(0x2545_F491_4F6C_DD1D);
+        // 53 random bits in (0, 1]
+        ((x >> 11) + 1) as f64 / (1u64 << 53) as f64
+    }
+
+    fn draw(&mut self) -> f64 {
+        if let Some(z) = self.spare.take() {
+            return z;
+        }
+        let r = (-2.0 * self.uniform().ln()).sqrt();
+        let theta = std::f64::consts::TAU * self.uniform();
+        self.spare = Some(r * theta.sin());
+        r * theta.cos()
+    }
+}
+
+/// Loss at `confidence` (positive = loss, floored at 0). Reorders `pnl`.
+fn loss_quantile(pnl: &mut [f64], confidence: f64) -> f64 {
+    if pnl.is_empty() {
+        return 0.0;
+    }
+    let idx = (((1.0 - confidence) * pnl.len() as f64) as usize).min(pnl.len() - 1);
+    let (_, q, _) = pnl.select_nth_unstable_by(idx, f64::total_cmp);
+    (-*q).max(0.0)
+}
+
+/// Monte Carlo VaR of every open position, with the paths kept for pricing
+/// candidate orders on the current market.
+#[derive(Clone, Debug)]
+pub struct VarScenarios {
+    /// VaR limit as a fraction of bankroll (0.0 = disabled).
+    pub max_var_frac: f64,
+    pub confidence: f64,
+    pub paths: usize,
+    /// Per path: the open positions' PnL at expiry.
+    base: Vec<f64>,
+    /// Per path: the current market's `B(τ)/√τ` (its asset, its expiry).
+    shock: Vec<f64>,
+    /// VaR of the open positions at the last rebuild (USDC, loss positive).
+    pub var: f64,
+}
+
+impl Default for VarScenarios {
+    fn default() -> Self {
+        Self { max_var_frac: 0.0, confidence: 0.99, paths: 2000, base: Vec::new(), shock: Vec::new(), var: 0.0 }
+    }
+}
+
+impl VarScenarios {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            max_var_frac: config.max_var_frac,
+            confidence: config.var_confidence.clamp(0.5, 0.9999),
+            paths: config.var_paths.max(100),
+            ..Self::default()
+        }
+    }
+
+    #[inline]
+    pub fn enabled(&self) -> bool {
+        self.max_var_frac > 0.0
+    }
+
+    /// Re-simulate `positions` under `correlation`. `asset` and `end_ms` are
+    /// the current market's, whose shock `var_with` prices orders against.
+    /// Assets missing from the matrix are taken as uncorrelated.
+    pub fn rebuild(&mut self, correlation: &Correlation, positions: &[VarPosition], asset: &str, end_ms: i64, now_ms: i64) {
+        let mut names: Vec<&str> = correlation.assets.iter().map(String::as_str).collect();
+        for a in positions.iter().map(|p| p.asset.as_str()).chain([asset]) {
+            if !names.contains(&a) {
+                names.push(a);
+            }
+        }
+        let n = names.len();
+        let rho: Vec<Vec<f64>> = (0..n)
+            .map(|i| (0..n).map(|j| if i == j { 1.0 } else { correlation.get(names[i], names[j]).unwrap_or(0.0) }).collect())
+            .collect();
+        let l = cholesky(&rho);
+
+        // Expiry grid (seconds ahead), shared by every asset's path
+        let tau_s = |end: i64| ((end - now_ms) as f64 / 1000.0).max(1e-3);
+        let open: Vec<&VarPosition> = positions.iter().filter(|p| p.end_ms > now_ms && p.net_up != 0.0).collect();
+        let mut times: Vec<f64> = open.iter().map(|p| tau_s(p.end_ms)).chain([tau_s(end_ms)]).collect();
+        times.sort_by(f64::total_cmp);
+        times.dedup();
+        let slot = |a: &str, end: i64| {
+            let k = times.iter().position(|&t| t == tau_s(end)).expect("expiry is on the grid");
+            k * n + names.iter().position(|&x| x == a).expect("asset is named")
+        };
+        // (shock slot, Φ⁻¹(p_up), net_up, p_up)
+        let legs: Vec<(usize, f64, f64, f64)> = open
+            .iter()
+            .map(|p| {
+                let p_up = p.p_up.clamp(P_EPS, 1.0 - P_EPS);
+                (slot(&p.asset, p.end_ms), inv_cdf(p_up), p.net_up, p_up)
+            })
+            .collect();
+        let current = slot(asset, end_ms);
+
+        let mut rng = Normals::new(SEED);
+        let (mut b, mut z, mut u) = (vec![0.0; n], vec![0.0; n], vec![0.0; times.len() * n]);
+        self.base.clear();
+        self.shock.clear();
+        for _ in 0..self.paths {
+            b.fill(0.0);
+            let mut t_prev = 0.0;
+            for (k, &t) in times.iter().enumerate() {
+                let dt_sqrt = (t - t_prev).sqrt();
+                z.iter_mut().for_each(|x| *x = rng.draw());
+                for a in 0..n {
+                    let x: f64 = (0..=a).map(|j| l[a][j] * z[j]).sum();
+                    b[a] += dt_sqrt * x;
+                    u[k * n + a] = b[a] / t.sqrt();
+                }
+                t_prev = t;
+            }
+            let pnl: f64 = legs
+                .iter()
+                .map(|&(s, d, net_up, p_up)| net_up * (if d + u[s] > 0.0 { 1.0 } else { 0.0 } - p_up))
+                .sum();
+            self.base.push(pnl);
+            self.shock.push(u[current]);
+        }
+        let mut pnl = self.base.clone();
+        self.var = loss_quantile(&mut pnl, self.confidence);
+    }
+
+    /// VaR after a current-market trade that adds `net_up` UP tokens
+    /// (negative: DOWN tokens) and `cash` USDC, at P(UP) `p_up`. `None` before
+    /// the first rebuild.
+    pub fn var_with(&self, p_up: f64, net_up: f64, cash: f64) -> Option<f64> {
+        if self.base.is_empty() {
+            return None;
+        }
+        let d = inv_cdf(p_up.clamp(P_EPS, 1.0 - P_EPS));
+        let mut pnl: Vec<f64> = self
+            .base
+            .iter()
+            .zip(&self.shock)
+            .map(|(base, u)| base + cash + if d + u > 0.0 { net_up } else { 0.0 })
+            .collect();
+        Some(loss_quantile(&mut pnl, self.confidence))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_000_000;
+
+    fn pos(asset: &str, end_ms: i64, net_up: f64, p_up: f64) -> VarPosition {
+        VarPosition { asset: asset.into(), end_ms, net_up, p_up }
+    }
+
+    fn corr(rho: f64) -> Correlation {
+        let mut c = Correlation::identity(vec!["btc".into(), "eth".into()]);
+        c.matrix[0][1] = rho;
+        c.matrix[1][0] = rho;
+        c
+    }
+
+    fn scenarios() -> VarScenarios {
+        VarScenarios { max_var_frac: 0.05, confidence: 0.99, paths: 4000, ..Default::default() }
+    }
+
+    // ── Correlation ──
+
+    /// Scenario: ETH returns are 2× BTC's every sample; SOL alternates independently.
+    /// Expected: identity for the first 29 returns; then ρ(btc, eth) = 1, ρ(btc, sol) ≈ 0.
+    #[test]
+    fn test_tracker_measures_correlation() {
+        let mut t = CorrelationTracker::new(vec!["btc".into(), "eth".into(), "sol".into()], 100);
+        let (mut btc, mut eth, mut sol) = (100.0_f64, 10.0_f64, 1.0_f64);
+        for i in 0..60 {
+            let r = if i % 3 == 0 { 0.002 } else { -0.001 };
+            btc *= f64::exp(r);
+            eth *= f64::exp(2.0 * r);
+            sol *= if i % 2 == 0 { 1.001 } else { 0.999 };
+            t.on_prices(&[btc, eth, sol]);
+            if i == 29 {
+                assert_eq!(t.correlation().get("btc", "eth"), Some(0.0), "too few samples");
+            }
+        }
+        let c = t.correlation();
+        assert!((c.get("btc", "eth").unwrap() - 1.0).abs() < 1e-9);
+        assert!(c.get("btc", "sol").unwrap().abs() < 0.2);
+    }
+
+    /// Scenario: 5m BTC and 15m ETH positions and a 1h human-readable slug in the wallet.
+    /// Expected: Expiry = start + interval; DOWN holdings flip to −size at 1 − price; 1h skipped.
+    #[test]
+    fn test_wallet_position_from_slug() {
+        let wallet = |slug: &str, up: bool| OpenPosition { slug: slug.into(), up, size: 40.0, price: 0.30 };
+        let eth = VarPosition::from_wallet(&wallet("eth-updown-15m-1760000000", false)).unwrap();
+        assert_eq!(eth.asset, "eth");
+        assert_eq!(eth.end_ms, 1_760_000_900_000);
+        assert_eq!(eth.net_up, -40.0);
+        assert!((eth.p_up - 0.70).abs() < 1e-12);
+        let btc = VarPosition::from_wallet(&wallet("btc-updown-5m-1760000000", true)).unwrap();
+        assert_eq!((btc.end_ms, btc.net_up, btc.p_up), (1_760_000_300_000, 40.0, 0.30));
+        assert!(VarPosition::from_wallet(&wallet("bitcoin-up-or-down-february-16-3am-et", true)).is_none());
+    }
+
+    // ── VaR ──
+
+    /// Scenario: 100 UP tokens at 0.5 on BTC and on ETH, same expiry; 70% VaR at ρ = 0, then
+    ///           ρ = 0.9, then BTC alone.
+    /// Expected: Independent, both lose on 25% of paths, inside the 30% tail → $0; at ρ = 0.9
+    ///           on ~43% → $100; BTC alone loses its $50 on half the paths → $50.
+    #[test]
+    fn test_correlation_raises_var() {
+        let end = NOW + 300_000;
+        let book = [pos("btc", end, 100.0, 0.5), pos("eth", end, 100.0, 0.5)];
+        let mut s = scenarios();
+        s.confidence = 0.7;
+        s.rebuild(&corr(0.0), &book, "btc", end, NOW);
+        let independent = s.var;
+        s.rebuild(&corr(0.9), &book, "btc", end, NOW);
+        let correlated = s.var;
+        assert!(independent < 1e-9, "independent 70% VaR {}", independent);
+        assert!((correlated - 100.0).abs() < 1e-9, "correlated 70% VaR {}", correlated);
+
+        s.rebuild(&corr(0.0), &book[..1], "btc", end, NOW);
+        assert!((s.var - 50.0).abs() < 1e-9);
+    }
+
+    /// Scenario: Long 100 BTC UP at 0.5 (VaR $50); a candidate buys 100 DOWN for $50, then
+    ///           100 more UP for $50.
+    /// Expected: The DOWN buy flattens the book (VaR 0); the UP buy doubles it ($100).
+    #[test]
+    fn test_var_with_prices_candidate() {
+        let end = NOW + 300_000;
+        let mut s = scenarios();
+        s.rebuild(&corr(0.0), &[pos("btc", end, 100.0, 0.5)], "btc", end, NOW);
+        assert!((s.var - 50.0).abs() < 1e-9);
+        assert!(s.var_with(0.5, -100.0, 100.0 - 50.0).unwrap() < 1e-9);
+        assert!((s.var_with(0.5, 100.0, -50.0).unwrap() - 100.0).abs() < 1e-9);
+        assert!(VarScenarios::default().var_with(0.5, 1.0, -0.5).is_none(), "not built");
+    }
+
+    /// Scenario: 5m and 15m BTC positions long UP at 0.5, vs the same two on BTC and an
+    ///           uncorrelated asset.
+    /// Expected: The shared first 5 minutes of path correlate the same-asset pair (ρ = √(1/3),
+    ///           both lose on ~35% of paths), so its 70% VaR is $100; the independent pair's is $0.
+    #[test]
+    fn test_intervals_share_path() {
+        let mut s = scenarios();
+        s.confidence = 0.7;
+        let (m5, m15) = (NOW + 300_000, NOW + 900_000);
+        s.rebuild(&corr(0.0), &[pos("btc", m5, 100.0, 0.5), pos("btc", m15, 100.0, 0.5)], "btc", m5, NOW);
+        let same = s.var;
+        s.rebuild(&corr(0.0), &[pos("btc", m5, 100.0, 0.5), pos("eth", m15, 100.0, 0.5)], "btc", m5, NOW);
+        assert!((same - 100.0).abs() < 1e-9, "same asset {}", same);
+        assert!(s.var < 1e-9, "independent {}", s.var);
+    }
+
+    /// Scenario: This process holds the 5m BTC market; the lagging wallet has it too, plus ETH.
+    /// Expected: Own entry kept, wallet's copy dropped, ETH added.
+    #[test]
+    fn test_merge_prefers_own_positions() {
+        let own = vec![pos("btc", NOW, 30.0, 0.6)];
+        let wallet = [pos("btc", NOW, 20.0, 0.6), pos("eth", NOW, 10.0, 0.4)];
+        let merged = merge_positions(own, &wallet);
+        assert_eq!(merged, vec![pos("btc", NOW, 30.0, 0.6), pos("eth", NOW, 10.0, 0.4)]);
+    }
+
+    /// Scenario: This process's 5m BTC fills have just netted flat; the lagging wallet still
+    ///           shows 20 UP there, plus ETH.
+    /// Expected: The stale BTC entry is dropped (the market is tracked here), the flat own
+    ///           entry isn't carried, ETH is added.
+    #[test]
+    fn test_merge_drops_wallet_copy_of_flat_own_position() {
+        let own = vec![pos("btc", NOW, 0.0, 0.6)];
+        let wallet = [pos("btc", NOW, 20.0, 0.6), pos("eth", NOW, 10.0, 0.4)];
+        let merged = merge_positions(own, &wallet);
+        assert_eq!(merged, vec![pos("eth", NOW, 10.0, 0.4)]);
+    }
+}
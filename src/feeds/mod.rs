@@ -1,2 +1,3 @@
 pub mod binance;
 pub mod polymarket;
+pub mod portfolio;
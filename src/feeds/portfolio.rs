@@ -0,0 +1,77 @@
+use tokio::sync::{mpsc, watch};
+
+use crate::config::Config;
+use crate::engine::var::{CorrelationTracker, PortfolioRisk, VarPosition};
+use crate::gateway::wallet::WalletSnapshot;
+use crate::types::FeedEvent;
+
+const TICKER_URL: &str = "https://api.binance.com/api/v3/ticker/price";
+
+/// Portfolio feed for correlated VaR (`MAX_VAR_FRAC > 0`). Lives across markets.
+///
+/// Every `VAR_SAMPLE_SECS` it samples the Binance spot price of each
+/// `VAR_ASSETS` asset (plus our own) into a rolling return correlation, reads
+/// the wallet's open positions, and sends both to the current market's engine
+/// as one `FeedEvent::Portfolio`. Between markets the sample still feeds the
+/// correlation window; only the send is skipped.
+pub async fn portfolio_feed(
+    config: Config,
+    feed_watch: watch::Receiver<Option<mpsc::Sender<FeedEvent>>>,
+    wallet_rx: watch::Receiver<Option<WalletSnapshot>>,
+) {
+    let mut assets = config.var_assets.clone();
+    if !assets.contains(&config.asset) {
+        assets.insert(0, config.asset.clone());
+    }
+    let symbols: Vec<String> = assets.iter().map(|a| format!("{}USDT", a.to_uppercase())).collect();
+    let query = serde_json::to_string(&symbols).unwrap_or_default();
+    let mut tracker = CorrelationTracker::new(assets, config.var_corr_window);
+    let http = reqwest::Client::new();
+    eprintln!(
+        "[PORTFOLIO] Sampling {} every {}s (window {})",
+        symbols.join(","),
+        config.var_sample_secs,
+        config.var_corr_window,
+    );
+
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(config.var_sample_secs.max(1)));
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    loop {
+        interval.tick().await;
+        match fetch_prices(&http, &query, &symbols).await {
+            Ok(prices) => tracker.on_prices(&prices),
+            Err(e) => eprintln!("[PORTFOLIO] Spot prices failed: {}", e),
+        }
+
+        let positions = wallet_rx
+            .borrow()
+            .as_ref()
+            .map(|w| w.positions.iter().filter_map(VarPosition::from_wallet).collect())
+            .unwrap_or_default();
+        let risk = PortfolioRisk { correlation: tracker.correlation(), positions };
+        let sender = feed_watch.borrow().clone();
+        if let Some(tx) = sender {
+            let _ = tx.send(FeedEvent::Portfolio(risk)).await;
+        }
+    }
+}
+
+/// Spot price per symbol, in `symbols` order (0.0 where missing).
+async fn fetch_prices(http: &reqwest::Client, query: &str, symbols: &[String]) -> Result<Vec<f64>, reqwest::Error> {
+    let body: serde_json::Value = http
+        .get(TICKER_URL)
+        .query(&[("symbols", query)])
+        .timeout(std::time::Duration::from_secs(5))
+        .send()
+        .await?
+        .json()
+        .await?;
+    let mut prices = vec![0.0; symbols.len()];
+    for t in body.as_array().into_iter().flatten() {
+        let (Some(sym), Some(px)) = (t["symbol"].as_str(), t["price"].as_str()) else { continue };
+        if let Some(i) = symbols.iter().position(|s| s == sym) {
+            prices[i] = px.parse().unwrap_or(0.0);
+        }
+    }
+    Ok(prices)
+}
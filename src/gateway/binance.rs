@@ -0,0 +1,265 @@
+//! Signed Binance REST client for delta-hedge orders, on spot or USDⓈ-M perps.
+//!
+//! Hedges are MARKET orders in the base asset (`BTCUSDT` for BTC markets),
+//! signed with HMAC-SHA256 over the query string as Binance's `SIGNED`
+//! endpoints require. `hedge_gateway` runs them off the engine task and
+//! returns one `HedgeFill` per `HedgeOrder`.
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use tokio::sync::mpsc;
+
+use crate::config::{Config, HedgeVenue};
+use crate::types::{HedgeFill, HedgeOrder};
+
+/// Request validity window Binance enforces against `timestamp` (ms).
+const RECV_WINDOW_MS: u64 = 5_000;
+
+/// Default REST endpoints per venue.
+fn default_base_url(venue: HedgeVenue) -> &'static str {
+    match venue {
+        HedgeVenue::Spot => "https://api.binance.com",
+        HedgeVenue::Perp => "https://fapi.binance.com",
+    }
+}
+
+fn order_path(venue: HedgeVenue) -> &'static str {
+    match venue {
+        HedgeVenue::Spot => "/api/v3/order",
+        HedgeVenue::Perp => "/fapi/v1/order",
+    }
+}
+
+/// Hex HMAC-SHA256 of `payload` under `secret` (the `signature` parameter).
+pub fn sign(secret: &str, payload: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(payload.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Quantity as Binance accepts it: fixed-point, no trailing zeros.
+fn format_qty(qty: f64) -> String {
+    let s = format!("{:.8}", qty);
+    s.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// Unsigned query for a MARKET order of `qty` (signed: + buys, − sells).
+fn order_query(symbol: &str, qty: f64, ts_ms: i64) -> String {
+    let side = if qty > 0.0 { "BUY" } else { "SELL" };
+    format!(
+        "symbol={}&side={}&type=MARKET&quantity={}&newOrderRespType=RESULT&recvWindow={}&timestamp={}",
+        symbol, side, format_qty(qty.abs()), RECV_WINDOW_MS, ts_ms,
+    )
+}
+
+fn num(v: &serde_json::Value, key: &str) -> Option<f64> {
+    match &v[key] {
+        serde_json::Value::String(s) => s.parse().ok(),
+        serde_json::Value::Number(n) => n.as_f64(),
+        _ => None,
+    }
+}
+
+/// (executed base qty, average price) from an order response. Spot reports
+/// `cummulativeQuoteQty`, perps `avgPrice` (with `cumQuote` as a fallback).
+fn parse_fill(venue: HedgeVenue, v: &serde_json::Value) -> Result<(f64, f64), String> {
+    let executed = num(v, "executedQty").ok_or_else(|| format!("no executedQty in {}", v))?;
+    if executed <= 0.0 {
+        return Ok((0.0, 0.0));
+    }
+    let avg = match venue {
+        HedgeVenue::Spot => num(v, "cummulativeQuoteQty").map(|q| q / executed),
+        HedgeVenue::Perp => num(v, "avgPrice")
+            .filter(|&p| p > 0.0)
+            .or_else(|| num(v, "cumQuote").map(|q| q / executed)),
+    };
+    avg.map(|p| (executed, p)).ok_or_else(|| format!("no fill price in {}", v))
+}
+
+/// Signed REST client for one hedge symbol on one venue.
+pub struct BinanceClient {
+    http: reqwest::Client,
+    base_url: String,
+    api_key: String,
+    secret: String,
+    venue: HedgeVenue,
+    symbol: String,
+}
+
+impl BinanceClient {
+    pub fn new(base_url: &str, api_key: &str, secret: &str, venue: HedgeVenue, symbol: &str) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+            secret: secret.to_string(),
+            venue,
+            symbol: symbol.to_string(),
+        }
+    }
+
+    /// Client for `{ASSET}USDT` on the configured venue; `None` without API credentials.
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let key = config.binance_api_key.as_deref()?;
+        let secret = config.binance_api_secret.as_deref()?;
+        let base = config.hedge_rest_url.as_deref().unwrap_or(default_base_url(config.hedge_venue));
+        let symbol = format!("{}USDT", config.asset_label());
+        Some(Self::new(base, key, secret, config.hedge_venue, &symbol))
+    }
+
+    /// Send a MARKET order for `qty` (signed) and return the signed executed
+    /// quantity and average price.
+    pub async fn market_order(&self, qty: f64, ts_ms: i64) -> Result<(f64, f64), String> {
+        let query = order_query(&self.symbol, qty, ts_ms);
+        let url = format!(
+            "{}{}?{}&signature={}",
+            self.base_url, order_path(self.venue), query, sign(&self.secret, &query),
+        );
+        let resp = self
+            .http
+            .post(&url)
+            .header("X-MBX-APIKEY", &self.api_key)
+            .send()
+            .await
+            .map_err(|e| format!("request failed: {}", e))?;
+        let status = resp.status();
+        let body: serde_json::Value = resp.json().await.map_err(|e| format!("bad response: {}", e))?;
+        if !status.is_success() {
+            return Err(format!("HTTP {}: {}", status.as_u16(), body));
+        }
+        let (executed, price) = parse_fill(self.venue, &body)?;
+        Ok((executed.copysign(qty), price))
+    }
+}
+
+/// Hedge gateway: sends each `HedgeOrder` and answers with a `HedgeFill`
+/// (zero quantity and the error on failure). Runs until either channel closes.
+pub async fn hedge_gateway(
+    mut rx: mpsc::Receiver<HedgeOrder>,
+    tx: mpsc::Sender<HedgeFill>,
+    client: BinanceClient,
+) {
+    while let Some(order) = rx.recv().await {
+        let ts_ms = chrono::Utc::now().timestamp_millis();
+        let fill = match client.market_order(order.qty, ts_ms).await {
+            Ok((qty, price)) => HedgeFill { id: order.id, qty, price, error: None },
+            Err(e) => HedgeFill {
+                id: order.id,
+                qty: 0.0,
+                price: 0.0,
+                error: Some(format!("{} {}: {}", client.symbol, format_qty(order.qty), e)),
+            },
+        };
+        if tx.send(fill).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Local stand-in for the Binance REST API: answers every request with a
+/// canned status and JSON body and records the raw requests.
+#[cfg(test)]
+pub mod mock {
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    pub type Requests = Arc<Mutex<Vec<String>>>;
+
+    /// Serve on an ephemeral local port; returns the base URL and the request log.
+    pub async fn serve(status: u16, body: &'static str) -> (String, Requests) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind mock");
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let log: Requests = Arc::default();
+        let requests = log.clone();
+        tokio::spawn(async move {
+            while let Ok((mut sock, _)) = listener.accept().await {
+                let mut buf = vec![0u8; 8192];
+                let n = sock.read(&mut buf).await.unwrap_or(0);
+                requests.lock().unwrap().push(String::from_utf8_lossy(&buf[..n]).into_owned());
+                let resp = format!(
+                    "HTTP/1.1 {} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status, body.len(), body,
+                );
+                let _ = sock.write_all(resp.as_bytes()).await;
+            }
+        });
+        (url, log)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ── Signing and request format ──
+
+    /// Scenario: The HMAC example from Binance's API documentation.
+    /// Expected: The documented signature.
+    #[test]
+    fn test_sign_matches_binance_example() {
+        let secret = "NhqPtmdSJYdKjVHjA7PZj4Mge3R5YNiP1e3UZjInClVN65XAbvqqM6A7H5fATj0j";
+        let query = "symbol=LTCBTC&side=BUY&type=LIMIT&timeInForce=GTC&quantity=1&price=0.1&recvWindow=5000&timestamp=1499827319559";
+        assert_eq!(sign(secret, query), "c8db56825ae71d6d79447849e617115f4a920fa2acdcab2b053c4b2838bd6b71");
+    }
+
+    /// Scenario: Order queries for a 0.003 buy and a 0.25 sell.
+    /// Expected: BUY/SELL side from the sign, unsigned trimmed quantity.
+    #[test]
+    fn test_order_query_format() {
+        assert_eq!(
+            order_query("BTCUSDT", 0.003, 1_000),
+            "symbol=BTCUSDT&side=BUY&type=MARKET&quantity=0.003&newOrderRespType=RESULT&recvWindow=5000&timestamp=1000",
+        );
+        assert!(order_query("BTCUSDT", -0.25, 1_000).contains("side=SELL&type=MARKET&quantity=0.25&"));
+    }
+
+    /// Scenario: A spot RESULT response and a perp RESULT response.
+    /// Expected: Average price from cummulativeQuoteQty (spot) and avgPrice (perp).
+    #[test]
+    fn test_parse_fill_per_venue() {
+        let spot = serde_json::json!({"executedQty": "0.00200000", "cummulativeQuoteQty": "190.00000000"});
+        assert_eq!(parse_fill(HedgeVenue::Spot, &spot).unwrap(), (0.002, 95_000.0));
+        let perp = serde_json::json!({"executedQty": "0.002", "avgPrice": "95010.0", "cumQuote": "190.02"});
+        assert_eq!(parse_fill(HedgeVenue::Perp, &perp).unwrap(), (0.002, 95_010.0));
+    }
+
+    // ── Mock server ──
+
+    /// Scenario: A 0.002 sell on perps against the mock server.
+    /// Expected: POST to /fapi/v1/order with the API key header and a valid signature;
+    ///           the fill comes back signed (−0.002) at the response's avgPrice.
+    #[tokio::test]
+    async fn test_perp_order_against_mock() {
+        let (url, log) = mock::serve(200, r#"{"orderId":1,"status":"FILLED","executedQty":"0.002","avgPrice":"95000.5","cumQuote":"190.001"}"#).await;
+        let client = BinanceClient::new(&url, "key-1", "secret-1", HedgeVenue::Perp, "BTCUSDT");
+
+        let (qty, price) = client.market_order(-0.002, 1_700_000_000_000).await.unwrap();
+        assert_eq!((qty, price), (-0.002, 95_000.5));
+
+        let req = log.lock().unwrap()[0].clone();
+        let line = req.lines().next().unwrap();
+        assert!(line.starts_with("POST /fapi/v1/order?symbol=BTCUSDT&side=SELL"), "{}", line);
+        assert!(req.to_lowercase().contains("x-mbx-apikey: key-1"));
+        let target = line.split(' ').nth(1).unwrap();
+        let (query, signature) = target.split_once('?').unwrap().1.rsplit_once("&signature=").unwrap();
+        assert_eq!(signature, sign("secret-1", query));
+    }
+
+    /// Scenario: The mock rejects a spot order with Binance's error JSON.
+    /// Expected: `market_order` errors; the gateway answers with a zero-quantity fill carrying the error.
+    #[tokio::test]
+    async fn test_spot_rejection_through_gateway() {
+        let (url, log) = mock::serve(400, r#"{"code":-2010,"msg":"Account has insufficient balance."}"#).await;
+        let client = BinanceClient::new(&url, "k", "s", HedgeVenue::Spot, "BTCUSDT");
+        let (order_tx, order_rx) = mpsc::channel(4);
+        let (fill_tx, mut fill_rx) = mpsc::channel(4);
+        tokio::spawn(hedge_gateway(order_rx, fill_tx, client));
+
+        order_tx.send(HedgeOrder { id: 7, qty: 0.01 }).await.unwrap();
+        let fill = fill_rx.recv().await.unwrap();
+        assert_eq!((fill.id, fill.qty), (7, 0.0));
+        assert!(fill.error.unwrap().contains("-2010"));
+        assert!(log.lock().unwrap()[0].starts_with("POST /api/v3/order?"));
+    }
+}
@@ -1 +1,3 @@
 pub mod order;
+pub mod binance;
+pub mod wallet;
@@ -1,20 +1,238 @@
+use std::collections::HashMap;
 use std::str::FromStr;
-use std::time::Instant;
-use tokio::sync::mpsc;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, watch};
+
+use polymarket_client_sdk::auth::state::Authenticated;
+use polymarket_client_sdk::auth::{Kind, Signer};
+use polymarket_client_sdk::clob::Client as ClobClient;
+use polymarket_client_sdk::clob::types::response::PostOrderResponse;
+use polymarket_client_sdk::clob::types::{
+    OrderStatusType, OrderType as ClobOrderType, Side as ClobSide, SignedOrder,
+};
+use polymarket_client_sdk::ctf::Client as CtfClient;
+use polymarket_client_sdk::ctf::types::MergePositionsRequest;
+use polymarket_client_sdk::types::{address, Address, Decimal, B256, U256};
+use alloy::providers::Provider;
 
 use crate::config::Config;
+use crate::gateway::wallet::{UsdcLedger, WalletSnapshot};
+use crate::math::fees::{FeeSchedule, Liquidity};
 use crate::types::*;
 
-/// Order gateway: receives orders from engine, executes on CLOB, feeds ack back.
+/// How often resting orders are polled for fills (live mode).
+const RESTING_POLL_MS: u64 = 500;
+
+/// Largest loss per share accepted when reversing the filled leg of a broken pair.
+const UNWIND_MAX_LOSS: f64 = 0.05;
+
+/// USDC.e on Polygon — CTF collateral for merging parity pairs.
+const USDC: Address = address!("0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174");
+
+/// A live order resting on the CLOB, polled for fills until matched or cancelled.
+struct RestingClob {
+    clob_id: String,
+    price: f64,
+    action: OrderAction,
+    /// Shares matched so far (CLOB `size_matched`).
+    matched_shares: f64,
+}
+
+impl RestingClob {
+    /// USDC notional newly matched since the last check, given the CLOB's cumulative `size_matched`.
+    fn take_new_fill(&mut self, size_matched: f64) -> Option<f64> {
+        let new_shares = size_matched - self.matched_shares;
+        if new_shares <= 1e-9 {
+            return None;
+        }
+        self.matched_shares = size_matched;
+        Some(new_shares * self.price)
+    }
+
+    /// Take the newly matched slice of engine order `id` and book it in the ledger.
+    fn settle_new_fill(&mut self, id: u64, size_matched: f64, usdc: &mut UsdcLedger) -> Option<f64> {
+        let fill = self.take_new_fill(size_matched)?;
+        usdc.settle_slice(id, self.action, fill, chrono::Utc::now().timestamp_millis());
+        Some(fill)
+    }
+}
+
+// Lives for one loop iteration; boxing the request would only add an allocation.
+#[allow(clippy::large_enum_variant)]
+enum GatewayStep {
+    Request(OrderRequest),
+    Poll,
+    /// The wallet service published a new reconciliation.
+    Wallet,
+}
+
+/// Whole shares for an order's USDC notional. The CLOB requires maker_amount
+/// (= shares * price) to have ≤2 decimal places, so shares are floored; the epsilon
+/// keeps `n * price / price` from flooring to `n - 1` (pair legs are sized that way).
+#[inline]
+fn shares_of(order: &Order) -> f64 {
+    (order.size / order.price + 1e-9).floor()
+}
+
+/// Round a limit onto the tick grid without crossing it: buys round down, sells up.
+#[inline]
+fn tick_round(price: f64, tick: f64, action: OrderAction) -> f64 {
+    let ticks = price / tick;
+    let ticks = match action {
+        OrderAction::Buy => (ticks + 1e-9).floor(),
+        OrderAction::Sell => (ticks - 1e-9).ceil(),
+    };
+    ticks * tick
+}
+
+/// Break-even limit for the missing leg of a pair whose other leg filled at
+/// `filled_price`, taker fees on both legs included: a bought pair may cost up
+/// to $1 all-in, a sold pair must return $1 net.
+fn chase_price(filled_price: f64, tick: f64, action: OrderAction, fees: &FeeSchedule) -> Option<f64> {
+    let fee = |p: f64| fees.fee_per_share(p, Liquidity::Taker);
+    let ticks = (1.0 / tick).round() as i64;
+    let mut n = (tick_round(1.0 - filled_price, tick, action) / tick).round() as i64;
+    // The fee curve is not invertible in closed form: step away from the
+    // fee-free break-even one tick at a time until the pair clears $1
+    loop {
+        if n < 1 || n > ticks - 1 {
+            return None;
+        }
+        let p = n as f64 * tick;
+        match action {
+            OrderAction::Buy if filled_price + fee(filled_price) + p + fee(p) > 1.0 + 1e-9 => n -= 1,
+            OrderAction::Sell if filled_price - fee(filled_price) + p - fee(p) < 1.0 - 1e-9 => n += 1,
+            _ => return Some(p),
+        }
+    }
+}
+
+/// Limit for reversing a leg that filled at `filled_price` with `action`:
+/// sell back (or buy back) giving up at most `UNWIND_MAX_LOSS` per share.
+fn unwind_price(filled_price: f64, tick: f64, action: OrderAction) -> f64 {
+    let p = match action {
+        OrderAction::Buy => tick_round(filled_price - UNWIND_MAX_LOSS, tick, OrderAction::Sell),
+        OrderAction::Sell => tick_round(filled_price + UNWIND_MAX_LOSS, tick, OrderAction::Buy),
+    };
+    p.clamp(tick, 1.0 - tick)
+}
+
+/// An engine order converted to CLOB units: tick-precision price, whole shares.
+struct ClobParams {
+    price: Decimal,
+    shares: Decimal,
+    token_id: U256,
+    price_str: String,
+    size_str: String,
+}
+
+impl ClobParams {
+    fn of(order: &Order, tick_decimals: usize) -> Result<Self, String> {
+        let price_str = format!("{:.prec$}", order.price, prec = tick_decimals);
+        let price = Decimal::from_str(&price_str).map_err(|e| format!("bad price: {}", e))?;
+        // Our size is USDC notional, the SDK expects shares (outcome tokens):
+        // a BUY spends shares * price USDC, a SELL gives up shares for shares * price.
+        let size_str = format!("{:.0}", shares_of(order));
+        let shares = Decimal::from_str(&size_str).map_err(|e| format!("bad size: {}", e))?;
+        let token_id = U256::from_str(&order.token_id).map_err(|e| format!("bad token_id: {}", e))?;
+        Ok(Self { price, shares, token_id, price_str, size_str })
+    }
+}
+
+/// Build and sign a limit order for `order`.
+async fn sign_limit<K: Kind, S: Signer>(
+    client: &ClobClient<Authenticated<K>>,
+    signer: &S,
+    order: &Order,
+    params: &ClobParams,
+) -> Result<SignedOrder, String> {
+    let mut builder = client
+        .limit_order()
+        .token_id(params.token_id)
+        .price(params.price)
+        .size(params.shares)
+        .side(match order.action {
+            OrderAction::Buy => ClobSide::Buy,
+            OrderAction::Sell => ClobSide::Sell,
+        })
+        .order_type(match order.order_type {
+            OrderType::GTC => ClobOrderType::GTC,
+            OrderType::FOK => ClobOrderType::FOK,
+            OrderType::GTD => ClobOrderType::GTD,
+        })
+        .post_only(order.post_only);
+
+    // GTD orders require an expiration timestamp
+    if let Some(exp_ms) = order.expiration_ms {
+        use chrono::{DateTime, Utc};
+        let exp_dt = DateTime::from_timestamp_millis(exp_ms)
+            .unwrap_or_else(|| Utc::now() + chrono::Duration::seconds(10));
+        builder = builder.expiration(exp_dt);
+    }
+
+    let signable = builder.build().await.map_err(|e| format!("build: {}", e))?;
+    client.sign(signer, signable).await.map_err(|e| format!("sign: {}", e))
+}
+
+/// PostOrderResponse doesn't impl Serialize, build JSON manually (recording/replay).
+fn response_json(resp: &PostOrderResponse) -> String {
+    serde_json::json!({
+        "success": resp.success,
+        "order_id": resp.order_id,
+        "status": format!("{:?}", resp.status),
+        "error_msg": resp.error_msg,
+        "making_amount": resp.making_amount.to_string(),
+        "taking_amount": resp.taking_amount.to_string(),
+        "trade_ids": resp.trade_ids,
+    })
+    .to_string()
+}
+
+/// Outcome of a FOK submission: matched in full, or rejected with the CLOB's reason.
+fn fok_status(resp: &PostOrderResponse) -> OrderStatus {
+    match (resp.success, &resp.status) {
+        (true, OrderStatusType::Matched) => OrderStatus::Filled,
+        (true, status) => OrderStatus::Rejected(format!("FOK not matched: {:?}", status)),
+        (false, _) => OrderStatus::Rejected(
+            resp.error_msg.clone().unwrap_or_else(|| "unknown error".to_string()),
+        ),
+    }
+}
+
+fn record_raw(telem_tx: &mpsc::Sender<TelemetryEvent>, order_id: u64, direction: &'static str, raw_json: String) {
+    let _ = telem_tx.try_send(TelemetryEvent::RawClobResponse(RawClobRecord {
+        ts_ms: chrono::Utc::now().timestamp_millis(),
+        order_id,
+        direction,
+        raw_json,
+    }));
+}
+
+/// Book a filled order's USDC in the ledger: buys spend, sells return.
+#[inline]
+fn settle_usdc(usdc: &mut UsdcLedger, order: &Order) {
+    usdc.settle(order, chrono::Utc::now().timestamp_millis());
+}
+
+/// Order gateway: receives order requests from engine, executes on CLOB, feeds acks back.
 /// Runs as a background task — never touches shared state.
 ///
-/// In dry_run mode: simulates immediate fills.
-/// In live mode: submits to Polymarket CLOB via polymarket-client-sdk.
+/// In dry_run mode: simulates immediate fills (cancels are no-ops).
+/// In live mode: submits to Polymarket CLOB via polymarket-client-sdk. Orders that
+/// rest (`Live`) are polled for fills — each newly matched slice is acked as
+/// `PartialFill`/`Filled` with its own size — and can be cancelled by engine ID.
+/// Anything still resting when the engine hangs up is cancelled.
+///
+/// Pre-flight USDC is a `UsdcLedger`: seeded from the wallet service's latest
+/// snapshot (or the CLOB balance before the first sync), debited and credited
+/// by fills (resting orders' slices as polled), held back by resting buys, and
+/// re-synced whenever `wallet_rx` publishes.
 pub async fn order_gateway(
-    mut order_rx: mpsc::Receiver<Order>,
+    mut order_rx: mpsc::Receiver<OrderRequest>,
     feed_tx: mpsc::Sender<FeedEvent>,
     telem_tx: mpsc::Sender<TelemetryEvent>,
     market_ctx_rx: tokio::sync::oneshot::Receiver<MarketContext>,
+    mut wallet_rx: watch::Receiver<Option<WalletSnapshot>>,
     config: Config,
 ) {
     eprintln!("[GW] Order gateway started (dry_run={})", config.dry_run);
@@ -37,14 +255,10 @@ pub async fn order_gateway(
     };
 
     // ── Initialize CLOB client for live execution ──
-    use polymarket_client_sdk::clob::{Client as ClobClient, Config as ClobConfig};
-    use polymarket_client_sdk::clob::types::{
-        Side as ClobSide, OrderType as ClobOrderType, SignatureType, OrderStatusType,
-        AssetType,
-    };
+    use polymarket_client_sdk::clob::Config as ClobConfig;
+    use polymarket_client_sdk::clob::types::{SignatureType, AssetType};
     use polymarket_client_sdk::clob::types::request::BalanceAllowanceRequest;
-    use polymarket_client_sdk::types::{Decimal, U256};
-    use polymarket_client_sdk::auth::{LocalSigner, Signer};
+    use polymarket_client_sdk::auth::LocalSigner;
     use polymarket_client_sdk::POLYGON;
 
     // Compute tick_size decimal places for price rounding
@@ -156,13 +370,171 @@ pub async fn order_gateway(
     let _ = &market_ctx; // used in live path
 
     // Track available USDC for pre-flight balance checks (live mode only)
-    let mut usdc_available: f64 = clob.as_ref().map(|(_, _, bal)| *bal).unwrap_or(0.0);
+    let mut usdc = UsdcLedger::new(clob.as_ref().map(|(_, _, bal)| *bal).unwrap_or(0.0));
+    if let Some(snapshot) = wallet_rx.borrow_and_update().as_ref() {
+        usdc.reconcile(snapshot);
+    }
+    let mut wallet_open = true;
     if !config.dry_run {
-        eprintln!("[GW] Available USDC for trading: ${:.2}", usdc_available);
+        eprintln!("[GW] Available USDC for trading: ${:.2}", usdc.available());
     }
 
+    // CTF client for merging filled parity pairs. Only an EOA holds its own outcome
+    // tokens (proxy/safe wallets would merge from the funder); neg-risk markets go
+    // through the adapter. Anything not merged simply redeems at settlement.
+    let merger = match (&clob, B256::from_str(&market_ctx.condition_id)) {
+        (Some((_, signer, _)), Ok(condition_id))
+            if config.polymarket_signature_type == 0 && !market_ctx.neg_risk =>
+        {
+            use alloy::providers::ProviderBuilder;
+            match ProviderBuilder::new().wallet(signer.clone()).connect(&config.polygon_rpc_url).await {
+                Ok(provider) => CtfClient::new(provider, POLYGON).ok().map(|ctf| (ctf, condition_id)),
+                Err(e) => {
+                    eprintln!("[GW] Polygon RPC unavailable, parity pairs will not be merged: {}", e);
+                    None
+                }
+            }
+        }
+        _ => None,
+    };
+
+    // Engine order ID → resting CLOB order (live mode only)
+    let mut resting: HashMap<u64, RestingClob> = HashMap::new();
+    let mut poll = tokio::time::interval(Duration::from_millis(RESTING_POLL_MS));
+    poll.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
     // ── Order processing loop ──
-    while let Some(order) = order_rx.recv().await {
+    loop {
+        let step = tokio::select! {
+            req = order_rx.recv() => match req {
+                Some(req) => GatewayStep::Request(req),
+                None => break,
+            },
+            _ = poll.tick(), if !resting.is_empty() => GatewayStep::Poll,
+            changed = wallet_rx.changed(), if wallet_open => match changed {
+                Ok(()) => GatewayStep::Wallet,
+                Err(_) => {
+                    wallet_open = false;
+                    continue;
+                }
+            },
+        };
+
+        let order = match step {
+            GatewayStep::Request(OrderRequest::Place(order)) => order,
+
+            GatewayStep::Request(OrderRequest::Pair(up, down)) => {
+                let Some((ref client, ref signer, _)) = clob else {
+                    // Dry run: both legs fill at their limits
+                    for leg in [&up, &down] {
+                        send_ack(&feed_tx, leg.id, OrderStatus::Filled, Some(leg.price), Some(leg.size), leg.created_at).await;
+                    }
+                    continue;
+                };
+                let merge = execute_pair(
+                    client, signer, [&up, &down], market_ctx.tick_size, tick_decimals, &market_ctx.fees,
+                    &mut usdc, &feed_tx, &telem_tx,
+                ).await;
+                // Only UP + DOWN of this market's condition merge; nested-window
+                // packages span two markets and are held to settlement
+                let same_market = up.instrument == Instrument::Current && down.instrument == Instrument::Current;
+                if let (Some(shares), Some((ctf, condition_id)), true) = (merge, &merger, same_market) {
+                    spawn_merge(ctf, *condition_id, shares, feed_tx.clone());
+                }
+                continue;
+            }
+
+            GatewayStep::Request(OrderRequest::Cancel(id)) => {
+                let Some((ref client, _, _)) = clob else {
+                    // Dry-run orders fill on submission; nothing is ever resting
+                    continue;
+                };
+                let Some(r) = resting.get(&id) else {
+                    eprintln!("[GW] #{} cancel: not resting (filled or already gone)", id);
+                    continue;
+                };
+                let submit_at = Instant::now();
+                match client.cancel_order(&r.clob_id).await {
+                    Ok(resp) if resp.canceled.contains(&r.clob_id) => {
+                        let mut r = resting.remove(&id).expect("resting order");
+                        // Report anything matched since the last poll before the cancel
+                        if let Ok(o) = client.order(&r.clob_id).await {
+                            let matched: f64 = o.size_matched.to_string().parse().unwrap_or(0.0);
+                            if let Some(fill) = r.settle_new_fill(id, matched, &mut usdc) {
+                                send_ack(&feed_tx, id, OrderStatus::PartialFill, Some(r.price), Some(fill), submit_at).await;
+                            }
+                        }
+                        usdc.release(id);
+                        eprintln!("[GW] #{} CANCELLED clob_id={}", id, r.clob_id);
+                        send_ack(&feed_tx, id, OrderStatus::Cancelled, None, None, submit_at).await;
+                    }
+                    Ok(resp) => {
+                        // Usually already matched; the next poll reports the fill
+                        eprintln!("[GW] #{} cancel refused: {:?}", id, resp.not_canceled);
+                    }
+                    Err(e) => eprintln!("[GW] #{} cancel error: {}", id, e),
+                }
+                continue;
+            }
+
+            GatewayStep::Wallet => {
+                if let Some(snapshot) = wallet_rx.borrow_and_update().as_ref() {
+                    let before = usdc.available();
+                    usdc.reconcile(snapshot);
+                    if (usdc.available() - before).abs() >= 0.01 {
+                        eprintln!("[GW] USDC re-synced: ${:.2} → ${:.2}", before, usdc.available());
+                    }
+                }
+                continue;
+            }
+
+            GatewayStep::Poll => {
+                let Some((ref client, _, _)) = clob else { continue };
+                let ids: Vec<u64> = resting.keys().copied().collect();
+                for id in ids {
+                    let clob_id = resting[&id].clob_id.clone();
+                    let o = match client.order(&clob_id).await {
+                        Ok(o) => o,
+                        Err(e) => {
+                            eprintln!("[GW] #{} poll error: {}", id, e);
+                            continue;
+                        }
+                    };
+                    let r = resting.get_mut(&id).expect("resting order");
+                    let matched: f64 = o.size_matched.to_string().parse().unwrap_or(0.0);
+                    let new_fill = r.settle_new_fill(id, matched, &mut usdc);
+                    let price = r.price;
+                    match o.status {
+                        OrderStatusType::Matched => {
+                            resting.remove(&id);
+                            usdc.release(id);
+                            send_ack(&feed_tx, id, OrderStatus::Filled, Some(price), new_fill, Instant::now()).await;
+                        }
+                        OrderStatusType::Canceled => {
+                            // Expired (GTD) or cancelled outside the engine
+                            resting.remove(&id);
+                            usdc.release(id);
+                            if let Some(fill) = new_fill {
+                                send_ack(&feed_tx, id, OrderStatus::PartialFill, Some(price), Some(fill), Instant::now()).await;
+                            }
+                            send_ack(&feed_tx, id, OrderStatus::Cancelled, None, None, Instant::now()).await;
+                        }
+                        _ => {
+                            if let Some(fill) = new_fill {
+                                send_ack(&feed_tx, id, OrderStatus::PartialFill, Some(price), Some(fill), Instant::now()).await;
+                            }
+                        }
+                    }
+                    if let Some(fill) = new_fill {
+                        eprintln!(
+                            "[GW] #{} resting fill ${:.2} @ {:.3}, USDC remaining: ${:.2}",
+                            id, fill, price, usdc.available()
+                        );
+                    }
+                }
+                continue;
+            }
+        };
         let submit_at = Instant::now();
 
         let ack = if config.dry_run {
@@ -180,76 +552,41 @@ pub async fn order_gateway(
             // ── Live CLOB execution ──
             let (ref client, ref signer, _) = *clob.as_ref().unwrap();
 
-            // Pre-flight: check USDC balance before sending to CLOB
+            // Pre-flight: check USDC balance before sending to CLOB (sells spend tokens, not USDC)
             let usdc_needed = order.size;
-            if usdc_available < usdc_needed {
+            if order.action == OrderAction::Buy && usdc.available() < usdc_needed {
                 let reason = format!(
                     "insufficient USDC: need ${:.2} but only ${:.2} available",
-                    usdc_needed, usdc_available
+                    usdc_needed, usdc.available()
                 );
                 send_rejected_ack(&feed_tx, &telem_tx, &order, submit_at, reason).await;
                 continue;
             }
 
             eprintln!(
-                "[GW] LIVE #{}: {:?} {:?} @ {:.tick$} x ${:.2} [{}] post_only={} token={:.8}..",
-                order.id, order.order_type, order.side, order.price, order.size,
+                "[GW] LIVE #{}: {:?} {:?} {:?} @ {:.tick$} x ${:.2} [{}] post_only={} token={:.8}..",
+                order.id, order.order_type, order.action, order.side, order.price, order.size,
                 order.strategy, order.post_only,
                 &order.token_id[..8.min(order.token_id.len())],
                 tick = tick_decimals,
             );
 
-            // Convert price to Decimal with tick_size precision
-            let price_str = format!("{:.prec$}", order.price, prec = tick_decimals);
-            let price_dec = match Decimal::from_str(&price_str) {
-                Ok(d) => d,
-                Err(e) => {
-                    eprintln!("[GW] Invalid price '{}': {}", price_str, e);
-                    send_rejected_ack(&feed_tx, &telem_tx, &order, submit_at, format!("bad price: {}", e)).await;
+            let params = match ClobParams::of(&order, tick_decimals) {
+                Ok(p) => p,
+                Err(reason) => {
+                    send_rejected_ack(&feed_tx, &telem_tx, &order, submit_at, reason).await;
                     continue;
                 }
             };
 
-            // Convert size: our size is USDC notional, SDK expects shares (outcome tokens)
-            // For BUY: you spend (shares * price) USDC to get (shares) tokens
-            // So shares = usdc_notional / price
-            // CLOB requires maker_amount (= shares * price) to have ≤2 decimal places,
-            // so we floor shares to ensure clean USDC amounts.
-            let shares = (order.size / order.price).floor();
-            let size_str = format!("{:.0}", shares);
-            let size_dec = match Decimal::from_str(&size_str) {
-                Ok(d) => d,
-                Err(e) => {
-                    eprintln!("[GW] Invalid size '{}': {}", size_str, e);
-                    send_rejected_ack(&feed_tx, &telem_tx, &order, submit_at, format!("bad size: {}", e)).await;
-                    continue;
-                }
-            };
-
-            // Parse token_id to U256
-            let token_id = match U256::from_str(&order.token_id) {
-                Ok(id) => id,
-                Err(e) => {
-                    eprintln!("[GW] Invalid token_id '{}': {}", order.token_id, e);
-                    send_rejected_ack(&feed_tx, &telem_tx, &order, submit_at, format!("bad token_id: {}", e)).await;
-                    continue;
-                }
-            };
-
-            // Map our order type → SDK order type
-            let clob_order_type = match order.order_type {
-                OrderType::GTC => ClobOrderType::GTC,
-                OrderType::FOK => ClobOrderType::FOK,
-                OrderType::GTD => ClobOrderType::GTD,
-            };
-
             // Record raw request for replay
             let request_json = serde_json::json!({
                 "order_id": order.id,
                 "token_id": order.token_id,
                 "side": format!("{:?}", order.side),
-                "price": price_str,
-                "size_shares": size_str,
+                "action": format!("{:?}", order.action),
+                "price": params.price_str,
+                "size_shares": params.size_str,
                 "size_usdc": order.size,
                 "order_type": format!("{:?}", order.order_type),
                 "post_only": order.post_only,
@@ -257,71 +594,19 @@ pub async fn order_gateway(
                 "strategy": order.strategy,
             })
             .to_string();
-
-            let _ = telem_tx.try_send(TelemetryEvent::RawClobResponse(RawClobRecord {
-                ts_ms: chrono::Utc::now().timestamp_millis(),
-                order_id: order.id,
-                direction: "submit",
-                raw_json: request_json,
-            }));
+            record_raw(&telem_tx, order.id, "submit", request_json);
 
             // Build → Sign → Post
             let result: Result<_, String> = async {
-                let mut builder = client
-                    .limit_order()
-                    .token_id(token_id)
-                    .price(price_dec)
-                    .size(size_dec)
-                    .side(ClobSide::Buy)
-                    .order_type(clob_order_type)
-                    .post_only(order.post_only);
-
-                // GTD orders require an expiration timestamp
-                if let Some(exp_ms) = order.expiration_ms {
-                    use chrono::{DateTime, Utc};
-                    let exp_dt = DateTime::from_timestamp_millis(exp_ms)
-                        .unwrap_or_else(|| Utc::now() + chrono::Duration::seconds(10));
-                    builder = builder.expiration(exp_dt);
-                }
-
-                let signable = builder
-                    .build()
-                    .await
-                    .map_err(|e| format!("build: {}", e))?;
-
-                let signed = client
-                    .sign(signer, signable)
-                    .await
-                    .map_err(|e| format!("sign: {}", e))?;
-
-                let resp = client
-                    .post_order(signed)
-                    .await
-                    .map_err(|e| format!("post: {}", e))?;
-
-                Ok(resp)
+                let signed = sign_limit(client, signer, &order, &params).await?;
+                client.post_order(signed).await.map_err(|e| format!("post: {}", e))
             }
             .await;
 
             match result {
                 Ok(resp) => {
-                    // PostOrderResponse doesn't impl Serialize, build JSON manually
-                    let raw = serde_json::json!({
-                        "success": resp.success,
-                        "order_id": resp.order_id,
-                        "status": format!("{:?}", resp.status),
-                        "error_msg": resp.error_msg,
-                        "making_amount": resp.making_amount.to_string(),
-                        "taking_amount": resp.taking_amount.to_string(),
-                        "trade_ids": resp.trade_ids,
-                    }).to_string();
-
-                    let _ = telem_tx.try_send(TelemetryEvent::RawClobResponse(RawClobRecord {
-                        ts_ms: chrono::Utc::now().timestamp_millis(),
-                        order_id: order.id,
-                        direction: "response",
-                        raw_json: raw.clone(),
-                    }));
+                    let raw = response_json(&resp);
+                    record_raw(&telem_tx, order.id, "response", raw.clone());
 
                     let latency = submit_at.elapsed().as_secs_f64() * 1000.0;
 
@@ -373,14 +658,23 @@ pub async fn order_gateway(
                     // For Matched orders, filled_size is in USDC (our convention)
                     let filled_size = match &status {
                         OrderStatus::Filled => {
-                            // Deduct from available balance
-                            usdc_available -= order.size;
+                            settle_usdc(&mut usdc, &order);
                             eprintln!(
-                                "[GW] USDC remaining: ${:.2} (spent ${:.2})",
-                                usdc_available, order.size
+                                "[GW] USDC remaining: ${:.2} ({:?} ${:.2})",
+                                usdc.available(), order.action, order.size
                             );
                             Some(order.size)
                         }
+                        OrderStatus::Live => {
+                            usdc.reserve(&order);
+                            resting.insert(order.id, RestingClob {
+                                clob_id: resp.order_id.clone(),
+                                price: order.price,
+                                action: order.action,
+                                matched_shares: 0.0,
+                            });
+                            None
+                        }
                         _ => None,
                     };
 
@@ -397,13 +691,7 @@ pub async fn order_gateway(
                 Err(e) => {
                     let latency = submit_at.elapsed().as_secs_f64() * 1000.0;
                     eprintln!("[GW] #{} ERROR: {} lat={:.1}ms", order.id, e, latency);
-
-                    let _ = telem_tx.try_send(TelemetryEvent::RawClobResponse(RawClobRecord {
-                        ts_ms: chrono::Utc::now().timestamp_millis(),
-                        order_id: order.id,
-                        direction: "error",
-                        raw_json: e.clone(),
-                    }));
+                    record_raw(&telem_tx, order.id, "error", e.clone());
 
                     OrderAck {
                         order_id: order.id,
@@ -435,13 +723,259 @@ pub async fn order_gateway(
 
         if feed_tx.send(FeedEvent::OrderAck(final_ack)).await.is_err() {
             eprintln!("[GW] Feed channel closed, exiting");
-            return;
+            break;
+        }
+    }
+
+    // Engine hung up (market over): nothing may be left resting on the book
+    if let (Some((ref client, _, _)), false) = (&clob, resting.is_empty()) {
+        let ids: Vec<&str> = resting.values().map(|r| r.clob_id.as_str()).collect();
+        match client.cancel_orders(&ids).await {
+            Ok(resp) => eprintln!(
+                "[GW] Cancelled {} resting orders ({} refused)",
+                resp.canceled.len(), resp.not_canceled.len()
+            ),
+            Err(e) => eprintln!("[GW] Failed to cancel {} resting orders: {}", ids.len(), e),
         }
     }
 
     eprintln!("[GW] Order gateway stopped");
 }
 
+/// Live: execute a parity pair (UP leg, DOWN leg, same share count, same action).
+///
+/// Both legs go out as FOK in one batch. If exactly one fills, the missing leg is
+/// retried once at its fee-inclusive break-even limit; failing that, the filled leg is reversed
+/// (giving back at most `UNWIND_MAX_LOSS` per share) and acked `Unwound`. A leg
+/// that can be neither completed nor reversed is acked `Filled` and raises an alert.
+/// Returns the share count of a completed buy pair, for merging.
+#[allow(clippy::too_many_arguments)]
+async fn execute_pair<K: Kind, S: Signer>(
+    client: &ClobClient<Authenticated<K>>,
+    signer: &S,
+    legs: [&Order; 2],
+    tick: f64,
+    tick_decimals: usize,
+    fees: &FeeSchedule,
+    usdc: &mut UsdcLedger,
+    feed_tx: &mpsc::Sender<FeedEvent>,
+    telem_tx: &mpsc::Sender<TelemetryEvent>,
+) -> Option<f64> {
+    let submit_at = Instant::now();
+    let action = legs[0].action;
+
+    // Pre-flight: a bought pair needs USDC for both legs up front
+    let usdc_needed = legs[0].size + legs[1].size;
+    if action == OrderAction::Buy && usdc.available() < usdc_needed {
+        let reason = format!(
+            "insufficient USDC for pair: need ${:.2} but only ${:.2} available",
+            usdc_needed, usdc.available()
+        );
+        for leg in legs {
+            send_rejected_ack(feed_tx, telem_tx, leg, submit_at, reason.clone()).await;
+        }
+        return None;
+    }
+
+    eprintln!(
+        "[GW] LIVE PAIR #{}+#{}: {:?} UP @ {:.tick$} + DOWN @ {:.tick$} x {} shares [{}]",
+        legs[0].id, legs[1].id, action, legs[0].price, legs[1].price,
+        shares_of(legs[0]), legs[0].strategy,
+        tick = tick_decimals,
+    );
+
+    let mut signed = Vec::with_capacity(2);
+    for leg in legs {
+        let result = match ClobParams::of(leg, tick_decimals) {
+            Ok(params) => sign_limit(client, signer, leg, &params).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(order) => signed.push(order),
+            Err(e) => {
+                let reason = format!("pair leg #{}: {}", leg.id, e);
+                for leg in legs {
+                    send_rejected_ack(feed_tx, telem_tx, leg, submit_at, reason.clone()).await;
+                }
+                return None;
+            }
+        }
+    }
+
+    let mut status: Vec<OrderStatus> = match client.post_orders(signed).await {
+        Ok(resps) => legs
+            .iter()
+            .enumerate()
+            .map(|(i, leg)| match resps.get(i) {
+                Some(resp) => {
+                    record_raw(telem_tx, leg.id, "response", response_json(resp));
+                    fok_status(resp)
+                }
+                None => OrderStatus::Rejected("no response for leg".to_string()),
+            })
+            .collect(),
+        Err(e) => {
+            let e = format!("post: {}", e);
+            for leg in legs {
+                record_raw(telem_tx, leg.id, "error", e.clone());
+            }
+            legs.iter().map(|_| OrderStatus::Rejected(e.clone())).collect()
+        }
+    };
+
+    let filled = [
+        matches!(status[0], OrderStatus::Filled),
+        matches!(status[1], OrderStatus::Filled),
+    ];
+    let (fi, mi) = match filled {
+        [true, true] => {
+            for leg in legs {
+                settle_usdc(usdc, leg);
+                send_ack(feed_tx, leg.id, OrderStatus::Filled, Some(leg.price), Some(leg.size), submit_at).await;
+            }
+            eprintln!("[GW] PAIR #{}+#{} MATCHED, USDC remaining: ${:.2}", legs[0].id, legs[1].id, usdc.available());
+            return (action == OrderAction::Buy).then(|| shares_of(legs[0]));
+        }
+        [false, false] => {
+            eprintln!("[GW] PAIR #{}+#{} not matched: {:?} / {:?}", legs[0].id, legs[1].id, status[0], status[1]);
+            for (leg, st) in legs.into_iter().zip(status) {
+                send_ack(feed_tx, leg.id, st, None, None, submit_at).await;
+            }
+            return None;
+        }
+        [true, false] => (0, 1),
+        [false, true] => (1, 0),
+    };
+
+    // ── Leg risk: one side filled alone ──
+    let (done, missing) = (legs[fi], legs[mi]);
+    let missing_status = status.swap_remove(mi);
+    let shares = shares_of(done);
+    settle_usdc(usdc, done);
+    eprintln!(
+        "[GW] PAIR leg #{} filled, #{} failed ({:?}) — resolving leg risk",
+        done.id, missing.id, missing_status
+    );
+
+    // 1. Chase the missing leg up to break-even: the pair still costs ≤ $1 with fees
+    if let Some(px) = chase_price(done.price, tick, action, fees) {
+        let chase = Order { price: px, size: shares * px, ..missing.clone() };
+        if post_fok(client, signer, &chase, tick_decimals, telem_tx).await {
+            settle_usdc(usdc, &chase);
+            eprintln!("[GW] PAIR leg #{} chased and filled @ {:.3}", missing.id, px);
+            send_ack(feed_tx, done.id, OrderStatus::Filled, Some(done.price), Some(done.size), submit_at).await;
+            send_ack(feed_tx, missing.id, OrderStatus::Filled, Some(px), Some(chase.size), submit_at).await;
+            return (action == OrderAction::Buy).then_some(shares);
+        }
+    }
+
+    // 2. Reverse the filled leg
+    let exit_px = unwind_price(done.price, tick, action);
+    let exit = Order {
+        price: exit_px,
+        size: shares * exit_px,
+        action: match action {
+            OrderAction::Buy => OrderAction::Sell,
+            OrderAction::Sell => OrderAction::Buy,
+        },
+        ..done.clone()
+    };
+    let status = if post_fok(client, signer, &exit, tick_decimals, telem_tx).await {
+        settle_usdc(usdc, &exit);
+        eprintln!("[GW] PAIR leg #{} unwound @ {:.3} (entry {:.3})", done.id, exit_px, done.price);
+        OrderStatus::Unwound { exit_price: exit_px }
+    } else {
+        let reason = format!(
+            "parity pair broken: #{} {} filled @ {:.3}, #{} failed and unwind @ {:.3} did not fill — position unhedged",
+            done.id, done.side, done.price, missing.id, exit_px
+        );
+        eprintln!("[GW] ⚠ {}", reason);
+        let _ = telem_tx.try_send(TelemetryEvent::OrderRejectedLocal(OrderRejectedRecord {
+            order_id: missing.id,
+            strategy: missing.strategy.to_string(),
+            reason,
+        }));
+        OrderStatus::Filled
+    };
+    send_ack(feed_tx, done.id, status, Some(done.price), Some(done.size), submit_at).await;
+    send_ack(feed_tx, missing.id, missing_status, None, None, submit_at).await;
+    None
+}
+
+/// Sign and post a single FOK order (pair chase/unwind). True if matched in full.
+async fn post_fok<K: Kind, S: Signer>(
+    client: &ClobClient<Authenticated<K>>,
+    signer: &S,
+    order: &Order,
+    tick_decimals: usize,
+    telem_tx: &mpsc::Sender<TelemetryEvent>,
+) -> bool {
+    let result: Result<_, String> = async {
+        let params = ClobParams::of(order, tick_decimals)?;
+        let signed = sign_limit(client, signer, order, &params).await?;
+        client.post_order(signed).await.map_err(|e| format!("post: {}", e))
+    }
+    .await;
+    match result {
+        Ok(resp) => {
+            record_raw(telem_tx, order.id, "response", response_json(&resp));
+            matches!(fok_status(&resp), OrderStatus::Filled)
+        }
+        Err(e) => {
+            eprintln!("[GW] #{} {:?} {:?} @ {:.3} error: {}", order.id, order.action, order.side, order.price, e);
+            record_raw(telem_tx, order.id, "error", e);
+            false
+        }
+    }
+}
+
+/// Merge `shares` UP+DOWN full sets back into USDC in the background. The pair's
+/// payoff is locked either way; merging frees the capital before settlement.
+/// On success the engine is told (`FeedEvent::PairsMerged`) so its holdings stop
+/// counting the merged pairs as sellable.
+fn spawn_merge<P>(ctf: &CtfClient<P>, condition_id: B256, shares: f64, feed_tx: mpsc::Sender<FeedEvent>)
+where
+    P: Provider + Clone + Send + Sync + 'static,
+{
+    let ctf = ctf.clone();
+    // Outcome tokens carry 6 decimals, like USDC
+    let amount = U256::from((shares * 1e6).round() as u64);
+    tokio::spawn(async move {
+        let req = MergePositionsRequest::for_binary_market(USDC, condition_id, amount);
+        match ctf.merge_positions(&req).await {
+            Ok(r) => {
+                eprintln!(
+                    "[GW] Merged {} pairs into USDC tx={} (block {})",
+                    shares, r.transaction_hash, r.block_number
+                );
+                let _ = feed_tx.send(FeedEvent::PairsMerged(shares)).await;
+            }
+            Err(e) => eprintln!("[GW] Merge of {} pairs failed (redeems at settlement): {}", shares, e),
+        }
+    });
+}
+
+/// Helper: feed an ack for a resting order (poll result or cancel) back to the engine.
+async fn send_ack(
+    feed_tx: &mpsc::Sender<FeedEvent>,
+    order_id: u64,
+    status: OrderStatus,
+    filled_price: Option<f64>,
+    filled_size: Option<f64>,
+    since: Instant,
+) {
+    let ack = OrderAck {
+        order_id,
+        status,
+        filled_price,
+        filled_size,
+        latency_ms: since.elapsed().as_secs_f64() * 1000.0,
+        clob_order_id: None,
+        raw_response: None,
+    };
+    let _ = feed_tx.send(FeedEvent::OrderAck(ack)).await;
+}
+
 /// Helper: send a Rejected ack back for validation errors before reaching the CLOB.
 /// Also emits an OrderRejectedLocal telemetry event so TG alerts fire.
 async fn send_rejected_ack(
@@ -479,3 +1013,95 @@ async fn send_rejected_ack(
 
     let _ = feed_tx.send(FeedEvent::OrderAck(ack)).await;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::test_helpers::make_order;
+
+    // ── Order sizing ──
+
+    /// Scenario: Pair leg sized as 31 shares × 0.47 (float product 14.57).
+    /// Expected: Floors back to exactly 31 shares, not 30.
+    #[test]
+    fn test_shares_of_survives_float_error() {
+        for price in [0.29, 0.47, 0.53, 0.57, 0.71] {
+            assert_eq!(shares_of(&make_order(1, Side::Up, OrderAction::Buy, price, 31.0 * price)), 31.0, "price {}", price);
+        }
+        assert_eq!(shares_of(&make_order(1, Side::Up, OrderAction::Buy, 0.50, 10.9)), 21.0);
+    }
+
+    /// Scenario: CLOB reports cumulative size_matched 10, 10, then 25 shares at 0.40.
+    /// Expected: Fills of $4, none, then $6 — each slice reported once.
+    #[test]
+    fn test_resting_fill_slices() {
+        let mut r = RestingClob { clob_id: String::new(), price: 0.40, action: OrderAction::Buy, matched_shares: 0.0 };
+        assert!((r.take_new_fill(10.0).unwrap() - 4.0).abs() < 1e-9);
+        assert!(r.take_new_fill(10.0).is_none());
+        assert!((r.take_new_fill(25.0).unwrap() - 6.0).abs() < 1e-9);
+    }
+
+    /// Scenario: $100 synced; a $20 buy rests at 0.40; a poll finds 10 shares matched, then
+    ///           the rest of the order is cancelled.
+    /// Expected: $80 available while resting (fill or not); after the cancel the $4 filled
+    ///           stays spent → $96, not the $100 an unbooked fill would leave.
+    #[test]
+    fn test_resting_fill_books_usdc() {
+        let mut usdc = UsdcLedger::new(100.0);
+        let buy = make_order(1, Side::Up, OrderAction::Buy, 0.40, 20.0);
+        usdc.reserve(&buy);
+        assert!((usdc.available() - 80.0).abs() < 1e-9);
+
+        let mut r = RestingClob { clob_id: String::new(), price: 0.40, action: OrderAction::Buy, matched_shares: 0.0 };
+        assert!((r.settle_new_fill(buy.id, 10.0, &mut usdc).unwrap() - 4.0).abs() < 1e-9);
+        assert!((usdc.available() - 80.0).abs() < 1e-9);
+
+        usdc.release(buy.id);
+        assert!((usdc.available() - 96.0).abs() < 1e-9);
+    }
+
+    // ── Pair leg risk ──
+
+    /// Scenario: UP leg of a buy pair filled at 0.46 on a fee-free market; DOWN leg failed.
+    /// Expected: DOWN is chased at 0.54 (pair cost exactly $1), never above.
+    #[test]
+    fn test_chase_to_break_even() {
+        let zero = FeeSchedule::zero();
+        let px = chase_price(0.46, 0.01, OrderAction::Buy, &zero).unwrap();
+        assert!((px - 0.54).abs() < 1e-9);
+        // Off-grid break-even rounds toward the safe side
+        assert!((chase_price(0.455, 0.01, OrderAction::Buy, &zero).unwrap() - 0.54).abs() < 1e-9);
+        assert!((chase_price(0.455, 0.01, OrderAction::Sell, &zero).unwrap() - 0.55).abs() < 1e-9);
+        // Nothing to chase at the edge of the price range
+        assert!(chase_price(0.995, 0.01, OrderAction::Buy, &zero).is_none());
+    }
+
+    /// Scenario: Same 0.46 fill on the default crypto fee curve (~0.7¢ per share taker fee near 0.5).
+    /// Expected: Buy chase backs off to 0.52 and a sell chase steps up to 0.56, so the
+    ///           pair still costs ≤ $1 (or returns ≥ $1) after both legs' fees.
+    #[test]
+    fn test_chase_break_even_includes_fees() {
+        let fees = FeeSchedule::default();
+        let fee = |p: f64| fees.fee_per_share(p, Liquidity::Taker);
+
+        let buy = chase_price(0.46, 0.01, OrderAction::Buy, &fees).unwrap();
+        assert!((buy - 0.52).abs() < 1e-9, "buy chase {}", buy);
+        assert!(0.46 + fee(0.46) + buy + fee(buy) <= 1.0);
+        assert!(0.46 + fee(0.46) + 0.53 + fee(0.53) > 1.0, "one tick higher would lose");
+
+        let sell = chase_price(0.46, 0.01, OrderAction::Sell, &fees).unwrap();
+        assert!((sell - 0.56).abs() < 1e-9, "sell chase {}", sell);
+        assert!(0.46 - fee(0.46) + sell - fee(sell) >= 1.0);
+    }
+
+    /// Scenario: Filled leg at 0.46 must be reversed.
+    /// Expected: A bought leg is sold at 0.41, a sold leg bought back at 0.51;
+    ///           limits stay inside [tick, 1 − tick].
+    #[test]
+    fn test_unwind_caps_loss() {
+        assert!((unwind_price(0.46, 0.01, OrderAction::Buy) - 0.41).abs() < 1e-9);
+        assert!((unwind_price(0.46, 0.01, OrderAction::Sell) - 0.51).abs() < 1e-9);
+        assert!((unwind_price(0.03, 0.01, OrderAction::Buy) - 0.01).abs() < 1e-9);
+        assert!((unwind_price(0.98, 0.01, OrderAction::Sell) - 0.99).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,289 @@
+//! Wallet service: one authoritative view of USDC and equity, shared by the
+//! gateway (pre-flight balance), risk (bankroll) and redemption.
+//!
+//! Every `WALLET_SYNC_SECS` the service reads the wallet's USDC.e balance on
+//! Polygon and its CTF positions from the Data API, and publishes a
+//! `WalletSnapshot` on a `watch` channel. Resolved positions that `auto-redeem`
+//! has not yet burned count as pending redemptions: equity, but not yet cash.
+//! Once redeemed they reappear as USDC on the next sync, so winnings are
+//! credited without either side having to tell the other.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use alloy::providers::ProviderBuilder;
+use alloy::sol;
+use polymarket_client_sdk::auth::LocalSigner;
+use polymarket_client_sdk::data::Client as DataClient;
+use polymarket_client_sdk::data::types::request::PositionsRequest;
+use polymarket_client_sdk::types::{address, Address, Decimal};
+use tokio::sync::watch;
+
+use crate::config::{BankrollMode, Config};
+use crate::types::{Order, OrderAction};
+
+/// USDC.e on Polygon (6 decimals).
+const USDC: Address = address!("0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174");
+
+/// How long a filled buy may take to show up on chain. Debits younger than
+/// this survive a re-sync, so a balance read just before settlement can't
+/// hand the gateway money it has already spent.
+const SETTLE_LAG_MS: i64 = 10_000;
+
+sol! {
+    #[sol(rpc)]
+    interface IERC20 {
+        function balanceOf(address account) external view returns (uint256);
+    }
+}
+
+/// An unresolved CTF position, as the Data API reports it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OpenPosition {
+    /// Market slug, e.g. `eth-updown-15m-1760000000`.
+    pub slug: String,
+    /// Holds the UP outcome (else DOWN).
+    pub up: bool,
+    /// Outcome tokens held.
+    pub size: f64,
+    /// Current price of the held outcome.
+    pub price: f64,
+}
+
+/// One reconciliation of the wallet.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WalletSnapshot {
+    pub ts_ms: i64,
+    /// USDC.e balance.
+    pub usdc: f64,
+    /// Open CTF positions at the Data API's current prices.
+    pub positions_value: f64,
+    /// Resolved positions awaiting redemption (winning tokens at $1).
+    pub pending_redemptions: f64,
+    /// Every open position, across all the processes sharing the wallet.
+    pub positions: Vec<OpenPosition>,
+}
+
+impl WalletSnapshot {
+    /// Cash + open positions + pending redemptions.
+    pub fn equity(&self) -> f64 {
+        self.usdc + self.positions_value + self.pending_redemptions
+    }
+}
+
+/// Bankroll to size the next market off. Without a snapshot (dry run, or no
+/// sync yet) it is the configured `BANKROLL`.
+pub fn bankroll(mode: BankrollMode, configured: f64, snapshot: Option<&WalletSnapshot>) -> f64 {
+    match (mode, snapshot) {
+        (_, None) => configured,
+        (BankrollMode::Fixed, Some(w)) => configured.min(w.equity()),
+        (BankrollMode::Compound, Some(w)) => w.equity(),
+    }
+}
+
+/// Gateway-side USDC: the last synced balance plus the gateway's own fills
+/// since, less what resting buys have reserved. Re-syncs drop the fills the
+/// chain has already seen; reservations stay until the order fills or goes.
+pub struct UsdcLedger {
+    synced: f64,
+    /// (ts_ms, signed USDC) of fills not yet covered by a sync.
+    local: Vec<(i64, f64)>,
+    /// Engine order ID → unmatched USDC of a resting buy.
+    reserved: HashMap<u64, f64>,
+}
+
+impl UsdcLedger {
+    pub fn new(balance: f64) -> Self {
+        Self { synced: balance, local: Vec::new(), reserved: HashMap::new() }
+    }
+
+    pub fn available(&self) -> f64 {
+        self.synced + self.local.iter().map(|(_, d)| d).sum::<f64>() - self.reserved.values().sum::<f64>()
+    }
+
+    /// Book a filled order: buys spend, sells return.
+    pub fn settle(&mut self, order: &Order, ts_ms: i64) {
+        self.settle_slice(order.id, order.action, order.size, ts_ms);
+    }
+
+    /// Book `usdc` matched on order `id`, out of its reservation if it has one.
+    pub fn settle_slice(&mut self, id: u64, action: OrderAction, usdc: f64, ts_ms: i64) {
+        if let Some(held) = self.reserved.get_mut(&id) {
+            *held = (*held - usdc).max(0.0);
+        }
+        let delta = match action {
+            OrderAction::Buy => -usdc,
+            OrderAction::Sell => usdc,
+        };
+        self.local.push((ts_ms, delta));
+    }
+
+    /// Hold a buy that rests on the book out of `available` (sells spend tokens).
+    pub fn reserve(&mut self, order: &Order) {
+        if order.action == OrderAction::Buy {
+            self.reserved.insert(order.id, order.size);
+        }
+    }
+
+    /// Free whatever a resting order still holds: it filled, expired or was cancelled.
+    pub fn release(&mut self, id: u64) {
+        self.reserved.remove(&id);
+    }
+
+    /// Take the snapshot's balance as truth. Fills after it are kept; so are
+    /// debits within `SETTLE_LAG_MS` before it, which may not have settled yet
+    /// (a credit in that window is dropped: erring low, never high).
+    pub fn reconcile(&mut self, snapshot: &WalletSnapshot) {
+        self.synced = snapshot.usdc;
+        self.local.retain(|&(ts, d)| ts > snapshot.ts_ms || (d < 0.0 && ts > snapshot.ts_ms - SETTLE_LAG_MS));
+    }
+}
+
+fn to_f64(d: Decimal) -> f64 {
+    d.to_string().parse().unwrap_or(0.0)
+}
+
+/// Address holding the wallet's USDC and tokens: the funder for proxy/safe
+/// wallets, the signer otherwise.
+fn wallet_address(config: &Config) -> Option<Address> {
+    if let Some(funder) = &config.polymarket_funder_address {
+        return funder.parse().ok();
+    }
+    let pk = config.polymarket_private_key.as_deref()?;
+    LocalSigner::from_str(pk).ok().map(|s| s.address())
+}
+
+/// Wallet service task (live only): reconciles every `wallet_sync_secs` and
+/// publishes the result. A failed read keeps the last snapshot.
+pub async fn wallet_service(config: Config, tx: watch::Sender<Option<WalletSnapshot>>) {
+    let Some(owner) = wallet_address(&config) else {
+        eprintln!("[WALLET] No wallet address (POLYMARKET_PRIVATE_KEY / POLYMARKET_FUNDER_ADDRESS), not syncing");
+        return;
+    };
+    let provider = match ProviderBuilder::new().connect(&config.polygon_rpc_url).await {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("[WALLET] Polygon RPC unavailable, not syncing: {}", e);
+            return;
+        }
+    };
+    let token = IERC20::new(USDC, provider);
+    let data = DataClient::default();
+    let request = PositionsRequest::builder()
+        .user(owner)
+        .size_threshold(Decimal::ZERO)
+        .limit(500)
+        .expect("500 is within the Data API page limit")
+        .build();
+    eprintln!("[WALLET] Syncing {} every {}s", owner, config.wallet_sync_secs);
+
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(config.wallet_sync_secs.max(1)));
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    loop {
+        interval.tick().await;
+        let ts_ms = chrono::Utc::now().timestamp_millis();
+        let usdc = match token.balanceOf(owner).call().await {
+            Ok(raw) => raw.to_string().parse::<f64>().unwrap_or(0.0) / 1_000_000.0,
+            Err(e) => {
+                eprintln!("[WALLET] USDC balance read failed: {}", e);
+                continue;
+            }
+        };
+        let positions = match data.positions(&request).await {
+            Ok(p) => p,
+            Err(e) => {
+                eprintln!("[WALLET] Data API positions failed: {}", e);
+                continue;
+            }
+        };
+        let mut snapshot = WalletSnapshot { ts_ms, usdc, ..Default::default() };
+        for p in &positions {
+            let value = to_f64(p.current_value);
+            if p.redeemable {
+                snapshot.pending_redemptions += value;
+            } else {
+                snapshot.positions_value += value;
+                snapshot.positions.push(OpenPosition {
+                    slug: p.slug.clone(),
+                    up: p.outcome.eq_ignore_ascii_case("up"),
+                    size: to_f64(p.size),
+                    price: to_f64(p.cur_price),
+                });
+            }
+        }
+        if tx.borrow().as_ref().is_none_or(|last| (last.equity() - snapshot.equity()).abs() >= 0.01) {
+            eprintln!(
+                "[WALLET] USDC=${:.2} positions=${:.2} pending_redeem=${:.2} equity=${:.2}",
+                snapshot.usdc, snapshot.positions_value, snapshot.pending_redemptions, snapshot.equity(),
+            );
+        }
+        if tx.send(Some(snapshot)).is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::test_helpers::make_order;
+    use crate::types::Side;
+
+    fn snapshot(ts_ms: i64, usdc: f64) -> WalletSnapshot {
+        WalletSnapshot { ts_ms, usdc, ..Default::default() }
+    }
+
+    // ── Bankroll ──
+
+    /// Scenario: BANKROLL=1000; equity $800 (600 cash, 150 open, 50 awaiting redemption), then $1,500.
+    /// Expected: Fixed sizes off 800 then 1000; compound off 800 then 1500; no snapshot → 1000.
+    #[test]
+    fn test_bankroll_modes() {
+        let down = WalletSnapshot { usdc: 600.0, positions_value: 150.0, pending_redemptions: 50.0, ..Default::default() };
+        let up = snapshot(0, 1_500.0);
+        assert_eq!(bankroll(BankrollMode::Fixed, 1_000.0, Some(&down)), 800.0);
+        assert_eq!(bankroll(BankrollMode::Fixed, 1_000.0, Some(&up)), 1_000.0);
+        assert_eq!(bankroll(BankrollMode::Compound, 1_000.0, Some(&down)), 800.0);
+        assert_eq!(bankroll(BankrollMode::Compound, 1_000.0, Some(&up)), 1_500.0);
+        assert_eq!(bankroll(BankrollMode::Compound, 1_000.0, None), 1_000.0);
+    }
+
+    // ── Ledger ──
+
+    /// Scenario: $100 synced; a $30 buy and a $10 sell fill, then a sync at a later time shows $80.
+    /// Expected: $80 available before the sync; after it, exactly the chain's $80 (fills already seen).
+    #[test]
+    fn test_ledger_resyncs_to_chain() {
+        let mut l = UsdcLedger::new(100.0);
+        l.settle(&make_order(1, Side::Up, OrderAction::Buy, 0.50, 30.0), 1_000);
+        l.settle(&make_order(1, Side::Up, OrderAction::Sell, 0.50, 10.0), 2_000);
+        assert!((l.available() - 80.0).abs() < 1e-9);
+        l.reconcile(&snapshot(60_000, 80.0));
+        assert!((l.available() - 80.0).abs() < 1e-9);
+    }
+
+    /// Scenario: $100 synced; a redemption credits $50 on chain; a $20 buy fills 2s before
+    ///           the next sync (not settled yet) and another $5 buy just after it.
+    /// Expected: Redemption credited; both recent buys still debited → 150 − 20 − 5 = 125.
+    #[test]
+    fn test_ledger_keeps_unsettled_debits() {
+        let mut l = UsdcLedger::new(100.0);
+        l.settle(&make_order(1, Side::Up, OrderAction::Buy, 0.50, 20.0), 58_000);
+        l.settle(&make_order(1, Side::Up, OrderAction::Buy, 0.50, 5.0), 61_000);
+        l.reconcile(&snapshot(60_000, 150.0));
+        assert!((l.available() - 125.0).abs() < 1e-9);
+    }
+
+    /// Scenario: $100 synced; a $40 buy rests and $10 of it matches; a sync after the fill
+    ///           reads $90 on chain.
+    /// Expected: $60 available throughout (the unmatched $30 stays held across the sync).
+    #[test]
+    fn test_ledger_holds_resting_buys() {
+        let mut l = UsdcLedger::new(100.0);
+        l.reserve(&make_order(1, Side::Up, OrderAction::Buy, 0.50, 40.0));
+        l.settle_slice(1, OrderAction::Buy, 10.0, 1_000);
+        assert!((l.available() - 60.0).abs() < 1e-9);
+        l.reconcile(&snapshot(60_000, 90.0));
+        assert!((l.available() - 60.0).abs() < 1e-9);
+    }
+}
@@ -11,11 +11,17 @@ mod types;
 use tokio::sync::{mpsc, watch};
 
 use config::Config;
+use engine::breakers::{BreakerLimits, CircuitBreakers};
+use engine::ensemble::Ensemble;
+use engine::hedge::Hedger;
 use engine::runner::run_engine;
 use engine::state::BinanceState;
 use feeds::binance::binance_feed;
+use feeds::portfolio::portfolio_feed;
 use feeds::polymarket::polymarket_feed;
+use gateway::binance::{hedge_gateway, BinanceClient};
 use gateway::order::order_gateway;
+use gateway::wallet::{wallet_service, WalletSnapshot};
 use market::discovery::discover_next_market;
 use telemetry::writer::telemetry_writer;
 use types::*;
@@ -28,23 +34,48 @@ async fn main() {
     // Install rustls crypto provider (required when both aws-lc-rs and ring features are active)
     let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
 
-    let config = Config::from_env();
+    let mut config = Config::from_env();
     let http = reqwest::Client::new();
 
     eprintln!("╔══════════════════════════════════════════════════╗");
     eprintln!("║  Polymarket {} {} Trading System", config.asset_label(), config.interval.label());
     eprintln!("║  Series: {} | Dry run: {}", config.series_id, config.dry_run);
-    eprintln!("║  Bankroll: ${:.0} | Max exposure: {:.0}%", config.bankroll, config.max_total_exposure_frac * 100.0);
-    eprintln!("║  Oracle: β={:.2} δ={:.1}s | EWMA λ={:.2}", config.oracle_beta, config.oracle_delta_s, config.ewma_lambda);
+    eprintln!("║  Bankroll: ${:.0} ({:?}) | Max exposure: {:.0}%", config.bankroll, config.bankroll_mode, config.max_total_exposure_frac * 100.0);
+    eprintln!("║  Oracle: β={:.2} δ={:.1}s cadence={:.1}s | EWMA λ={:.2}", config.oracle_beta, config.oracle_delta_s, config.oracle_cadence_s, config.ewma_lambda);
     let secs_per_year: f64 = 365.25 * 24.0 * 3600.0;
     let sigma_floor_ps = config.sigma_floor_annual / secs_per_year.sqrt();
     eprintln!("║  Vol floor: {:.0}% annual → σ_floor={:.6}/s", config.sigma_floor_annual * 100.0, sigma_floor_ps);
+    eprintln!("║  Signal ensemble: {}", if config.ensemble { "on" } else { "off" });
+    if config.hedge {
+        eprintln!("║  Delta hedge: {:?} band=${:.0} step={}", config.hedge_venue, config.hedge_band_usd, config.hedge_step);
+    }
+    eprintln!("║  Strategy params: {} override(s)", config.strategy_params.len());
+    if !config.shadow_strategies.is_empty() {
+        let mut shadow: Vec<&str> = config.shadow_strategies.iter().map(String::as_str).collect();
+        shadow.sort();
+        eprintln!("║  Shadow (paper only): {}", shadow.join(", "));
+    }
     eprintln!("╚══════════════════════════════════════════════════╝");
+    for key in strategies::params::unknown_keys(&config.strategy_params) {
+        eprintln!("[CONFIG] Ignoring unknown strategy param '{}'", key);
+    }
+    if !config.risk_rules.is_empty() {
+        eprintln!("[CONFIG] Risk rules: {}", config.risk_rules.join(" → "));
+    }
+    for name in engine::risk_rules::unknown(&config.risk_rules) {
+        eprintln!("[CONFIG] Ignoring unknown risk rule '{}'", name);
+    }
+    if !engine::pipeline::DECONFLICTION_POLICIES.contains(&config.deconflict_policy.as_str()) {
+        eprintln!("[CONFIG] Unknown DECONFLICT_POLICY '{}', using first_wins", config.deconflict_policy);
+    } else if config.deconflict_policy != "first_wins" {
+        eprintln!("[CONFIG] Deconfliction: {}", config.deconflict_policy);
+    }
 
     // ── Persistent Binance feed (lives across all markets) ──
     let (feed_swap_tx, feed_swap_rx) = watch::channel::<Option<mpsc::Sender<FeedEvent>>>(None);
     let (price_tx, mut price_rx) = watch::channel::<f64>(0.0);
 
+    let portfolio_swap_rx = feed_swap_rx.clone();
     let bn_url = config.binance_ws.clone();
     let bn_fallback = config.binance_ws_fallback.clone();
     let _binance_handle = tokio::spawn(async move {
@@ -61,6 +92,45 @@ async fn main() {
     }
     eprintln!("[MAIN] Binance online: ${:.2}", *price_rx.borrow());
 
+    // Ensemble calibration — learned across markets for the process lifetime
+    let mut ensemble = config.ensemble.then(Ensemble::new);
+
+    // Delta hedger — its Binance position carries across markets
+    let mut hedger = config.hedge.then(|| {
+        let client = if config.dry_run || config.hedge_dry_run {
+            None
+        } else {
+            let client = BinanceClient::from_config(&config);
+            if client.is_none() {
+                eprintln!("[CONFIG] HEDGE without BINANCE_API_KEY/BINANCE_API_SECRET: hedging in dry run");
+            }
+            client
+        };
+        let gateway = client.map(|client| {
+            let (order_tx, order_rx) = mpsc::channel::<HedgeOrder>(16);
+            let (fill_tx, fill_rx) = mpsc::channel::<HedgeFill>(16);
+            tokio::spawn(hedge_gateway(order_rx, fill_tx, client));
+            (order_tx, fill_rx)
+        });
+        Hedger::new(&config, gateway)
+    });
+
+    // Circuit breakers — cool-offs and sample windows carry across markets
+    let mut breakers = CircuitBreakers::new(BreakerLimits::from_config(&config));
+
+    // Wallet service — authoritative USDC + equity for gateway and bankroll (live only)
+    let configured_bankroll = config.bankroll;
+    let (wallet_tx, wallet_rx) = watch::channel::<Option<WalletSnapshot>>(None);
+    if !config.dry_run {
+        let wallet_config = config.clone();
+        tokio::spawn(wallet_service(wallet_config, wallet_tx));
+    }
+
+    // Portfolio feed — spot correlation + wallet positions for the VaR limit
+    if config.max_var_frac > 0.0 {
+        tokio::spawn(portfolio_feed(config.clone(), portfolio_swap_rx, wallet_rx.clone()));
+    }
+
     // Persistent Binance state — created once, threaded through every market
     let mut binance_state = BinanceState::new(
         config.ewma_lambda,
@@ -106,9 +176,16 @@ async fn main() {
         market.strike = fetch_binance_candle_open(&http, &config).await;
         eprintln!("[MAIN] Strike set (candle open): ${:.2}", market.strike);
 
+        // Size this market off the latest wallet equity
+        let snapshot = wallet_rx.borrow().clone();
+        config.bankroll = gateway::wallet::bankroll(config.bankroll_mode, configured_bankroll, snapshot.as_ref());
+        if let Some(w) = snapshot {
+            eprintln!("[MAIN] Equity ${:.2} → bankroll ${:.2} ({:?})", w.equity(), config.bankroll, config.bankroll_mode);
+        }
+
         // 4. Create per-market channels
         let (feed_tx, feed_rx) = mpsc::channel::<FeedEvent>(4096);
-        let (order_tx, order_rx) = mpsc::channel::<Order>(64);
+        let (order_tx, order_rx) = mpsc::channel::<OrderRequest>(64);
         let (telem_tx, telem_rx) = mpsc::channel::<TelemetryEvent>(4096);
 
         // 5. Activate Binance → this market's feed channel
@@ -139,9 +216,10 @@ async fn main() {
         let gw_feed_tx = feed_tx.clone();
         let gw_telem_tx = telem_tx.clone();
         let gw_config = config.clone();
+        let gw_wallet_rx = wallet_rx.clone();
         let (market_ctx_tx, market_ctx_rx) = tokio::sync::oneshot::channel::<MarketContext>();
         let gw_handle = tokio::spawn(async move {
-            order_gateway(order_rx, gw_feed_tx, gw_telem_tx, market_ctx_rx, gw_config).await;
+            order_gateway(order_rx, gw_feed_tx, gw_telem_tx, market_ctx_rx, gw_wallet_rx, gw_config).await;
         });
 
         // Send market context to gateway (tick_size, neg_risk, token IDs)
@@ -150,6 +228,8 @@ async fn main() {
             down_token_id: market.down_token_id.clone(),
             tick_size: market.tick_size,
             neg_risk: market.neg_risk,
+            condition_id: market.condition_id.clone(),
+            fees: market.fees,
         });
 
         // 9. Spawn telemetry writer
@@ -163,7 +243,7 @@ async fn main() {
         drop(feed_tx);
 
         // 10. Run core engine (blocks until market ends), returns BinanceState
-        binance_state = run_engine(market.clone(), binance_state, feed_rx, order_tx, telem_tx, &config).await;
+        binance_state = run_engine(market.clone(), binance_state, feed_rx, order_tx, telem_tx, &config, ensemble.as_mut(), hedger.as_mut(), &mut breakers).await;
 
         // 11. Pause Binance delivery (trades dropped between markets)
         let _ = feed_swap_tx.send(None);
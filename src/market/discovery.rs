@@ -1,4 +1,5 @@
 use crate::config::Config;
+use crate::math::fees::FeeSchedule;
 use crate::types::MarketInfo;
 
 /// Discover the current or next Up/Down market via Gamma API.
@@ -151,7 +152,7 @@ fn parse_event_to_market_info(
         return Ok(None);
     }
 
-    // Extract tick size and neg_risk from market data
+    // Extract tick size, neg_risk and fees from market data
     let tick_size = markets.iter()
         .find_map(|m| m.get("minimum_tick_size")
             .and_then(|v| v.as_str())
@@ -160,6 +161,15 @@ fn parse_event_to_market_info(
     let neg_risk = event.get("neg_risk")
         .and_then(|v| v.as_bool())
         .unwrap_or(false);
+    // Fee schedule: both outcome markets share one schedule, take the first.
+    let fees = markets.first()
+        .map(FeeSchedule::from_gamma)
+        .unwrap_or_default();
+    // CTF condition (needed to merge UP+DOWN pairs back into USDC)
+    let condition_id = markets.iter()
+        .find_map(|m| m.get("conditionId").and_then(|v| v.as_str()))
+        .unwrap_or("")
+        .to_string();
 
     Ok(Some(MarketInfo {
         slug: slug.to_string(),
@@ -170,6 +180,8 @@ fn parse_event_to_market_info(
         strike: 0.0,
         tick_size,
         neg_risk,
+        fees,
+        condition_id,
     }))
 }
 
@@ -709,4 +721,29 @@ mod tests {
         let result = parse_event_to_market_info(&event, "slug-1705320000", 300_000).unwrap();
         assert!(result.is_none(), "Missing markets key should return None");
     }
+
+    /// Scenario: market objects carry feesEnabled=false, or no fee fields at all
+    /// Expected: fee-free schedule for the former, crypto default curve for the latter
+    #[test]
+    fn test_parse_event_fee_schedule() {
+        let free: serde_json::Value = serde_json::from_str(r#"{
+            "endDate": "2024-01-15T12:05:00Z",
+            "markets": [
+                {"groupItemTitle": "Up", "clobTokenIds": "[\"up-tok\"]", "feesEnabled": false},
+                {"groupItemTitle": "Down", "clobTokenIds": "[\"down-tok\"]", "feesEnabled": false}
+            ]
+        }"#).unwrap();
+        let info = parse_event_to_market_info(&free, "slug-1705320000", 300_000).unwrap().unwrap();
+        assert_eq!(info.fees, FeeSchedule::zero());
+
+        let default: serde_json::Value = serde_json::from_str(r#"{
+            "endDate": "2024-01-15T12:05:00Z",
+            "markets": [
+                {"groupItemTitle": "Up", "clobTokenIds": "[\"up-tok\"]"},
+                {"groupItemTitle": "Down", "clobTokenIds": "[\"down-tok\"]"}
+            ]
+        }"#).unwrap();
+        let info = parse_event_to_market_info(&default, "slug-1705320000", 300_000).unwrap().unwrap();
+        assert_eq!(info.fees, FeeSchedule::default());
+    }
 }
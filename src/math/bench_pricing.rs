@@ -0,0 +1,126 @@
+// Pricing-kernel benchmarks: erfc-based cdf vs the legacy Abramowitz-Stegun form,
+// and scalar vs batch p_fair/delta/gamma over a vol-surface-sized grid.
+// Only compiled under #[cfg(test)].
+
+use std::time::Instant;
+
+use crate::math::normal::{cdf, phi};
+use crate::math::pricing::{
+    delta_bin, delta_bin_batch, gamma_bin, gamma_bin_batch, p_fair, p_fair_batch,
+};
+
+const ITERATIONS: u32 = 100;
+/// Points per sweep: 40 strikes × 25 expiries, roughly a backtest grid row.
+const GRID: usize = 1000;
+/// Maximum allowed time for 100 sweeps of 1000 points (100k evaluations).
+/// Very conservative — release builds run ~20–90ns per point, debug ~250–550ns.
+const MAX_TOTAL_US: u128 = 100_000;
+
+/// Previous `normal::cdf`: Abramowitz & Stegun 26.2.17, max abs error 7.5e-8.
+fn cdf_as(x: f64) -> f64 {
+    if x >= 0.0 {
+        let t = 1.0 / (1.0 + 0.231_641_9 * x);
+        let poly = t * (0.319_381_530
+            + t * (-0.356_563_782 + t * (1.781_477_937 + t * (-1.821_255_978 + t * 1.330_274_429))));
+        1.0 - phi(x) * poly
+    } else {
+        1.0 - cdf_as(-x)
+    }
+}
+
+fn grid() -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+    let (mut s, mut k, mut tau) = (Vec::with_capacity(GRID), Vec::with_capacity(GRID), Vec::with_capacity(GRID));
+    for i in 0..40 {
+        for j in 0..25 {
+            s.push(100_000.0);
+            k.push(98_000.0 + 100.0 * i as f64);
+            tau.push(10.0 + 36.0 * j as f64);
+        }
+    }
+    (s, k, tau)
+}
+
+fn report(label: &str, elapsed_us: u128) {
+    let evals = ITERATIONS as f64 * GRID as f64;
+    eprintln!(
+        "[BENCH] {}: {}μs total, {:.1}ns/point ({} iters × {} points)",
+        label, elapsed_us, elapsed_us as f64 * 1000.0 / evals, ITERATIONS, GRID,
+    );
+}
+
+/// Scenario: erfc-based cdf vs legacy A&S cdf over z ∈ [-10, 10] and the lower tail.
+/// Expected: Both under budget; new cdf agrees with A&S within its 7.5e-8 error bound,
+///   while A&S loses all relative precision past z ≈ -6 (new cdf is exact there).
+#[test]
+fn test_bench_cdf_erfc_vs_as() {
+    let xs: Vec<f64> = (0..GRID).map(|i| -10.0 + 20.0 * i as f64 / GRID as f64).collect();
+    for &x in &xs {
+        assert!((cdf(x) - cdf_as(x)).abs() < 7.5e-8, "x={}", x);
+    }
+    // Φ(-8) = 6.2209605742718e-16: A&S is off by orders of magnitude in relative terms
+    let exact = 6.220_960_574_271_819e-16;
+    assert!(((cdf(-8.0) - exact) / exact).abs() < 1e-13);
+    assert!(((cdf_as(-8.0) - exact) / exact).abs() > 1e-3);
+
+    let mut sink = 0.0;
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        for &x in &xs {
+            sink += cdf_as(x);
+        }
+    }
+    let as_us = start.elapsed().as_micros();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        for &x in &xs {
+            sink += cdf(x);
+        }
+    }
+    let erfc_us = start.elapsed().as_micros();
+    std::hint::black_box(sink);
+
+    report("cdf (A&S 26.2.17)", as_us);
+    report("cdf (erfc, Cody)", erfc_us);
+    assert!(erfc_us < MAX_TOTAL_US, "erfc cdf too slow: {}μs", erfc_us);
+}
+
+/// Scenario: p_fair/delta/gamma over a 1000-point (S, K, τ) grid, scalar loop vs batch kernels.
+/// Expected: Identical outputs; batch sweep under budget (speedup printed for inspection).
+#[test]
+fn test_bench_batch_vs_scalar() {
+    let (s, k, tau) = grid();
+    let sigma = 1.5e-4;
+    let mut out_scalar = vec![0.0; GRID * 3];
+    let mut p = vec![0.0; GRID];
+    let mut dl = vec![0.0; GRID];
+    let mut gm = vec![0.0; GRID];
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        for i in 0..GRID {
+            out_scalar[i] = p_fair(s[i], k[i], sigma, tau[i]);
+            out_scalar[GRID + i] = delta_bin(s[i], k[i], sigma, tau[i]);
+            out_scalar[2 * GRID + i] = gamma_bin(s[i], k[i], sigma, tau[i]);
+        }
+        std::hint::black_box(&out_scalar);
+    }
+    let scalar_us = start.elapsed().as_micros();
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        p_fair_batch(&s, &k, &tau, sigma, &mut p);
+        delta_bin_batch(&s, &k, &tau, sigma, &mut dl);
+        gamma_bin_batch(&s, &k, &tau, sigma, &mut gm);
+        std::hint::black_box((&p, &dl, &gm));
+    }
+    let batch_us = start.elapsed().as_micros();
+
+    assert_eq!(&out_scalar[..GRID], &p[..]);
+    assert_eq!(&out_scalar[GRID..2 * GRID], &dl[..]);
+    assert_eq!(&out_scalar[2 * GRID..], &gm[..]);
+
+    report("p/delta/gamma scalar", scalar_us);
+    report("p/delta/gamma batch", batch_us);
+    eprintln!("[BENCH] batch speedup: {:.2}x", scalar_us as f64 / batch_us.max(1) as f64);
+    assert!(batch_us < 3 * MAX_TOTAL_US, "batch sweep too slow: {}μs", batch_us);
+}
@@ -71,6 +71,7 @@ pub struct SampledEwmaVol {
     seeded: bool,
     n_samples: u32,
     min_samples: u32,
+    last_return: f64,
 }
 
 impl SampledEwmaVol {
@@ -83,6 +84,7 @@ impl SampledEwmaVol {
             seeded: false,
             n_samples: 0,
             min_samples,
+            last_return: 0.0,
         }
     }
 
@@ -110,6 +112,7 @@ impl SampledEwmaVol {
         let r_sq_per_sec = (r * r) / dt_s;
 
         self.sigma_sq = self.lambda * self.sigma_sq + (1.0 - self.lambda) * r_sq_per_sec;
+        self.last_return = r / dt_s.sqrt();
         self.n_samples += 1;
         self.last_sample_price = price;
         self.last_sample_ts = ts_ms;
@@ -131,6 +134,26 @@ impl SampledEwmaVol {
     pub fn n_samples(&self) -> u32 {
         self.n_samples
     }
+
+    /// Effective number of samples behind the current estimate: the EWMA
+    /// memory (1+λ)/(1-λ), capped by samples seen. sigma_real is only
+    /// published once `min_samples` are in, so that is the floor.
+    #[inline]
+    pub fn effective_samples(&self) -> f64 {
+        let memory = if self.lambda < 1.0 {
+            (1.0 + self.lambda) / (1.0 - self.lambda)
+        } else {
+            f64::INFINITY
+        };
+        (self.n_samples.max(self.min_samples) as f64).min(memory)
+    }
+
+    /// Log-return of the most recent sample, normalized to per-√s
+    /// (so gaps longer than 1s don't inflate magnitude).
+    #[inline]
+    pub fn last_return(&self) -> f64 {
+        self.last_return
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,204 @@
+use crate::types::OrderType;
+
+/// Which side of the match an order executes on. Takers pay the fee curve,
+/// makers earn a rebate funded from it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Liquidity {
+    Maker,
+    Taker,
+}
+
+impl Liquidity {
+    /// Liquidity role implied by the CLOB order parameters.
+    /// Post-only orders can never cross, so they always rest as maker.
+    /// FOK and plain GTD/GTC at the ask take liquidity.
+    #[inline]
+    pub fn of(order_type: OrderType, post_only: bool) -> Self {
+        match (order_type, post_only) {
+            (OrderType::GTC, true) | (OrderType::GTD, true) => Liquidity::Maker,
+            _ => Liquidity::Taker,
+        }
+    }
+}
+
+/// Per-market fee schedule.
+///
+/// Polymarket crypto up/down markets charge takers on a curve that peaks at
+/// p = 0.5 and vanishes toward 0 and 1:
+///
+///   fee_usdc = shares * p * rate * (p * (1 - p))^exponent
+///
+/// A fraction of collected taker fees is paid back to makers as a rebate.
+/// Fee-free markets use `FeeSchedule::zero()`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FeeSchedule {
+    pub taker_fee_rate: f64,
+    pub taker_exponent: f64,
+    /// Maker rebate as a fraction of the taker fee at the same price.
+    pub maker_rebate_frac: f64,
+}
+
+/// Default crypto up/down curve: ~1.56% of notional at p = 0.5.
+pub const CRYPTO_TAKER_FEE_RATE: f64 = 0.25;
+pub const CRYPTO_TAKER_EXPONENT: f64 = 2.0;
+pub const CRYPTO_MAKER_REBATE_FRAC: f64 = 0.20;
+
+impl Default for FeeSchedule {
+    fn default() -> Self {
+        Self {
+            taker_fee_rate: CRYPTO_TAKER_FEE_RATE,
+            taker_exponent: CRYPTO_TAKER_EXPONENT,
+            maker_rebate_frac: CRYPTO_MAKER_REBATE_FRAC,
+        }
+    }
+}
+
+impl FeeSchedule {
+    /// Fee-free market.
+    pub const fn zero() -> Self {
+        Self {
+            taker_fee_rate: 0.0,
+            taker_exponent: 0.0,
+            maker_rebate_frac: 0.0,
+        }
+    }
+
+    /// Build a schedule from a Gamma market object.
+    ///
+    /// `feesEnabled: false` → fee-free. `takerBaseFee` (bps of notional at the
+    /// p = 0.5 peak) rescales the curve; `makerRebateRate` overrides the rebate
+    /// fraction. Missing fields fall back to the crypto defaults.
+    pub fn from_gamma(market: &serde_json::Value) -> Self {
+        if market.get("feesEnabled").and_then(|v| v.as_bool()) == Some(false) {
+            return Self::zero();
+        }
+        let mut sched = Self::default();
+        if let Some(bps) = market.get("takerBaseFee").and_then(json_f64) {
+            // fee / notional at p = 0.5 is rate * 0.25^exponent
+            let peak = 0.25_f64.powf(sched.taker_exponent);
+            sched.taker_fee_rate = (bps / 10_000.0 / peak).max(0.0);
+        }
+        if let Some(frac) = market.get("makerRebateRate").and_then(json_f64) {
+            sched.maker_rebate_frac = frac.clamp(0.0, 1.0);
+        }
+        sched
+    }
+
+    /// Taker fee as a fraction of notional at `price`.
+    #[inline]
+    pub fn taker_rate(&self, price: f64) -> f64 {
+        if self.taker_fee_rate <= 0.0 || price <= 0.0 || price >= 1.0 {
+            return 0.0;
+        }
+        self.taker_fee_rate * (price * (1.0 - price)).powf(self.taker_exponent)
+    }
+
+    /// Signed fee per share at `price`: positive = paid (taker),
+    /// negative = rebate received (maker).
+    #[inline]
+    pub fn fee_per_share(&self, price: f64, liquidity: Liquidity) -> f64 {
+        let taker = price * self.taker_rate(price);
+        match liquidity {
+            Liquidity::Taker => taker,
+            Liquidity::Maker => -self.maker_rebate_frac * taker,
+        }
+    }
+
+    /// All-in cost per share: price plus fee (or minus rebate).
+    /// Strategies compare fair value against this and size Kelly off it.
+    #[inline]
+    pub fn effective_price(&self, price: f64, liquidity: Liquidity) -> f64 {
+        price + self.fee_per_share(price, liquidity)
+    }
+
+    /// Signed fee for a fill of `size` at `price`, in the same units as
+    /// settlement PnL (`price * size` is the amount at risk).
+    #[inline]
+    pub fn fill_fee(&self, price: f64, size: f64, liquidity: Liquidity) -> f64 {
+        self.fee_per_share(price, liquidity) * size
+    }
+}
+
+fn json_f64(v: &serde_json::Value) -> Option<f64> {
+    v.as_f64().or_else(|| v.as_str().and_then(|s| s.parse().ok()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ── Liquidity ──
+
+    /// Scenario: Map each (order_type, post_only) combination used by the risk manager.
+    /// Expected: Post-only GTC/GTD are maker; FOK and crossing GTD are taker.
+    #[test]
+    fn test_liquidity_of_order_params() {
+        assert_eq!(Liquidity::of(OrderType::GTC, true), Liquidity::Maker);
+        assert_eq!(Liquidity::of(OrderType::GTD, true), Liquidity::Maker);
+        assert_eq!(Liquidity::of(OrderType::GTD, false), Liquidity::Taker);
+        assert_eq!(Liquidity::of(OrderType::FOK, false), Liquidity::Taker);
+    }
+
+    // ── Fee curve ──
+
+    /// Scenario: Default schedule evaluated at p = 0.5.
+    /// Expected: Taker rate = 0.25 * 0.25^2 = 1.5625% of notional.
+    #[test]
+    fn test_taker_rate_peak() {
+        let f = FeeSchedule::default();
+        assert!((f.taker_rate(0.5) - 0.015625).abs() < 1e-12);
+        assert!((f.fee_per_share(0.5, Liquidity::Taker) - 0.0078125).abs() < 1e-12);
+    }
+
+    /// Scenario: Taker rate evaluated at symmetric prices and near the boundaries.
+    /// Expected: Curve is symmetric in p ↔ 1-p, peaks at 0.5, and is zero at 0 and 1.
+    #[test]
+    fn test_taker_rate_shape() {
+        let f = FeeSchedule::default();
+        assert!((f.taker_rate(0.2) - f.taker_rate(0.8)).abs() < 1e-12);
+        assert!(f.taker_rate(0.5) > f.taker_rate(0.3));
+        assert!(f.taker_rate(0.95) < 0.001);
+        assert_eq!(f.taker_rate(0.0), 0.0);
+        assert_eq!(f.taker_rate(1.0), 0.0);
+    }
+
+    /// Scenario: Maker vs taker fee per share at the same price.
+    /// Expected: Maker receives rebate_frac of the taker fee (negative sign).
+    #[test]
+    fn test_maker_rebate_sign_and_size() {
+        let f = FeeSchedule::default();
+        let taker = f.fee_per_share(0.4, Liquidity::Taker);
+        let maker = f.fee_per_share(0.4, Liquidity::Maker);
+        assert!(taker > 0.0);
+        assert!((maker + CRYPTO_MAKER_REBATE_FRAC * taker).abs() < 1e-12);
+        assert!(f.effective_price(0.4, Liquidity::Maker) < 0.4);
+        assert!(f.effective_price(0.4, Liquidity::Taker) > 0.4);
+    }
+
+    /// Scenario: Zero schedule.
+    /// Expected: No fee, no rebate, effective price equals quoted price.
+    #[test]
+    fn test_zero_schedule() {
+        let f = FeeSchedule::zero();
+        assert_eq!(f.fill_fee(0.5, 100.0, Liquidity::Taker), 0.0);
+        assert_eq!(f.effective_price(0.5, Liquidity::Maker), 0.5);
+    }
+
+    // ── Gamma parsing ──
+
+    /// Scenario: Gamma market objects with fees disabled, a bps override, and no fee fields.
+    /// Expected: zero schedule, rescaled peak, and defaults respectively.
+    #[test]
+    fn test_from_gamma() {
+        let off = serde_json::json!({ "feesEnabled": false });
+        assert_eq!(FeeSchedule::from_gamma(&off), FeeSchedule::zero());
+
+        let bps = serde_json::json!({ "feesEnabled": true, "takerBaseFee": "300", "makerRebateRate": 0.5 });
+        let f = FeeSchedule::from_gamma(&bps);
+        assert!((f.taker_rate(0.5) - 0.03).abs() < 1e-12, "peak={}", f.taker_rate(0.5));
+        assert_eq!(f.maker_rebate_frac, 0.5);
+
+        let none = serde_json::json!({});
+        assert_eq!(FeeSchedule::from_gamma(&none), FeeSchedule::default());
+    }
+}
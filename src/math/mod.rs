@@ -4,3 +4,8 @@ pub mod ewma;
 pub mod vwap;
 pub mod regime;
 pub mod oracle;
+pub mod vol_surface;
+pub mod fees;
+
+#[cfg(test)]
+mod bench_pricing;
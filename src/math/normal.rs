@@ -5,27 +5,253 @@ pub fn phi(x: f64) -> f64 {
     INV_SQRT_2PI * (-0.5 * x * x).exp()
 }
 
-/// Standard normal CDF: Phi(x) via Abramowitz & Stegun 26.2.17
-/// Max error < 7.5e-8. Zero heap allocation.
+// Cody (1969) rational Chebyshev coefficients for erf/erfc (CALERF).
+// Relative error < 1e-15 over the full double range.
+const ERF_THRESH: f64 = 0.46875;
+const SQRPI: f64 = 5.641_895_835_477_563e-1; // 1/sqrt(pi)
+const ERF_A: [f64; 5] = [
+    3.161_123_743_870_565_5e0, 1.138_641_541_510_501_6e2, 3.774_852_376_853_02e2,
+    3.209_377_589_138_469_4e3, 1.857_777_061_846_031_5e-1,
+];
+const ERF_B: [f64; 4] = [
+    2.360_129_095_234_412_2e1, 2.440_246_379_344_441_7e2, 1.282_616_526_077_372_3e3,
+    2.844_236_833_439_171e3,
+];
+const ERFC_C: [f64; 9] = [
+    5.641_884_969_886_701e-1, 8.883_149_794_388_377e0, 6.611_919_063_714_163e1,
+    2.986_351_381_974_001e2, 8.819_522_212_417_69e2, 1.712_047_612_634_070_7e3,
+    2.051_078_377_826_071_6e3, 1.230_339_354_797_997_2e3, 2.153_115_354_744_038_3e-8,
+];
+const ERFC_D: [f64; 8] = [
+    1.574_492_611_070_983_5e1, 1.176_939_508_913_125e2, 5.371_811_018_620_099e2,
+    1.621_389_574_566_690_3e3, 3.290_799_235_733_459_7e3, 4.362_619_090_143_247e3,
+    3.439_367_674_143_721_6e3, 1.230_339_354_803_749_5e3,
+];
+const ERFC_P: [f64; 6] = [
+    3.053_266_349_612_323_6e-1, 3.603_448_999_498_044_5e-1, 1.257_817_261_112_292_6e-1,
+    1.608_378_514_874_227_5e-2, 6.587_491_615_298_378e-4, 1.631_538_713_730_209_7e-2,
+];
+const ERFC_Q: [f64; 5] = [
+    2.568_520_192_289_822e0, 1.872_952_849_923_467_3e0, 5.279_051_029_514_285e-1,
+    6.051_834_131_244_132e-2, 2.335_204_976_268_691_8e-3,
+];
+
+/// Scaled complementary error function exp(y^2) * erfc(y) for y > ERF_THRESH.
+/// Never underflows; the unscaled erfc is recovered by the caller.
+#[inline]
+fn erfcx_tail(y: f64) -> f64 {
+    if y <= 4.0 {
+        let mut num = ERFC_C[8] * y;
+        let mut den = y;
+        for (c, d) in ERFC_C[..7].iter().zip(&ERFC_D[..7]) {
+            num = (num + c) * y;
+            den = (den + d) * y;
+        }
+        (num + ERFC_C[7]) / (den + ERFC_D[7])
+    } else {
+        let z = 1.0 / (y * y);
+        let mut num = ERFC_P[5] * z;
+        let mut den = z;
+        for (p, q) in ERFC_P[..4].iter().zip(&ERFC_Q[..4]) {
+            num = (num + p) * z;
+            den = (den + q) * z;
+        }
+        let r = z * (num + ERFC_P[4]) / (den + ERFC_Q[4]);
+        (SQRPI - r) / y
+    }
+}
+
+/// Complementary error function erfc(x) = 1 - erf(x).
+/// Full double precision (relative) in both tails; underflows to 0 only past x ≈ 26.5.
+#[inline]
+pub fn erfc(x: f64) -> f64 {
+    let y = x.abs();
+    if y <= ERF_THRESH {
+        let ysq = y * y;
+        let mut num = ERF_A[4] * ysq;
+        let mut den = ysq;
+        for (a, b) in ERF_A[..3].iter().zip(&ERF_B[..3]) {
+            num = (num + a) * ysq;
+            den = (den + b) * ysq;
+        }
+        return 1.0 - x * (num + ERF_A[3]) / (den + ERF_B[3]);
+    }
+    // exp(-y^2) split as exp(-ysq^2) * exp(-(y-ysq)(y+ysq)) to avoid cancellation in y^2
+    let ysq = (y * 16.0).trunc() / 16.0;
+    let del = (y - ysq) * (y + ysq);
+    let r = (-ysq * ysq).exp() * (-del).exp() * erfcx_tail(y);
+    if x < 0.0 { 2.0 - r } else { r }
+}
+
+/// Standard normal CDF: Phi(x) = erfc(-x / sqrt(2)) / 2
+/// Relative error ~1e-15 in the lower tail (Phi(-8) ≈ 6.2e-16 is exact to the last digits),
+/// where certainty_capture and lp_extreme price. Zero heap allocation.
 #[inline]
 pub fn cdf(x: f64) -> f64 {
-    if x >= 0.0 {
-        const P: f64 = 0.231_641_9;
-        const B1: f64 = 0.319_381_530;
-        const B2: f64 = -0.356_563_782;
-        const B3: f64 = 1.781_477_937;
-        const B4: f64 = -1.821_255_978;
-        const B5: f64 = 1.330_274_429;
-
-        let t = 1.0 / (1.0 + P * x);
-        let t2 = t * t;
-        let t3 = t2 * t;
-        let t4 = t3 * t;
-        let t5 = t4 * t;
-        1.0 - phi(x) * (B1 * t + B2 * t2 + B3 * t3 + B4 * t4 + B5 * t5)
+    0.5 * erfc(-x * std::f64::consts::FRAC_1_SQRT_2)
+}
+
+/// Natural log of the standard normal CDF: ln Phi(x).
+/// Finite for any finite x — for x ≪ 0 uses ln Phi(x) = ln(1/2) - y^2 + ln erfcx(y)
+/// with y = -x/sqrt(2), so it keeps working long after Phi(x) itself underflows to 0.
+#[inline]
+pub fn log_cdf(x: f64) -> f64 {
+    let y = -x * std::f64::consts::FRAC_1_SQRT_2;
+    if y > ERF_THRESH {
+        -std::f64::consts::LN_2 - y * y + erfcx_tail(y).ln()
+    } else {
+        // Phi(x) ≥ 0.25 here; ln_1p keeps precision as Phi → 1
+        (-0.5 * erfc(-y)).ln_1p()
+    }
+}
+
+/// Inverse standard normal CDF: x with Phi(x) = p, for p in (0, 1).
+/// Acklam's rational approximation (relative error ~1e-9) refined by one
+/// Halley step against `cdf`, which takes it to ~1e-15. Returns ∓inf at 0 and 1.
+pub fn inv_cdf(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1, 2.209_460_984_245_205e2, -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2, -3.066_479_806_614_716e1, 2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1, 1.615_858_368_580_409e2, -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1, -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3, -3.223_964_580_411_365e-1, -2.400_758_277_161_838,
+        -2.549_732_539_343_734, 4.374_664_141_464_968, 2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3, 3.224_671_290_700_398e-1, 2.445_134_137_142_996, 3.754_408_661_907_416,
+    ];
+    const P_LOW: f64 = 0.024_25;
+
+    if p <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p >= 1.0 {
+        return f64::INFINITY;
+    }
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    let x = if p < P_LOW {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - P_LOW {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
     } else {
-        1.0 - cdf(-x)
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    };
+    let u = (cdf(x) - p) * (2.0 * std::f64::consts::PI).sqrt() * (0.5 * x * x).exp();
+    x - u / (1.0 + 0.5 * x * u)
+}
+
+// Gauss-Legendre half-nodes and weights (6, 12 and 20 points) for the BVN integral.
+const GL6_X: [f64; 3] = [0.932_469_514_203_152_2, 0.661_209_386_466_264_7, 0.238_619_186_083_197];
+const GL6_W: [f64; 3] = [0.171_324_492_379_170_5, 0.360_761_573_048_138_4, 0.467_913_934_572_690_4];
+const GL12_X: [f64; 6] = [
+    0.981_560_634_246_719_1, 0.904_117_256_370_475, 0.769_902_674_194_305,
+    0.587_317_954_286_617_1, 0.367_831_498_998_180_2, 0.125_233_408_511_469_2,
+];
+const GL12_W: [f64; 6] = [
+    0.047_175_336_386_511_77, 0.106_939_325_995_318_3, 0.160_078_328_543_346_4,
+    0.203_167_426_723_065_9, 0.233_492_536_538_354_7, 0.249_147_045_813_402_9,
+];
+const GL20_X: [f64; 10] = [
+    0.993_128_599_185_094_9, 0.963_971_927_277_913_8, 0.912_234_428_251_326,
+    0.839_116_971_822_218_8, 0.746_331_906_460_150_8, 0.636_053_680_726_515,
+    0.510_867_001_950_827_1, 0.373_706_088_715_419_6, 0.227_785_851_141_645_1,
+    0.076_526_521_133_497_33,
+];
+const GL20_W: [f64; 10] = [
+    0.017_614_007_139_152_12, 0.040_601_429_800_386_94, 0.062_672_048_334_109_06,
+    0.083_276_741_576_704_75, 0.101_930_119_817_240_4, 0.118_194_531_961_518_4,
+    0.131_688_638_449_176_6, 0.142_096_109_318_382_1, 0.149_172_986_472_603_7,
+    0.152_753_387_130_725_9,
+];
+
+/// Bivariate standard normal CDF: P(X < x, Y < y) with corr(X, Y) = rho.
+/// Genz (2004) refinement of Drezner-Wesolowsky: Gauss-Legendre quadrature of
+/// Plackett's identity for |rho| < 0.925, Owen's expansion near |rho| = 1.
+/// Absolute error ~1e-15. Not on the hot path (nested-window pricing).
+pub fn bvn_cdf(x: f64, y: f64, rho: f64) -> f64 {
+    // Genz works with the upper orthant P(X > h, Y > k)
+    let (h, mut k) = (-x, -y);
+    if rho == 0.0 {
+        return cdf(x) * cdf(y);
     }
+    let (xs, ws): (&[f64], &[f64]) = if rho.abs() < 0.3 {
+        (&GL6_X, &GL6_W)
+    } else if rho.abs() < 0.75 {
+        (&GL12_X, &GL12_W)
+    } else {
+        (&GL20_X, &GL20_W)
+    };
+    let two_pi = 2.0 * std::f64::consts::PI;
+    let mut hk = h * k;
+
+    let bvn = if rho.abs() < 0.925 {
+        let hs = (h * h + k * k) / 2.0;
+        let asr = rho.asin() / 2.0;
+        let mut sum = 0.0;
+        for (&xi, &wi) in xs.iter().zip(ws) {
+            for node in [1.0 - xi, 1.0 + xi] {
+                let sn = (asr * node).sin();
+                sum += wi * ((sn * hk - hs) / (1.0 - sn * sn)).exp();
+            }
+        }
+        sum * asr / two_pi + cdf(-h) * cdf(-k)
+    } else {
+        if rho < 0.0 {
+            k = -k;
+            hk = -hk;
+        }
+        let mut bvn = 0.0;
+        if rho.abs() < 1.0 {
+            let a_s = 1.0 - rho * rho;
+            let mut a = a_s.sqrt();
+            let bs = (h - k) * (h - k);
+            let c = (4.0 - hk) / 8.0;
+            let d = (12.0 - hk) / 80.0;
+            let asr = -(bs / a_s + hk) / 2.0;
+            if asr > -100.0 {
+                bvn = a * asr.exp() * (1.0 - c * (bs - a_s) * (1.0 - d * bs) / 3.0 + c * d * a_s * a_s);
+            }
+            if hk > -100.0 {
+                let b = bs.sqrt();
+                let sp = two_pi.sqrt() * cdf(-b / a);
+                bvn -= (-hk / 2.0).exp() * sp * b * (1.0 - c * bs * (1.0 - d * bs) / 3.0);
+            }
+            a /= 2.0;
+            let mut sum = 0.0;
+            for (&xi, &wi) in xs.iter().zip(ws) {
+                for node in [1.0 - xi, 1.0 + xi] {
+                    let xs2 = (a * node) * (a * node);
+                    let asr = -(bs / xs2 + hk) / 2.0;
+                    if asr > -100.0 {
+                        let sp = 1.0 + c * xs2 * (1.0 + 5.0 * d * xs2);
+                        let rs = (1.0 - xs2).sqrt();
+                        let ep = (-(hk / 2.0) * xs2 / ((1.0 + rs) * (1.0 + rs))).exp() / rs;
+                        sum += wi * asr.exp() * (sp - ep);
+                    }
+                }
+            }
+            bvn = (a * sum - bvn) / two_pi;
+        }
+        if rho > 0.0 {
+            bvn + cdf(-h.max(k))
+        } else if h >= k {
+            -bvn
+        } else {
+            let l = if h < 0.0 { cdf(k) - cdf(h) } else { cdf(-h) - cdf(-k) };
+            l - bvn
+        }
+    };
+    bvn.clamp(0.0, 1.0)
 }
 
 #[cfg(test)]
@@ -86,4 +312,127 @@ mod tests {
         assert!(cdf(10.0) > 0.999_999);
         assert!(cdf(-10.0) < 1e-6);
     }
+
+    // ── Tail precision ──
+
+    /// Scenario: erfc evaluated at x = 1, 3, 5, 10 against reference double-precision values.
+    /// Expected: Relative error below 1e-14 everywhere, including erfc(10) ≈ 2.1e-45.
+    #[test]
+    fn test_erfc_known_values() {
+        let cases = [
+            (1.0, 0.157_299_207_050_285_13),
+            (3.0, 2.209_049_699_858_544e-5),
+            (5.0, 1.537_459_794_428_035e-12),
+            (10.0, 2.088_487_583_762_545e-45),
+            (-1.0, 1.842_700_792_949_715),
+        ];
+        for &(x, want) in &cases {
+            let got = erfc(x);
+            assert!(((got - want) / want).abs() < 1e-14, "erfc({}) = {:e}, want {:e}", x, got, want);
+        }
+    }
+
+    /// Scenario: Lower-tail CDF at z = -5, -8, -20 where the old A&S form had only absolute accuracy.
+    /// Expected: Relative error below 1e-13 — tails are resolved to full precision, not rounded to 0.
+    #[test]
+    fn test_cdf_lower_tail_relative() {
+        let cases = [
+            (-5.0, 2.866_515_718_791_946e-7),
+            (-8.0, 6.220_960_574_271_819e-16),
+            (-20.0, 2.753_624_118_606_331_4e-89),
+        ];
+        for &(x, want) in &cases {
+            let got = cdf(x);
+            assert!(((got - want) / want).abs() < 1e-13, "cdf({}) = {:e}, want {:e}", x, got, want);
+        }
+    }
+
+    /// Scenario: CDF at |x| up to 7 compared through the reflection identity at tighter tolerance.
+    /// Expected: Phi(x) + Phi(-x) == 1 to within 1e-15.
+    #[test]
+    fn test_cdf_symmetry_full_precision() {
+        for i in 0..=70 {
+            let x = i as f64 * 0.1;
+            assert!((cdf(x) + cdf(-x) - 1.0).abs() < 1e-15, "x={}", x);
+        }
+    }
+
+    /// Scenario: inv_cdf at p from 1e-10 to 0.999, then fed back through cdf.
+    /// Expected: The tail probability round-trips to 1e-12 relative; inv_cdf(0.975) ≈ 1.959964;
+    ///           ∓inf at 0 and 1.
+    #[test]
+    fn test_inv_cdf_round_trip() {
+        for &p in &[1e-10, 1e-4, 0.01, 0.02425, 0.2, 0.5, 0.7, 0.975, 0.999] {
+            let x = inv_cdf(p);
+            let (got, want) = if p <= 0.5 { (cdf(x), p) } else { (cdf(-x), 1.0 - p) };
+            assert!(((got - want) / want).abs() < 1e-12, "p={} x={}", p, x);
+        }
+        assert!((inv_cdf(0.975) - 1.959_963_984_540_054).abs() < 1e-12);
+        assert_eq!(inv_cdf(0.5), 0.0);
+        assert_eq!(inv_cdf(0.0), f64::NEG_INFINITY);
+        assert_eq!(inv_cdf(1.0), f64::INFINITY);
+    }
+
+    /// Scenario: log_cdf compared to ln(cdf) across the range where cdf is comfortably representable.
+    /// Expected: Agreement to 1e-12 relative (absolute near 0 where ln Phi → 0).
+    #[test]
+    fn test_log_cdf_matches_ln_cdf() {
+        for i in -300..=80 {
+            let x = i as f64 * 0.1;
+            let a = log_cdf(x);
+            let b = cdf(x).ln();
+            assert!((a - b).abs() <= 1e-12 * b.abs().max(1e-3), "x={} log_cdf={} ln(cdf)={}", x, a, b);
+        }
+        // Upper tail: ln Phi(6) ≈ -Phi(-6), preserved via ln_1p
+        assert!(((log_cdf(6.0) + 9.865_876_455_243_787e-10) / 9.865_876_455_243_787e-10).abs() < 1e-12);
+    }
+
+    /// Scenario: log_cdf at z = -40, where Phi(-40) ≈ 1e-350 underflows f64.
+    /// Expected: Finite value matching the asymptotic series ln Phi(-40) ≈ -804.60844.
+    #[test]
+    fn test_log_cdf_extreme_no_underflow() {
+        assert_eq!(cdf(-40.0), 0.0);
+        let v = log_cdf(-40.0);
+        assert!((v + 804.608_442_013_753_7).abs() < 1e-9, "log_cdf(-40) = {}", v);
+        assert!(log_cdf(-1e4).is_finite());
+    }
+
+    // ── Bivariate normal ──
+
+    /// Scenario: BVN at the origin for correlations across all three quadrature regimes.
+    /// Expected: Matches the closed form 1/4 + asin(rho)/(2*pi) to 1e-14.
+    #[test]
+    fn test_bvn_origin_closed_form() {
+        for rho in [-0.99, -0.8, -0.5, -0.1, 0.1, 0.5, 0.8, 0.95, 0.999] {
+            let exact = 0.25 + f64::asin(rho) / (2.0 * std::f64::consts::PI);
+            let v = bvn_cdf(0.0, 0.0, rho);
+            assert!((v - exact).abs() < 1e-14, "rho={} bvn={} exact={}", rho, v, exact);
+        }
+    }
+
+    /// Scenario: BVN with rho = 0 and with rho at the ±1 limits.
+    /// Expected: Independence gives the product of marginals; rho = 1 gives
+    /// Phi(min(x, y)); rho = -1 gives max(0, Phi(x) + Phi(y) - 1).
+    #[test]
+    fn test_bvn_limits() {
+        let (x, y) = (0.4, -0.7);
+        assert!((bvn_cdf(x, y, 0.0) - cdf(x) * cdf(y)).abs() < 1e-15);
+        assert!((bvn_cdf(x, y, 1.0) - cdf(y)).abs() < 1e-12);
+        assert!((bvn_cdf(x, y, -1.0) - (cdf(x) + cdf(y) - 1.0).max(0.0)).abs() < 1e-12);
+    }
+
+    /// Scenario: Reference values from direct quadrature of phi(t)·Phi((y - rho·t)/sqrt(1 - rho^2)),
+    /// plus the x/y symmetry.
+    /// Expected: Agrees to 1e-12 and is symmetric in its arguments.
+    #[test]
+    fn test_bvn_reference_values() {
+        assert!((bvn_cdf(1.0, 2.0, 0.5) - 0.831_860_831_130_882_6).abs() < 1e-12);
+        assert!((bvn_cdf(-1.0, 0.5, -0.6) - 0.049_633_471_210_141_97).abs() < 1e-12);
+        // |rho| > 0.925: Owen's expansion branch
+        assert!((bvn_cdf(0.3, -0.2, 0.97) - 0.419_994_396_456_792_8).abs() < 1e-10);
+        assert!((bvn_cdf(0.3, -0.2, -0.95) - 0.070_688_647_333_683_17).abs() < 1e-10);
+        assert!((bvn_cdf(1.0, 2.0, 0.5) - bvn_cdf(2.0, 1.0, 0.5)).abs() < 1e-15);
+        // Marginal consistency: Phi2(x, +inf-ish) → Phi(x)
+        assert!((bvn_cdf(-0.3, 12.0, 0.7) - cdf(-0.3)).abs() < 1e-12);
+    }
 }
@@ -1,3 +1,8 @@
+use crate::math::normal::cdf;
+
+/// Quadrature points per uniform in `settle_prob_up` (jitter × cadence grid).
+const SETTLE_GRID: usize = 8;
+
 /// Oracle basis model.
 ///
 /// S_est(t) = S_binance(t) + beta
@@ -9,17 +14,22 @@
 /// delta_oracle_s: oracle timestamp uncertainty in seconds.
 ///   Prevents z/d2 from going to infinity as tau → 0.
 ///   Calibrate from historical oracle timestamp jitter. Typical: 1-5s.
+///
+/// cadence_s: interval between oracle price updates in seconds. The report
+///   settling the market carries a price sampled up to one interval earlier.
 #[derive(Clone)]
 pub struct OracleBasis {
     pub beta: f64,
     pub delta_oracle_s: f64,
+    pub cadence_s: f64,
 }
 
 impl OracleBasis {
-    pub fn new(beta: f64, delta_oracle_s: f64) -> Self {
+    pub fn new(beta: f64, delta_oracle_s: f64, cadence_s: f64) -> Self {
         Self {
             beta,
             delta_oracle_s,
+            cadence_s,
         }
     }
 
@@ -35,6 +45,52 @@ impl OracleBasis {
     pub fn tau_eff(&self, tau_s: f64) -> f64 {
         (tau_s + self.delta_oracle_s).max(0.001)
     }
+
+    /// Probability the oracle settles at or above `strike`, for the final seconds.
+    ///
+    /// The settling price is sampled at `tau_s + u - v` seconds from now, with
+    /// timestamp jitter u ~ U[-delta_oracle_s, +delta_oracle_s] and staleness
+    /// v ~ U[0, cadence_s], integrated on a midpoint grid. A sample time still
+    /// ahead diffuses from `binance_now` with per-second `sigma`; one already
+    /// past is read off the Binance path via `price_ago(lag_s)` (falling back to
+    /// `binance_now`). Either way the print carries `basis_sd` dollars of
+    /// oracle-vs-Binance noise around `price + beta`.
+    pub fn settle_prob_up(
+        &self,
+        binance_now: f64,
+        strike: f64,
+        sigma: f64,
+        tau_s: f64,
+        basis_sd: f64,
+        price_ago: impl Fn(f64) -> Option<f64>,
+    ) -> f64 {
+        let n = SETTLE_GRID as f64;
+        let mut sum = 0.0;
+        for i in 0..SETTLE_GRID {
+            let u = self.delta_oracle_s * (2.0 * (i as f64 + 0.5) / n - 1.0);
+            for j in 0..SETTLE_GRID {
+                let v = self.cadence_s * (j as f64 + 0.5) / n;
+                let t = tau_s + u - v;
+                let (price, var_path) = if t > 0.0 {
+                    (binance_now, sigma * sigma * t)
+                } else {
+                    (price_ago(-t).unwrap_or(binance_now), 0.0)
+                };
+                let s = self.s_est(price);
+                let sd_basis = basis_sd / s;
+                let sd = (var_path + sd_basis * sd_basis).sqrt();
+                let m = (s / strike).ln() - 0.5 * var_path;
+                sum += if sd > 0.0 {
+                    cdf(m / sd)
+                } else if m >= 0.0 {
+                    1.0
+                } else {
+                    0.0
+                };
+            }
+        }
+        sum / (n * n)
+    }
 }
 
 #[cfg(test)]
@@ -45,7 +101,7 @@ mod tests {
     /// Expected: s_est = 100,000 + 10 = 100,010, adding the oracle-vs-Binance basis offset.
     #[test]
     fn test_s_est() {
-        let ob = OracleBasis::new(10.0, 2.0);
+        let ob = OracleBasis::new(10.0, 2.0, 1.0);
         assert_eq!(ob.s_est(100_000.0), 100_010.0);
     }
 
@@ -53,7 +109,7 @@ mod tests {
     /// Expected: tau_eff(5) = 7.0 (adds jitter buffer); tau_eff(-5) = 0.001 (floor prevents zero/negative).
     #[test]
     fn test_tau_eff() {
-        let ob = OracleBasis::new(0.0, 2.0);
+        let ob = OracleBasis::new(0.0, 2.0, 1.0);
         assert_eq!(ob.tau_eff(5.0), 7.0);
         // Floor at 0.001
         assert_eq!(ob.tau_eff(-5.0), 0.001);
@@ -63,7 +119,7 @@ mod tests {
     /// Expected: s_est is pass-through (100,000), tau_eff(300) = 300 (no adjustment).
     #[test]
     fn test_zero_beta() {
-        let ob = OracleBasis::new(0.0, 0.0);
+        let ob = OracleBasis::new(0.0, 0.0, 1.0);
         assert_eq!(ob.s_est(100_000.0), 100_000.0);
         assert_eq!(ob.tau_eff(300.0), 300.0);
     }
@@ -75,7 +131,7 @@ mod tests {
     #[test]
     fn test_negative_beta() {
         // Oracle consistently settles below Binance
-        let ob = OracleBasis::new(-25.0, 2.0);
+        let ob = OracleBasis::new(-25.0, 2.0, 1.0);
         assert_eq!(ob.s_est(100_000.0), 99_975.0);
     }
 
@@ -84,7 +140,7 @@ mod tests {
     #[test]
     fn test_large_delta_oracle() {
         // Very uncertain oracle timestamp (e.g., 10 second jitter)
-        let ob = OracleBasis::new(0.0, 10.0);
+        let ob = OracleBasis::new(0.0, 10.0, 1.0);
         assert_eq!(ob.tau_eff(5.0), 15.0);
         assert_eq!(ob.tau_eff(0.0), 10.0);
     }
@@ -94,7 +150,7 @@ mod tests {
     #[test]
     fn test_tau_eff_floor_with_zero_delta() {
         // delta_oracle_s = 0, tau_s = 0 → should floor to 0.001
-        let ob = OracleBasis::new(0.0, 0.0);
+        let ob = OracleBasis::new(0.0, 0.0, 1.0);
         assert_eq!(ob.tau_eff(0.0), 0.001);
     }
 
@@ -103,7 +159,7 @@ mod tests {
     #[test]
     fn test_tau_eff_floor_with_large_negative_tau() {
         // Even with large negative tau, floor applies
-        let ob = OracleBasis::new(0.0, 2.0);
+        let ob = OracleBasis::new(0.0, 2.0, 1.0);
         assert_eq!(ob.tau_eff(-100.0), 0.001);
     }
 
@@ -111,7 +167,7 @@ mod tests {
     /// Expected: s_est(0) = 10.0, showing beta is added unconditionally regardless of input price.
     #[test]
     fn test_s_est_zero_price() {
-        let ob = OracleBasis::new(10.0, 2.0);
+        let ob = OracleBasis::new(10.0, 2.0, 1.0);
         assert_eq!(ob.s_est(0.0), 10.0);
     }
 
@@ -119,7 +175,57 @@ mod tests {
     /// Expected: s_est = 95,000 + 500 = 95,500, confirming linear addition even with large offsets.
     #[test]
     fn test_s_est_large_beta() {
-        let ob = OracleBasis::new(500.0, 2.0);
+        let ob = OracleBasis::new(500.0, 2.0, 1.0);
         assert_eq!(ob.s_est(95_000.0), 95_500.0);
     }
+
+    // ── settle_prob_up() ──
+
+    /// Scenario: Far from expiry (tau = 300s, jitter 2s, cadence 1s), no basis noise.
+    /// Expected: Matches the plain diffusion probability at the grid's mean
+    ///           sample time within 1e-3 -- seconds of jitter barely matter.
+    #[test]
+    fn test_settle_prob_matches_diffusion_far_from_expiry() {
+        let ob = OracleBasis::new(0.0, 2.0, 1.0);
+        let sigma = 0.0001;
+        let p = ob.settle_prob_up(96_050.0, 96_000.0, sigma, 300.0, 0.0, |_| None);
+        let t = 300.0 - 0.5;
+        let m = (96_050.0_f64 / 96_000.0).ln() - 0.5 * sigma * sigma * t;
+        let expected = cdf(m / (sigma * t.sqrt()));
+        assert!((p - expected).abs() < 1e-3, "p = {}, expected = {}", p, expected);
+    }
+
+    /// Scenario: Market already expired (tau = -5s, jitter 1s, cadence 1s), price now at
+    /// the strike, but the Binance path was $40 above strike 4-7s ago; $5 basis noise.
+    /// Expected: ~1.0 -- every sample time is in the past, so the path decides, not spot.
+    #[test]
+    fn test_settle_prob_reads_past_path() {
+        let ob = OracleBasis::new(0.0, 1.0, 1.0);
+        let p = ob.settle_prob_up(96_000.0, 96_000.0, 0.0001, -5.0, 5.0, |lag| {
+            if (3.0..=8.0).contains(&lag) { Some(96_040.0) } else { None }
+        });
+        assert!(p > 0.999, "p = {}", p);
+    }
+
+    /// Scenario: 1s left, spot $3 above strike, 2s jitter, 4s cadence; the path was $10
+    /// below strike over the last few seconds.
+    /// Expected: Well below the diffusion-only answer -- most oracle samples land on the
+    ///           stale path below strike.
+    #[test]
+    fn test_settle_prob_stale_samples_pull_toward_path() {
+        let ob = OracleBasis::new(0.0, 2.0, 4.0);
+        let path = |_: f64| Some(95_990.0);
+        let with_path = ob.settle_prob_up(96_003.0, 96_000.0, 0.00005, 1.0, 2.0, path);
+        let spot_only = ob.settle_prob_up(96_003.0, 96_000.0, 0.00005, 1.0, 2.0, |_| None);
+        assert!(spot_only > 0.6, "spot_only = {}", spot_only);
+        assert!(with_path < spot_only - 0.3, "with_path = {}, spot_only = {}", with_path, spot_only);
+    }
+
+    /// Scenario: Zero jitter, zero cadence, zero basis noise, tau = 0, spot exactly at strike.
+    /// Expected: 1.0 -- settlement is ≥ strike (ties resolve UP), no division by zero.
+    #[test]
+    fn test_settle_prob_degenerate_tie_resolves_up() {
+        let ob = OracleBasis::new(0.0, 0.0, 0.0);
+        assert_eq!(ob.settle_prob_up(96_000.0, 96_000.0, 0.0001, 0.0, 0.0, |_| None), 1.0);
+    }
 }
@@ -1,4 +1,4 @@
-use super::normal::{cdf, phi};
+use super::normal::{bvn_cdf, cdf, log_cdf, phi};
 
 /// d2 = [ln(S/K) - sigma^2 * tau / 2] / (sigma * sqrt(tau))
 /// tau is in seconds, sigma is in per-second units.
@@ -17,6 +17,19 @@ pub fn p_fair(s: f64, k: f64, sigma: f64, tau: f64) -> f64 {
     cdf(d2(s, k, sigma, tau))
 }
 
+/// Joint fair price of two nested binaries on one price path:
+/// P(S_tau1 > K1 and S_tau2 > K2) for tau1 <= tau2, both measured from now.
+/// The log-price increments share their first tau1 seconds, so the two d2
+/// variables are bivariate normal with correlation sqrt(tau1 / tau2).
+/// A first window that has already expired (tau1 <= 0) is decided by S.
+pub fn p_joint_up(s: f64, k1: f64, tau1: f64, k2: f64, tau2: f64, sigma: f64) -> f64 {
+    if tau1 <= 0.0 {
+        return if s >= k1 { p_fair(s, k2, sigma, tau2) } else { 0.0 };
+    }
+    let rho = (tau1 / tau2).sqrt().min(1.0);
+    bvn_cdf(d2(s, k1, sigma, tau1), d2(s, k2, sigma, tau2), rho)
+}
+
 /// z-score for certainty measurement: z = ln(S/K) / (sigma * sqrt(tau))
 /// Omits the drift term for simplicity (negligible for short tau).
 #[inline]
@@ -61,9 +74,26 @@ pub fn vega_bin(s: f64, k: f64, sigma: f64, tau: f64) -> f64 {
     phi(d) * (-sqrt_tau - d / sigma)
 }
 
-/// Newton-Raphson implied vol from market price.
-/// Returns None if convergence fails.
-/// Not on hot path — called during cross-timeframe analysis.
+/// Binary theta: dP/dt = -dP/dtau = phi(d2) * d1 / (2 * tau), per second of
+/// calendar time, where d1 = d2 + sigma * sqrt(tau). Positive above the strike
+/// (the price drifts to 1 as time passes), negative below.
+#[inline]
+pub fn theta_bin(s: f64, k: f64, sigma: f64, tau: f64) -> f64 {
+    if s <= 0.0 || sigma <= 0.0 || tau <= 0.0 {
+        return 0.0;
+    }
+    let d = d2(s, k, sigma, tau);
+    let d1 = d + sigma * tau.sqrt();
+    phi(d) * d1 / (2.0 * tau)
+}
+
+/// Newton-Raphson implied vol from market price, with bisection fallback.
+/// Returns None if neither converges.
+/// Not on hot path — called during cross-timeframe analysis and vol-surface refresh.
+///
+/// Newton stalls near the 0.01/0.99 bounds (vega → 0) and when the true sigma
+/// sits below its clamp; in both cases `implied_vol_bisect` takes over. A loose
+/// Newton result (within 1 cent) is only returned if bisection also fails.
 pub fn implied_vol(market_price: f64, s: f64, k: f64, tau: f64, max_iter: u32) -> Option<f64> {
     if market_price <= 0.01 || market_price >= 0.99 || tau <= 0.0 {
         return None;
@@ -82,6 +112,9 @@ pub fn implied_vol(market_price: f64, s: f64, k: f64, tau: f64, max_iter: u32) -
         sigma -= diff / v;
         sigma = sigma.clamp(0.001, 50.0);
     }
+    if let Some(iv) = implied_vol_bisect(market_price, s, k, tau, BISECT_MAX_ITER) {
+        return Some(iv);
+    }
     let final_p = p_fair(s, k, sigma, tau);
     if (final_p - market_price).abs() < 0.01 {
         Some(sigma)
@@ -90,6 +123,154 @@ pub fn implied_vol(market_price: f64, s: f64, k: f64, tau: f64, max_iter: u32) -
     }
 }
 
+const BISECT_SIGMA_LO: f64 = 1e-8;
+const BISECT_SIGMA_HI: f64 = 50.0;
+const BISECT_MAX_ITER: u32 = 200;
+
+/// Bisection implied vol on the monotone branch of p(σ). Robust where Newton fails.
+///
+/// With x = σ·√τ and a = ln(S/K), d2 = a/x − x/2:
+///   S ≥ K: p decreasing in σ on (0, ∞) — bracket [σ_lo, σ_hi]
+///   S < K: p increasing up to σ* = √(2·ln(K/S)/τ), then decreasing —
+///          bracket the low-vol branch [σ_lo, σ*] (prices above p(σ*) are unattainable)
+///
+/// Bisects in log-σ on ln p(σ) − ln(market). Returns None if the price is outside the bracket's range.
+pub fn implied_vol_bisect(market_price: f64, s: f64, k: f64, tau: f64, max_iter: u32) -> Option<f64> {
+    if market_price <= 0.0 || market_price >= 1.0 || tau <= 0.0 || s <= 0.0 || k <= 0.0 {
+        return None;
+    }
+    let increasing = s < k;
+    let mut lo = BISECT_SIGMA_LO;
+    let mut hi = if increasing {
+        (2.0 * (k / s).ln() / tau).sqrt().min(BISECT_SIGMA_HI)
+    } else {
+        BISECT_SIGMA_HI
+    };
+
+    // Price must lie between the bracket endpoints
+    let p_lo = p_fair(s, k, lo, tau);
+    let p_hi = p_fair(s, k, hi, tau);
+    let (p_min, p_max) = if p_lo < p_hi { (p_lo, p_hi) } else { (p_hi, p_lo) };
+    if market_price < p_min || market_price > p_max {
+        return None;
+    }
+
+    // Compare in log space: a 1e-9 tolerance is then relative, so tail prices
+    // (p ~ 1e-4 and below) are matched to their own precision, not to an absolute 1e-9
+    let ln_market = market_price.ln();
+    for _ in 0..max_iter {
+        let mid = (lo * hi).sqrt();
+        let diff = log_cdf(d2(s, k, mid, tau)) - ln_market;
+        if diff.abs() < 1e-9 || hi / lo < 1.0 + 1e-12 {
+            return Some(mid);
+        }
+        // Move toward the side that reduces |diff| given the branch's monotonicity
+        if (diff > 0.0) == increasing {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+    Some((lo * hi).sqrt())
+}
+
+// ── Batch kernels ──
+//
+// Structure-of-arrays pricing of many (S, K, τ) points at a shared σ, for vol-surface
+// sweeps and backtest parameter grids. σ-only terms are hoisted, lanes are processed in
+// fixed-width chunks with no cross-lane dependencies, and guard failures are selected
+// per lane rather than branched on. ln/exp/cdf are still scalar libm calls per lane, so
+// the layout is ready for a vector math library without changing callers.
+// Each lane is bit-identical to the scalar function on the same inputs.
+
+const LANES: usize = 4;
+
+/// Per-lane (d2, σ·√τ) with the scalar guards folded into a select.
+/// Invalid lanes carry d2 = 0 like `d2()`.
+#[inline(always)]
+fn d2_lane(s: f64, k: f64, tau: f64, sigma: f64, half_var: f64) -> (f64, f64) {
+    let valid = s > 0.0 && k > 0.0 && tau > 0.0;
+    let sig_sqrt_tau = sigma * tau.sqrt();
+    let d = ((s / k).ln() - half_var * tau) / sig_sqrt_tau;
+    (if valid { d } else { 0.0 }, sig_sqrt_tau)
+}
+
+/// Drive `lane` over equal-length input slices in LANES-wide chunks plus a scalar tail.
+#[inline(always)]
+fn batch_apply<F>(s: &[f64], k: &[f64], tau: &[f64], out: &mut [f64], lane: F)
+where
+    F: Fn(f64, f64, f64) -> f64,
+{
+    let n = out.len();
+    assert!(
+        s.len() == n && k.len() == n && tau.len() == n,
+        "batch pricing: slice lengths differ (s={}, k={}, tau={}, out={})",
+        s.len(), k.len(), tau.len(), n,
+    );
+    let split = n - n % LANES;
+    let (out_body, out_tail) = out.split_at_mut(split);
+    for (((o, s), k), t) in out_body
+        .chunks_exact_mut(LANES)
+        .zip(s.chunks_exact(LANES))
+        .zip(k.chunks_exact(LANES))
+        .zip(tau.chunks_exact(LANES))
+    {
+        o[0] = lane(s[0], k[0], t[0]);
+        o[1] = lane(s[1], k[1], t[1]);
+        o[2] = lane(s[2], k[2], t[2]);
+        o[3] = lane(s[3], k[3], t[3]);
+    }
+    for (((o, &s), &k), &t) in out_tail
+        .iter_mut()
+        .zip(&s[split..])
+        .zip(&k[split..])
+        .zip(&tau[split..])
+    {
+        *o = lane(s, k, t);
+    }
+}
+
+/// Batch `p_fair`: out[i] = Φ(d2(s[i], k[i], σ, tau[i])).
+/// Panics if the slices differ in length.
+pub fn p_fair_batch(s: &[f64], k: &[f64], tau: &[f64], sigma: f64, out: &mut [f64]) {
+    if sigma <= 0.0 {
+        batch_apply(s, k, tau, out, |_, _, _| cdf(0.0));
+        return;
+    }
+    let half_var = 0.5 * sigma * sigma;
+    batch_apply(s, k, tau, out, |s, k, t| cdf(d2_lane(s, k, t, sigma, half_var).0));
+}
+
+/// Batch `delta_bin`: out[i] = φ(d2) / (S·σ·√τ), 0 where the scalar guard fails.
+/// Panics if the slices differ in length.
+pub fn delta_bin_batch(s: &[f64], k: &[f64], tau: &[f64], sigma: f64, out: &mut [f64]) {
+    if sigma <= 0.0 {
+        batch_apply(s, k, tau, out, |_, _, _| 0.0);
+        return;
+    }
+    let half_var = 0.5 * sigma * sigma;
+    batch_apply(s, k, tau, out, |s, k, t| {
+        let (d, _) = d2_lane(s, k, t, sigma, half_var);
+        let v = phi(d) / (s * sigma * t.sqrt());
+        if s > 0.0 && t > 0.0 { v } else { 0.0 }
+    });
+}
+
+/// Batch `gamma_bin`: out[i] = −φ(d2) / (S²·σ√τ) · [1 + d2/(σ√τ)], 0 where the scalar guard fails.
+/// Panics if the slices differ in length.
+pub fn gamma_bin_batch(s: &[f64], k: &[f64], tau: &[f64], sigma: f64, out: &mut [f64]) {
+    if sigma <= 0.0 {
+        batch_apply(s, k, tau, out, |_, _, _| 0.0);
+        return;
+    }
+    let half_var = 0.5 * sigma * sigma;
+    batch_apply(s, k, tau, out, |s, k, t| {
+        let (d, sst) = d2_lane(s, k, t, sigma, half_var);
+        let v = -phi(d) / (s * s * sst) * (1.0 + d / sst);
+        if s > 0.0 && t > 0.0 { v } else { 0.0 }
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -275,13 +456,31 @@ mod tests {
         assert!(v != 0.0, "Vega at ATM should be nonzero: {}", v);
     }
 
+    // ── theta_bin tests ──
+
+    /// Scenario: theta_bin at S above and below K, compared with a finite difference in tau.
+    /// Expected: Positive above the strike, negative below, and equal to -(dP/dtau).
+    #[test]
+    fn test_theta_bin_sign_and_finite_difference() {
+        let (k, sigma, tau) = (100_000.0, 0.001, 300.0);
+        assert!(theta_bin(100_500.0, k, sigma, tau) > 0.0);
+        assert!(theta_bin(99_500.0, k, sigma, tau) < 0.0);
+
+        let s = 100_200.0;
+        let h = 1e-3;
+        let fd = -(p_fair(s, k, sigma, tau + h) - p_fair(s, k, sigma, tau - h)) / (2.0 * h);
+        let th = theta_bin(s, k, sigma, tau);
+        assert!((th - fd).abs() < 1e-6 * fd.abs().max(1e-9), "theta={} fd={}", th, fd);
+        assert_eq!(theta_bin(s, k, sigma, 0.0), 0.0);
+    }
+
     // ── p_fair edge cases ──
 
     /// Scenario: sigma=0 so d2 returns 0.0 via its guard clause, regardless of S > K.
-    /// Expected: p_fair = Phi(0) = 0.5 within CDF approximation error (~7.5e-8).
+    /// Expected: p_fair = Phi(0) = 0.5 exactly (erfc(0) = 1).
     #[test]
     fn test_p_fair_zero_sigma() {
-        // sigma=0 → d2=0 → cdf(0) ≈ 0.5 (CDF approximation has ~7.5e-8 max error)
+        // sigma=0 → d2=0 → cdf(0) = 0.5
         let p = p_fair(105_000.0, 100_000.0, 0.0, 300.0);
         assert!((p - 0.5).abs() < 1e-8, "Zero sigma → p=0.5: {}", p);
     }
@@ -364,4 +563,156 @@ mod tests {
             }
         }
     }
+
+    // ── Bisection fallback ──
+
+    /// Scenario: Realistic per-second sigma (1e-4) slightly ITM — below Newton's 0.001 clamp.
+    /// Expected: implied_vol falls back to bisection and recovers sigma to 1e-7.
+    #[test]
+    fn test_implied_vol_bisect_realistic_sigma() {
+        let (s, k, tau) = (100_050.0, 100_000.0, 300.0);
+        let sigma_true = 1e-4;
+        let p = p_fair(s, k, sigma_true, tau);
+        let iv = implied_vol(p, s, k, tau, 15).expect("bisection should recover");
+        assert!((iv - sigma_true).abs() < 1e-7, "iv = {}", iv);
+    }
+
+    /// Scenario: Deep OTM (S < K) price near the 0.01 bound on the low-vol branch.
+    /// Expected: Bisection recovers sigma where p(σ) is increasing.
+    #[test]
+    fn test_implied_vol_bisect_otm_near_bound() {
+        let (s, k, tau) = (99_700.0, 100_000.0, 300.0);
+        let sigma_true = 8e-5;
+        let p = p_fair(s, k, sigma_true, tau);
+        assert!(p > 0.01 && p < 0.05, "setup p = {}", p);
+        let iv = implied_vol_bisect(p, s, k, tau, 200).expect("should invert");
+        assert!((iv - sigma_true).abs() / sigma_true < 1e-4, "iv = {}", iv);
+    }
+
+    /// Scenario: Deep ITM price of 0.985 (vega ~ 0, Newton stalls).
+    /// Expected: implied_vol returns a sigma that reprices to within 1e-6.
+    #[test]
+    fn test_implied_vol_near_upper_bound() {
+        let (s, k, tau) = (100_300.0, 100_000.0, 300.0);
+        let iv = implied_vol(0.985, s, k, tau, 15).expect("should invert near 0.99");
+        assert!((p_fair(s, k, iv, tau) - 0.985).abs() < 1e-6);
+    }
+
+    /// Scenario: OTM binary (S < K) quoted at 0.45 — above the max attainable Φ(−√(2·ln(K/S))).
+    /// Expected: Bisection returns None (no sigma reprices an OTM binary that high).
+    #[test]
+    fn test_implied_vol_bisect_unattainable_otm() {
+        let (s, k, tau) = (99_000.0, 100_000.0, 300.0);
+        assert!(implied_vol_bisect(0.45, s, k, tau, 200).is_none());
+    }
+
+    /// Scenario: Deep-OTM tail price p ≈ 1e-6 (S < K on the low-vol branch).
+    /// Expected: Log-space bisection recovers sigma to 1e-8 relative and reprices to 1e-9 relative.
+    #[test]
+    fn test_implied_vol_bisect_tail_price_relative() {
+        let (s, k, tau) = (99_500.0, 100_000.0, 300.0);
+        let sigma_true = 6e-5;
+        let p = p_fair(s, k, sigma_true, tau);
+        assert!(p > 1e-8 && p < 1e-4, "setup p = {:e}", p);
+        let iv = implied_vol_bisect(p, s, k, tau, 200).expect("should invert tail price");
+        assert!((iv - sigma_true).abs() / sigma_true < 1e-8, "iv = {}", iv);
+        assert!((p_fair(s, k, iv, tau) / p - 1.0).abs() < 1e-9);
+    }
+
+    // ── Batch kernels ──
+
+    /// Build a grid of (S, K, τ) lanes including guard-failing lanes, with a length not divisible by 4.
+    fn batch_grid() -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+        let (mut s, mut k, mut tau) = (Vec::new(), Vec::new(), Vec::new());
+        for i in 0..9 {
+            for j in 0..5 {
+                s.push(99_000.0 + 250.0 * i as f64);
+                k.push(100_000.0);
+                tau.push(1.0 + 150.0 * j as f64);
+            }
+        }
+        s.extend([0.0, -1.0, 100_000.0, 100_000.0]);
+        k.extend([100_000.0, 100_000.0, 0.0, 100_000.0]);
+        tau.extend([60.0, 60.0, 60.0, 0.0]);
+        (s, k, tau)
+    }
+
+    /// Scenario: p_fair/delta/gamma batch kernels over a 49-lane grid (chunks + tail, guard lanes).
+    /// Expected: Every lane is bit-identical to the scalar function.
+    #[test]
+    fn test_batch_kernels_match_scalar() {
+        let (s, k, tau) = batch_grid();
+        assert_ne!(s.len() % 4, 0, "grid should exercise the scalar tail");
+        let sigma = 2e-4;
+        let mut p = vec![0.0; s.len()];
+        let mut dl = vec![0.0; s.len()];
+        let mut gm = vec![0.0; s.len()];
+        p_fair_batch(&s, &k, &tau, sigma, &mut p);
+        delta_bin_batch(&s, &k, &tau, sigma, &mut dl);
+        gamma_bin_batch(&s, &k, &tau, sigma, &mut gm);
+        for i in 0..s.len() {
+            assert_eq!(p[i], p_fair(s[i], k[i], sigma, tau[i]), "p lane {}", i);
+            assert_eq!(dl[i], delta_bin(s[i], k[i], sigma, tau[i]), "delta lane {}", i);
+            assert_eq!(gm[i], gamma_bin(s[i], k[i], sigma, tau[i]), "gamma lane {}", i);
+        }
+    }
+
+    /// Scenario: Batch kernels called with sigma = 0.
+    /// Expected: Scalar fallbacks — p = 0.5, delta = gamma = 0 in every lane.
+    #[test]
+    fn test_batch_kernels_zero_sigma() {
+        let (s, k, tau) = batch_grid();
+        let mut out = vec![f64::NAN; s.len()];
+        p_fair_batch(&s, &k, &tau, 0.0, &mut out);
+        assert!(out.iter().all(|&v| v == 0.5));
+        delta_bin_batch(&s, &k, &tau, 0.0, &mut out);
+        assert!(out.iter().all(|&v| v == 0.0));
+        gamma_bin_batch(&s, &k, &tau, 0.0, &mut out);
+        assert!(out.iter().all(|&v| v == 0.0));
+    }
+
+    /// Scenario: Output slice one element shorter than the inputs.
+    /// Expected: Panics instead of silently pricing a truncated batch.
+    #[test]
+    #[should_panic(expected = "slice lengths differ")]
+    fn test_batch_length_mismatch_panics() {
+        let x = [100_000.0; 5];
+        let mut out = [0.0; 4];
+        p_fair_batch(&x, &x, &[60.0; 5], 1e-4, &mut out);
+    }
+
+    // ── Joint nested-window pricing ──
+
+    /// Scenario: Two co-terminal binaries (tau1 = tau2) with strikes 99_950 < 100_050.
+    /// Expected: Perfect correlation — P(both UP) is the higher-strike marginal.
+    #[test]
+    fn test_joint_coterminal_is_min_marginal() {
+        let (s, sigma, tau) = (100_000.0, 2e-4, 120.0);
+        let p_lo = p_fair(s, 99_950.0, sigma, tau);
+        let p_hi = p_fair(s, 100_050.0, sigma, tau);
+        let j = p_joint_up(s, 99_950.0, tau, 100_050.0, tau, sigma);
+        assert!((j - p_hi).abs() < 1e-9, "joint={} p_hi={} p_lo={}", j, p_hi, p_lo);
+    }
+
+    /// Scenario: A 60s window inside a 600s window, both ATM.
+    /// Expected: Fréchet bounds hold and the pair is positively dependent:
+    /// p1·p2 < joint < min(p1, p2).
+    #[test]
+    fn test_joint_nested_positive_dependence() {
+        let (s, k, sigma) = (100_000.0, 100_000.0, 2e-4);
+        let p1 = p_fair(s, k, sigma, 60.0);
+        let p2 = p_fair(s, k, sigma, 600.0);
+        let j = p_joint_up(s, k, 60.0, k, 600.0, sigma);
+        assert!(j > p1 * p2 && j < p1.min(p2), "p1={} p2={} joint={}", p1, p2, j);
+    }
+
+    /// Scenario: The inner window has already expired with S above / below its strike.
+    /// Expected: The joint collapses to the outer marginal, or to zero.
+    #[test]
+    fn test_joint_expired_inner_window() {
+        let (sigma, tau2) = (2e-4, 300.0);
+        let j_up = p_joint_up(100_000.0, 99_000.0, 0.0, 100_000.0, tau2, sigma);
+        assert!((j_up - p_fair(100_000.0, 100_000.0, sigma, tau2)).abs() < 1e-15);
+        assert_eq!(p_joint_up(100_000.0, 101_000.0, 0.0, 100_000.0, tau2, sigma), 0.0);
+    }
 }
@@ -1,5 +1,7 @@
 use std::collections::VecDeque;
 
+use crate::math::normal::cdf;
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Regime {
     Range,
@@ -86,6 +88,349 @@ impl RegimeClassifier {
     pub fn total_ticks(&self) -> u32 {
         self.total
     }
+
+    /// Tick-based trend probability: linear ramp of dominant_frac from the
+    /// Range cutoff (60% → 0.0) to the Trend cutoff (75% → 1.0).
+    /// Returns 0.0 with fewer than 10 ticks (no evidence of trend).
+    #[inline]
+    pub fn trend_prob(&self) -> f64 {
+        if self.total < 10 {
+            return 0.0;
+        }
+        ((self.dominant_frac() - 0.60) / 0.15).clamp(0.0, 1.0)
+    }
+}
+
+// ─── Probabilistic regime models over 1s returns ───
+
+/// Regime model fed with 1-second log-returns (normalized to per-√s).
+///
+/// Unlike `RegimeClassifier`, which labels tick direction, implementations
+/// account for move magnitude and return a continuous probability of trend:
+///   0.0 = no evidence of trend (random walk or mean-reverting)
+///   1.0 = strong, persistent directional drift
+pub trait RegimeModel {
+    /// Feed one sampled return. Called once per second, off the per-tick path.
+    fn update(&mut self, r: f64);
+
+    /// Probability that price is trending, in [0, 1]. Cached — O(1).
+    fn trend_prob(&self) -> f64;
+
+    /// Whether enough returns have been observed for `trend_prob` to be meaningful.
+    fn is_ready(&self) -> bool;
+
+    /// Direction of the detected drift. Only meaningful when `trend_prob` is high.
+    fn trend_direction_up(&self) -> bool;
+}
+
+/// Lo-MacKinlay variance ratio over a rolling window of 1s returns.
+///
+/// VR(q) = E[(r_t + … + r_{t-q+1})²] / (q · E[r²]), using uncentered moments
+/// so that both drift and positive autocorrelation push VR above 1.
+///   VR ≈ 1: random walk (H ≈ 0.5)
+///   VR > 1: trending / persistent (H > 0.5)
+///   VR < 1: mean-reverting (H < 0.5)
+///
+/// trend_prob = 2·Φ(z) − 1 (floored at 0), where z is the homoskedastic
+/// VR test statistic — i.e. one-sided confidence that VR > 1.
+#[derive(Clone)]
+pub struct VarianceRatio {
+    q: usize,
+    window: usize,
+    returns: VecDeque<f64>,
+    ratio: f64,
+    prob: f64,
+    drift: f64,
+}
+
+impl VarianceRatio {
+    pub fn new(q: usize, window: usize) -> Self {
+        let q = q.max(2);
+        Self {
+            q,
+            window: window.max(q * 2),
+            returns: VecDeque::with_capacity(window + 1),
+            ratio: 1.0,
+            prob: 0.0,
+            drift: 0.0,
+        }
+    }
+
+    /// Current variance ratio VR(q). 1.0 until ready.
+    #[inline]
+    pub fn ratio(&self) -> f64 {
+        self.ratio
+    }
+
+    /// Hurst exponent implied by VR(q): H = 0.5 · (1 + ln(VR) / ln(q)), clamped to [0, 1].
+    #[inline]
+    pub fn hurst(&self) -> f64 {
+        if self.ratio <= 0.0 {
+            return 0.0;
+        }
+        (0.5 * (1.0 + self.ratio.ln() / (self.q as f64).ln())).clamp(0.0, 1.0)
+    }
+
+    /// Number of returns in the window.
+    #[inline]
+    pub fn n_returns(&self) -> usize {
+        self.returns.len()
+    }
+
+    fn recompute(&mut self) {
+        let n = self.returns.len();
+        let q = self.q;
+        let sum_r2: f64 = self.returns.iter().map(|r| r * r).sum();
+        self.drift = self.returns.iter().sum();
+        if sum_r2 <= 0.0 {
+            // Flat price: no movement, no trend
+            self.ratio = 1.0;
+            self.prob = 0.0;
+            return;
+        }
+
+        // Overlapping q-period sums via a rolling window
+        let mut q_sum: f64 = self.returns.iter().take(q).sum();
+        let mut sum_q2 = q_sum * q_sum;
+        for i in q..n {
+            q_sum += self.returns[i] - self.returns[i - q];
+            sum_q2 += q_sum * q_sum;
+        }
+        let m = (n - q + 1) as f64;
+        self.ratio = (sum_q2 / m) / (q as f64 * sum_r2 / n as f64);
+
+        // Asymptotic variance of VR(q) under the random-walk null
+        let qf = q as f64;
+        let var_vr = 2.0 * (2.0 * qf - 1.0) * (qf - 1.0) / (3.0 * qf * n as f64);
+        let z = (self.ratio - 1.0) / var_vr.sqrt();
+        self.prob = (2.0 * cdf(z) - 1.0).max(0.0);
+    }
+}
+
+impl RegimeModel for VarianceRatio {
+    #[inline]
+    fn update(&mut self, r: f64) {
+        if !r.is_finite() {
+            return;
+        }
+        self.returns.push_back(r);
+        while self.returns.len() > self.window {
+            self.returns.pop_front();
+        }
+        if self.is_ready() {
+            self.recompute();
+        }
+    }
+
+    #[inline]
+    fn trend_prob(&self) -> f64 {
+        self.prob
+    }
+
+    #[inline]
+    fn is_ready(&self) -> bool {
+        self.returns.len() >= self.q * 4
+    }
+
+    #[inline]
+    fn trend_direction_up(&self) -> bool {
+        self.drift > 0.0
+    }
+}
+
+/// Two-state Gaussian HMM over 1s returns, refit by Baum-Welch on a rolling window.
+///
+///   State 0 (range): r ~ N(0, σ₀²)
+///   State 1 (trend): r ~ N(μ₁, σ₁²)
+///
+/// The transition matrix is fixed and sticky (p_stay) so single-tick reversals
+/// cannot be explained by regime switching. trend_prob is the filtered posterior
+/// of the trend state at the latest return, discounted by the significance of μ₁
+/// (two-sided t-test on the trend-state mean) — a trend state with no drift is
+/// just a second noise state.
+#[derive(Clone)]
+pub struct GaussianHmm {
+    window: usize,
+    min_returns: usize,
+    p_stay: f64,
+    em_iters: usize,
+    returns: VecDeque<f64>,
+    trend_mean: f64,
+    posterior: f64,
+    prob: f64,
+}
+
+impl GaussianHmm {
+    pub fn new(window: usize, p_stay: f64, em_iters: usize) -> Self {
+        Self {
+            window,
+            min_returns: (window / 3).max(10),
+            p_stay: p_stay.clamp(0.5, 0.999),
+            em_iters: em_iters.max(1),
+            returns: VecDeque::with_capacity(window + 1),
+            trend_mean: 0.0,
+            posterior: 0.0,
+            prob: 0.0,
+        }
+    }
+
+    /// Fitted trend-state mean μ₁ (per-√s return units).
+    #[inline]
+    pub fn trend_mean(&self) -> f64 {
+        self.trend_mean
+    }
+
+    /// Filtered posterior P(trend state | returns) at the latest return,
+    /// before the drift-significance discount.
+    #[inline]
+    pub fn posterior(&self) -> f64 {
+        self.posterior
+    }
+
+    /// Number of returns in the window.
+    #[inline]
+    pub fn n_returns(&self) -> usize {
+        self.returns.len()
+    }
+
+    fn fit(&mut self) {
+        let xs: Vec<f64> = self.returns.iter().copied().collect();
+        let n = xs.len();
+        let nf = n as f64;
+
+        let m1 = xs.iter().sum::<f64>() / nf;
+        let m2 = xs.iter().map(|r| r * r).sum::<f64>() / nf;
+        if m2 <= 0.0 {
+            self.posterior = 0.0;
+            self.prob = 0.0;
+            return;
+        }
+        let floor = (m2 * 1e-3).max(1e-300);
+
+        // Initialise: range state absorbs total second moment, trend state the drift
+        let mut var0 = m2.max(floor);
+        let mut mu1 = m1;
+        let mut var1 = (m2 - m1 * m1).max(floor);
+        let mut pi = [0.5, 0.5];
+        let stay = self.p_stay;
+        let switch = 1.0 - stay;
+
+        let mut emit = vec![[0.0f64; 2]; n];
+        let mut alpha = vec![[0.0f64; 2]; n];
+        let mut beta = vec![[1.0f64; 2]; n];
+        let mut scale = vec![1.0f64; n];
+        let mut w1 = 0.0;
+
+        for _ in 0..self.em_iters {
+            // Emissions, rescaled per-t by the larger likelihood (cancels in posteriors)
+            for (t, &r) in xs.iter().enumerate() {
+                let l0 = -0.5 * (var0.ln() + r * r / var0);
+                let l1 = -0.5 * (var1.ln() + (r - mu1) * (r - mu1) / var1);
+                let lmax = l0.max(l1);
+                emit[t] = [(l0 - lmax).exp(), (l1 - lmax).exp()];
+            }
+
+            // Forward pass (scaled)
+            for t in 0..n {
+                let (p0, p1) = if t == 0 {
+                    (pi[0], pi[1])
+                } else {
+                    let a = alpha[t - 1];
+                    (a[0] * stay + a[1] * switch, a[0] * switch + a[1] * stay)
+                };
+                let a0 = p0 * emit[t][0];
+                let a1 = p1 * emit[t][1];
+                let c = a0 + a1;
+                if c > 0.0 && c.is_finite() {
+                    alpha[t] = [a0 / c, a1 / c];
+                    scale[t] = c;
+                } else {
+                    alpha[t] = [0.5, 0.5];
+                    scale[t] = 1.0;
+                }
+            }
+
+            // Backward pass (same scaling)
+            beta[n - 1] = [1.0, 1.0];
+            for t in (0..n - 1).rev() {
+                let b = beta[t + 1];
+                let e = emit[t + 1];
+                let c = scale[t + 1];
+                beta[t] = [
+                    (stay * e[0] * b[0] + switch * e[1] * b[1]) / c,
+                    (switch * e[0] * b[0] + stay * e[1] * b[1]) / c,
+                ];
+            }
+
+            // M-step
+            let (mut s0_r2, mut s1_r, mut s1_r2) = (0.0, 0.0, 0.0);
+            let mut w0 = 0.0;
+            w1 = 0.0;
+            for (t, &r) in xs.iter().enumerate() {
+                let g0 = alpha[t][0] * beta[t][0];
+                let g1 = alpha[t][1] * beta[t][1];
+                let g = g0 + g1;
+                let (g0, g1) = if g > 0.0 { (g0 / g, g1 / g) } else { (0.5, 0.5) };
+                if t == 0 {
+                    pi = [g0, g1];
+                }
+                w0 += g0;
+                w1 += g1;
+                s0_r2 += g0 * r * r;
+                s1_r += g1 * r;
+                s1_r2 += g1 * r * r;
+            }
+            if w0 > 1e-9 {
+                var0 = (s0_r2 / w0).max(floor);
+            }
+            if w1 > 1e-9 {
+                mu1 = s1_r / w1;
+                var1 = (s1_r2 / w1 - mu1 * mu1).max(floor);
+            }
+        }
+
+        self.trend_mean = mu1;
+        self.posterior = alpha[n - 1][1];
+
+        // Discount by significance of the trend-state drift
+        let t_stat = if w1 > 0.0 {
+            mu1.abs() / var1.sqrt() * w1.sqrt()
+        } else {
+            0.0
+        };
+        let significance = (2.0 * cdf(t_stat) - 1.0).max(0.0);
+        self.prob = (self.posterior * significance).clamp(0.0, 1.0);
+    }
+}
+
+impl RegimeModel for GaussianHmm {
+    fn update(&mut self, r: f64) {
+        if !r.is_finite() {
+            return;
+        }
+        self.returns.push_back(r);
+        while self.returns.len() > self.window {
+            self.returns.pop_front();
+        }
+        if self.is_ready() {
+            self.fit();
+        }
+    }
+
+    #[inline]
+    fn trend_prob(&self) -> f64 {
+        self.prob
+    }
+
+    #[inline]
+    fn is_ready(&self) -> bool {
+        self.returns.len() >= self.min_returns
+    }
+
+    #[inline]
+    fn trend_direction_up(&self) -> bool {
+        self.trend_mean > 0.0
+    }
 }
 
 #[cfg(test)]
@@ -322,4 +667,156 @@ mod tests {
         // 10 up out of 20 → up_count(10) == total/2(10) → trend_direction_up = false
         assert!(!rc.trend_direction_up());
     }
+
+    // ── RegimeClassifier::trend_prob ──
+
+    /// Scenario: Tick classifier at 50%, ~67% and 80% dominant, plus < 10 ticks.
+    /// Expected: Ramp 0 at Range, strictly between 0 and 1 when Ambiguous, 1 at Trend, 0 when sparse.
+    #[test]
+    fn test_classifier_trend_prob_ramp() {
+        let mut range = RegimeClassifier::new(30_000);
+        let mut ambiguous = RegimeClassifier::new(30_000);
+        let mut trend = RegimeClassifier::new(30_000);
+        let mut sparse = RegimeClassifier::new(30_000);
+        for i in 0..100 {
+            range.update(i * 100, i % 2 == 0);
+            ambiguous.update(i * 100, i % 3 != 0);
+            trend.update(i * 100, i % 5 != 0);
+        }
+        for i in 0..5 {
+            sparse.update(i * 100, true);
+        }
+        assert_eq!(range.trend_prob(), 0.0);
+        let p = ambiguous.trend_prob();
+        assert!(p > 0.0 && p < 1.0, "ambiguous trend_prob = {}", p);
+        assert_eq!(trend.trend_prob(), 1.0);
+        assert_eq!(sparse.trend_prob(), 0.0);
+    }
+
+    // ── Probabilistic models ──
+
+    /// Deterministic zero-mean pseudo-noise in per-√s return units (~1e-4).
+    fn noise(i: i64) -> f64 {
+        let x = ((i * 7919 + 13) % 101) as f64 - 50.0;
+        x * 2e-6
+    }
+
+    /// Scenario: 120 returns of steady +1bp drift with small noise fed to VarianceRatio(5).
+    /// Expected: VR well above 1, Hurst > 0.5, trend_prob near 1, direction up.
+    #[test]
+    fn test_vr_trend() {
+        let mut vr = VarianceRatio::new(5, 120);
+        for i in 0..120 {
+            vr.update(1e-4 + noise(i) * 0.2);
+        }
+        assert!(vr.is_ready());
+        assert!(vr.ratio() > 2.0, "VR = {}", vr.ratio());
+        assert!(vr.hurst() > 0.5, "H = {}", vr.hurst());
+        assert!(vr.trend_prob() > 0.95, "p = {}", vr.trend_prob());
+        assert!(vr.trend_direction_up());
+    }
+
+    /// Scenario: 120 strictly alternating ±1bp returns (pure mean reversion).
+    /// Expected: VR < 1, Hurst < 0.5, trend_prob 0.
+    #[test]
+    fn test_vr_mean_reverting() {
+        let mut vr = VarianceRatio::new(5, 120);
+        for i in 0..120 {
+            vr.update(if i % 2 == 0 { 1e-4 } else { -1e-4 });
+        }
+        assert!(vr.ratio() < 1.0, "VR = {}", vr.ratio());
+        assert!(vr.hurst() < 0.5, "H = {}", vr.hurst());
+        assert_eq!(vr.trend_prob(), 0.0);
+    }
+
+    /// Scenario: Large tick burst in one second vs. many small directional moves.
+    /// Expected: A single outsized return among noise does not register as a trend,
+    /// unlike the tick classifier which only sees direction.
+    #[test]
+    fn test_vr_single_jump_not_trend() {
+        let mut vr = VarianceRatio::new(5, 120);
+        for i in 0..120 {
+            let r = if i == 60 { 2e-3 } else { noise(i) };
+            vr.update(r);
+        }
+        assert!(vr.trend_prob() < 0.5, "p = {}", vr.trend_prob());
+    }
+
+    /// Scenario: Fewer than 4·q returns fed; then a flat (zero-return) window.
+    /// Expected: Not ready with trend_prob 0; flat window gives VR = 1 and trend_prob 0.
+    #[test]
+    fn test_vr_warmup_and_flat() {
+        let mut vr = VarianceRatio::new(5, 120);
+        for _ in 0..19 {
+            vr.update(1e-4);
+        }
+        assert!(!vr.is_ready());
+        assert_eq!(vr.trend_prob(), 0.0);
+
+        let mut flat = VarianceRatio::new(5, 120);
+        for _ in 0..40 {
+            flat.update(0.0);
+        }
+        assert!(flat.is_ready());
+        assert_eq!(flat.ratio(), 1.0);
+        assert_eq!(flat.trend_prob(), 0.0);
+    }
+
+    /// Scenario: 120 returns of steady -1bp drift with noise fed to the HMM.
+    /// Expected: Trend state mean negative, posterior and trend_prob high, direction down.
+    #[test]
+    fn test_hmm_down_trend() {
+        let mut hmm = GaussianHmm::new(120, 0.95, 8);
+        for i in 0..120 {
+            hmm.update(-1e-4 + noise(i) * 0.2);
+        }
+        assert!(hmm.is_ready());
+        assert!(hmm.trend_mean() < 0.0);
+        assert!(!hmm.trend_direction_up());
+        assert!(hmm.trend_prob() > 0.9, "p = {}", hmm.trend_prob());
+    }
+
+    /// Scenario: 120 strictly alternating ±1bp returns fed to the HMM.
+    /// Expected: Trend-state drift is insignificant → trend_prob near 0.
+    #[test]
+    fn test_hmm_alternating_not_trend() {
+        let mut hmm = GaussianHmm::new(120, 0.95, 8);
+        for i in 0..120 {
+            hmm.update(if i % 2 == 0 { 1e-4 } else { -1e-4 });
+        }
+        assert!(hmm.trend_prob() < 0.1, "p = {}", hmm.trend_prob());
+    }
+
+    /// Scenario: 80 noise returns followed by 40 returns of strong +2bp drift.
+    /// Expected: HMM detects the regime switch — trend_prob at the end exceeds
+    /// the value after the noise phase, and the latest posterior favours trend.
+    #[test]
+    fn test_hmm_detects_switch() {
+        let mut hmm = GaussianHmm::new(120, 0.95, 8);
+        for i in 0..80 {
+            hmm.update(noise(i));
+        }
+        let p_noise = hmm.trend_prob();
+        for i in 80..120 {
+            hmm.update(2e-4 + noise(i) * 0.2);
+        }
+        assert!(hmm.posterior() > 0.8, "posterior = {}", hmm.posterior());
+        assert!(hmm.trend_prob() > p_noise, "p_noise={} p_trend={}", p_noise, hmm.trend_prob());
+        assert!(hmm.trend_direction_up());
+    }
+
+    /// Scenario: HMM fed fewer returns than its warmup, plus non-finite returns.
+    /// Expected: Not ready and trend_prob 0; NaN/inf ignored (window size unchanged).
+    #[test]
+    fn test_hmm_warmup_and_non_finite() {
+        let mut hmm = GaussianHmm::new(120, 0.95, 8);
+        for _ in 0..10 {
+            hmm.update(1e-4);
+        }
+        hmm.update(f64::NAN);
+        hmm.update(f64::INFINITY);
+        assert_eq!(hmm.n_returns(), 10);
+        assert!(!hmm.is_ready());
+        assert_eq!(hmm.trend_prob(), 0.0);
+    }
 }
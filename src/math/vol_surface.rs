@@ -0,0 +1,380 @@
+use std::collections::VecDeque;
+
+use super::pricing::{delta_bin_batch, gamma_bin_batch, implied_vol, p_fair_batch};
+
+const NEWTON_ITERS: u32 = 15;
+
+/// Bid/ask implied vols for one token, in per-second units.
+/// Either side is None when the quote is missing or not invertible.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct IvQuote {
+    pub bid: Option<f64>,
+    pub ask: Option<f64>,
+}
+
+impl IvQuote {
+    /// Mid IV: average of bid/ask IVs, or whichever side exists.
+    #[inline]
+    pub fn mid(&self) -> Option<f64> {
+        match (self.bid, self.ask) {
+            (Some(b), Some(a)) => Some((b + a) / 2.0),
+            (Some(x), None) | (None, Some(x)) => Some(x),
+            (None, None) => None,
+        }
+    }
+}
+
+/// One node of the vol surface: a single market (strike, expiry) with IVs
+/// for both tokens.
+///
+/// DOWN prices are converted to the UP-equivalent probability (1 − q) before
+/// inversion, so both tokens are expressed on the same binary-call smile.
+/// Note the DOWN bid maps to an UP ask-equivalent and vice versa.
+#[derive(Clone, Debug)]
+pub struct SurfacePoint {
+    pub expiry_ms: i64,
+    pub strike: f64,
+    pub tau_s: f64,
+    pub up: IvQuote,
+    pub down: IvQuote,
+    pub updated_ms: i64,
+    /// Model P(UP), delta and gamma at realized vol, as of the last
+    /// `VolSurface::reprice`. Zero until then.
+    pub fair_up: f64,
+    pub delta: f64,
+    pub gamma: f64,
+}
+
+impl SurfacePoint {
+    /// Invert top-of-book quotes into bid/ask IVs.
+    /// `up`/`down` are (bid, ask); zero or out-of-range prices yield None.
+    pub fn compute(s: f64, strike: f64, expiry_ms: i64, tau_s: f64, up: (f64, f64), down: (f64, f64)) -> Self {
+        Self {
+            expiry_ms,
+            strike,
+            tau_s,
+            up: IvQuote {
+                bid: iv_of(up.0, s, strike, tau_s),
+                ask: iv_of(up.1, s, strike, tau_s),
+            },
+            down: IvQuote {
+                bid: valid_price(down.0).and_then(|q| iv_of(1.0 - q, s, strike, tau_s)),
+                ask: valid_price(down.1).and_then(|q| iv_of(1.0 - q, s, strike, tau_s)),
+            },
+            updated_ms: 0,
+            fair_up: 0.0,
+            delta: 0.0,
+            gamma: 0.0,
+        }
+    }
+
+    /// Mid IV across both tokens. Falls back to whichever token has quotes.
+    #[inline]
+    pub fn mid_iv(&self) -> Option<f64> {
+        match (self.up.mid(), self.down.mid()) {
+            (Some(u), Some(d)) => Some((u + d) / 2.0),
+            (Some(x), None) | (None, Some(x)) => Some(x),
+            (None, None) => None,
+        }
+    }
+}
+
+#[inline]
+fn valid_price(p: f64) -> Option<f64> {
+    if p > 0.0 && p < 1.0 { Some(p) } else { None }
+}
+
+#[inline]
+fn iv_of(price: f64, s: f64, k: f64, tau: f64) -> Option<f64> {
+    valid_price(price).and_then(|p| implied_vol(p, s, k, tau, NEWTON_ITERS))
+}
+
+/// ATM implied vol vs realized vol at one point in time (both per-second).
+#[derive(Clone, Copy, Debug)]
+pub struct IvRvSample {
+    pub ts_ms: i64,
+    pub atm_iv: f64,
+    pub rv: f64,
+}
+
+impl IvRvSample {
+    #[inline]
+    pub fn spread(&self) -> f64 {
+        self.atm_iv - self.rv
+    }
+}
+
+/// Implied-vol surface across all tracked markets (strikes × expiries),
+/// plus a 1s-sampled time series of ATM IV vs realized vol.
+///
+/// Points are keyed by (expiry_ms, strike) and kept sorted by expiry,
+/// so `term_structure()` reads directly off the vector.
+#[derive(Clone)]
+pub struct VolSurface {
+    points: Vec<SurfacePoint>,
+    history: VecDeque<IvRvSample>,
+    history_window_ms: i64,
+    refresh_interval_ms: i64,
+    last_refresh_ms: i64,
+}
+
+impl VolSurface {
+    pub fn new(history_window_ms: i64, refresh_interval_ms: i64) -> Self {
+        Self {
+            points: Vec::with_capacity(4),
+            history: VecDeque::with_capacity(512),
+            history_window_ms,
+            refresh_interval_ms,
+            last_refresh_ms: i64::MIN,
+        }
+    }
+
+    /// Whether a refresh is due (throttles IV inversion off the quote hot path).
+    #[inline]
+    pub fn refresh_due(&self, now_ms: i64) -> bool {
+        now_ms.saturating_sub(self.last_refresh_ms) >= self.refresh_interval_ms
+    }
+
+    #[inline]
+    pub fn mark_refreshed(&mut self, now_ms: i64) {
+        self.last_refresh_ms = now_ms;
+    }
+
+    /// Insert or replace the point for (expiry_ms, strike).
+    pub fn upsert(&mut self, mut point: SurfacePoint, now_ms: i64) {
+        point.updated_ms = now_ms;
+        if let Some(existing) = self
+            .points
+            .iter_mut()
+            .find(|p| p.expiry_ms == point.expiry_ms && p.strike == point.strike)
+        {
+            *existing = point;
+            return;
+        }
+        let idx = self.points.partition_point(|p| p.expiry_ms <= point.expiry_ms);
+        self.points.insert(idx, point);
+    }
+
+    /// Drop expired markets.
+    pub fn prune(&mut self, now_ms: i64) {
+        self.points.retain(|p| p.expiry_ms > now_ms);
+    }
+
+    /// Price every point at spot `s` and realized vol `sigma` in one pass of the
+    /// batch kernels, filling `fair_up`, `delta` and `gamma`.
+    pub fn reprice(&mut self, s: f64, sigma: f64) {
+        let n = self.points.len();
+        let spot = vec![s; n];
+        let strike: Vec<f64> = self.points.iter().map(|p| p.strike).collect();
+        let tau: Vec<f64> = self.points.iter().map(|p| p.tau_s).collect();
+        let (mut fair, mut delta, mut gamma) = (vec![0.0; n], vec![0.0; n], vec![0.0; n]);
+        p_fair_batch(&spot, &strike, &tau, sigma, &mut fair);
+        delta_bin_batch(&spot, &strike, &tau, sigma, &mut delta);
+        gamma_bin_batch(&spot, &strike, &tau, sigma, &mut gamma);
+        for (i, p) in self.points.iter_mut().enumerate() {
+            p.fair_up = fair[i];
+            p.delta = delta[i];
+            p.gamma = gamma[i];
+        }
+    }
+
+    /// All points, sorted by expiry.
+    #[inline]
+    pub fn points(&self) -> &[SurfacePoint] {
+        &self.points
+    }
+
+    /// Point for a given (expiry_ms, strike).
+    pub fn point(&self, expiry_ms: i64, strike: f64) -> Option<&SurfacePoint> {
+        self.points
+            .iter()
+            .find(|p| p.expiry_ms == expiry_ms && p.strike == strike)
+    }
+
+    /// Record an ATM IV / RV pair. Sampled at most once per second; evicts
+    /// samples older than the history window.
+    pub fn record(&mut self, ts_ms: i64, atm_iv: f64, rv: f64) {
+        if let Some(last) = self.history.back() {
+            if ts_ms - last.ts_ms < 1000 {
+                return;
+            }
+        }
+        self.history.push_back(IvRvSample { ts_ms, atm_iv, rv });
+        let cutoff = ts_ms - self.history_window_ms;
+        while self.history.front().is_some_and(|s| s.ts_ms < cutoff) {
+            self.history.pop_front();
+        }
+    }
+
+    /// ATM IV vs RV time series (oldest first).
+    #[inline]
+    pub fn history(&self) -> &VecDeque<IvRvSample> {
+        &self.history
+    }
+
+    /// Mean IV − RV spread over the history window.
+    pub fn mean_iv_rv_spread(&self) -> Option<f64> {
+        if self.history.is_empty() {
+            return None;
+        }
+        let sum: f64 = self.history.iter().map(|s| s.spread()).sum();
+        Some(sum / self.history.len() as f64)
+    }
+
+    /// Term structure: (tau_s, mid IV) for every point with an invertible quote,
+    /// ordered by expiry.
+    pub fn term_structure(&self) -> Vec<(f64, f64)> {
+        self.points
+            .iter()
+            .filter_map(|p| p.mid_iv().map(|iv| (p.tau_s, iv)))
+            .filter(|&(t, iv)| t > 0.0 && iv > 0.0)
+            .collect()
+    }
+
+    /// Power-law term-structure exponent b in σ(τ) = a · τ^b (OLS in log-log space).
+    /// b > 0: upward-sloping (longer expiries richer). None with < 2 distinct expiries.
+    pub fn term_slope(&self) -> Option<f64> {
+        let ts = self.term_structure();
+        if ts.len() < 2 {
+            return None;
+        }
+        let n = ts.len() as f64;
+        let (mut sx, mut sy, mut sxy, mut sxx) = (0.0, 0.0, 0.0, 0.0);
+        for &(t, iv) in &ts {
+            let x = t.ln();
+            let y = iv.ln();
+            sx += x;
+            sy += y;
+            sxy += x * y;
+            sxx += x * x;
+        }
+        let denom = n * sxx - sx * sx;
+        if denom.abs() < 1e-10 {
+            return None;
+        }
+        Some((n * sxy - sx * sy) / denom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::pricing::{delta_bin, gamma_bin, p_fair};
+
+    const S: f64 = 100_050.0;
+    const K: f64 = 100_000.0;
+
+    /// Scenario: UP and DOWN quotes generated from sigma=1e-4 with a 1-cent half-spread.
+    /// Expected: All four IVs invert; UP bid IV > UP ask IV (S > K → p decreasing in σ,
+    /// so the cheaper quote implies more vol), and mid IV lands near the true sigma.
+    #[test]
+    fn test_surface_point_bid_ask_ivs() {
+        let sigma = 1e-4;
+        let tau = 300.0;
+        let p = p_fair(S, K, sigma, tau);
+        let pt = SurfacePoint::compute(S, K, 300_000, tau, (p - 0.01, p + 0.01), (1.0 - p - 0.01, 1.0 - p + 0.01));
+        for iv in [pt.up.bid, pt.up.ask, pt.down.bid, pt.down.ask] {
+            assert!(iv.is_some(), "all sides should invert: {:?}", pt);
+        }
+        // S > K: lower price ↔ higher vol
+        assert!(pt.up.bid.unwrap() > pt.up.ask.unwrap());
+        let mid = pt.mid_iv().unwrap();
+        assert!((mid - sigma).abs() / sigma < 0.1, "mid iv = {}", mid);
+    }
+
+    /// Scenario: Only UP quotes present (DOWN zero / 1.0).
+    /// Expected: DOWN IVs None; mid_iv falls back to UP.
+    #[test]
+    fn test_surface_point_missing_token() {
+        let p = p_fair(S, K, 1e-4, 300.0);
+        let pt = SurfacePoint::compute(S, K, 300_000, 300.0, (p - 0.01, p + 0.01), (0.0, 1.0));
+        assert_eq!(pt.down, IvQuote::default());
+        assert_eq!(pt.mid_iv(), pt.up.mid());
+    }
+
+    /// Scenario: Three expiries inserted out of order, one re-upserted, then the earliest expires.
+    /// Expected: Points stay sorted and unique; prune removes the expired point.
+    #[test]
+    fn test_upsert_sorted_and_prune() {
+        let mut vs = VolSurface::new(60_000, 250);
+        for &exp in &[900_000, 300_000, 3_600_000, 300_000] {
+            let pt = SurfacePoint::compute(S, K, exp, (exp / 1000) as f64, (0.55, 0.57), (0.43, 0.45));
+            vs.upsert(pt, 1_000);
+        }
+        let exps: Vec<i64> = vs.points().iter().map(|p| p.expiry_ms).collect();
+        assert_eq!(exps, vec![300_000, 900_000, 3_600_000]);
+        vs.prune(300_000);
+        assert_eq!(vs.points().len(), 2);
+        assert!(vs.point(900_000, K).is_some());
+    }
+
+    /// Scenario: Five strikes at two expiries (one batch chunk plus a tail), repriced at σ = 1e-4.
+    /// Expected: Each point's fair/delta/gamma equals the scalar kernels at its (K, τ).
+    #[test]
+    fn test_reprice_matches_scalar() {
+        let mut vs = VolSurface::new(60_000, 250);
+        for &exp in &[300_000, 900_000] {
+            for i in 0..5 {
+                let k = K - 100.0 + 50.0 * i as f64;
+                vs.upsert(SurfacePoint::compute(S, k, exp, (exp / 1000) as f64, (0.55, 0.57), (0.43, 0.45)), 0);
+            }
+        }
+        let sigma = 1e-4;
+        vs.reprice(S, sigma);
+        assert_eq!(vs.points().len(), 10);
+        for p in vs.points() {
+            assert_eq!(p.fair_up, p_fair(S, p.strike, sigma, p.tau_s));
+            assert_eq!(p.delta, delta_bin(S, p.strike, sigma, p.tau_s));
+            assert_eq!(p.gamma, gamma_bin(S, p.strike, sigma, p.tau_s));
+        }
+    }
+
+    /// Scenario: Prices generated from σ(τ) = 1e-4 · (τ/300)^0.2 at 5m/15m/1h.
+    /// Expected: term_slope recovers b ≈ 0.2.
+    #[test]
+    fn test_term_slope_power_law() {
+        let mut vs = VolSurface::new(60_000, 250);
+        for &tau in &[300.0, 900.0, 3600.0] {
+            let sigma = 1e-4 * (tau / 300.0_f64).powf(0.2);
+            let p = p_fair(S, K, sigma, tau);
+            let pt = SurfacePoint::compute(S, K, (tau * 1000.0) as i64, tau, (p, p), (1.0 - p, 1.0 - p));
+            vs.upsert(pt, 0);
+        }
+        let b = vs.term_slope().expect("3 expiries");
+        assert!((b - 0.2).abs() < 0.01, "b = {}", b);
+    }
+
+    /// Scenario: Single expiry on the surface.
+    /// Expected: term_slope None (needs ≥ 2 expiries).
+    #[test]
+    fn test_term_slope_needs_two_points() {
+        let mut vs = VolSurface::new(60_000, 250);
+        vs.upsert(SurfacePoint::compute(S, K, 300_000, 300.0, (0.55, 0.57), (0.43, 0.45)), 0);
+        assert!(vs.term_slope().is_none());
+    }
+
+    /// Scenario: IV/RV samples recorded every 500ms for 5s, window 2s.
+    /// Expected: Sub-second samples dropped, old samples evicted, mean spread = IV − RV.
+    #[test]
+    fn test_iv_rv_history() {
+        let mut vs = VolSurface::new(2_000, 250);
+        for i in 0..10 {
+            vs.record(i * 500, 1.2e-4, 1.0e-4);
+        }
+        // Samples at 0,1000,...,4000; window keeps ts >= 2000 → 2000, 3000, 4000
+        assert_eq!(vs.history().len(), 3);
+        let spread = vs.mean_iv_rv_spread().unwrap();
+        assert!((spread - 0.2e-4).abs() < 1e-12);
+    }
+
+    /// Scenario: Refresh throttle at 250ms.
+    /// Expected: Due initially, not due 100ms after a refresh, due again at 250ms.
+    #[test]
+    fn test_refresh_throttle() {
+        let mut vs = VolSurface::new(60_000, 250);
+        assert!(vs.refresh_due(0));
+        vs.mark_refreshed(1_000);
+        assert!(!vs.refresh_due(1_100));
+        assert!(vs.refresh_due(1_250));
+    }
+}
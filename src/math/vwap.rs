@@ -60,6 +60,40 @@ impl VwapTracker {
     pub fn len(&self) -> usize {
         self.buffer.len()
     }
+
+    /// VWAP drift in price units per second.
+    ///
+    /// Splits the window at its time midpoint and divides the change in VWAP
+    /// between the two halves by the distance between their volume-weighted
+    /// mean timestamps. Exact for a linear price ramp. Returns 0.0 with less
+    /// than 1s of data or an empty half. O(n) in the window.
+    pub fn slope_per_s(&self) -> f64 {
+        let (first, last) = match (self.buffer.front(), self.buffer.back()) {
+            (Some(f), Some(l)) => (f.0, l.0),
+            _ => return 0.0,
+        };
+        if last - first < 1000 {
+            return 0.0;
+        }
+        let mid = first + (last - first) / 2;
+        // [sum_pq, sum_tq, sum_q] per half; times relative to `first` keep precision
+        let mut old = [0.0_f64; 3];
+        let mut new = [0.0_f64; 3];
+        for &(t, p, q) in &self.buffer {
+            let half = if t < mid { &mut old } else { &mut new };
+            half[0] += p * q;
+            half[1] += (t - first) as f64 * q;
+            half[2] += q;
+        }
+        if old[2] <= 0.0 || new[2] <= 0.0 {
+            return 0.0;
+        }
+        let dt_s = (new[1] / new[2] - old[1] / old[2]) / 1000.0;
+        if dt_s <= 0.0 {
+            return 0.0;
+        }
+        (new[0] / new[2] - old[0] / old[2]) / dt_s
+    }
 }
 
 #[cfg(test)]
@@ -147,6 +181,49 @@ mod tests {
         assert_eq!(v.len(), 3); // ts=2000, 3000, 7000
     }
 
+    // ── slope_per_s() tests ──
+
+    /// Scenario: One trade per second for 10s on a linear ramp of +$2/s, equal quantities.
+    /// Expected: slope_per_s() = 2.0 exactly (half-window VWAPs over their mean times).
+    #[test]
+    fn test_slope_linear_ramp() {
+        let mut v = VwapTracker::new(30_000);
+        for i in 0..10 {
+            v.update(1000 + i * 1000, 100.0 + 2.0 * i as f64, 1.0);
+        }
+        assert!((v.slope_per_s() - 2.0).abs() < 1e-9, "slope = {}", v.slope_per_s());
+    }
+
+    /// Scenario: Falling ramp with uneven quantities (-$0.5/s).
+    /// Expected: Negative slope of -0.5 -- volume weighting applies to both price and time.
+    #[test]
+    fn test_slope_falling_weighted() {
+        let mut v = VwapTracker::new(30_000);
+        for i in 0..20 {
+            let qty = if i % 3 == 0 { 5.0 } else { 1.0 };
+            v.update(i * 500, 200.0 - 0.25 * i as f64, qty);
+        }
+        assert!((v.slope_per_s() + 0.5).abs() < 1e-9, "slope = {}", v.slope_per_s());
+    }
+
+    /// Scenario: Flat prices, a single trade, and two trades 500ms apart.
+    /// Expected: 0.0 in every case (no drift, or not enough history to measure one).
+    #[test]
+    fn test_slope_zero_cases() {
+        let mut v = VwapTracker::new(30_000);
+        assert_eq!(v.slope_per_s(), 0.0);
+        v.update(1000, 100.0, 1.0);
+        assert_eq!(v.slope_per_s(), 0.0);
+        v.update(1500, 105.0, 1.0);
+        assert_eq!(v.slope_per_s(), 0.0);
+
+        let mut flat = VwapTracker::new(30_000);
+        for i in 0..10 {
+            flat.update(i * 1000, 100.0, 1.0 + i as f64);
+        }
+        assert!(flat.slope_per_s().abs() < 1e-12);
+    }
+
     // ── has_data after eviction ──
 
     /// Scenario: One trade added, then a second trade far in the future (t=100s) evicts the first.
@@ -12,7 +12,7 @@ use crate::strategies::convexity_fade::ConvexityFade;
 use crate::strategies::strike_misalign::StrikeMisalign;
 use crate::strategies::lp_extreme::LpExtreme;
 use crate::engine::risk::StrategyRiskManager;
-use crate::types::{Side, Signal};
+use crate::types::{Instrument, OrderAction, Side, Signal};
 
 const ITERATIONS: u32 = 1000;
 /// Maximum allowed time for 1000 evaluate() calls (10ms = 10μs per call).
@@ -244,6 +244,10 @@ fn test_latency_risk_check() {
         size_frac: 0.02,
         is_passive: false,
         use_bid: false,
+        action: OrderAction::Buy,
+        quote: false,
+        pair: false,
+        instrument: Instrument::Current,
     };
 
     // Warmup
@@ -1,7 +1,10 @@
+use crate::engine::sizing::Bet;
 use crate::engine::state::MarketState;
+use crate::math::fees::Liquidity;
 use crate::math::pricing::{p_fair, z_score};
-use crate::strategies::{kelly, Strategy};
-use crate::types::{EvalTrigger, Side, Signal};
+use crate::strategies::params::Param;
+use crate::strategies::Strategy;
+use crate::types::{EvalTrigger, Instrument, OrderAction, Side, Signal};
 
 /// Edge 2: Certainty Capture (Settlement Convergence)
 ///
@@ -11,7 +14,13 @@ use crate::types::{EvalTrigger, Side, Signal};
 pub struct CertaintyCapture;
 
 const Z_MIN: f64 = 1.5;     // ~$130 from strike at typical vol
-const MIN_EDGE: f64 = 0.02;
+const MIN_EDGE: f64 = 0.02; // net of taker fee
+
+/// Thresholds searched by the `optimize` binary (overridable via `StrategyParams`).
+pub const PARAMS: &[Param] = &[
+    Param { name: "z_min", default: Z_MIN, grid: &[1.25, 1.5, 1.75, 2.0] },
+    Param { name: "min_edge", default: MIN_EDGE, grid: &[0.01, 0.02, 0.03] },
+];
 
 impl Strategy for CertaintyCapture {
     fn name(&self) -> &'static str {
@@ -24,6 +33,9 @@ impl Strategy for CertaintyCapture {
 
     #[inline]
     fn evaluate(&self, state: &MarketState, now_ms: i64) -> Option<Signal> {
+        let z_min = state.params.get("certainty_capture", "z_min", Z_MIN);
+        let min_edge = state.params.get("certainty_capture", "min_edge", MIN_EDGE);
+
         let sigma = state.sigma_real();
         if sigma <= 0.0 {
             return None;
@@ -39,7 +51,7 @@ impl Strategy for CertaintyCapture {
         let z = z_score(s, k, sigma, tau);
         let z_abs = z.abs();
 
-        if z_abs < Z_MIN {
+        if z_abs < z_min {
             return None;
         }
 
@@ -59,12 +71,19 @@ impl Strategy for CertaintyCapture {
             return None;
         }
 
-        let edge = fair - market_ask;
-        if edge < MIN_EDGE {
+        // GTD at the ask crosses the spread: pay the taker fee
+        let all_in_price = state.info.fees.effective_price(market_ask, Liquidity::Taker);
+        let edge = fair - all_in_price;
+        if edge < min_edge {
             return None;
         }
 
         let confidence = (z_abs / 3.0).clamp(0.375, 0.99);
+        let size_frac = state.sizing.size(
+            state,
+            &Bet { strategy: "certainty_capture", side, fair, price: all_in_price },
+            now_ms,
+        );
 
         Some(Signal {
             strategy: "certainty_capture",
@@ -73,9 +92,13 @@ impl Strategy for CertaintyCapture {
             fair_value: fair,
             market_price: market_ask,
             confidence,
-            size_frac: kelly(edge, market_ask),
+            size_frac,
             is_passive: false,
             use_bid: false,
+            action: OrderAction::Buy,
+            quote: false,
+            pair: false,
+            instrument: Instrument::Current,
         })
     }
 }
@@ -119,6 +142,25 @@ mod tests {
         assert!(!sig.is_passive);
     }
 
+    /// Scenario: Same UP setup evaluated under a fee-free schedule and the default taker curve.
+    /// Expected: Edge shrinks by exactly the taker fee per share at the ask; sizing never grows.
+    #[test]
+    fn test_edge_net_of_taker_fee() {
+        use crate::math::fees::{FeeSchedule, Liquidity};
+        let (mut state, now) = make_state(95_000.0, 97_000.0, 0.001, 60.0, 0.90, 0.50);
+        state.info.fees = FeeSchedule::zero();
+        let gross = CertaintyCapture.evaluate(&state, now).unwrap();
+
+        state.info.fees = FeeSchedule::default();
+        let net = CertaintyCapture.evaluate(&state, now).unwrap();
+
+        let fee = FeeSchedule::default().fee_per_share(0.90, Liquidity::Taker);
+        assert!(fee > 0.0);
+        assert!((gross.edge - net.edge - fee).abs() < 1e-12, "gross={} net={}", gross.edge, net.edge);
+        assert!(net.size_frac <= gross.size_frac);
+        assert_eq!(net.market_price, 0.90, "Order price stays at the quoted ask");
+    }
+
     /// Scenario: BTC at $93k vs $95k with tau=60s giving z ~-2.73; down_ask stale at 0.90.
     /// Expected: DOWN signal -- near-certain DOWN outcome with residual mispricing.
     #[test]
@@ -190,11 +232,11 @@ mod tests {
     }
 
     /// Scenario: BTC at $99k vs $95k with tau=60s giving z ~5.2.
-    /// Expected: Size capped at 15% by kelly() clamp — risk manager clips further.
+    /// Expected: Size capped at 15% by sizer clamp (MAX_KELLY_FRAC) — risk manager clips further.
     #[test]
     fn test_sizing_high_z() {
         // BTC at 99000 vs 95000 with tau=60 → z ≈ 5.2
-        // kelly() clamps at 0.15; risk manager's max_per_trade_frac handles the rest
+        // KellySizer clamps at 0.15; risk manager's max_per_trade_frac handles the rest
         let (state, now) = make_state(95_000.0, 99_000.0, 0.001, 60.0, 0.90, 0.50);
         let sig = CertaintyCapture.evaluate(&state, now);
         if let Some(sig) = sig {
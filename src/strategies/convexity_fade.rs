@@ -1,15 +1,18 @@
+use crate::engine::sizing::Bet;
 use crate::engine::state::MarketState;
+use crate::math::fees::Liquidity;
 use crate::math::normal::phi;
 use crate::math::pricing::{d2, p_fair, z_score};
-use crate::math::regime::Regime;
-use crate::strategies::{kelly, Strategy};
-use crate::types::{EvalTrigger, Side, Signal};
+use crate::strategies::params::Param;
+use crate::strategies::Strategy;
+use crate::types::{EvalTrigger, Instrument, OrderAction, Side, Signal};
 
 /// Edge 3: Convexity Fading (Near-Strike Oscillation Trading)
 ///
 /// Near ATM and near expiry, binary delta amplifies small BTC oscillations
 /// into large probability swings. Fade these swings in range-bound conditions.
-/// Requires regime == Range. Disabled when Trend or τ_eff < 30s.
+/// Confidence and size scale down with trend probability; skipped outright when
+/// a trend is near-certain or τ_eff < 30s.
 pub struct ConvexityFade;
 
 const MAX_DIST_FRAC: f64 = 0.003; // within 0.3% of strike
@@ -20,6 +23,14 @@ const MAX_SPREAD: f64 = 0.08;          // skip if PM spread blown out (high unce
 const IMBALANCE_SKIP: f64 = 0.25;      // skip if bid/total depth < 25% (heavy sell pressure)
 const IMBALANCE_LEVELS: usize = 5;
 const MAX_Z_ABS: f64 = 0.40;           // skip if |z| > 0.40 (drifting from ATM → adverse selection)
+const MAX_TREND_PROB: f64 = 0.85;      // skip when trend is near-certain (fade thesis broken)
+
+/// Thresholds searched by the `optimize` binary (overridable via `StrategyParams`).
+pub const PARAMS: &[Param] = &[
+    Param { name: "max_z_abs", default: MAX_Z_ABS, grid: &[0.3, 0.4, 0.5] },
+    Param { name: "max_dist_frac", default: MAX_DIST_FRAC, grid: &[0.002, 0.003, 0.004] },
+    Param { name: "min_edge", default: MIN_EDGE, grid: &[0.01, 0.02, 0.03] },
+];
 
 impl Strategy for ConvexityFade {
     fn name(&self) -> &'static str {
@@ -32,11 +43,17 @@ impl Strategy for ConvexityFade {
 
     #[inline]
     fn evaluate(&self, state: &MarketState, now_ms: i64) -> Option<Signal> {
-        // Require non-trending regime (Range or Ambiguous ok, Trend blocked)
-        let regime = state.bn.regime.classify();
-        if regime == Regime::Trend {
+        let max_z_abs = state.params.get("convexity_fade", "max_z_abs", MAX_Z_ABS);
+        let max_dist_frac = state.params.get("convexity_fade", "max_dist_frac", MAX_DIST_FRAC);
+        let min_edge = state.params.get("convexity_fade", "min_edge", MIN_EDGE);
+
+        // Regime: fading works in range-bound markets. Scale conviction down
+        // continuously as trend probability rises; skip only when near-certain.
+        let trend_prob = state.bn.trend_prob();
+        if trend_prob > MAX_TREND_PROB {
             return None;
         }
+        let regime_scale = 1.0 - trend_prob;
 
         let sigma = state.sigma_real();
         if sigma <= 0.0 {
@@ -49,7 +66,7 @@ impl Strategy for ConvexityFade {
         }
 
         let dist_frac = state.distance_frac().abs();
-        if dist_frac > MAX_DIST_FRAC {
+        if dist_frac > max_dist_frac {
             return None;
         }
 
@@ -62,7 +79,7 @@ impl Strategy for ConvexityFade {
         // z-score gate: high |z| means BTC is drifting from strike.
         // Mean-reversion thesis fails when price is genuinely moving.
         let z = z_score(s, k, sigma, tau);
-        if z.abs() > MAX_Z_ABS {
+        if z.abs() > max_z_abs {
             return None;
         }
 
@@ -84,14 +101,14 @@ impl Strategy for ConvexityFade {
         let (side, fair, market_bid) = if state.up_ask > 0.0
             && state.up_ask < 1.0
             && edge_up > edge_down
-            && edge_up > MIN_EDGE
+            && edge_up > min_edge
         {
             // UP is mispriced — post at best bid
             if state.up_bid <= 0.0 || state.up_bid >= 1.0 { return None; }
             (Side::Up, fair_up, state.up_bid)
         } else if state.down_ask > 0.0
             && state.down_ask < 1.0
-            && edge_down > MIN_EDGE
+            && edge_down > min_edge
         {
             // DOWN is mispriced — post at best bid
             if state.down_bid <= 0.0 || state.down_bid >= 1.0 { return None; }
@@ -100,9 +117,11 @@ impl Strategy for ConvexityFade {
             return None;
         };
 
-        // Recompute edge against bid price (larger than ask-edge since bid < ask)
-        let edge = fair - market_bid;
-        if edge < MIN_EDGE {
+        // Recompute edge against bid price (larger than ask-edge since bid < ask).
+        // Post-only at the bid rests as maker and earns the rebate.
+        let all_in_price = state.info.fees.effective_price(market_bid, Liquidity::Maker);
+        let edge = fair - all_in_price;
+        if edge < min_edge {
             return None;
         }
 
@@ -133,7 +152,13 @@ impl Strategy for ConvexityFade {
             (edge / expected_swing).clamp(0.3, 0.65)
         } else {
             0.3
-        };
+        } * regime_scale;
+
+        let size_frac = state.sizing.size(
+            state,
+            &Bet { strategy: "convexity_fade", side, fair, price: all_in_price },
+            now_ms,
+        ) * regime_scale;
 
         Some(Signal {
             strategy: "convexity_fade",
@@ -142,9 +167,13 @@ impl Strategy for ConvexityFade {
             fair_value: fair,
             market_price: market_bid,
             confidence,
-            size_frac: kelly(edge, market_bid),
+            size_frac,
             is_passive: false,
             use_bid: true,
+            action: OrderAction::Buy,
+            quote: false,
+            pair: false,
+            instrument: Instrument::Current,
         })
     }
 }
@@ -299,4 +328,48 @@ mod tests {
             assert_eq!(sig.side, Side::Down);
         }
     }
+
+    // ── Continuous regime scaling ──
+
+    /// Scenario: Same ATM fade setup in Range (trend_prob 0) vs Ambiguous (~67% up ticks).
+    /// Expected: Both fire; Ambiguous has strictly lower confidence and size (scaled by 1 - trend_prob).
+    #[test]
+    fn test_confidence_scales_with_trend_prob() {
+        let setup = |state: &mut MarketState| {
+            inject_book(state, Side::Up,
+                vec![(0.41, 100.0)],
+                vec![(0.42, 100.0)],
+            );
+        };
+        let (mut range, now) = make_state(95_000.0, 95_000.0, 0.001, 120.0, 0.42, 0.50);
+        force_regime_range(&mut range, now);
+        setup(&mut range);
+        let (mut ambiguous, _) = make_state(95_000.0, 95_000.0, 0.001, 120.0, 0.42, 0.50);
+        for i in 0..30 {
+            ambiguous.bn.regime.update(now - 20_000 + i * 100, i % 3 != 0);
+        }
+        setup(&mut ambiguous);
+        assert!(ambiguous.bn.trend_prob() > 0.0 && ambiguous.bn.trend_prob() < 0.85);
+
+        let s_range = ConvexityFade.evaluate(&range, now).expect("range should fire");
+        let s_amb = ConvexityFade.evaluate(&ambiguous, now).expect("ambiguous should fire");
+        assert!(s_amb.confidence < s_range.confidence,
+            "ambiguous conf {} should be < range conf {}", s_amb.confidence, s_range.confidence);
+        assert!(s_amb.size_frac < s_range.size_frac);
+    }
+
+    /// Scenario: Tick classifier says Range, but 1s returns show steady +1bp/s drift.
+    /// Expected: None -- warmed-up return models override the tick label (trend near-certain).
+    #[test]
+    fn test_none_when_return_models_trend() {
+        let (mut state, now) = make_state(95_000.0, 95_000.0, 0.001, 120.0, 0.42, 0.50);
+        force_regime_range(&mut state, now);
+        feed_sample_returns(&mut state, 1e-4, 120);
+        inject_book(&mut state, Side::Up,
+            vec![(0.41, 100.0)],
+            vec![(0.42, 100.0)],
+        );
+        assert!(state.bn.trend_prob() > 0.85, "trend_prob = {}", state.bn.trend_prob());
+        assert!(ConvexityFade.evaluate(&state, now).is_none());
+    }
 }
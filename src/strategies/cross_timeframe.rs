@@ -1,7 +1,10 @@
+use crate::engine::sizing::Bet;
 use crate::engine::state::MarketState;
+use crate::math::fees::Liquidity;
 use crate::math::pricing::implied_vol;
-use crate::strategies::{kelly, Strategy};
-use crate::types::{EvalTrigger, Side, Signal};
+use crate::strategies::params::Param;
+use crate::strategies::Strategy;
+use crate::types::{EvalTrigger, Instrument, OrderAction, Side, Signal};
 
 /// Edge 4: Cross-Timeframe Relative Value
 ///
@@ -14,6 +17,12 @@ const MIN_VOL_DEVIATION: f64 = 0.05; // 5 vol points minimum outlier
 const MIN_EDGE: f64 = 0.01;
 const DEPTH_WEIGHT_LEVELS: usize = 3; // levels for depth confidence weighting in OLS
 
+/// Thresholds searched by the `optimize` binary (overridable via `StrategyParams`).
+pub const PARAMS: &[Param] = &[
+    Param { name: "min_vol_deviation", default: MIN_VOL_DEVIATION, grid: &[0.03, 0.05, 0.08] },
+    Param { name: "min_edge", default: MIN_EDGE, grid: &[0.005, 0.01, 0.02] },
+];
+
 impl Strategy for CrossTimeframe {
     fn name(&self) -> &'static str {
         "cross_timeframe"
@@ -25,6 +34,9 @@ impl Strategy for CrossTimeframe {
 
     #[inline]
     fn evaluate(&self, state: &MarketState, now_ms: i64) -> Option<Signal> {
+        let min_vol_deviation = state.params.get("cross_timeframe", "min_vol_deviation", MIN_VOL_DEVIATION);
+        let min_edge = state.params.get("cross_timeframe", "min_edge", MIN_EDGE);
+
         // Need at least 2 cross-market data points
         if state.cross_markets.len() < 1 {
             return None;
@@ -123,7 +135,7 @@ impl Strategy for CrossTimeframe {
         let fitted_iv = (ln_a + b * tau.ln()).exp();
         let deviation = our_iv - fitted_iv;
 
-        if deviation.abs() < MIN_VOL_DEVIATION {
+        if deviation.abs() < min_vol_deviation {
             return None;
         }
 
@@ -163,12 +175,19 @@ impl Strategy for CrossTimeframe {
             }
         };
 
-        let edge = fair - market_ask;
-        if edge < MIN_EDGE {
+        // GTD at the ask crosses the spread: pay the taker fee
+        let all_in_price = state.info.fees.effective_price(market_ask, Liquidity::Taker);
+        let edge = fair - all_in_price;
+        if edge < min_edge {
             return None;
         }
 
         let confidence = (deviation.abs() / 0.15).clamp(0.3, 0.7);
+        let size_frac = state.sizing.size(
+            state,
+            &Bet { strategy: "cross_timeframe", side, fair, price: all_in_price },
+            now_ms,
+        );
 
         Some(Signal {
             strategy: "cross_timeframe",
@@ -177,9 +196,13 @@ impl Strategy for CrossTimeframe {
             fair_value: fair,
             market_price: market_ask,
             confidence,
-            size_frac: kelly(edge, market_ask),
+            size_frac,
             is_passive: false,
             use_bid: false,
+            action: OrderAction::Buy,
+            quote: false,
+            pair: false,
+            instrument: Instrument::Current,
         })
     }
 }
@@ -215,7 +238,10 @@ mod tests {
             down_bid: 0.43,
             down_ask: 0.45,
             strike: 95_000.0,
+            start_ms: 0,
             end_ms: now + 900_000,
+            up_token_id: String::new(),
+            down_token_id: String::new(),
         });
         assert!(CrossTimeframe.evaluate(&state, now).is_none());
     }
@@ -237,7 +263,10 @@ mod tests {
             down_bid: 0.43,
             down_ask: 0.45,
             strike: 95_000.0,
+            start_ms: 0,
             end_ms: now + 5_000, // only 5s left → cm_tau < 10 → skipped
+            up_token_id: String::new(),
+            down_token_id: String::new(),
         });
         assert!(CrossTimeframe.evaluate(&state, now).is_none());
     }
@@ -259,7 +288,10 @@ mod tests {
             down_bid: 0.38,
             down_ask: 0.40,
             strike: 95_000.0,
+            start_ms: 0,
             end_ms: now + 900_000,
+            up_token_id: String::new(),
+            down_token_id: String::new(),
         });
         state.cross_markets.insert(Interval::H1, CrossMarketState {
             interval: Interval::H1,
@@ -268,7 +300,10 @@ mod tests {
             down_bid: 0.40,
             down_ask: 0.42,
             strike: 95_000.0,
+            start_ms: 0,
             end_ms: now + 3_600_000,
+            up_token_id: String::new(),
+            down_token_id: String::new(),
         });
         // May or may not produce signal — depends on IV deviation
         // This test verifies no panics in the OLS path with real data
@@ -294,7 +329,10 @@ mod tests {
             down_bid: 0.45,
             down_ask: 0.47,
             strike: 95_000.0,
+            start_ms: 0,
             end_ms: now + 300_000,
+            up_token_id: String::new(),
+            down_token_id: String::new(),
         });
         state.cross_markets.insert(Interval::M15, CrossMarketState {
             interval: Interval::M15,
@@ -303,7 +341,10 @@ mod tests {
             down_bid: 0.42,
             down_ask: 0.44,
             strike: 95_000.0,
+            start_ms: 0,
             end_ms: now + 900_000,
+            up_token_id: String::new(),
+            down_token_id: String::new(),
         });
         state.cross_markets.insert(Interval::H1, CrossMarketState {
             interval: Interval::H1,
@@ -312,7 +353,10 @@ mod tests {
             down_bid: 0.44,
             down_ask: 0.46,
             strike: 95_000.0,
+            start_ms: 0,
             end_ms: now + 3_600_000,
+            up_token_id: String::new(),
+            down_token_id: String::new(),
         });
         // Should not panic with 4 IV points in OLS fitting
         let _result = CrossTimeframe.evaluate(&state, now);
@@ -335,7 +379,10 @@ mod tests {
             down_bid: 0.01,
             down_ask: 0.02,
             strike: 95_000.0,
+            start_ms: 0,
             end_ms: now + 900_000,
+            up_token_id: String::new(),
+            down_token_id: String::new(),
         });
         // IV extraction may fail for extreme prices — but should not panic
         let _result = CrossTimeframe.evaluate(&state, now);
@@ -349,7 +396,8 @@ mod tests {
         state.cross_markets.insert(Interval::M15, CrossMarketState {
             interval: Interval::M15,
             up_bid: 0.55, up_ask: 0.57, down_bid: 0.43, down_ask: 0.45,
-            strike: 95_000.0, end_ms: now + 900_000,
+            strike: 95_000.0, start_ms: 0, end_ms: now + 900_000,
+            up_token_id: String::new(), down_token_id: String::new(),
         });
         assert!(CrossTimeframe.evaluate(&state, now).is_none());
     }
@@ -363,7 +411,8 @@ mod tests {
         state.cross_markets.insert(Interval::M15, CrossMarketState {
             interval: Interval::M15,
             up_bid: 0.55, up_ask: 0.57, down_bid: 0.43, down_ask: 0.45,
-            strike: 95_000.0, end_ms: now + 900_000,
+            strike: 95_000.0, start_ms: 0, end_ms: now + 900_000,
+            up_token_id: String::new(), down_token_id: String::new(),
         });
         assert!(CrossTimeframe.evaluate(&state, now).is_none());
     }
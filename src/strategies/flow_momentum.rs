@@ -0,0 +1,317 @@
+use crate::engine::sizing::Bet;
+use crate::engine::state::MarketState;
+use crate::math::fees::Liquidity;
+use crate::math::pricing::p_fair;
+use crate::strategies::params::Param;
+use crate::strategies::Strategy;
+use crate::types::{EvalTrigger, Instrument, OrderAction, Side, Signal};
+
+/// Edge 10: Order-Flow Momentum
+///
+/// The other directional edges price the binary driftless. Over a few seconds
+/// spot momentum persists: when aggressive Binance flow, the VWAP slope and the
+/// regime models all point the same way, shift S by the expected drift before
+/// pricing and buy the trend side if it is still cheap:
+///   mu    = vwap_slope / S                          (log drift per second)
+///   shift = trend_prob · |imbalance| · mu · min(τ, 30s), capped at ±0.5·σ√τ
+///   fair  = p_fair(S · e^shift, K, σ, τ)
+/// Evaluates on every BinanceTrade. Never trades against the trend.
+pub struct FlowMomentum;
+
+const FLOW_WINDOW_MS: i64 = 10_000;  // trade-sign imbalance lookback
+const MIN_FLOW_TRADES: usize = 20;    // fewer trades → imbalance is noise
+const MIN_IMBALANCE: f64 = 0.30;      // |buy − sell| / total volume
+const MIN_TREND_PROB: f64 = 0.50;
+const DRIFT_HORIZON_S: f64 = 30.0;    // momentum assumed to persist at most this long
+const MAX_DRIFT_SIGMAS: f64 = 0.5;    // drift shift capped at 0.5·σ√τ
+const MIN_TAU_S: f64 = 15.0;          // settlement noise dominates below this
+const MIN_EDGE: f64 = 0.03;           // net of taker fee, same bar as latency_arb
+const MIN_CONFIDENCE: f64 = 0.3;
+
+/// Thresholds searched by the `optimize` binary (overridable via `StrategyParams`).
+pub const PARAMS: &[Param] = &[
+    Param { name: "min_imbalance", default: MIN_IMBALANCE, grid: &[0.2, 0.3, 0.4] },
+    Param { name: "min_edge", default: MIN_EDGE, grid: &[0.02, 0.03, 0.04] },
+];
+
+/// Signed volume imbalance of aggressive trades in the last FLOW_WINDOW_MS of
+/// Binance time: (buy − sell) / (buy + sell) ∈ [−1, 1], and the trade count.
+#[inline]
+fn flow_imbalance(state: &MarketState) -> (f64, usize) {
+    let cutoff = state.bn.binance_ts - FLOW_WINDOW_MS;
+    let (mut buy, mut sell, mut n) = (0.0, 0.0, 0);
+    for t in state.bn.trade_buffer.iter().rev().take_while(|t| t.exchange_ts_ms >= cutoff) {
+        if t.is_buy {
+            buy += t.qty;
+        } else {
+            sell += t.qty;
+        }
+        n += 1;
+    }
+    let total = buy + sell;
+    if total <= 0.0 {
+        return (0.0, n);
+    }
+    ((buy - sell) / total, n)
+}
+
+impl Strategy for FlowMomentum {
+    fn name(&self) -> &'static str {
+        "flow_momentum"
+    }
+
+    fn trigger(&self) -> EvalTrigger {
+        EvalTrigger::BinanceTrade
+    }
+
+    #[inline]
+    fn evaluate(&self, state: &MarketState, now_ms: i64) -> Option<Signal> {
+        let min_imbalance = state.params.get("flow_momentum", "min_imbalance", MIN_IMBALANCE);
+        let min_edge = state.params.get("flow_momentum", "min_edge", MIN_EDGE);
+
+        let sigma = state.sigma_real();
+        if sigma <= 0.0 {
+            return None;
+        }
+
+        let s = state.s_est();
+        let k = state.info.strike;
+        let tau = state.tau_eff_s(now_ms);
+        if tau < MIN_TAU_S || s <= 0.0 || k <= 0.0 {
+            return None;
+        }
+
+        // ── Three momentum readings must agree ──
+        let (imbalance, n_trades) = flow_imbalance(state);
+        if n_trades < MIN_FLOW_TRADES || imbalance.abs() < min_imbalance {
+            return None;
+        }
+        let trend_prob = state.bn.trend_prob();
+        if trend_prob < MIN_TREND_PROB {
+            return None;
+        }
+        let slope = state.bn.vwap_tracker.slope_per_s();
+        let up = state.bn.regime.trend_direction_up();
+        if slope == 0.0 || (imbalance > 0.0) != up || (slope > 0.0) != up {
+            return None;
+        }
+
+        // ── Drift-adjusted fair value ──
+        let conviction = trend_prob * imbalance.abs();
+        let mu = slope / s;
+        let cap = MAX_DRIFT_SIGMAS * sigma * tau.sqrt();
+        let shift = (conviction * mu * tau.min(DRIFT_HORIZON_S)).clamp(-cap, cap);
+        let fair_up = p_fair(s * shift.exp(), k, sigma, tau);
+
+        let (side, fair, ask) = if up {
+            (Side::Up, fair_up, state.up_ask)
+        } else {
+            (Side::Down, 1.0 - fair_up, state.down_ask)
+        };
+        if ask <= 0.0 || ask >= 1.0 {
+            return None;
+        }
+
+        let all_in_price = state.info.fees.effective_price(ask, Liquidity::Taker);
+        let edge = fair - all_in_price;
+        if edge < min_edge {
+            return None;
+        }
+
+        let confidence = conviction.clamp(MIN_CONFIDENCE, 1.0);
+        let size_frac = state.sizing.size(
+            state,
+            &Bet { strategy: "flow_momentum", side, fair, price: all_in_price },
+            now_ms,
+        );
+
+        Some(Signal {
+            strategy: "flow_momentum",
+            side,
+            edge,
+            fair_value: fair,
+            market_price: ask,
+            confidence,
+            size_frac,
+            is_passive: false,
+            use_bid: false,
+            action: OrderAction::Buy,
+            quote: false,
+            pair: false,
+            instrument: Instrument::Current,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::latency_arb::LatencyArb;
+    use crate::strategies::test_helpers::*;
+
+    /// ATM at $96k, σ = 1bp/s, 120s left, both sides quoted at 0.50.
+    fn atm_state() -> (MarketState, i64) {
+        make_state(96_000.0, 96_000.0, 0.0001, 120.0, 0.50, 0.50)
+    }
+
+    // ── Gates ──
+
+    /// Scenario: Strong aligned up-flow, but realized vol is zero.
+    /// Expected: None -- fair value is undefined without sigma.
+    #[test]
+    fn test_none_when_sigma_zero() {
+        let (mut state, now) = atm_state();
+        state.bn.sigma_real_cached = 0.0;
+        inject_flow(&mut state, now, 40, 2.5, 9);
+        assert!(FlowMomentum.evaluate(&state, now).is_none());
+    }
+
+    /// Scenario: Strong aligned up-flow with 10s to expiry.
+    /// Expected: None -- below MIN_TAU_S the drift horizon is settlement noise.
+    #[test]
+    fn test_none_near_expiry() {
+        let (mut state, now) = make_state(96_000.0, 96_000.0, 0.0001, 10.0, 0.50, 0.50);
+        inject_flow(&mut state, now, 40, 2.5, 9);
+        assert!(FlowMomentum.evaluate(&state, now).is_none());
+    }
+
+    /// Scenario: Rising price with 90% buys, but only 15 trades in the window.
+    /// Expected: None -- fewer than MIN_FLOW_TRADES, imbalance is noise.
+    #[test]
+    fn test_none_with_thin_flow() {
+        let (mut state, now) = atm_state();
+        inject_flow(&mut state, now, 15, 2.5, 9);
+        assert!(FlowMomentum.evaluate(&state, now).is_none());
+    }
+
+    /// Scenario: Price ticks up steadily but 80% of the volume is aggressive selling.
+    /// Expected: None -- flow disagrees with the VWAP slope and tick trend.
+    #[test]
+    fn test_none_when_flow_disagrees_with_trend() {
+        let (mut state, now) = atm_state();
+        inject_flow(&mut state, now, 40, 2.5, 2);
+        assert!(FlowMomentum.evaluate(&state, now).is_none());
+    }
+
+    /// Scenario: Balanced flow (50% buys) on a rising tape.
+    /// Expected: None -- |imbalance| = 0 < MIN_IMBALANCE.
+    #[test]
+    fn test_none_when_flow_balanced() {
+        let (mut state, now) = atm_state();
+        inject_flow(&mut state, now, 40, 2.5, 5);
+        assert!(FlowMomentum.evaluate(&state, now).is_none());
+    }
+
+    /// Scenario: Aligned up-flow and up-ticks, but warmed-up 1s return models see
+    /// only alternating ±0.1bp noise.
+    /// Expected: None -- trend_prob below MIN_TREND_PROB overrides the tick label.
+    #[test]
+    fn test_none_when_return_models_see_no_trend() {
+        let (mut state, now) = atm_state();
+        inject_flow(&mut state, now, 40, 2.5, 9);
+        feed_sample_returns(&mut state, 0.0, 120);
+        assert!(state.bn.trend_prob() < MIN_TREND_PROB, "trend_prob = {}", state.bn.trend_prob());
+        assert!(FlowMomentum.evaluate(&state, now).is_none());
+    }
+
+    /// Scenario: Aligned up-flow, but the last trade is 20s before the Binance clock.
+    /// Expected: None -- every trade is outside FLOW_WINDOW_MS, no current flow.
+    #[test]
+    fn test_none_when_flow_is_stale() {
+        let (mut state, now) = atm_state();
+        inject_flow(&mut state, now - 20_000, 40, 2.5, 9);
+        assert!(FlowMomentum.evaluate(&state, now).is_none());
+    }
+
+    /// Scenario: Aligned up-flow, but UP is already offered at 0.80.
+    /// Expected: None -- the drift-shifted fair value is priced in.
+    #[test]
+    fn test_none_when_move_priced_in() {
+        let (mut state, now) = make_state(96_000.0, 96_000.0, 0.0001, 120.0, 0.80, 0.22);
+        inject_flow(&mut state, now, 40, 2.5, 9);
+        assert!(FlowMomentum.evaluate(&state, now).is_none());
+    }
+
+    // ── Signals ──
+
+    /// Scenario: ATM, +$12.5/s tape with 90% aggressive buys, UP still at 0.50.
+    /// Expected: Buy UP, FOK at the ask, fair above the driftless p_fair = 0.5.
+    #[test]
+    fn test_buys_up_on_aligned_up_flow() {
+        let (mut state, now) = atm_state();
+        inject_flow(&mut state, now, 40, 2.5, 9);
+        let sig = FlowMomentum.evaluate(&state, now).expect("aligned up-flow should fire");
+        assert_eq!(sig.side, Side::Up);
+        assert_eq!(sig.action, OrderAction::Buy);
+        assert!(!sig.is_passive);
+        assert_eq!(sig.market_price, 0.50);
+        assert!(sig.fair_value > 0.55, "fair = {}", sig.fair_value);
+        assert!(sig.edge >= MIN_EDGE);
+        assert!(sig.size_frac > 0.0);
+    }
+
+    /// Scenario: Mirror image -- falling tape with 90% aggressive sells.
+    /// Expected: Buy DOWN; never UP against the trend.
+    #[test]
+    fn test_buys_down_on_aligned_down_flow() {
+        let (mut state, now) = atm_state();
+        inject_flow(&mut state, now, 40, -2.5, 1);
+        let sig = FlowMomentum.evaluate(&state, now).expect("aligned down-flow should fire");
+        assert_eq!(sig.side, Side::Down);
+        assert!(sig.fair_value > 0.55, "fair = {}", sig.fair_value);
+    }
+
+    /// Scenario: Very steep tape (+$50/s) that would shift S by several σ√τ.
+    /// Expected: fair capped at p_fair(S·e^(0.5σ√τ)) -- the drift term cannot dominate.
+    #[test]
+    fn test_drift_shift_is_capped() {
+        let (mut state, now) = atm_state();
+        inject_flow(&mut state, now, 40, 10.0, 9);
+        let sig = FlowMomentum.evaluate(&state, now).expect("steep up-flow should fire");
+        let tau = state.tau_eff_s(now);
+        let cap = MAX_DRIFT_SIGMAS * 0.0001 * tau.sqrt();
+        let max_fair = p_fair(state.s_est() * cap.exp(), 96_000.0, 0.0001, tau);
+        assert!(sig.fair_value <= max_fair + 1e-12, "fair {} > cap {}", sig.fair_value, max_fair);
+        assert!((sig.fair_value - max_fair).abs() < 1e-9, "steep tape should hit the cap");
+    }
+
+    /// Scenario: Same tape at 90% and 70% buys.
+    /// Expected: Weaker imbalance → lower conviction → lower fair and confidence.
+    #[test]
+    fn test_conviction_scales_with_imbalance() {
+        let (mut strong, now) = atm_state();
+        inject_flow(&mut strong, now, 40, 0.5, 9);
+        let (mut weak, _) = atm_state();
+        inject_flow(&mut weak, now, 40, 0.5, 7);
+        let s_strong = FlowMomentum.evaluate(&strong, now).expect("strong should fire");
+        let s_weak = FlowMomentum.evaluate(&weak, now).expect("weak should fire");
+        assert!(s_weak.fair_value < s_strong.fair_value);
+        assert!(s_weak.confidence < s_strong.confidence);
+    }
+
+    // ── Versus latency_arb ──
+
+    /// Scenario: ATM with quotes at 0.50 that already match the driftless fair value,
+    /// on a strongly trending tape.
+    /// Expected: latency_arb sees no mispricing; flow_momentum trades the trend.
+    #[test]
+    fn test_fires_where_latency_arb_is_flat() {
+        let (mut state, now) = atm_state();
+        inject_book(&mut state, Side::Up, vec![(0.49, 500.0)], vec![(0.50, 500.0)]);
+        inject_book(&mut state, Side::Down, vec![(0.49, 500.0)], vec![(0.50, 500.0)]);
+        inject_flow(&mut state, now, 40, 2.5, 9);
+        assert!(LatencyArb.evaluate(&state, now).is_none());
+        assert!(FlowMomentum.evaluate(&state, now).is_some());
+    }
+
+    /// Scenario: BTC $300 above strike with UP lagging at 0.60, but no trend in the flow.
+    /// Expected: latency_arb hits the stale quote; flow_momentum stays out (not a momentum setup).
+    #[test]
+    fn test_silent_where_only_latency_arb_fires() {
+        let (mut state, now) = make_state(96_000.0, 96_300.0, 0.0001, 120.0, 0.60, 0.42);
+        inject_book(&mut state, Side::Up, vec![(0.59, 500.0)], vec![(0.60, 500.0)]);
+        inject_flow(&mut state, now, 40, 0.0, 5);
+        assert!(LatencyArb.evaluate(&state, now).is_some());
+        assert!(FlowMomentum.evaluate(&state, now).is_none());
+    }
+}
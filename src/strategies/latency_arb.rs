@@ -1,7 +1,10 @@
+use crate::engine::sizing::Bet;
 use crate::engine::state::MarketState;
+use crate::math::fees::Liquidity;
 use crate::math::pricing::{delta_bin, p_fair};
-use crate::strategies::{kelly, Strategy};
-use crate::types::{EvalTrigger, Side, Signal};
+use crate::strategies::params::Param;
+use crate::strategies::Strategy;
+use crate::types::{EvalTrigger, Instrument, OrderAction, Side, Signal};
 
 /// Edge 1: Microstructure Latency Arbitrage
 ///
@@ -10,11 +13,17 @@ use crate::types::{EvalTrigger, Side, Signal};
 /// Evaluates on every BinanceTrade — the signal IS the Binance move.
 pub struct LatencyArb;
 
-const MIN_EDGE: f64 = 0.03; // 3 cents minimum after fees
+const MIN_EDGE: f64 = 0.03; // 3 cents minimum net of taker fee
 const MIN_CONFIDENCE: f64 = 0.3;
 const MIN_ASK_DEPTH: f64 = 50.0;   // minimum $50 of ask-side liquidity across top levels
 const MAX_WALK_LEVELS: usize = 3;   // max ask levels to walk for VWAP fill estimate
 
+/// Thresholds searched by the `optimize` binary (overridable via `StrategyParams`).
+pub const PARAMS: &[Param] = &[
+    Param { name: "min_edge", default: MIN_EDGE, grid: &[0.02, 0.03, 0.04, 0.05] },
+    Param { name: "min_ask_depth", default: MIN_ASK_DEPTH, grid: &[25.0, 50.0, 100.0] },
+];
+
 impl Strategy for LatencyArb {
     fn name(&self) -> &'static str {
         "latency_arb"
@@ -26,6 +35,9 @@ impl Strategy for LatencyArb {
 
     #[inline]
     fn evaluate(&self, state: &MarketState, now_ms: i64) -> Option<Signal> {
+        let min_edge = state.params.get("latency_arb", "min_edge", MIN_EDGE);
+        let min_ask_depth = state.params.get("latency_arb", "min_ask_depth", MIN_ASK_DEPTH);
+
         let sigma = state.sigma_real();
         if sigma <= 0.0 {
             return None;
@@ -67,7 +79,7 @@ impl Strategy for LatencyArb {
             best_fair = 1.0 - fair;
         }
 
-        if best_edge < MIN_EDGE {
+        if best_edge < min_edge {
             return None;
         }
 
@@ -79,7 +91,7 @@ impl Strategy for LatencyArb {
 
         // Skip if ask-side liquidity is too thin to absorb a meaningful order
         let ask_liquidity = book.ask_depth(MAX_WALK_LEVELS);
-        if ask_liquidity < MIN_ASK_DEPTH {
+        if ask_liquidity < min_ask_depth {
             return None;
         }
 
@@ -88,9 +100,11 @@ impl Strategy for LatencyArb {
         // If edge still clears MIN_EDGE under this worst-case, the signal is robust.
         let (effective_price, _fillable) = book.vwap_fill_ask(ask_liquidity)?;
 
-        // Recompute edge against realistic fill price (not optimistic best ask)
-        let effective_edge = best_fair - effective_price;
-        if effective_edge < MIN_EDGE {
+        // Recompute edge against realistic fill price (not optimistic best ask),
+        // net of the taker fee — FOK always crosses.
+        let all_in_price = state.info.fees.effective_price(effective_price, Liquidity::Taker);
+        let effective_edge = best_fair - all_in_price;
+        if effective_edge < min_edge {
             return None;
         }
 
@@ -99,6 +113,12 @@ impl Strategy for LatencyArb {
         let _ = delta; // used for future delta-weighted sizing
         let confidence = (effective_edge / 0.10).clamp(MIN_CONFIDENCE, 1.0);
 
+        let size_frac = state.sizing.size(
+            state,
+            &Bet { strategy: "latency_arb", side: best_side, fair: best_fair, price: all_in_price },
+            now_ms,
+        );
+
         Some(Signal {
             strategy: "latency_arb",
             side: best_side,
@@ -106,9 +126,13 @@ impl Strategy for LatencyArb {
             fair_value: best_fair,
             market_price: effective_price,
             confidence,
-            size_frac: kelly(effective_edge, effective_price),
+            size_frac,
             is_passive: false,
             use_bid: false,
+            action: OrderAction::Buy,
+            quote: false,
+            pair: false,
+            instrument: Instrument::Current,
         })
     }
 }
@@ -182,6 +206,24 @@ mod tests {
         assert!(!sig.is_passive);
     }
 
+    /// Scenario: The mispriced setup above, with `latency_arb.min_edge` overridden
+    ///           just above and then just below the signal's edge.
+    /// Expected: The override gates the signal exactly like the constant does.
+    #[test]
+    fn test_min_edge_override() {
+        let (mut state, now) = make_state(95_000.0, 96_000.0, 0.001, 120.0, 0.55, 0.50);
+        inject_book(&mut state, Side::Up,
+            vec![(0.53, 100.0)],
+            vec![(0.55, 100.0), (0.56, 100.0), (0.57, 100.0)],
+        );
+        let edge = LatencyArb.evaluate(&state, now).expect("default fires").edge;
+
+        state.params.set("latency_arb", "min_edge", edge + 0.01);
+        assert!(LatencyArb.evaluate(&state, now).is_none());
+        state.params.set("latency_arb", "min_edge", edge - 0.01);
+        assert!(LatencyArb.evaluate(&state, now).is_some());
+    }
+
     // ── Parameterized success tests across price/vol/tau ──
 
     /// Scenario: BTC at 5 prices above $95k strike ($96k-$100k), deep book at 0.55.
@@ -1,14 +1,17 @@
+use crate::engine::sizing::Bet;
 use crate::engine::state::MarketState;
+use crate::math::fees::Liquidity;
 use crate::math::pricing::z_score;
-use crate::math::regime::Regime;
+use crate::strategies::params::Param;
 use crate::strategies::Strategy;
-use crate::types::{EvalTrigger, Side, Signal};
+use crate::types::{EvalTrigger, Instrument, OrderAction, Side, Signal};
 
 /// Edge 6: Extreme Probability Liquidity Provision
 ///
 /// At P near 0 or 1, market makers retreat. Provide liquidity
 /// on the losing side, earning wide spreads.
-/// Requires |z| > 1.5, regime != Trend, τ_eff > min_tau (interval-scaled).
+/// Requires |z| > 1.5, τ_eff > min_tau (interval-scaled). Confidence and size
+/// scale down with trend probability; skipped when a trend is near-certain.
 /// Places passive limit orders (is_passive = true).
 pub struct LpExtreme;
 
@@ -18,6 +21,13 @@ const MAX_SPREAD: f64 = 0.10;          // don't LP when spread > 10 cents
 const IMBALANCE_LEVELS: usize = 5;
 const IMBALANCE_THRESHOLD: f64 = 0.30; // adverse selection: ask-heavy depth
 const QUEUE_DEPTH_MAX: f64 = 500.0;    // scale down if bid queue already large
+const MAX_TREND_PROB: f64 = 0.85;      // skip when trend is near-certain (adverse selection)
+
+/// Thresholds searched by the `optimize` binary (overridable via `StrategyParams`).
+pub const PARAMS: &[Param] = &[
+    Param { name: "z_min", default: Z_MIN, grid: &[1.25, 1.5, 1.75] },
+    Param { name: "min_edge", default: MIN_EDGE, grid: &[0.01, 0.02, 0.03] },
+];
 
 impl Strategy for LpExtreme {
     fn name(&self) -> &'static str {
@@ -30,6 +40,9 @@ impl Strategy for LpExtreme {
 
     #[inline]
     fn evaluate(&self, state: &MarketState, now_ms: i64) -> Option<Signal> {
+        let z_min = state.params.get("lp_extreme", "z_min", Z_MIN);
+        let min_edge = state.params.get("lp_extreme", "min_edge", MIN_EDGE);
+
         let sigma = state.sigma_real();
         if sigma <= 0.0 {
             return None;
@@ -44,10 +57,13 @@ impl Strategy for LpExtreme {
             return None;
         }
 
-        // Don't provide liquidity during trends (adverse selection too high)
-        if state.bn.regime.classify() == Regime::Trend {
+        // Trends mean adverse selection for resting liquidity: scale conviction
+        // down continuously with trend probability, skip when near-certain.
+        let trend_prob = state.bn.trend_prob();
+        if trend_prob > MAX_TREND_PROB {
             return None;
         }
+        let regime_scale = 1.0 - trend_prob;
 
         let s = state.s_est();
         let k = state.info.strike;
@@ -58,7 +74,7 @@ impl Strategy for LpExtreme {
         let z = z_score(s, k, sigma, tau);
         let z_abs = z.abs();
 
-        if z_abs < Z_MIN {
+        if z_abs < z_min {
             return None;
         }
 
@@ -96,9 +112,9 @@ impl Strategy for LpExtreme {
 
         // When adverse selection detected, require double the minimum edge
         let effective_min_edge = if adverse_selection {
-            MIN_EDGE * 2.0
+            min_edge * 2.0
         } else {
-            MIN_EDGE
+            min_edge
         };
 
         // True probability of the losing side winning
@@ -110,8 +126,10 @@ impl Strategy for LpExtreme {
 
         // Only provide liquidity if we're getting positive EV
         // EV = true_prob * (1 - cost) - (1 - true_prob) * cost
-        // Simplifies to: true_prob - cost  for unit payoff
-        let edge = true_prob - market_ask;
+        // Simplifies to: true_prob - cost  for unit payoff.
+        // GTC post-only rests as maker, so cost is net of the rebate.
+        let all_in_price = state.info.fees.effective_price(market_ask, Liquidity::Maker);
+        let edge = true_prob - all_in_price;
 
         // For extreme LP, edge can be negative (the losing side SHOULD be cheap)
         // But we want to buy below fair — even if fair is very low
@@ -119,22 +137,19 @@ impl Strategy for LpExtreme {
             return None;
         }
 
-        // Kelly sizing for binary: f* = (1-p) - p*(1-a)/a
-        // where p = true probability of the winning side, a = our buy price
-        let p_winning = 1.0 - true_prob;
-        let a = market_ask;
-        let f_star = if a > 0.0 && a < 1.0 {
-            (true_prob - p_winning * (1.0 - a) / a).max(0.0)
-        } else {
-            0.0
-        };
+        // Kelly sizing against the market's holdings (fractional, uncertainty-shrunk)
+        let kelly_frac = state.sizing.size(
+            state,
+            &Bet { strategy: "lp_extreme", side, fair: true_prob, price: all_in_price },
+            now_ms,
+        );
 
         // Queue depth scaling: reduce size when large bid queue exists ahead of us.
         // Our passive order sits behind existing bids — thick queue = low fill probability.
         let bid_queue = book.bid_depth(3);
         let queue_scale = (1.0 - bid_queue / QUEUE_DEPTH_MAX).clamp(0.2, 1.0);
 
-        let size_frac = (f_star * 0.5 * queue_scale).max(0.0); // half-Kelly, scaled by queue depth
+        let size_frac = (kelly_frac * queue_scale * regime_scale).max(0.0); // scaled by queue depth + regime
         if size_frac < 0.001 {
             return None;
         }
@@ -144,7 +159,7 @@ impl Strategy for LpExtreme {
             (z_abs / 4.0).clamp(0.2, 0.6)
         } else {
             (z_abs / 4.0).clamp(0.3, 0.8)
-        };
+        } * regime_scale;
 
         Some(Signal {
             strategy: "lp_extreme",
@@ -156,6 +171,10 @@ impl Strategy for LpExtreme {
             size_frac,
             is_passive: true, // passive limit orders
             use_bid: false,
+            action: OrderAction::Buy,
+            quote: false,
+            pair: false,
+            instrument: Instrument::Current,
         })
     }
 }
@@ -0,0 +1,480 @@
+use crate::engine::queue_sim::PRICE_EPS;
+use crate::engine::state::MarketState;
+use crate::math::fees::Liquidity;
+use crate::math::pricing::{delta_bin, p_fair};
+use crate::strategies::params::Param;
+use crate::strategies::StatefulStrategy;
+use crate::types::{EvalTrigger, Instrument, Order, OrderAck, OrderAction, OrderStatus, Side, Signal};
+
+/// Edge 7: Two-Sided Market Making
+///
+/// Quotes bid and ask on both UP and DOWN tokens around p_fair, Avellaneda-Stoikov
+/// style in probability space:
+///   σ_p = Δ·S·σ                               (probability vol per √s)
+///   r   = p_fair − q·γ·σ_p²·T                 (inventory-skewed reservation price)
+///   δ   = ½·γ·σ_p²·T + (1/γ)·ln(1 + γ/κ)      (half spread)
+/// with q = net UP inventory (UP − DOWN shares held, $1 each at settlement) in
+/// quote lots and
+/// T = min(τ_eff, HORIZON_S). DOWN quotes mirror UP around 1 − r. Prices snap
+/// outward to the market's tick and stay post-only; asks only sell shares held,
+/// at most a lot's worth.
+///
+/// Quotes rest GTC post-only (`Signal::quote`). A resting quote is cancelled and
+/// replaced when its target moves by a tick (Binance moves shift p_fair), and all
+/// quotes are pulled near expiry or when feeds go stale.
+pub struct MarketMaker {
+    /// Resting quote per (side, action) slot, learned from `on_order_sent`.
+    slots: [Option<Resting>; 4],
+    cancels: Vec<u64>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Resting {
+    order_id: u64,
+    price: f64,
+    size: f64,
+}
+
+/// Desired quote for one slot.
+#[derive(Clone, Copy, Debug)]
+struct Target {
+    price: f64,
+    size: f64,
+}
+
+const GAMMA: f64 = 0.5;               // risk aversion (probability² per lot)
+const KAPPA: f64 = 100.0;             // fill-intensity decay with distance from fair
+const HORIZON_S: f64 = 30.0;          // inventory holding horizon for the variance term
+const MIN_TAU_S: f64 = 20.0;          // pull all quotes inside the last 20s
+const QUOTE_FRAC: f64 = 0.005;        // one quote lot = 0.5% of bankroll
+const MAX_INVENTORY_LOTS: f64 = 4.0;  // stop bidding a side once net inventory reaches this
+const MIN_ORDER_USD: f64 = 1.0;       // risk manager rejects anything smaller
+
+/// Thresholds searched by the `optimize` binary (overridable via `StrategyParams`).
+pub const PARAMS: &[Param] = &[
+    Param { name: "gamma", default: GAMMA, grid: &[0.25, 0.5, 1.0] },
+    Param { name: "kappa", default: KAPPA, grid: &[50.0, 100.0, 200.0] },
+];
+
+impl MarketMaker {
+    pub fn new() -> Self {
+        Self { slots: [None; 4], cancels: Vec::new() }
+    }
+
+    #[inline]
+    fn slot(side: Side, action: OrderAction) -> usize {
+        let base = match side {
+            Side::Up => 0,
+            Side::Down => 2,
+        };
+        match action {
+            OrderAction::Buy => base,
+            OrderAction::Sell => base + 1,
+        }
+    }
+
+    fn cancel_all(&mut self) {
+        for slot in self.slots.iter_mut() {
+            if let Some(r) = slot.take() {
+                self.cancels.push(r.order_id);
+            }
+        }
+    }
+
+    /// Keep, replace or pull one slot. Replacement cancels the old order and emits
+    /// a new quote; the slot is refilled when the pipeline reports the order sent.
+    #[allow(clippy::too_many_arguments)]
+    fn requote(
+        &mut self,
+        state: &MarketState,
+        side: Side,
+        action: OrderAction,
+        fair: f64,
+        target: Option<Target>,
+        bankroll: f64,
+        out: &mut Vec<Signal>,
+    ) {
+        let idx = Self::slot(side, action);
+        if let (Some(r), Some(t)) = (self.slots[idx], target) {
+            if (r.price - t.price).abs() < PRICE_EPS && r.size <= t.size + PRICE_EPS {
+                return;
+            }
+        }
+        if let Some(r) = self.slots[idx].take() {
+            self.cancels.push(r.order_id);
+        }
+        let Some(t) = target else { return };
+
+        let fee = state.info.fees.fee_per_share(t.price, Liquidity::Maker);
+        let edge = match action {
+            OrderAction::Buy => fair - (t.price + fee),
+            OrderAction::Sell => (t.price - fee) - fair,
+        };
+        out.push(Signal {
+            strategy: "market_maker",
+            side,
+            edge,
+            fair_value: fair,
+            market_price: t.price,
+            confidence: 0.5,
+            size_frac: t.size / bankroll,
+            is_passive: true,
+            use_bid: false,
+            action,
+            quote: true,
+            pair: false,
+            instrument: Instrument::Current,
+        });
+    }
+}
+
+impl Default for MarketMaker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// σ_p²·T: variance of p_fair over the inventory holding horizon.
+#[inline]
+fn holding_variance(s: f64, k: f64, sigma: f64, tau: f64) -> f64 {
+    let sigma_p = delta_bin(s, k, sigma, tau) * s * sigma;
+    sigma_p * sigma_p * tau.min(HORIZON_S)
+}
+
+/// Bid at or below `raw`, on the tick grid, strictly below the best ask.
+fn bid_price(raw: f64, best_ask: f64, tick: f64) -> Option<f64> {
+    let px = ((raw / tick + 1e-6).floor() * tick).min(best_ask - tick).min(1.0 - tick);
+    let px = (px / tick).round() * tick;
+    (px >= tick - PRICE_EPS).then_some(px)
+}
+
+/// Ask at or above `raw`, on the tick grid, strictly above the best bid.
+fn ask_price(raw: f64, best_bid: f64, tick: f64) -> Option<f64> {
+    let px = ((raw / tick - 1e-6).ceil() * tick).max(best_bid + tick).max(tick);
+    let px = (px / tick).round() * tick;
+    (px <= 1.0 - tick + PRICE_EPS).then_some(px)
+}
+
+impl StatefulStrategy for MarketMaker {
+    fn name(&self) -> &'static str {
+        "market_maker"
+    }
+
+    fn trigger(&self) -> EvalTrigger {
+        EvalTrigger::Both
+    }
+
+    fn on_market_start(&mut self, _state: &MarketState) {
+        self.slots = [None; 4];
+        self.cancels.clear();
+    }
+
+    fn evaluate(&mut self, state: &MarketState, now_ms: i64, out: &mut Vec<Signal>) {
+        let gamma = state.params.get("market_maker", "gamma", GAMMA);
+        let kappa = state.params.get("market_maker", "kappa", KAPPA);
+
+        let sigma = state.sigma_real();
+        let tau = state.tau_eff_s(now_ms);
+        let s = state.s_est();
+        let k = state.info.strike;
+        let bankroll = state.sizing.bankroll();
+        if sigma <= 0.0
+            || tau < MIN_TAU_S
+            || s <= 0.0
+            || k <= 0.0
+            || bankroll <= 0.0
+            || state.is_stale(now_ms)
+        {
+            self.cancel_all();
+            return;
+        }
+
+        let p = p_fair(s, k, sigma, tau);
+        let var = holding_variance(s, k, sigma, tau);
+
+        let lot = QUOTE_FRAC * bankroll;
+        let h = &state.position.holdings;
+        let q = (h.shares(Side::Up) - h.shares(Side::Down)) / lot;
+
+        let r = p - q * gamma * var;
+        let half_spread = 0.5 * gamma * var + (1.0 + gamma / kappa).ln() / gamma;
+
+        let tick = if state.info.tick_size > 0.0 { state.info.tick_size } else { 0.01 };
+
+        for side in [Side::Up, Side::Down] {
+            let (fair, reservation, best_bid, best_ask, held, can_bid) = match side {
+                Side::Up => (p, r, state.up_bid, state.up_ask, h.shares(Side::Up), q < MAX_INVENTORY_LOTS),
+                Side::Down => {
+                    (1.0 - p, 1.0 - r, state.down_bid, state.down_ask, h.shares(Side::Down), q > -MAX_INVENTORY_LOTS)
+                }
+            };
+            // No two-sided book on this token yet: nothing to anchor post-only prices to
+            let has_book = best_bid > 0.0 && best_ask > 0.0;
+
+            let bid = if has_book && can_bid {
+                bid_price(reservation - half_spread, best_ask, tick).map(|price| Target { price, size: lot })
+            } else {
+                None
+            };
+            // Order size is USDC at the limit price: sell the shares held, up to a lot
+            let ask = if has_book {
+                ask_price(reservation + half_spread, best_bid, tick)
+                    .map(|price| Target { price, size: held.min(lot / price) * price })
+                    .filter(|t| t.size >= MIN_ORDER_USD)
+            } else {
+                None
+            };
+
+            self.requote(state, side, OrderAction::Buy, fair, bid, bankroll, out);
+            self.requote(state, side, OrderAction::Sell, fair, ask, bankroll, out);
+        }
+    }
+
+    fn on_order_sent(&mut self, order: &Order, _state: &MarketState) {
+        self.slots[Self::slot(order.side, order.action)] =
+            Some(Resting { order_id: order.id, price: order.price, size: order.size });
+    }
+
+    fn drain_cancels(&mut self, out: &mut Vec<u64>) {
+        out.append(&mut self.cancels);
+    }
+
+    fn on_order_ack(&mut self, ack: &OrderAck, _state: &MarketState) {
+        let Some(slot) = self
+            .slots
+            .iter_mut()
+            .find(|s| s.is_some_and(|r| r.order_id == ack.order_id))
+        else {
+            return;
+        };
+        if ack.status.is_terminal() {
+            *slot = None;
+        } else if let (OrderStatus::PartialFill, Some(r), Some(filled)) = (&ack.status, slot.as_mut(), ack.filled_size) {
+            r.size = (r.size - filled).max(0.0);
+        }
+    }
+
+    fn on_market_end(&mut self, _outcome: Side, _state: &MarketState) {
+        self.slots = [None; 4];
+        self.cancels.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::sizing::KellySizer;
+    use crate::strategies::test_helpers::*;
+    use crate::types::OrderType;
+    use std::time::Instant;
+
+    /// ATM market with 120s left, $1000 bankroll and a 0.48/0.52 book on both tokens.
+    fn setup() -> (MarketState, i64) {
+        let (mut state, now) = make_state(95_000.0, 95_000.0, 0.0005, 120.0, 0.52, 0.52);
+        state.sizing = KellySizer::from_config(&make_config());
+        inject_book(&mut state, Side::Up, vec![(0.48, 100.0)], vec![(0.52, 100.0)]);
+        inject_book(&mut state, Side::Down, vec![(0.48, 100.0)], vec![(0.52, 100.0)]);
+        (state, now)
+    }
+
+    /// Pretend the pipeline dispatched every signal, assigning IDs from `next_id`.
+    fn send_all(mm: &mut MarketMaker, state: &MarketState, sigs: &[Signal], next_id: &mut u64) {
+        for sig in sigs {
+            let order = Order {
+                id: *next_id,
+                side: sig.side,
+                price: sig.market_price,
+                size: sig.size_frac * state.sizing.bankroll(),
+                strategy: sig.strategy,
+                signal_edge: sig.edge,
+                is_passive: true,
+                created_at: Instant::now(),
+                order_type: OrderType::GTC,
+                post_only: true,
+                expiration_ms: None,
+                token_id: String::new(),
+                action: sig.action,
+                instrument: sig.instrument,
+            };
+            mm.on_order_sent(&order, state);
+            *next_id += 1;
+        }
+    }
+
+    fn find(sigs: &[Signal], side: Side, action: OrderAction) -> Option<&Signal> {
+        sigs.iter().find(|s| s.side == side && s.action == action)
+    }
+
+    // ── Quoting ──
+
+    /// Scenario: Flat inventory, ATM market, two-sided books on both tokens.
+    /// Expected: Bids on UP and DOWN only (nothing held to sell), near-symmetric around
+    ///           0.50, on the 1c tick, strictly inside the post-only bounds.
+    #[test]
+    fn test_flat_inventory_quotes_both_bids() {
+        let (state, now) = setup();
+        let mut mm = MarketMaker::new();
+        let mut out = Vec::new();
+        mm.evaluate(&state, now, &mut out);
+
+        assert_eq!(out.len(), 2, "Only bids without inventory");
+        let up = find(&out, Side::Up, OrderAction::Buy).expect("UP bid");
+        let down = find(&out, Side::Down, OrderAction::Buy).expect("DOWN bid");
+        assert!((up.market_price - down.market_price).abs() <= 0.01 + 1e-9, "ATM quotes are near-symmetric");
+        assert!(up.market_price + down.market_price < 1.0, "Both bids filling never locks in a loss");
+        assert!(up.market_price < 0.50 && up.market_price < state.up_ask);
+        let ticks = up.market_price / 0.01;
+        assert!((ticks - ticks.round()).abs() < 1e-9, "On the tick grid: {}", up.market_price);
+        assert!(out.iter().all(|s| s.quote && s.is_passive && s.edge > 0.0));
+        assert!((up.size_frac - QUOTE_FRAC).abs() < 1e-12);
+    }
+
+    /// Scenario: Long 3 lots of UP (15 shares at 0.50); compare quotes with the flat book.
+    /// Expected: Reservation price skews down — UP bid no higher, DOWN bid no lower —
+    ///           and an UP ask appears to work the inventory off.
+    #[test]
+    fn test_inventory_skews_quotes() {
+        let (state, now) = setup();
+        let mut flat = Vec::new();
+        MarketMaker::new().evaluate(&state, now, &mut flat);
+
+        let (mut long, _) = setup();
+        long.position.record_fill(Side::Up, 0.50, 7.5);
+        let mut skewed = Vec::new();
+        MarketMaker::new().evaluate(&long, now, &mut skewed);
+
+        let up_flat = find(&flat, Side::Up, OrderAction::Buy).unwrap().market_price;
+        let up_long = find(&skewed, Side::Up, OrderAction::Buy).unwrap().market_price;
+        let dn_flat = find(&flat, Side::Down, OrderAction::Buy).unwrap().market_price;
+        let dn_long = find(&skewed, Side::Down, OrderAction::Buy).unwrap().market_price;
+        assert!(up_long < up_flat, "UP bid backs off when long UP: {} vs {}", up_long, up_flat);
+        assert!(dn_long > dn_flat, "DOWN bid leans in when long UP: {} vs {}", dn_long, dn_flat);
+
+        let ask = find(&skewed, Side::Up, OrderAction::Sell).expect("UP ask from inventory");
+        assert!(ask.market_price > long.up_bid, "Ask stays post-only");
+        assert!((ask.size_frac * 1000.0 - 5.0).abs() < 1e-9, "One lot offered");
+        assert!(find(&skewed, Side::Down, OrderAction::Sell).is_none(), "No DOWN held, no DOWN ask");
+    }
+
+    /// Scenario: Long 4 lots of UP (20 shares, the inventory cap).
+    /// Expected: No further UP bid; DOWN bid still quoted.
+    #[test]
+    fn test_inventory_cap_stops_bidding() {
+        let (mut state, now) = setup();
+        state.position.record_fill(Side::Up, 0.50, 10.0);
+        let mut out = Vec::new();
+        MarketMaker::new().evaluate(&state, now, &mut out);
+        assert!(find(&out, Side::Up, OrderAction::Buy).is_none());
+        assert!(find(&out, Side::Down, OrderAction::Buy).is_some());
+    }
+
+    /// Scenario: $3 of UP bought at 0.60 (5 shares, under a lot), ask quoted lower.
+    /// Expected: The ask sells exactly the 5 shares held: size = 5 × ask price,
+    ///           not the $3 spent, which would sell more shares than are held.
+    #[test]
+    fn test_ask_sized_from_shares_held() {
+        let (mut state, now) = setup();
+        state.position.record_fill(Side::Up, 0.60, 3.0);
+        let mut out = Vec::new();
+        MarketMaker::new().evaluate(&state, now, &mut out);
+
+        let ask = find(&out, Side::Up, OrderAction::Sell).expect("UP ask from inventory");
+        assert!(ask.market_price < 0.60, "Ask below entry: {}", ask.market_price);
+        let size = ask.size_frac * 1000.0;
+        assert!((size / ask.market_price - 5.0).abs() < 1e-9, "Sells 5 shares: size {}", size);
+    }
+
+    /// Scenario: Holding-horizon variance $300 off the strike at two vols and two expiries.
+    /// Expected: Grows with realised vol and as expiry approaches — both widen the spread.
+    #[test]
+    fn test_variance_grows_with_vol_and_expiry() {
+        let (k, s) = (95_000.0, 95_300.0);
+        assert!(holding_variance(s, k, 0.0010, 120.0) > holding_variance(s, k, 0.0005, 120.0));
+        assert!(holding_variance(s, k, 0.0005, 60.0) > holding_variance(s, k, 0.0005, 120.0));
+    }
+
+    // ── Cancel / replace ──
+
+    /// Scenario: Quote, dispatch, re-evaluate unchanged, then Binance jumps $150.
+    /// Expected: Unchanged state keeps resting quotes (no churn); the move cancels
+    ///           both stale bids and emits replacements.
+    #[test]
+    fn test_requote_on_binance_move() {
+        let (mut state, now) = setup();
+        let mut mm = MarketMaker::new();
+        let mut out = Vec::new();
+        let mut next_id = 1;
+        mm.evaluate(&state, now, &mut out);
+        send_all(&mut mm, &state, &out, &mut next_id);
+
+        out.clear();
+        mm.evaluate(&state, now, &mut out);
+        let mut cancels = Vec::new();
+        mm.drain_cancels(&mut cancels);
+        assert!(out.is_empty() && cancels.is_empty(), "No churn without a move");
+
+        state.bn.binance_price = 95_150.0;
+        mm.evaluate(&state, now, &mut out);
+        mm.drain_cancels(&mut cancels);
+        cancels.sort_unstable();
+        assert_eq!(cancels, vec![1, 2]);
+        assert_eq!(out.len(), 2);
+        let up = find(&out, Side::Up, OrderAction::Buy).unwrap();
+        assert!(up.market_price > 0.47, "UP bid follows p_fair up: {}", up.market_price);
+    }
+
+    /// Scenario: Resting quotes, then the market enters its last 20s.
+    /// Expected: Everything is cancelled and nothing new is quoted.
+    #[test]
+    fn test_pulls_quotes_near_expiry() {
+        let (mut state, now) = setup();
+        let mut mm = MarketMaker::new();
+        let mut out = Vec::new();
+        let mut next_id = 10;
+        mm.evaluate(&state, now, &mut out);
+        send_all(&mut mm, &state, &out, &mut next_id);
+
+        // Fresh feeds, 10s left
+        let late = state.info.end_ms - 10_000;
+        state.bn.binance_ts = late;
+        state.pm_last_ts = late;
+        out.clear();
+        mm.evaluate(&state, late, &mut out);
+        let mut cancels = Vec::new();
+        mm.drain_cancels(&mut cancels);
+        assert!(out.is_empty());
+        assert_eq!(cancels.len(), 2);
+    }
+
+    /// Scenario: A resting bid is acknowledged as filled.
+    /// Expected: Slot is freed — no cancel is sent for it on the next requote.
+    #[test]
+    fn test_terminal_ack_frees_slot() {
+        let (mut state, now) = setup();
+        let mut mm = MarketMaker::new();
+        let mut out = Vec::new();
+        let mut next_id = 1;
+        mm.evaluate(&state, now, &mut out);
+        send_all(&mut mm, &state, &out, &mut next_id);
+        let up_id = 1 + out.iter().position(|s| s.side == Side::Up).unwrap() as u64;
+
+        let ack = OrderAck {
+            order_id: up_id,
+            status: OrderStatus::Filled,
+            filled_price: Some(0.47),
+            filled_size: Some(5.0),
+            latency_ms: 0.0,
+            clob_order_id: None,
+            raw_response: None,
+        };
+        mm.on_order_ack(&ack, &state);
+
+        state.bn.binance_price = 95_150.0;
+        out.clear();
+        mm.evaluate(&state, now, &mut out);
+        let mut cancels = Vec::new();
+        mm.drain_cancels(&mut cancels);
+        assert!(!cancels.contains(&up_id), "Filled order must not be cancelled");
+        assert_eq!(cancels.len(), 1);
+    }
+}
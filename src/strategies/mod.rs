@@ -2,8 +2,15 @@ pub mod latency_arb;
 pub mod certainty_capture;
 pub mod convexity_fade;
 pub mod cross_timeframe;
+pub mod flow_momentum;
 pub mod strike_misalign;
 pub mod lp_extreme;
+pub mod market_maker;
+pub mod nested_arb;
+pub mod parity_arb;
+pub mod pin_risk;
+pub mod params;
+pub mod registry;
 
 #[cfg(test)]
 pub(crate) mod test_helpers;
@@ -11,7 +18,7 @@ pub(crate) mod test_helpers;
 mod bench_latency;
 
 use crate::engine::state::MarketState;
-use crate::types::{EvalTrigger, Signal};
+use crate::types::{EvalTrigger, Fill, Order, OrderAck, Side, Signal};
 
 /// Strategy trait: stateless pure function of market state.
 /// Same code runs in live engine and backtester.
@@ -23,6 +30,52 @@ pub trait Strategy: Send + Sync {
     fn evaluate(&self, state: &MarketState, now_ms: i64) -> Option<Signal>;
 }
 
+/// Stateful strategy: owns per-market memory and receives lifecycle callbacks.
+///
+/// Every `Strategy` is also a `StatefulStrategy` with no-op hooks (blanket impl),
+/// so engines hold a single trait-object type. Implement this directly when a
+/// strategy needs to remember its own signals, resting orders or fills.
+///
+/// Driven by `engine::pipeline::StrategyHost` in the same order in live, backtest
+/// and replay: `on_market_start` → (`evaluate` → `on_order_sent`* | `on_order_ack` → `on_fill`)*
+/// → `on_market_end`. Orders, acks and fills are routed only to the strategy that
+/// originated the order.
+pub trait StatefulStrategy: Send {
+    fn name(&self) -> &'static str;
+    fn trigger(&self) -> EvalTrigger {
+        EvalTrigger::PolymarketQuote
+    }
+    /// Once per market, before the first evaluation. Reset per-market memory here.
+    fn on_market_start(&mut self, _state: &MarketState) {}
+    /// Push this evaluation's signals onto `out` (quoting strategies may push several).
+    fn evaluate(&mut self, state: &MarketState, now_ms: i64, out: &mut Vec<Signal>);
+    /// Every order the pipeline dispatched from this strategy's signals, with its engine ID.
+    fn on_order_sent(&mut self, _order: &Order, _state: &MarketState) {}
+    /// Resting orders this strategy wants cancelled, drained by the engine after evaluation.
+    fn drain_cancels(&mut self, _out: &mut Vec<u64>) {}
+    /// Every acknowledgement for one of this strategy's orders (Live, Filled, Rejected, ...).
+    fn on_order_ack(&mut self, _ack: &OrderAck, _state: &MarketState) {}
+    /// Every fill of one of this strategy's orders, after the ack that carried it.
+    fn on_fill(&mut self, _fill: &Fill, _state: &MarketState) {}
+    /// Once per market at settlement, with the resolved outcome.
+    fn on_market_end(&mut self, _outcome: Side, _state: &MarketState) {}
+}
+
+impl<T: Strategy> StatefulStrategy for T {
+    fn name(&self) -> &'static str {
+        Strategy::name(self)
+    }
+    fn trigger(&self) -> EvalTrigger {
+        Strategy::trigger(self)
+    }
+    #[inline]
+    fn evaluate(&mut self, state: &MarketState, now_ms: i64, out: &mut Vec<Signal>) {
+        if let Some(sig) = Strategy::evaluate(self, state, now_ms) {
+            out.push(sig);
+        }
+    }
+}
+
 /// Evaluate a filtered subset of strategies, filling pre-allocated buffer.
 #[inline]
 pub fn evaluate_filtered(
@@ -39,14 +92,6 @@ pub fn evaluate_filtered(
     }
 }
 
-/// Half-Kelly position sizing.
-pub fn kelly(edge: f64, price: f64) -> f64 {
-    if price >= 1.0 || edge <= 0.0 {
-        return 0.0;
-    }
-    ((edge / (1.0 - price)) * 0.5).clamp(0.0, 0.15)
-}
-
 /// Time left as fraction of total window (1.0 at start, 0.0 at end).
 pub fn time_left_fraction(state: &MarketState, now_ms: i64) -> f64 {
     let total = (state.info.end_ms - state.info.start_ms).max(1) as f64;
@@ -57,72 +102,7 @@ pub fn time_left_fraction(state: &MarketState, now_ms: i64) -> f64 {
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    /// Scenario: Positive edge (5%) at 50-cent price.
-    /// Expected: Half-Kelly produces a positive position size.
-    #[test]
-    fn test_kelly_positive_edge() {
-        let f = kelly(0.05, 0.50);
-        assert!(f > 0.0, "Positive edge should produce positive sizing: {}", f);
-    }
-
-    /// Scenario: Zero edge at 50-cent price.
-    /// Expected: Kelly returns 0 -- no position without edge.
-    #[test]
-    fn test_kelly_zero_edge() {
-        assert_eq!(kelly(0.0, 0.50), 0.0);
-    }
-
-    /// Scenario: Price equals 1.0 (certain outcome, no payout upside).
-    /// Expected: Kelly returns 0 -- denominator is zero, no sizing.
-    #[test]
-    fn test_kelly_price_at_one() {
-        assert_eq!(kelly(0.05, 1.0), 0.0);
-    }
-
-    // ── Kelly edge cases ──
-
-    /// Scenario: Negative edge (-5%) at 50-cent price.
-    /// Expected: Kelly returns 0 -- never size into a negative-EV trade.
-    #[test]
-    fn test_kelly_negative_edge() {
-        assert_eq!(kelly(-0.05, 0.50), 0.0);
-    }
-
-    /// Scenario: Price above 1.0 (impossible for valid binary, but edge case).
-    /// Expected: Kelly returns 0 -- invalid price triggers early return.
-    #[test]
-    fn test_kelly_price_above_one() {
-        assert_eq!(kelly(0.10, 1.5), 0.0);
-    }
-
-    /// Scenario: Huge edge (90%) at near-zero price (1 cent) producing raw Kelly ~0.45.
-    /// Expected: Result is clamped to the 15% max position size cap.
-    #[test]
-    fn test_kelly_clamp_at_015() {
-        // edge=0.90, price=0.01 → raw = (0.90 / 0.99) * 0.5 ≈ 0.4545 → clamped to 0.15
-        let f = kelly(0.90, 0.01);
-        assert!((f - 0.15).abs() < 1e-10, "Should clamp to 0.15: {}", f);
-    }
-
-    /// Scenario: 10% edge at 40-cent price, within normal sizing range.
-    /// Expected: Result matches exact half-Kelly formula (0.10/0.60)*0.5 = 0.0833.
-    #[test]
-    fn test_kelly_exact_formula() {
-        // edge=0.10, price=0.40 → kelly = (0.10 / 0.60) * 0.5 = 0.0833
-        let f = kelly(0.10, 0.40);
-        let expected = (0.10 / 0.60) * 0.5;
-        assert!((f - expected).abs() < 1e-10, "kelly = {}, expected = {}", f, expected);
-    }
-
-    /// Scenario: 5% edge at price 0.99, denominator near zero producing raw Kelly ~2.5.
-    /// Expected: Result is clamped to the 15% max position size cap.
-    #[test]
-    fn test_kelly_price_near_one() {
-        // price=0.99 → denominator = 0.01 → kelly = (0.05 / 0.01) * 0.5 = 2.5 → clamped 0.15
-        let f = kelly(0.05, 0.99);
-        assert!((f - 0.15).abs() < 1e-10, "Should clamp: {}", f);
-    }
+    use crate::types::{Instrument, OrderAction};
 
     // ── time_left_fraction tests ──
 
@@ -202,6 +182,10 @@ mod tests {
             size_frac: 0.01,
             is_passive: false,
             use_bid: false,
+            action: OrderAction::Buy,
+            quote: false,
+            pair: false,
+            instrument: Instrument::Current,
         }];
         evaluate_filtered(&strategies, &state, now, &mut buf);
         assert!(buf.is_empty(), "Buffer should be cleared even with no strategies");
@@ -0,0 +1,404 @@
+use crate::engine::state::{CrossMarketState, MarketState};
+use crate::math::fees::Liquidity;
+use crate::math::pricing::{p_fair, p_joint_up};
+use crate::strategies::params::Param;
+use crate::strategies::StatefulStrategy;
+use crate::types::{EvalTrigger, Instrument, Order, OrderAck, OrderAction, Side, Signal};
+
+/// Edge 9: Nested-Window Arbitrage
+///
+/// A cross market whose window ends no later than the current one (the three 5m
+/// markets inside a 15m, or the 15m around the last 5m) settles on the same
+/// price path. Both strikes are already fixed by the elapsed path, so under the
+/// shared diffusion (S_est, σ_real) the two outcomes are jointly normal with
+/// ρ = √(τ_first / τ_last):
+///   p1 = P(first-to-expire UP), p2 = P(other UP), p12 = P(both UP)
+///
+/// One UP on one market plus one DOWN on the other pays ≥ $1 in every outcome
+/// but one:
+///   UP first + DOWN other   pays $0 only if (first DOWN, other UP): p2 − p12
+///   DOWN first + UP other   pays $0 only if (first UP, other DOWN): p1 − p12
+/// Co-terminal windows with nested strikes make that outcome impossible (the
+/// strict bound: UP on the lower strike + DOWN on the higher is worth ≥ $1); late
+/// in an inner window the elapsed path makes it nearly so.
+///
+/// Buy the package when its all-in cost is below $1 by MIN_EDGE and its break
+/// probability is at most MAX_BREAK_PROB. Legs go out as a `Signal::pair` routed
+/// to their own markets; the cross leg settles against its market's strike.
+pub struct NestedArb {
+    /// Package legs dispatched and not yet terminally acked.
+    in_flight: Vec<u64>,
+    /// Cross-market expiry and the four asks when the last package went out.
+    last_quote: Option<(i64, [f64; 4])>,
+}
+
+/// One priced package: UP on `up_on`, DOWN on `down_on`.
+#[derive(Clone, Copy, Debug)]
+struct Package {
+    up_on: Instrument,
+    down_on: Instrument,
+    up_ask: f64,
+    down_ask: f64,
+    /// Marginal fair value of each leg under the shared diffusion.
+    up_fair: f64,
+    down_fair: f64,
+    /// P(package pays $0).
+    break_prob: f64,
+    /// Expected payoff minus all-in cost, per package.
+    edge: f64,
+}
+
+const MIN_EDGE: f64 = 0.01;          // all-in cost at least 1¢ under $1
+const MAX_BREAK_PROB: f64 = 0.005;   // worst state nearly impossible given the path
+const LEG_FRAC: f64 = 0.02;          // per-leg notional; no cross-market depth to walk
+const MIN_TIME_LEFT_S: f64 = 5.0;    // CLOB stops matching around expiry
+
+/// Thresholds searched by the `optimize` binary (overridable via `StrategyParams`).
+pub const PARAMS: &[Param] = &[
+    Param { name: "min_edge", default: MIN_EDGE, grid: &[0.005, 0.01, 0.02] },
+    Param { name: "max_break_prob", default: MAX_BREAK_PROB, grid: &[0.002, 0.005, 0.01] },
+];
+
+impl NestedArb {
+    pub fn new() -> Self {
+        Self { in_flight: Vec::new(), last_quote: None }
+    }
+}
+
+impl Default for NestedArb {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[inline]
+fn tradable(ask: f64) -> bool {
+    ask > 0.0 && ask < 1.0
+}
+
+/// Best package between the current market and one nested cross market, if any
+/// clears both gates. `None` unless the cross market is tradable and settles
+/// within the current window.
+fn price_package(
+    state: &MarketState,
+    cross: Instrument,
+    cm: &CrossMarketState,
+    now_ms: i64,
+) -> Option<Package> {
+    if cm.strike <= 0.0
+        || now_ms < cm.start_ms
+        || cm.end_ms > state.info.end_ms
+        || cm.up_token_id.is_empty()
+        || cm.down_token_id.is_empty()
+    {
+        return None;
+    }
+    let cm_left_s = (cm.end_ms - now_ms) as f64 / 1000.0;
+    if cm_left_s < MIN_TIME_LEFT_S {
+        return None;
+    }
+    let (s, sigma) = (state.s_est(), state.sigma_real());
+    let (k1, tau1) = (cm.strike, state.oracle.tau_eff(cm_left_s));
+    let (k2, tau2) = (state.info.strike, state.tau_eff_s(now_ms));
+
+    let p1 = p_fair(s, k1, sigma, tau1);
+    let p2 = p_fair(s, k2, sigma, tau2);
+    let p12 = p_joint_up(s, k1, tau1, k2, tau2, sigma);
+
+    let fees = state.info.fees;
+    let current = Instrument::Current;
+    let min_edge = state.params.get("nested_arb", "min_edge", MIN_EDGE);
+    let max_break_prob = state.params.get("nested_arb", "max_break_prob", MAX_BREAK_PROB);
+    let candidates = [
+        // UP on the cross market, DOWN on the current one
+        (cross, current, cm.up_ask, state.down_ask, p1, 1.0 - p2, p2 - p12),
+        // DOWN on the cross market, UP on the current one
+        (current, cross, state.up_ask, cm.down_ask, p2, 1.0 - p1, p1 - p12),
+    ];
+    candidates
+        .into_iter()
+        .filter(|c| tradable(c.2) && tradable(c.3))
+        .filter_map(|(up_on, down_on, up_ask, down_ask, up_fair, down_fair, break_prob)| {
+            let cost = fees.effective_price(up_ask, Liquidity::Taker)
+                + fees.effective_price(down_ask, Liquidity::Taker);
+            let break_prob = break_prob.max(0.0);
+            if cost > 1.0 - min_edge || break_prob > max_break_prob {
+                return None;
+            }
+            Some(Package {
+                up_on,
+                down_on,
+                up_ask,
+                down_ask,
+                up_fair,
+                down_fair,
+                break_prob,
+                edge: up_fair + down_fair - cost,
+            })
+        })
+        .max_by(|a, b| a.edge.total_cmp(&b.edge))
+}
+
+impl StatefulStrategy for NestedArb {
+    fn name(&self) -> &'static str {
+        "nested_arb"
+    }
+
+    fn trigger(&self) -> EvalTrigger {
+        EvalTrigger::Both
+    }
+
+    fn on_market_start(&mut self, _state: &MarketState) {
+        self.in_flight.clear();
+        self.last_quote = None;
+    }
+
+    fn evaluate(&mut self, state: &MarketState, now_ms: i64, out: &mut Vec<Signal>) {
+        // One package at a time: the gateway resolves its leg risk first
+        if !self.in_flight.is_empty()
+            || state.cross_markets.is_empty()
+            || state.time_left_s(now_ms) < MIN_TIME_LEFT_S
+            || state.sigma_real() <= 0.0
+            || state.s_est() <= 0.0
+            || state.info.strike <= 0.0
+        {
+            return;
+        }
+
+        let Some((end_ms, pkg)) = state
+            .cross_markets
+            .iter()
+            .filter_map(|(&iv, cm)| {
+                price_package(state, Instrument::Cross(iv), cm, now_ms).map(|p| (cm.end_ms, p))
+            })
+            .max_by(|a, b| a.1.edge.total_cmp(&b.1.edge))
+        else {
+            return;
+        };
+
+        // The quotes that priced this package are spoken for until one moves
+        let quote = (end_ms, [pkg.up_ask, pkg.down_ask, state.up_ask, state.down_ask]);
+        if self.last_quote == Some(quote) {
+            return;
+        }
+        self.last_quote = Some(quote);
+
+        for (side, instrument, ask, fair) in [
+            (Side::Up, pkg.up_on, pkg.up_ask, pkg.up_fair),
+            (Side::Down, pkg.down_on, pkg.down_ask, pkg.down_fair),
+        ] {
+            out.push(Signal {
+                strategy: "nested_arb",
+                side,
+                edge: pkg.edge,
+                fair_value: fair,
+                market_price: ask,
+                confidence: 1.0 - pkg.break_prob,
+                size_frac: LEG_FRAC,
+                is_passive: false,
+                use_bid: false,
+                action: OrderAction::Buy,
+                quote: false,
+                pair: true,
+                instrument,
+            });
+        }
+    }
+
+    fn on_order_sent(&mut self, order: &Order, _state: &MarketState) {
+        self.in_flight.push(order.id);
+    }
+
+    fn on_order_ack(&mut self, ack: &OrderAck, _state: &MarketState) {
+        if ack.status.is_terminal() {
+            self.in_flight.retain(|&id| id != ack.order_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Interval;
+    use crate::math::fees::FeeSchedule;
+    use crate::strategies::test_helpers::*;
+    use crate::types::{OrderStatus, OrderType};
+    use std::time::Instant;
+
+    /// Current market: strike 95_000, S = 95_000, 120s left, fee-free.
+    fn setup(up_ask: f64, down_ask: f64) -> (MarketState, i64) {
+        let (mut state, now) = make_state(95_000.0, 95_000.0, 0.0005, 120.0, up_ask, down_ask);
+        state.info.fees = FeeSchedule::zero();
+        (state, now)
+    }
+
+    /// Cross market ending `left_s` from `now`, started a minute ago.
+    fn cross(state: &mut MarketState, now: i64, strike: f64, left_s: i64, up_ask: f64, down_ask: f64) {
+        state.cross_markets.insert(Interval::M5, CrossMarketState {
+            interval: Interval::M5,
+            up_bid: up_ask - 0.02,
+            up_ask,
+            down_bid: down_ask - 0.02,
+            down_ask,
+            strike,
+            start_ms: now - 60_000,
+            end_ms: now + left_s * 1000,
+            up_token_id: "cm-up".into(),
+            down_token_id: "cm-down".into(),
+        });
+    }
+
+    fn eval(na: &mut NestedArb, state: &MarketState, now: i64) -> Vec<Signal> {
+        let mut out = Vec::new();
+        na.evaluate(state, now, &mut out);
+        out
+    }
+
+    // ── Static bound (co-terminal) ──
+
+    /// Scenario: Cross market ends with the current one at a lower strike (94_900 < 95_000).
+    /// UP there costs 0.55 and DOWN here 0.40: $0.95 for a package worth ≥ $1.
+    /// Expected: Two pair legs, UP routed to the cross market and DOWN to the current one,
+    /// with zero break probability (confidence 1).
+    #[test]
+    fn test_coterminal_bound_violation_bought() {
+        let (mut state, now) = setup(0.62, 0.40);
+        cross(&mut state, now, 94_900.0, 120, 0.55, 0.47);
+        let sigs = eval(&mut NestedArb::new(), &state, now);
+        assert_eq!(sigs.len(), 2);
+        let (up, down) = (&sigs[0], &sigs[1]);
+        assert_eq!((up.side, up.instrument), (Side::Up, Instrument::Cross(Interval::M5)));
+        assert_eq!((down.side, down.instrument), (Side::Down, Instrument::Current));
+        assert!(up.pair && down.pair);
+        assert_eq!((up.market_price, down.market_price), (0.55, 0.40));
+        assert!((up.confidence - 1.0).abs() < 1e-12, "confidence={}", up.confidence);
+        assert!(up.edge > 0.05, "edge={}", up.edge);
+    }
+
+    /// Scenario: Same co-terminal strikes, but the crossed package is priced at $1.00.
+    /// Expected: No signal -- the bound holds.
+    #[test]
+    fn test_bound_respected_no_trade() {
+        let (mut state, now) = setup(0.62, 0.45);
+        cross(&mut state, now, 94_900.0, 120, 0.55, 0.47);
+        assert!(eval(&mut NestedArb::new(), &state, now).is_empty());
+    }
+
+    /// Scenario: Co-terminal cross market at a HIGHER strike (95_100). UP there + DOWN
+    /// here is cheap, but it pays $0 whenever S ends between the strikes.
+    /// Expected: No signal -- the break probability is far above MAX_BREAK_PROB.
+    #[test]
+    fn test_wrong_strike_order_not_an_arb() {
+        let (mut state, now) = setup(0.70, 0.40);
+        cross(&mut state, now, 95_100.0, 120, 0.45, 0.58);
+        assert!(eval(&mut NestedArb::new(), &state, now).is_empty());
+    }
+
+    /// Scenario: Taker fees lift a $0.98 package over the 1¢ margin.
+    /// Expected: No signal once fees are charged; a signal without them.
+    #[test]
+    fn test_fees_close_thin_violation() {
+        let (mut state, now) = setup(0.62, 0.47);
+        cross(&mut state, now, 94_900.0, 120, 0.51, 0.47);
+        assert_eq!(eval(&mut NestedArb::new(), &state, now).len(), 2);
+        state.info.fees = FeeSchedule::default();
+        assert!(eval(&mut NestedArb::new(), &state, now).is_empty());
+    }
+
+    // ── Path-conditional bound (inner window) ──
+
+    /// Scenario: Inner 5m window with 10s left, its strike 500 below S; current window
+    /// 120s left, ATM. The inner UP is still offered at a stale 0.50. The package
+    /// (UP there + DOWN here) breaks only if the inner window flips, which the
+    /// elapsed path makes ~impossible.
+    /// Expected: Package bought with break probability under MAX_BREAK_PROB.
+    #[test]
+    fn test_inner_window_decided_by_path() {
+        let (mut state, now) = setup(0.55, 0.44);
+        cross(&mut state, now, 94_500.0, 10, 0.50, 0.47);
+        let sigs = eval(&mut NestedArb::new(), &state, now);
+        assert_eq!(sigs.len(), 2);
+        assert!(sigs[0].confidence > 1.0 - MAX_BREAK_PROB && sigs[0].confidence < 1.0);
+    }
+
+    /// Scenario: Inner window ATM with 60s left: its outcome is a coin flip.
+    /// Expected: No signal even though the package is cheap -- the break state is likely.
+    #[test]
+    fn test_undecided_inner_window_skipped() {
+        let (mut state, now) = setup(0.55, 0.44);
+        cross(&mut state, now, 95_000.0, 60, 0.50, 0.50);
+        assert!(eval(&mut NestedArb::new(), &state, now).is_empty());
+    }
+
+    // ── Eligibility ──
+
+    /// Scenario: Cross market outlives the current window, has not started, or has no token IDs.
+    /// Expected: None of them is traded.
+    #[test]
+    fn test_ineligible_cross_markets() {
+        let (mut state, now) = setup(0.62, 0.40);
+        cross(&mut state, now, 94_900.0, 900, 0.55, 0.47);
+        assert!(eval(&mut NestedArb::new(), &state, now).is_empty(), "ends after us");
+
+        cross(&mut state, now, 94_900.0, 120, 0.55, 0.47);
+        state.cross_markets.get_mut(&Interval::M5).unwrap().start_ms = now + 1;
+        assert!(eval(&mut NestedArb::new(), &state, now).is_empty(), "not started");
+
+        cross(&mut state, now, 94_900.0, 120, 0.55, 0.47);
+        state.cross_markets.get_mut(&Interval::M5).unwrap().up_token_id.clear();
+        assert!(eval(&mut NestedArb::new(), &state, now).is_empty(), "no token ID");
+    }
+
+    /// Scenario: A package goes out; the same quotes are evaluated again, then the legs
+    /// are acked and a quote moves.
+    /// Expected: Silent while legs are in flight and while quotes are unchanged; fires
+    /// again once acked and re-priced.
+    #[test]
+    fn test_one_package_at_a_time() {
+        let (mut state, now) = setup(0.62, 0.40);
+        cross(&mut state, now, 94_900.0, 120, 0.55, 0.47);
+        let mut na = NestedArb::new();
+        let sigs = eval(&mut na, &state, now);
+        for (i, sig) in sigs.iter().enumerate() {
+            na.on_order_sent(&order_for(sig, i as u64 + 1), &state);
+        }
+        assert!(eval(&mut na, &state, now).is_empty(), "legs in flight");
+        for id in [1, 2] {
+            na.on_order_ack(&ack(id), &state);
+        }
+        assert!(eval(&mut na, &state, now).is_empty(), "same quotes");
+        cross(&mut state, now, 94_900.0, 120, 0.54, 0.47);
+        assert_eq!(eval(&mut na, &state, now).len(), 2);
+    }
+
+    fn order_for(sig: &Signal, id: u64) -> Order {
+        Order {
+            id,
+            side: sig.side,
+            price: sig.market_price,
+            size: sig.size_frac * 1000.0,
+            strategy: sig.strategy,
+            signal_edge: sig.edge,
+            is_passive: false,
+            created_at: Instant::now(),
+            order_type: OrderType::FOK,
+            post_only: false,
+            expiration_ms: None,
+            token_id: String::new(),
+            action: sig.action,
+            instrument: sig.instrument,
+        }
+    }
+
+    fn ack(id: u64) -> OrderAck {
+        OrderAck {
+            order_id: id,
+            status: OrderStatus::Filled,
+            filled_price: Some(0.5),
+            filled_size: Some(10.0),
+            latency_ms: 0.0,
+            clob_order_id: None,
+            raw_response: None,
+        }
+    }
+}
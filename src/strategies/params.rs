@@ -0,0 +1,150 @@
+//! Tunable strategy thresholds.
+//!
+//! Each strategy declares the constants worth searching over as [`Param`]s
+//! (name, hand-picked default, search grid) next to its `StrategySpec`.
+//! Overrides are loaded from `STRATEGY_PARAMS_FILE` into a [`StrategyParams`]
+//! on `MarketState` and read back by the strategy with its constant as the
+//! fallback, so an empty table behaves exactly like the compiled defaults.
+//!
+//! File format, one override per line (`#` starts a comment):
+//!
+//! ```text
+//! latency_arb.min_edge = 0.04
+//! convexity_fade.max_z_abs = 0.35
+//! ```
+
+use std::collections::HashMap;
+
+use crate::config::Config;
+use crate::strategies::registry;
+
+/// One tunable threshold of a strategy.
+#[derive(Debug, Clone, Copy)]
+pub struct Param {
+    pub name: &'static str,
+    /// Value of the strategy's constant; used when no override is set.
+    pub default: f64,
+    /// Values the `optimize` binary searches (should include `default`).
+    pub grid: &'static [f64],
+}
+
+/// Per-strategy parameter overrides, keyed by registry names.
+#[derive(Debug, Clone, Default)]
+pub struct StrategyParams {
+    overrides: HashMap<(&'static str, &'static str), f64>,
+}
+
+/// Resolve `strategy.param` against the registry.
+pub fn resolve(key: &str) -> Option<(&'static str, &'static Param)> {
+    let (strategy, name) = key.trim().split_once('.')?;
+    let spec = registry::spec(strategy)?;
+    let param = spec.params.iter().find(|p| p.name == name)?;
+    Some((spec.name, param))
+}
+
+/// Keys of `map` that name no registered strategy parameter.
+pub fn unknown_keys(map: &HashMap<String, f64>) -> Vec<String> {
+    let mut keys: Vec<String> = map.keys().filter(|k| resolve(k).is_none()).cloned().collect();
+    keys.sort();
+    keys
+}
+
+/// Parse a params file: `strategy.param = value` per line, `#` comments.
+/// Malformed lines are skipped.
+pub fn parse(text: &str) -> HashMap<String, f64> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.split('#').next()?.trim();
+            let (k, v) = line.split_once('=')?;
+            let v: f64 = v.trim().parse().ok()?;
+            Some((k.trim().to_string(), v))
+        })
+        .collect()
+}
+
+impl StrategyParams {
+    /// Overrides from `config.strategy_params`; unknown keys are ignored
+    /// (the bot reports them at startup via [`unknown_keys`]).
+    pub fn from_config(config: &Config) -> Self {
+        let mut params = Self::default();
+        for (key, &value) in &config.strategy_params {
+            if let Some((strategy, param)) = resolve(key) {
+                params.set(strategy, param.name, value);
+            }
+        }
+        params
+    }
+
+    pub fn set(&mut self, strategy: &'static str, name: &'static str, value: f64) {
+        self.overrides.insert((strategy, name), value);
+    }
+
+    /// Override for `strategy.name`, or `default` (the strategy's constant).
+    #[inline]
+    pub fn get(&self, strategy: &'static str, name: &'static str, default: f64) -> f64 {
+        if self.overrides.is_empty() {
+            return default;
+        }
+        self.overrides.get(&(strategy, name)).copied().unwrap_or(default)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.overrides.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::registry::STRATEGIES;
+    use crate::strategies::test_helpers::make_config;
+
+    /// Scenario: Every parameter declared in the registry.
+    /// Expected: Names unique per strategy, grids non-empty, sorted and containing the default.
+    #[test]
+    fn test_declared_grids_contain_defaults() {
+        for spec in STRATEGIES {
+            assert!(!spec.params.is_empty(), "{} declares no tunables", spec.name);
+            for (i, p) in spec.params.iter().enumerate() {
+                assert!(spec.params[..i].iter().all(|q| q.name != p.name), "{}.{} duplicated", spec.name, p.name);
+                assert!(p.grid.windows(2).all(|w| w[0] < w[1]), "{}.{} grid unsorted", spec.name, p.name);
+                assert!(p.grid.contains(&p.default), "{}.{} grid misses default", spec.name, p.name);
+            }
+        }
+    }
+
+    /// Scenario: Params file with comments, blank lines, spaces and a malformed value.
+    /// Expected: Only the well-formed overrides are kept.
+    #[test]
+    fn test_parse_file() {
+        let text = "# chosen by optimize\nlatency_arb.min_edge = 0.04\n\nconvexity_fade.max_z_abs=0.35  # tighter\nbad.line = x\n";
+        let map = parse(text);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["latency_arb.min_edge"], 0.04);
+        assert_eq!(map["convexity_fade.max_z_abs"], 0.35);
+    }
+
+    /// Scenario: Config with one known override, one unknown parameter and one unknown strategy.
+    /// Expected: Known key applied, the others reported and ignored; unset params use the default.
+    #[test]
+    fn test_from_config_resolves_registry_keys() {
+        let mut config = make_config();
+        config.strategy_params.insert("latency_arb.min_edge".into(), 0.05);
+        config.strategy_params.insert("latency_arb.nope".into(), 1.0);
+        config.strategy_params.insert("nope.min_edge".into(), 1.0);
+
+        let params = StrategyParams::from_config(&config);
+        assert_eq!(params.get("latency_arb", "min_edge", 0.03), 0.05);
+        assert_eq!(params.get("certainty_capture", "min_edge", 0.02), 0.02);
+        assert_eq!(unknown_keys(&config.strategy_params), vec!["latency_arb.nope", "nope.min_edge"]);
+    }
+
+    /// Scenario: Empty table.
+    /// Expected: Every lookup returns the compiled default.
+    #[test]
+    fn test_empty_is_defaults() {
+        let params = StrategyParams::default();
+        assert!(params.is_empty());
+        assert_eq!(params.get("convexity_fade", "max_z_abs", 0.40), 0.40);
+    }
+}
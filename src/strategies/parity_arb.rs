@@ -0,0 +1,416 @@
+use crate::engine::state::MarketState;
+use crate::math::fees::{FeeSchedule, Liquidity};
+use crate::strategies::params::Param;
+use crate::strategies::StatefulStrategy;
+use crate::types::{EvalTrigger, Instrument, Order, OrderAck, OrderAction, Side, Signal};
+
+/// Edge 8: Complement-Parity Arbitrage
+///
+/// One UP and one DOWN token of the same market always settle to exactly $1
+/// together. Walking both books level by level:
+///   buy  pair: 1 − (up_ask + fee) − (down_ask + fee) ≥ MIN_EDGE → buy both
+///   sell pair: (up_bid − fee) + (down_bid − fee) − 1 ≥ MIN_EDGE → sell both
+/// The walk stops at the first level pair that no longer clears MIN_EDGE, so the
+/// size is all the depth that is profitable at the margin; the edge reported is
+/// the VWAP profit per pair. Selling needs a held pair (UP and DOWN inventory).
+///
+/// Legs go out as `Signal::pair` (FOK at the worst level walked, same share
+/// count) and the gateway executes them together, resolving leg risk and merging
+/// bought pairs back into USDC through the CTF contract.
+pub struct ParityArb {
+    /// Pair legs dispatched and not yet terminally acked.
+    in_flight: Vec<u64>,
+    /// Top of both books when the last pair went out. That liquidity is spoken
+    /// for until the book changes (simulated fills never consume it).
+    last_top: Option<[f64; 8]>,
+}
+
+/// Depth one pair trade can take, walked through both ladders.
+#[derive(Clone, Copy, Debug)]
+struct PairDepth {
+    /// Whole pairs (floored).
+    shares: f64,
+    /// Volume-weighted per-share prices, before fees.
+    up_vwap: f64,
+    down_vwap: f64,
+    /// Worst level reached on each ladder: the FOK limit.
+    up_limit: f64,
+    down_limit: f64,
+    /// Average profit per pair, net of taker fees.
+    edge: f64,
+}
+
+const MIN_EDGE: f64 = 0.01;          // 1¢ per pair net of fees, at the margin
+const MIN_SHARES: f64 = 5.0;         // not worth two FOKs (and their leg risk) below this
+const MIN_TIME_LEFT_S: f64 = 5.0;    // CLOB stops matching around expiry
+const LEVEL_EPS: f64 = 1e-9;
+
+/// Thresholds searched by the `optimize` binary (overridable via `StrategyParams`).
+pub const PARAMS: &[Param] = &[
+    Param { name: "min_edge", default: MIN_EDGE, grid: &[0.005, 0.01, 0.02] },
+];
+
+impl ParityArb {
+    pub fn new() -> Self {
+        Self { in_flight: Vec::new(), last_top: None }
+    }
+}
+
+impl Default for ParityArb {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Best level (price, size) of all four ladders.
+fn top_of_book(state: &MarketState) -> [f64; 8] {
+    let lvl = |l: &[(f64, f64)]| l.first().copied().unwrap_or((0.0, 0.0));
+    let (ua, da) = (lvl(&state.up_book.asks), lvl(&state.down_book.asks));
+    let (ub, db) = (lvl(&state.up_book.bids), lvl(&state.down_book.bids));
+    [ua.0, ua.1, da.0, da.1, ub.0, ub.1, db.0, db.1]
+}
+
+/// Walk two ladders (best level first) together, taking pairs while the marginal
+/// pair's `margin(up_price, down_price)` clears `min_edge`, up to `max_shares`.
+fn walk_pair(
+    up: &[(f64, f64)],
+    down: &[(f64, f64)],
+    max_shares: f64,
+    min_edge: f64,
+    margin: impl Fn(f64, f64) -> f64,
+) -> Option<PairDepth> {
+    let (mut i, mut j) = (0, 0);
+    let (mut up_left, mut down_left) = (up.first()?.1, down.first()?.1);
+    let (mut taken, mut up_cost, mut down_cost, mut profit) = (0.0, 0.0, 0.0, 0.0);
+    let (mut up_limit, mut down_limit) = (0.0, 0.0);
+
+    while i < up.len() && j < down.len() && taken < max_shares {
+        let (pu, pd) = (up[i].0, down[j].0);
+        let m = margin(pu, pd);
+        if m < min_edge {
+            break;
+        }
+        let take = up_left.min(down_left).min(max_shares - taken);
+        if take > 0.0 {
+            taken += take;
+            up_cost += take * pu;
+            down_cost += take * pd;
+            profit += take * m;
+            up_limit = pu;
+            down_limit = pd;
+        }
+        up_left -= take;
+        down_left -= take;
+        if up_left <= LEVEL_EPS {
+            i += 1;
+            up_left = up.get(i).map_or(0.0, |l| l.1);
+        }
+        if down_left <= LEVEL_EPS {
+            j += 1;
+            down_left = down.get(j).map_or(0.0, |l| l.1);
+        }
+    }
+
+    let shares = taken.floor();
+    if shares < MIN_SHARES {
+        return None;
+    }
+    Some(PairDepth {
+        shares,
+        up_vwap: up_cost / taken,
+        down_vwap: down_cost / taken,
+        up_limit,
+        down_limit,
+        edge: profit / taken,
+    })
+}
+
+/// Per-pair profit of buying UP at `pu` and DOWN at `pd`, taker fees included.
+#[inline]
+fn buy_margin(fees: &FeeSchedule, pu: f64, pd: f64) -> f64 {
+    1.0 - fees.effective_price(pu, Liquidity::Taker) - fees.effective_price(pd, Liquidity::Taker)
+}
+
+/// Per-pair profit of selling UP at `pu` and DOWN at `pd`, taker fees included.
+#[inline]
+fn sell_margin(fees: &FeeSchedule, pu: f64, pd: f64) -> f64 {
+    (pu - fees.fee_per_share(pu, Liquidity::Taker)) + (pd - fees.fee_per_share(pd, Liquidity::Taker)) - 1.0
+}
+
+impl StatefulStrategy for ParityArb {
+    fn name(&self) -> &'static str {
+        "parity_arb"
+    }
+
+    fn trigger(&self) -> EvalTrigger {
+        EvalTrigger::PolymarketQuote
+    }
+
+    fn on_market_start(&mut self, _state: &MarketState) {
+        self.in_flight.clear();
+        self.last_top = None;
+    }
+
+    fn evaluate(&mut self, state: &MarketState, now_ms: i64, out: &mut Vec<Signal>) {
+        let bankroll = state.sizing.bankroll();
+        // One pair at a time: the gateway resolves a pair before the next goes out
+        if !self.in_flight.is_empty()
+            || bankroll <= 0.0
+            || state.time_left_s(now_ms) < MIN_TIME_LEFT_S
+        {
+            return;
+        }
+        let top = top_of_book(state);
+        if self.last_top == Some(top) {
+            return;
+        }
+
+        let fees = state.info.fees;
+        let h = &state.position.holdings;
+        let held_pairs = h.shares(Side::Up).min(h.shares(Side::Down));
+        let min_edge = state.params.get("parity_arb", "min_edge", MIN_EDGE);
+
+        // Selling a held pair above $1 beats both merging and holding to settlement
+        let sell = walk_pair(&state.up_book.bids, &state.down_book.bids, held_pairs, min_edge, |pu, pd| {
+            sell_margin(&fees, pu, pd)
+        });
+        let (action, depth) = match sell {
+            Some(d) => (OrderAction::Sell, d),
+            None => match walk_pair(&state.up_book.asks, &state.down_book.asks, f64::INFINITY, min_edge, |pu, pd| {
+                buy_margin(&fees, pu, pd)
+            }) {
+                Some(d) => (OrderAction::Buy, d),
+                None => return,
+            },
+        };
+
+        // Per-share value of each leg given the other leg's all-in VWAP
+        let (up_fair, down_fair) = match action {
+            OrderAction::Buy => (
+                1.0 - fees.effective_price(depth.down_vwap, Liquidity::Taker),
+                1.0 - fees.effective_price(depth.up_vwap, Liquidity::Taker),
+            ),
+            OrderAction::Sell => (
+                1.0 - (depth.down_vwap - fees.fee_per_share(depth.down_vwap, Liquidity::Taker)),
+                1.0 - (depth.up_vwap - fees.fee_per_share(depth.up_vwap, Liquidity::Taker)),
+            ),
+        };
+
+        self.last_top = Some(top);
+        for (side, limit, fair) in [
+            (Side::Up, depth.up_limit, up_fair),
+            (Side::Down, depth.down_limit, down_fair),
+        ] {
+            out.push(Signal {
+                strategy: "parity_arb",
+                side,
+                edge: depth.edge,
+                fair_value: fair,
+                market_price: limit,
+                confidence: 1.0,
+                size_frac: depth.shares * limit / bankroll,
+                is_passive: false,
+                use_bid: false,
+                action,
+                quote: false,
+                pair: true,
+                instrument: Instrument::Current,
+            });
+        }
+    }
+
+    fn on_order_sent(&mut self, order: &Order, _state: &MarketState) {
+        self.in_flight.push(order.id);
+    }
+
+    fn on_order_ack(&mut self, ack: &OrderAck, _state: &MarketState) {
+        if ack.status.is_terminal() {
+            self.in_flight.retain(|&id| id != ack.order_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::sizing::KellySizer;
+    use crate::math::fees::FeeSchedule;
+    use crate::strategies::test_helpers::*;
+    use crate::types::{OrderStatus, OrderType};
+    use std::time::Instant;
+
+    /// 120s left, $1000 bankroll, fee-free unless a test sets fees.
+    fn setup(up_asks: Vec<(f64, f64)>, down_asks: Vec<(f64, f64)>) -> (MarketState, i64) {
+        let (mut state, now) = make_state(95_000.0, 95_000.0, 0.0005, 120.0, 0.50, 0.50);
+        state.sizing = KellySizer::from_config(&make_config());
+        state.info.fees = FeeSchedule::zero();
+        inject_book(&mut state, Side::Up, vec![(0.40, 100.0)], up_asks);
+        inject_book(&mut state, Side::Down, vec![(0.40, 100.0)], down_asks);
+        (state, now)
+    }
+
+    fn eval(pa: &mut ParityArb, state: &MarketState, now: i64) -> Vec<Signal> {
+        let mut out = Vec::new();
+        pa.evaluate(state, now, &mut out);
+        out
+    }
+
+    fn order_for(sig: &Signal, id: u64) -> Order {
+        Order {
+            id,
+            side: sig.side,
+            price: sig.market_price,
+            size: sig.size_frac * 1000.0,
+            strategy: sig.strategy,
+            signal_edge: sig.edge,
+            is_passive: false,
+            created_at: Instant::now(),
+            order_type: OrderType::FOK,
+            post_only: false,
+            expiration_ms: None,
+            token_id: String::new(),
+            action: sig.action,
+            instrument: sig.instrument,
+        }
+    }
+
+    // ── Buy side ──
+
+    /// Scenario: UP asks 0.45×50 then 0.47×50, DOWN asks 0.50×30 then 0.52×100, no fees.
+    /// Level pairs: (0.45,0.50) margin 0.05 for 30, (0.45,0.52) margin 0.03 for 20,
+    /// (0.47,0.52) margin 0.01 for 50 — all clear the 1¢ MIN_EDGE.
+    /// Expected: two pair legs, UP first, 100 shares each, limits at the worst levels
+    /// (0.47 / 0.52), VWAP edge (30·0.05 + 20·0.03 + 50·0.01)/100 = 0.026.
+    #[test]
+    fn test_buys_pair_through_depth() {
+        let (state, now) = setup(vec![(0.45, 50.0), (0.47, 50.0)], vec![(0.50, 30.0), (0.52, 100.0)]);
+        let mut pa = ParityArb::new();
+        let sigs = eval(&mut pa, &state, now);
+
+        assert_eq!(sigs.len(), 2);
+        assert!(sigs.iter().all(|s| s.pair && s.action == OrderAction::Buy));
+        assert_eq!((sigs[0].side, sigs[1].side), (Side::Up, Side::Down));
+        assert!((sigs[0].market_price - 0.47).abs() < 1e-9);
+        assert!((sigs[1].market_price - 0.52).abs() < 1e-9);
+        let up_shares = sigs[0].size_frac * 1000.0 / sigs[0].market_price;
+        let down_shares = sigs[1].size_frac * 1000.0 / sigs[1].market_price;
+        assert!((up_shares - 100.0).abs() < 1e-6 && (down_shares - 100.0).abs() < 1e-6);
+        assert!((sigs[0].edge - 0.026).abs() < 1e-9, "edge = {}", sigs[0].edge);
+    }
+
+    /// Scenario: Second DOWN level at 0.54 makes (0.47, 0.54) a −1¢ pair.
+    /// Expected: the walk stops there — 50 shares, DOWN limit stays at the 0.52 level.
+    #[test]
+    fn test_walk_stops_at_unprofitable_level() {
+        let (state, now) = setup(vec![(0.45, 50.0), (0.47, 50.0)], vec![(0.52, 50.0), (0.54, 100.0)]);
+        let mut pa = ParityArb::new();
+        let sigs = eval(&mut pa, &state, now);
+
+        assert_eq!(sigs.len(), 2);
+        let shares = sigs[1].size_frac * 1000.0 / sigs[1].market_price;
+        assert!((shares - 50.0).abs() < 1e-6, "shares = {}", shares);
+        assert!((sigs[0].market_price - 0.45).abs() < 1e-9);
+        assert!((sigs[1].market_price - 0.52).abs() < 1e-9);
+    }
+
+    /// Scenario: 0.48 + 0.50 = 0.98 — a 2¢ gross gap, but default crypto fees
+    /// (~0.78¢ per share per leg near 0.5) leave less than the 1¢ MIN_EDGE.
+    /// Expected: fee-free it trades; with fees, no signal.
+    #[test]
+    fn test_fees_close_thin_gap() {
+        let (mut state, now) = setup(vec![(0.48, 100.0)], vec![(0.50, 100.0)]);
+        assert_eq!(eval(&mut ParityArb::new(), &state, now).len(), 2);
+
+        state.info.fees = FeeSchedule::default();
+        assert!(eval(&mut ParityArb::new(), &state, now).is_empty());
+    }
+
+    /// Scenario: Asks sum to exactly $1 (0.50 + 0.50).
+    /// Expected: no pair.
+    #[test]
+    fn test_no_arb_at_parity() {
+        let (state, now) = setup(vec![(0.50, 100.0)], vec![(0.50, 100.0)]);
+        assert!(eval(&mut ParityArb::new(), &state, now).is_empty());
+    }
+
+    // ── Sell side ──
+
+    /// Scenario: Bids 0.53 + 0.50 = 1.03 and we hold 40 UP / 60 DOWN shares, both bought at 0.45.
+    /// Expected: sell pair of the 40 held pairs (not the $18 paid for them), limits at the bids.
+    /// Without inventory the same book produces nothing (asks sum > $1).
+    #[test]
+    fn test_sells_held_pair_above_parity() {
+        let (mut state, now) = setup(vec![(0.55, 100.0)], vec![(0.52, 100.0)]);
+        inject_book(&mut state, Side::Up, vec![(0.53, 100.0)], vec![(0.55, 100.0)]);
+        inject_book(&mut state, Side::Down, vec![(0.50, 100.0)], vec![(0.52, 100.0)]);
+        assert!(eval(&mut ParityArb::new(), &state, now).is_empty());
+
+        state.position.record_fill(Side::Up, 0.45, 18.0);
+        state.position.record_fill(Side::Down, 0.45, 27.0);
+        let sigs = eval(&mut ParityArb::new(), &state, now);
+
+        assert_eq!(sigs.len(), 2);
+        assert!(sigs.iter().all(|s| s.pair && s.action == OrderAction::Sell));
+        assert!((sigs[0].market_price - 0.53).abs() < 1e-9);
+        let shares = sigs[0].size_frac * 1000.0 / sigs[0].market_price;
+        assert!((shares - 40.0).abs() < 1e-6, "shares = {}", shares);
+        assert!((sigs[0].edge - 0.03).abs() < 1e-9);
+    }
+
+    /// Scenario: Same bids above parity, but the 40 held pairs were merged into USDC.
+    /// Expected: nothing left to sell — only the 20 unpaired DOWN shares remain.
+    #[test]
+    fn test_merged_pairs_not_sold() {
+        let (mut state, now) = setup(vec![(0.55, 100.0)], vec![(0.52, 100.0)]);
+        inject_book(&mut state, Side::Up, vec![(0.53, 100.0)], vec![(0.55, 100.0)]);
+        inject_book(&mut state, Side::Down, vec![(0.50, 100.0)], vec![(0.52, 100.0)]);
+        state.position.record_fill(Side::Up, 0.45, 18.0);
+        state.position.record_fill(Side::Down, 0.45, 27.0);
+        state.position.on_merge(40.0);
+        assert!(eval(&mut ParityArb::new(), &state, now).is_empty());
+    }
+
+    // ── Lifecycle ──
+
+    /// Scenario: A pair goes out; evaluate again before and after its acks, then
+    /// on an unchanged and on a refreshed book.
+    /// Expected: silent while legs are in flight and while the book is the one
+    /// already traded against; fires again once the book changes.
+    #[test]
+    fn test_one_pair_at_a_time() {
+        let (mut state, now) = setup(vec![(0.45, 100.0)], vec![(0.50, 100.0)]);
+        let mut pa = ParityArb::new();
+        let sigs = eval(&mut pa, &state, now);
+        assert_eq!(sigs.len(), 2);
+        pa.on_order_sent(&order_for(&sigs[0], 1), &state);
+        pa.on_order_sent(&order_for(&sigs[1], 2), &state);
+
+        inject_book(&mut state, Side::Up, vec![(0.40, 100.0)], vec![(0.44, 100.0)]);
+        assert!(eval(&mut pa, &state, now).is_empty(), "legs in flight");
+
+        for id in [1, 2] {
+            let ack = OrderAck {
+                order_id: id,
+                status: OrderStatus::Filled,
+                filled_price: None,
+                filled_size: None,
+                latency_ms: 0.0,
+                clob_order_id: None,
+                raw_response: None,
+            };
+            pa.on_order_ack(&ack, &state);
+        }
+        assert_eq!(eval(&mut pa, &state, now).len(), 2, "new book after acks");
+        assert!(eval(&mut pa, &state, now).is_empty(), "same book already traded");
+    }
+
+    /// Scenario: 3 seconds to expiry with a wide-open gap.
+    /// Expected: no signal (inside MIN_TIME_LEFT_S).
+    #[test]
+    fn test_silent_near_expiry() {
+        let (state, now) = setup(vec![(0.40, 100.0)], vec![(0.40, 100.0)]);
+        let late = state.info.end_ms - 3_000;
+        assert!(eval(&mut ParityArb::new(), &state, late).is_empty());
+        assert_eq!(eval(&mut ParityArb::new(), &state, now).len(), 2);
+    }
+}
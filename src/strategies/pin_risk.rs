@@ -0,0 +1,269 @@
+use crate::engine::sizing::Bet;
+use crate::engine::state::MarketState;
+use crate::math::fees::Liquidity;
+use crate::math::pricing::z_score;
+use crate::strategies::params::Param;
+use crate::strategies::Strategy;
+use crate::types::{EvalTrigger, Instrument, OrderAction, Side, Signal};
+
+/// Edge 11: Late-Window Pin Risk
+///
+/// In the last 30s of a near-strike market the outcome hinges on which oracle
+/// print settles it, not on a 30s diffusion. The print carries a price sampled
+/// up to one oracle interval before its (jittered) timestamp, so part of the
+/// settlement distribution is already fixed by the recent Binance path:
+///   P(UP) = E_{u,v}[ P(S(τ + u − v) + β ≥ K) ],  u ~ U[±δ_oracle], v ~ U[0, cadence]
+/// priced by `OracleBasis::settle_prob_up` with the trade buffer as the path.
+/// Takes whichever side the quotes misprice against it. Covers the window
+/// convexity_fade leaves (τ_eff < 30s) below certainty_capture's |z| ≥ 1.5.
+pub struct PinRisk;
+
+const MAX_TIME_LEFT_S: f64 = 30.0;
+const MIN_TIME_LEFT_S: f64 = 1.0;   // an order needs to land before the close
+const MAX_Z_ABS: f64 = 1.5;         // beyond this is certainty_capture's trade
+const BASIS_SD: f64 = 5.0;          // $ of oracle-vs-Binance noise in one print
+const MIN_EDGE: f64 = 0.04;         // net of taker fee; jitter makes fair noisy
+const MIN_ASK_SIZE: f64 = 20.0;     // shares at the best ask
+const MIN_CONFIDENCE: f64 = 0.3;
+
+/// Thresholds searched by the `optimize` binary (overridable via `StrategyParams`).
+pub const PARAMS: &[Param] = &[
+    Param { name: "basis_sd", default: BASIS_SD, grid: &[2.5, 5.0, 10.0] },
+    Param { name: "min_edge", default: MIN_EDGE, grid: &[0.03, 0.04, 0.06] },
+];
+
+/// P(UP) over oracle jitter and staleness, anchored on the Binance clock so the
+/// path lookup and the diffusion agree.
+#[inline]
+fn settle_fair_up(state: &MarketState, sigma: f64) -> f64 {
+    let bn_ts = state.bn.binance_ts;
+    let tau_s = (state.info.end_ms - bn_ts) as f64 / 1000.0;
+    let basis_sd = state.params.get("pin_risk", "basis_sd", BASIS_SD);
+    state.oracle.settle_prob_up(state.bn.binance_price, state.info.strike, sigma, tau_s, basis_sd, |lag_s| {
+        state.bn.price_at(bn_ts - (lag_s * 1000.0) as i64)
+    })
+}
+
+impl Strategy for PinRisk {
+    fn name(&self) -> &'static str {
+        "pin_risk"
+    }
+
+    fn trigger(&self) -> EvalTrigger {
+        EvalTrigger::Both
+    }
+
+    #[inline]
+    fn evaluate(&self, state: &MarketState, now_ms: i64) -> Option<Signal> {
+        let min_edge = state.params.get("pin_risk", "min_edge", MIN_EDGE);
+
+        let time_left = state.time_left_s(now_ms);
+        if !(MIN_TIME_LEFT_S..=MAX_TIME_LEFT_S).contains(&time_left) {
+            return None;
+        }
+
+        let sigma = state.sigma_real();
+        if sigma <= 0.0 {
+            return None;
+        }
+
+        let s = state.s_est();
+        let k = state.info.strike;
+        if s <= 0.0 || k <= 0.0 || state.bn.binance_price <= 0.0 {
+            return None;
+        }
+
+        if z_score(s, k, sigma, state.tau_eff_s(now_ms)).abs() >= MAX_Z_ABS {
+            return None;
+        }
+
+        let fair_up = settle_fair_up(state, sigma);
+
+        let fees = &state.info.fees;
+        let mut best: Option<(Side, f64, f64, f64, f64)> = None; // (side, fair, ask, all_in, edge)
+        for (side, fair, ask, book) in [
+            (Side::Up, fair_up, state.up_ask, &state.up_book),
+            (Side::Down, 1.0 - fair_up, state.down_ask, &state.down_book),
+        ] {
+            if ask <= 0.0 || ask >= 1.0 || book.ask_depth(1) < MIN_ASK_SIZE {
+                continue;
+            }
+            let all_in = fees.effective_price(ask, Liquidity::Taker);
+            let edge = fair - all_in;
+            if edge >= min_edge && best.is_none_or(|b| edge > b.4) {
+                best = Some((side, fair, ask, all_in, edge));
+            }
+        }
+        let (side, fair, ask, all_in_price, edge) = best?;
+
+        let confidence = (edge / 0.10).clamp(MIN_CONFIDENCE, 1.0);
+        let size_frac = state.sizing.size(
+            state,
+            &Bet { strategy: "pin_risk", side, fair, price: all_in_price },
+            now_ms,
+        );
+
+        Some(Signal {
+            strategy: "pin_risk",
+            side,
+            edge,
+            fair_value: fair,
+            market_price: ask,
+            confidence,
+            size_frac,
+            is_passive: false,
+            use_bid: false,
+            action: OrderAction::Buy,
+            quote: false,
+            pair: false,
+            instrument: Instrument::Current,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::pricing::p_fair;
+    use crate::strategies::test_helpers::*;
+    use crate::types::BinanceTrade;
+    use std::time::Instant;
+
+    const K: f64 = 96_000.0;
+    const SIGMA: f64 = 0.0001;
+
+    /// Near-strike state with `tau_s` left and 200 shares on both asks.
+    fn pin_state(binance: f64, tau_s: f64, up_ask: f64, down_ask: f64) -> (MarketState, i64) {
+        let (mut state, now) = make_state(K, binance, SIGMA, tau_s, up_ask, down_ask);
+        inject_book(&mut state, Side::Up, vec![(up_ask - 0.02, 200.0)], vec![(up_ask, 200.0)]);
+        inject_book(&mut state, Side::Down, vec![(down_ask - 0.02, 200.0)], vec![(down_ask, 200.0)]);
+        (state, now)
+    }
+
+    /// Binance path: `price` every 100ms over the `secs` seconds before `now_ms`.
+    fn push_path(state: &mut MarketState, now_ms: i64, secs: i64, price: f64) {
+        for i in (1..=secs * 10).rev() {
+            state.bn.trade_buffer.push_back(BinanceTrade {
+                exchange_ts_ms: now_ms - i * 100,
+                recv_at: Instant::now(),
+                price,
+                qty: 0.1,
+                is_buy: true,
+            });
+        }
+    }
+
+    // ── Window gates ──
+
+    /// Scenario: Mispriced near-strike market with 60s left.
+    /// Expected: None -- outside the final 30s, convexity_fade's territory.
+    #[test]
+    fn test_none_before_window() {
+        let (state, now) = pin_state(96_015.0, 60.0, 0.45, 0.57);
+        assert!(PinRisk.evaluate(&state, now).is_none());
+    }
+
+    /// Scenario: Mispriced near-strike market with 0.5s left.
+    /// Expected: None -- an order would not land before the close.
+    #[test]
+    fn test_none_at_close() {
+        let (state, now) = pin_state(96_015.0, 0.5, 0.45, 0.57);
+        assert!(PinRisk.evaluate(&state, now).is_none());
+    }
+
+    /// Scenario: Realized vol is zero.
+    /// Expected: None -- no diffusion to price the remaining seconds.
+    #[test]
+    fn test_none_when_sigma_zero() {
+        let (mut state, now) = pin_state(96_015.0, 5.0, 0.45, 0.57);
+        state.bn.sigma_real_cached = 0.0;
+        assert!(PinRisk.evaluate(&state, now).is_none());
+    }
+
+    /// Scenario: BTC $100 above strike with 10s left (|z| ≈ 3), UP at 0.80.
+    /// Expected: None -- far from the pin, certainty_capture owns this.
+    #[test]
+    fn test_none_away_from_strike() {
+        let (state, now) = pin_state(96_100.0, 10.0, 0.80, 0.22);
+        assert!(PinRisk.evaluate(&state, now).is_none());
+    }
+
+    /// Scenario: Exactly at the strike with 10s left, both sides at 0.50.
+    /// Expected: None -- fair ≈ 0.5, no edge after the taker fee.
+    #[test]
+    fn test_none_when_fairly_priced() {
+        let (state, now) = pin_state(K, 10.0, 0.50, 0.50);
+        assert!(PinRisk.evaluate(&state, now).is_none());
+    }
+
+    /// Scenario: UP mispriced at 0.45 but only 5 shares offered.
+    /// Expected: None -- below MIN_ASK_SIZE at the best ask.
+    #[test]
+    fn test_none_on_thin_ask() {
+        let (mut state, now) = pin_state(96_015.0, 5.0, 0.45, 0.57);
+        inject_book(&mut state, Side::Up, vec![(0.43, 200.0)], vec![(0.45, 5.0)]);
+        push_path(&mut state, now, 5, 96_015.0);
+        assert!(PinRisk.evaluate(&state, now).is_none());
+    }
+
+    // ── Signals ──
+
+    /// Scenario: BTC has sat $15 above strike for 5s, 5s left, UP offered at 0.45.
+    /// Expected: Buy UP -- most of the settlement distribution is above strike.
+    #[test]
+    fn test_buys_up_when_pinned_above() {
+        let (mut state, now) = pin_state(96_015.0, 5.0, 0.45, 0.57);
+        push_path(&mut state, now, 5, 96_015.0);
+        let sig = PinRisk.evaluate(&state, now).expect("pinned above should fire");
+        assert_eq!(sig.side, Side::Up);
+        assert!(sig.fair_value > 0.70, "fair = {}", sig.fair_value);
+        assert!(sig.edge >= MIN_EDGE);
+        assert_eq!(sig.market_price, 0.45);
+        assert!(!sig.is_passive);
+    }
+
+    /// Scenario: BTC just ticked $5 above strike with 2s left after sitting $15 below it,
+    /// and the oracle updates every 4s. The book follows spot: UP 0.60, DOWN 0.42.
+    /// Expected: Buy DOWN -- half the oracle samples land on the stale path below the
+    ///           strike, although the driftless spot model says UP is favoured.
+    #[test]
+    fn test_stale_oracle_path_flips_side() {
+        let (mut state, now) = pin_state(96_005.0, 2.0, 0.60, 0.42);
+        state.oracle.cadence_s = 4.0;
+        push_path(&mut state, now, 10, 95_985.0);
+        state.bn.trade_buffer.push_back(BinanceTrade {
+            exchange_ts_ms: now,
+            recv_at: Instant::now(),
+            price: 96_005.0,
+            qty: 0.1,
+            is_buy: true,
+        });
+        let spot_model = p_fair(state.s_est(), K, SIGMA, state.tau_eff_s(now));
+        assert!(spot_model > 0.55, "spot model = {}", spot_model);
+
+        let sig = PinRisk.evaluate(&state, now).expect("stale path should fire");
+        assert_eq!(sig.side, Side::Down);
+        assert!(sig.fair_value > 0.55, "DOWN fair = {}", sig.fair_value);
+    }
+
+    /// Scenario: Pinned-above market, once with a 1s and once with an 8s oracle cadence;
+    /// the path is $15 above strike only in the last second (below before).
+    /// Expected: The slower oracle reaches further back into the below-strike path
+    ///           → lower UP fair; the fast one still buys UP.
+    #[test]
+    fn test_cadence_widens_path_dependence() {
+        let setup = |cadence: f64| {
+            let (mut state, now) = pin_state(96_015.0, 3.0, 0.30, 0.72);
+            state.oracle.cadence_s = cadence;
+            push_path(&mut state, now - 1000, 10, 95_985.0);
+            push_path(&mut state, now, 1, 96_015.0);
+            (state, now)
+        };
+        let (fast, now) = setup(1.0);
+        let (slow, _) = setup(8.0);
+        let (fair_fast, fair_slow) = (settle_fair_up(&fast, SIGMA), settle_fair_up(&slow, SIGMA));
+        assert!(fair_slow < fair_fast - 0.1, "slow {} vs fast {}", fair_slow, fair_fast);
+        let sig = PinRisk.evaluate(&fast, now).expect("fast oracle should fire");
+        assert_eq!(sig.side, Side::Up);
+    }
+}
@@ -0,0 +1,348 @@
+use crate::config::Config;
+use crate::engine::risk::StrategyLimits;
+use crate::strategies::certainty_capture::CertaintyCapture;
+use crate::strategies::convexity_fade::ConvexityFade;
+use crate::strategies::cross_timeframe::CrossTimeframe;
+use crate::strategies::flow_momentum::FlowMomentum;
+use crate::strategies::latency_arb::LatencyArb;
+use crate::strategies::lp_extreme::LpExtreme;
+use crate::strategies::market_maker::MarketMaker;
+use crate::strategies::nested_arb::NestedArb;
+use crate::strategies::parity_arb::ParityArb;
+use crate::strategies::pin_risk::PinRisk;
+use crate::strategies::strike_misalign::StrikeMisalign;
+use crate::strategies::params::Param;
+use crate::strategies::StatefulStrategy;
+use crate::strategies::{
+    certainty_capture, convexity_fade, cross_timeframe, flow_momentum, latency_arb, lp_extreme,
+    market_maker, nested_arb, parity_arb, pin_risk, strike_misalign,
+};
+use crate::types::EvalTrigger;
+
+/// Display colour for a strategy in the TUIs. Kept free of any UI crate so the
+/// library does not depend on ratatui; each renderer maps it to its own colour type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrategyColor {
+    Yellow,
+    LightCyan,
+    LightMagenta,
+    LightBlue,
+    LightRed,
+    LightGreen,
+    LightYellow,
+    White,
+    Cyan,
+    Magenta,
+    Red,
+}
+
+/// Everything the engines need to know about one strategy, declared once.
+///
+/// Live runner, backtester and replay build their `StrategyHost` from this table,
+/// `StrategyRiskManager` takes its per-strategy limits from it, `Config` reads one
+/// env toggle per entry, and the renderers take label and colour from it.
+pub struct StrategySpec {
+    /// Must equal `StatefulStrategy::name()` of the built instance.
+    pub name: &'static str,
+    /// Two-letter label used in tables and trade markers.
+    pub short: &'static str,
+    pub color: StrategyColor,
+    /// Must equal `StatefulStrategy::trigger()` of the built instance.
+    pub trigger: EvalTrigger,
+    /// Default per-strategy risk limits.
+    pub limits: StrategyLimits,
+    /// Env var that enables/disables the strategy in the live bot.
+    pub env_toggle: &'static str,
+    pub enabled_by_default: bool,
+    /// Tunable thresholds (defaults + search grids) for `StrategyParams` / `optimize`.
+    pub params: &'static [Param],
+    /// Fresh instance; called once per engine (state is reset by `on_market_start`).
+    pub build: fn() -> Box<dyn StatefulStrategy>,
+}
+
+// Cooldowns tuned for 300s (5-min) markets.
+// Target: 4-6 total orders per market. Each strategy gets 1-2 shots.
+// Portfolio cap (15% = $150) binds before individual caps sum.
+
+/// All known strategies, in evaluation order.
+pub static STRATEGIES: &[StrategySpec] = &[
+    StrategySpec {
+        name: "latency_arb",
+        short: "LA",
+        color: StrategyColor::Yellow,
+        trigger: EvalTrigger::BinanceTrade,
+        limits: StrategyLimits {
+            max_per_trade_frac: 0.02,   // $20 per trade
+            max_total_frac: 0.04,       // $40 total (2 orders)
+            cooldown_ms: 60_000,        // 60s between orders
+            max_orders_per_market: 2,
+        },
+        env_toggle: "STRAT_LATENCY_ARB",
+        enabled_by_default: true,
+        params: latency_arb::PARAMS,
+        build: || Box::new(LatencyArb),
+    },
+    StrategySpec {
+        name: "certainty_capture",
+        short: "CC",
+        color: StrategyColor::LightCyan,
+        trigger: EvalTrigger::PolymarketQuote,
+        limits: StrategyLimits {
+            max_per_trade_frac: 0.03,   // $30 per trade
+            max_total_frac: 0.03,       // $30 total (1 order)
+            cooldown_ms: 120_000,       // 120s — fires once, late in market
+            max_orders_per_market: 1,
+        },
+        env_toggle: "STRAT_CERTAINTY_CAPTURE",
+        enabled_by_default: true,
+        params: certainty_capture::PARAMS,
+        build: || Box::new(CertaintyCapture),
+    },
+    StrategySpec {
+        name: "convexity_fade",
+        short: "CF",
+        color: StrategyColor::LightMagenta,
+        trigger: EvalTrigger::PolymarketQuote,
+        limits: StrategyLimits {
+            max_per_trade_frac: 0.01,   // $10 per trade
+            max_total_frac: 0.02,       // $20 total (2 orders)
+            cooldown_ms: 60_000,        // 60s between orders
+            max_orders_per_market: 2,
+        },
+        env_toggle: "STRAT_CONVEXITY_FADE",
+        enabled_by_default: true,
+        params: convexity_fade::PARAMS,
+        build: || Box::new(ConvexityFade),
+    },
+    StrategySpec {
+        name: "cross_timeframe",
+        short: "CT",
+        color: StrategyColor::LightBlue,
+        trigger: EvalTrigger::PolymarketQuote,
+        limits: StrategyLimits {
+            max_per_trade_frac: 0.005,
+            max_total_frac: 0.02,
+            cooldown_ms: 120_000,
+            max_orders_per_market: 1,
+        },
+        // Off in live until a cross-market feed exists
+        env_toggle: "STRAT_CROSS_TF",
+        enabled_by_default: false,
+        params: cross_timeframe::PARAMS,
+        build: || Box::new(CrossTimeframe),
+    },
+    StrategySpec {
+        name: "strike_misalign",
+        short: "SM",
+        color: StrategyColor::LightRed,
+        trigger: EvalTrigger::MarketOpen,
+        limits: StrategyLimits {
+            max_per_trade_frac: 0.02,   // $20 per trade
+            max_total_frac: 0.04,       // $40 total (2 orders)
+            cooldown_ms: 30_000,        // 30s — allows re-entry if edge persists
+            max_orders_per_market: 2,
+        },
+        env_toggle: "STRAT_STRIKE_MISALIGN",
+        enabled_by_default: true,
+        params: strike_misalign::PARAMS,
+        build: || Box::new(StrikeMisalign),
+    },
+    StrategySpec {
+        name: "lp_extreme",
+        short: "LP",
+        color: StrategyColor::LightGreen,
+        trigger: EvalTrigger::Both,
+        limits: StrategyLimits {
+            max_per_trade_frac: 0.02,   // $20 per trade
+            max_total_frac: 0.02,       // $20 total (1 order)
+            cooldown_ms: 120_000,       // 120s — one tail risk shot
+            max_orders_per_market: 1,
+        },
+        env_toggle: "STRAT_LP_EXTREME",
+        enabled_by_default: true,
+        params: lp_extreme::PARAMS,
+        build: || Box::new(LpExtreme),
+    },
+    StrategySpec {
+        name: "market_maker",
+        short: "MM",
+        color: StrategyColor::LightYellow,
+        trigger: EvalTrigger::Both,
+        limits: StrategyLimits {
+            max_per_trade_frac: 0.01,   // $10 per quote (one $5 lot)
+            max_total_frac: 0.06,       // $60 resting + filled bids
+            cooldown_ms: 0,             // requotes on every tick move
+            max_orders_per_market: 400, // quotes + replacements
+        },
+        // Off until quoting has been validated on recorded books
+        env_toggle: "STRAT_MARKET_MAKER",
+        enabled_by_default: false,
+        params: market_maker::PARAMS,
+        build: || Box::new(MarketMaker::new()),
+    },
+    StrategySpec {
+        name: "parity_arb",
+        short: "PA",
+        color: StrategyColor::White,
+        trigger: EvalTrigger::PolymarketQuote,
+        limits: StrategyLimits {
+            max_per_trade_frac: 0.05,   // $50 per leg
+            max_total_frac: 0.20,       // $200 in locked pairs (both legs)
+            cooldown_ms: 0,             // one pair in flight at a time instead
+            max_orders_per_market: 40,  // 20 pairs
+        },
+        // Off until pair execution has been exercised live with small size
+        env_toggle: "STRAT_PARITY_ARB",
+        enabled_by_default: false,
+        params: parity_arb::PARAMS,
+        build: || Box::new(ParityArb::new()),
+    },
+    StrategySpec {
+        name: "nested_arb",
+        short: "NA",
+        color: StrategyColor::Cyan,
+        trigger: EvalTrigger::Both,
+        limits: StrategyLimits {
+            max_per_trade_frac: 0.02,   // $20 per leg
+            max_total_frac: 0.12,       // $120 across open packages (both legs)
+            cooldown_ms: 0,             // one package in flight at a time instead
+            max_orders_per_market: 20,  // 10 packages
+        },
+        // Needs a cross-market quote feed; no live producer is wired yet
+        env_toggle: "STRAT_NESTED_ARB",
+        enabled_by_default: false,
+        params: nested_arb::PARAMS,
+        build: || Box::new(NestedArb::new()),
+    },
+    StrategySpec {
+        name: "flow_momentum",
+        short: "FM",
+        color: StrategyColor::Magenta,
+        trigger: EvalTrigger::BinanceTrade,
+        limits: StrategyLimits {
+            max_per_trade_frac: 0.02,   // $20 — directional bet on a drift estimate
+            max_total_frac: 0.06,       // $60
+            cooldown_ms: 20_000,        // one shot per momentum burst
+            max_orders_per_market: 2,
+        },
+        // Off until it beats latency_arb on recorded markets (backtest --compare)
+        env_toggle: "STRAT_FLOW_MOMENTUM",
+        enabled_by_default: false,
+        params: flow_momentum::PARAMS,
+        build: || Box::new(FlowMomentum),
+    },
+    StrategySpec {
+        name: "pin_risk",
+        short: "PR",
+        color: StrategyColor::Red,
+        trigger: EvalTrigger::Both,
+        limits: StrategyLimits {
+            max_per_trade_frac: 0.01,   // $10 — fair value rests on the oracle model
+            max_total_frac: 0.02,       // $20
+            cooldown_ms: 5_000,         // the whole window is 30s
+            max_orders_per_market: 2,
+        },
+        // Off until ORACLE_DELTA_S / ORACLE_CADENCE_S are calibrated on settlements
+        env_toggle: "STRAT_PIN_RISK",
+        enabled_by_default: false,
+        params: pin_risk::PARAMS,
+        build: || Box::new(PinRisk),
+    },
+];
+
+/// Look up a strategy by name.
+pub fn spec(name: &str) -> Option<&'static StrategySpec> {
+    STRATEGIES.iter().find(|s| s.name == name)
+}
+
+/// Two-letter label, `??` for unregistered names.
+pub fn short(name: &str) -> &'static str {
+    spec(name).map_or("??", |s| s.short)
+}
+
+/// Display colour, `White` for unregistered names.
+pub fn color(name: &str) -> StrategyColor {
+    spec(name).map_or(StrategyColor::White, |s| s.color)
+}
+
+/// Instantiate every registered strategy (backtest, replay).
+pub fn build_all() -> Vec<Box<dyn StatefulStrategy>> {
+    STRATEGIES.iter().map(|s| (s.build)()).collect()
+}
+
+/// Instantiate the strategies enabled in `config` (live).
+pub fn build_enabled(config: &Config) -> Vec<Box<dyn StatefulStrategy>> {
+    STRATEGIES
+        .iter()
+        .filter(|s| config.is_strategy_enabled(s.name))
+        .map(|s| (s.build)())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// Scenario: Build every registered strategy and compare it against its spec.
+    /// Expected: Instance name and trigger match the declared ones, so the host
+    ///           partitions exactly as the registry documents.
+    #[test]
+    fn test_specs_match_instances() {
+        for spec in STRATEGIES {
+            let s = (spec.build)();
+            assert_eq!(s.name(), spec.name);
+            assert_eq!(s.trigger(), spec.trigger, "trigger mismatch for {}", spec.name);
+        }
+    }
+
+    /// Scenario: Names, short labels and env toggles across the whole registry.
+    /// Expected: All unique — duplicates would alias risk state or config keys.
+    #[test]
+    fn test_registry_keys_unique() {
+        let names: HashSet<_> = STRATEGIES.iter().map(|s| s.name).collect();
+        let shorts: HashSet<_> = STRATEGIES.iter().map(|s| s.short).collect();
+        let toggles: HashSet<_> = STRATEGIES.iter().map(|s| s.env_toggle).collect();
+        assert_eq!(names.len(), STRATEGIES.len());
+        assert_eq!(shorts.len(), STRATEGIES.len());
+        assert_eq!(toggles.len(), STRATEGIES.len());
+    }
+
+    /// Scenario: Default limits of every registered strategy.
+    /// Expected: Per-trade cap never exceeds the strategy's total cap and order budget is non-zero.
+    #[test]
+    fn test_default_limits_consistent() {
+        for spec in STRATEGIES {
+            let l = &spec.limits;
+            assert!(l.max_per_trade_frac > 0.0 && l.max_per_trade_frac <= l.max_total_frac, "{}", spec.name);
+            assert!(l.max_orders_per_market >= 1, "{}", spec.name);
+        }
+    }
+
+    /// Scenario: Display lookups for a known and an unknown strategy name.
+    /// Expected: Registered metadata for the known name, `??`/White fallback otherwise.
+    #[test]
+    fn test_display_lookup() {
+        assert_eq!(short("lp_extreme"), "LP");
+        assert_eq!(color("latency_arb"), StrategyColor::Yellow);
+        assert_eq!(short("nope"), "??");
+        assert_eq!(color("nope"), StrategyColor::White);
+    }
+
+    /// Scenario: Default config, then the same with latency_arb switched off and cross_timeframe on.
+    /// Expected: build_enabled follows each spec's default, then the per-name toggles.
+    #[test]
+    fn test_build_enabled_follows_config() {
+        let mut config = crate::strategies::test_helpers::make_config();
+        let names: Vec<_> = build_enabled(&config).iter().map(|s| s.name()).collect();
+        assert!(names.contains(&"latency_arb"));
+        assert!(!names.contains(&"cross_timeframe"), "cross_timeframe is off by default");
+        assert_eq!(build_all().len(), STRATEGIES.len());
+
+        config.strategy_toggles.insert("latency_arb".into(), false);
+        config.strategy_toggles.insert("cross_timeframe".into(), true);
+        let names: Vec<_> = build_enabled(&config).iter().map(|s| s.name()).collect();
+        assert!(!names.contains(&"latency_arb"));
+        assert!(names.contains(&"cross_timeframe"));
+    }
+}
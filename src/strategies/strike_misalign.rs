@@ -1,8 +1,11 @@
+use crate::engine::sizing::Bet;
 use crate::engine::state::MarketState;
+use crate::math::fees::Liquidity;
 use crate::math::normal::phi;
 use crate::math::pricing::d2;
-use crate::strategies::{kelly, Strategy};
-use crate::types::{EvalTrigger, Side, Signal};
+use crate::strategies::params::Param;
+use crate::strategies::Strategy;
+use crate::types::{EvalTrigger, Instrument, OrderAction, Side, Signal};
 
 /// Edge 5: Strike Misalignment (Opening Bias)
 ///
@@ -15,6 +18,12 @@ pub struct StrikeMisalign;
 const MIN_DP: f64 = 0.02; // minimum probability shift to trade
 const MIN_EDGE: f64 = 0.02;
 
+/// Thresholds searched by the `optimize` binary (overridable via `StrategyParams`).
+pub const PARAMS: &[Param] = &[
+    Param { name: "min_dp", default: MIN_DP, grid: &[0.01, 0.02, 0.03] },
+    Param { name: "min_edge", default: MIN_EDGE, grid: &[0.01, 0.02, 0.03] },
+];
+
 /// Compute the active window for strike misalignment based on market duration.
 /// 5m → 15s, 15m → 30s, 1h → 120s, 4h → 300s (matches Interval::open_window_ms).
 fn max_active_ms(state: &MarketState) -> i64 {
@@ -34,6 +43,9 @@ impl Strategy for StrikeMisalign {
 
     #[inline]
     fn evaluate(&self, state: &MarketState, now_ms: i64) -> Option<Signal> {
+        let min_dp = state.params.get("strike_misalign", "min_dp", MIN_DP);
+        let min_edge = state.params.get("strike_misalign", "min_edge", MIN_EDGE);
+
         // Only active in the opening window (scales with interval)
         let elapsed_ms = now_ms - state.info.start_ms;
         if elapsed_ms < 0 || elapsed_ms > max_active_ms(state) {
@@ -68,7 +80,7 @@ impl Strategy for StrikeMisalign {
         let sensitivity = phi(d) / (s_ref * sigma * tau.sqrt());
         let dp = -sensitivity * epsilon;
 
-        if dp.abs() < MIN_DP {
+        if dp.abs() < min_dp {
             return None;
         }
 
@@ -92,12 +104,19 @@ impl Strategy for StrikeMisalign {
             1.0 - crate::math::pricing::p_fair(s_ref, k, sigma, tau)
         };
 
-        let edge = fair - market_bid;
-        if edge < MIN_EDGE {
+        // Post-only at the bid rests as maker: edge includes the rebate
+        let all_in_price = state.info.fees.effective_price(market_bid, Liquidity::Maker);
+        let edge = fair - all_in_price;
+        if edge < min_edge {
             return None;
         }
 
         let confidence = (dp.abs() / 0.10).clamp(0.4, 0.9);
+        let size_frac = state.sizing.size(
+            state,
+            &Bet { strategy: "strike_misalign", side, fair, price: all_in_price },
+            now_ms,
+        );
 
         Some(Signal {
             strategy: "strike_misalign",
@@ -106,9 +125,13 @@ impl Strategy for StrikeMisalign {
             fair_value: fair,
             market_price: market_bid,
             confidence,
-            size_frac: kelly(edge, market_bid),
+            size_frac,
             is_passive: false,
             use_bid: true,
+            action: OrderAction::Buy,
+            quote: false,
+            pair: false,
+            instrument: Instrument::Current,
         })
     }
 }
@@ -1,10 +1,14 @@
 // Shared test fixtures for strategy and risk manager tests.
 // Only compiled under #[cfg(test)].
 
-use crate::config::{Config, Interval};
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+use crate::config::{BankrollMode, Config, HedgeVenue, Interval};
 use crate::engine::state::{BinanceState, MarketState};
+use crate::math::fees::FeeSchedule;
 use crate::math::oracle::OracleBasis;
-use crate::types::{MarketInfo, Side};
+use crate::types::{BinanceTrade, Instrument, MarketInfo, Order, OrderAction, OrderType, Side, Signal};
 
 /// Build a MarketState with the given parameters.
 /// Returns (state, now_ms) where now_ms is the timestamp to pass to evaluate().
@@ -29,9 +33,11 @@ pub fn make_state(
         strike,
         tick_size: 0.01,
         neg_risk: false,
+        fees: FeeSchedule::default(),
+        condition_id: String::new(),
     };
 
-    let oracle = OracleBasis::new(0.0, 2.0);
+    let oracle = OracleBasis::new(0.0, 2.0, 1.0);
     let bn = BinanceState::new(0.94, 5, 0.30, 30_000, 60_000);
     let mut state = MarketState::new(info, bn, oracle);
 
@@ -66,6 +72,46 @@ pub fn inject_book(state: &mut MarketState, side: Side, bids: Vec<(f64, f64)>, a
     }
 }
 
+/// Build an active buy signal on the current market with fair value `price + edge`.
+pub fn make_signal(strategy: &'static str, side: Side, edge: f64, confidence: f64, price: f64) -> Signal {
+    Signal {
+        strategy,
+        side,
+        edge,
+        fair_value: price + edge,
+        market_price: price,
+        confidence,
+        size_frac: 0.02,
+        is_passive: false,
+        use_bid: false,
+        action: OrderAction::Buy,
+        quote: false,
+        pair: false,
+        instrument: Instrument::Current,
+    }
+}
+
+/// Build an order on the current market: a latency_arb FOK taker unless the
+/// caller overrides fields (`Order { order_type: OrderType::GTC, ..make_order(..) }`).
+pub fn make_order(id: u64, side: Side, action: OrderAction, price: f64, size: f64) -> Order {
+    Order {
+        id,
+        side,
+        price,
+        size,
+        strategy: "latency_arb",
+        signal_edge: 0.05,
+        is_passive: false,
+        created_at: Instant::now(),
+        order_type: OrderType::FOK,
+        post_only: false,
+        expiration_ms: None,
+        token_id: String::new(),
+        action,
+        instrument: Instrument::Current,
+    }
+}
+
 /// Feed alternating up/down ticks to force Range regime (< 60% dominant).
 pub fn force_regime_range(state: &mut MarketState, now_ms: i64) {
     for i in 0..20 {
@@ -80,11 +126,46 @@ pub fn force_regime_trend(state: &mut MarketState, now_ms: i64) {
     }
 }
 
+/// Feed 1s returns into the probabilistic regime models (variance ratio + HMM).
+/// `drift` is the per-second log-return; a small alternating jitter keeps the
+/// variance non-degenerate.
+pub fn feed_sample_returns(state: &mut MarketState, drift: f64, n: usize) {
+    for i in 0..n {
+        let jitter = if i % 2 == 0 { 1e-5 } else { -1e-5 };
+        state.bn.on_sample_return(drift + jitter);
+    }
+}
+
 /// Inject a VWAP data point.
 pub fn inject_vwap(state: &mut MarketState, price: f64, qty: f64, now_ms: i64) {
     state.bn.vwap_tracker.update(now_ms, price, qty);
 }
 
+/// Feed `n` Binance trades 200ms apart ending at `now_ms` and at the state's
+/// current price, each `step` above the previous one. `buys_per_10` of every ten
+/// trades are aggressive buys. Updates the trade buffer, VWAP tracker and tick
+/// classifier the way `on_binance_trade` does, without touching sigma.
+pub fn inject_flow(state: &mut MarketState, now_ms: i64, n: usize, step: f64, buys_per_10: usize) {
+    let last = state.bn.binance_price;
+    for i in 0..n {
+        let back = (n - 1 - i) as f64;
+        let ts = now_ms - (n - 1 - i) as i64 * 200;
+        let price = last - step * back;
+        if state.bn.prev_binance_price > 0.0 && price != state.bn.prev_binance_price {
+            state.bn.regime.update(ts, price > state.bn.prev_binance_price);
+        }
+        state.bn.prev_binance_price = price;
+        state.bn.vwap_tracker.update(ts, price, 1.0);
+        state.bn.trade_buffer.push_back(BinanceTrade {
+            exchange_ts_ms: ts,
+            recv_at: Instant::now(),
+            price,
+            qty: 1.0,
+            is_buy: i % 10 < buys_per_10,
+        });
+    }
+}
+
 /// Build a minimal Config for risk manager tests.
 pub fn make_config() -> Config {
     Config {
@@ -101,24 +182,65 @@ pub fn make_config() -> Config {
         max_orders_per_market: 10,
         cooldown_ms: 5000,
         bankroll: 1000.0,
+        bankroll_mode: BankrollMode::Fixed,
+        wallet_sync_secs: 30,
         max_total_exposure_frac: 0.15,
         daily_loss_halt_frac: -0.03,
         weekly_loss_halt_frac: -0.08,
+        mtm_stop_loss_frac: 0.0,
+        mtm_take_profit_frac: 0.0,
+        strategy_stop_loss_frac: 0.0,
+        strategy_take_profit_frac: 0.0,
+        mtm_exit: false,
+        breaker_max_rejects: 0,
+        breaker_reject_cooloff_s: 0.0,
+        breaker_latency_p95_ms: 0.0,
+        breaker_latency_cooloff_s: 0.0,
+        breaker_adverse_window_s: 0.0,
+        breaker_adverse_limit: 0.0,
+        breaker_adverse_cooloff_s: 0.0,
+        breaker_calibration_limit: 0.0,
+        breaker_calibration_cooloff_s: 0.0,
+        risk_rules: Vec::new(),
+        exec_latency_ms: 250.0,
+        exec_min_fill_prob: 0.0,
         oracle_beta: 0.0,
         oracle_delta_s: 2.0,
+        oracle_cadence_s: 1.0,
         ewma_lambda: 0.94,
         sigma_floor_annual: 0.30,
         max_portfolio_delta: 0.0,
         max_portfolio_gamma_neg: 0.0,
-        strategy_latency_arb: true,
-        strategy_certainty_capture: true,
-        strategy_convexity_fade: true,
-        strategy_strike_misalign: true,
-        strategy_lp_extreme: true,
-        strategy_cross_timeframe: false,
+        max_dollar_delta: 0.0,
+        max_var_frac: 0.0,
+        var_confidence: 0.99,
+        var_paths: 2000,
+        var_assets: Vec::new(),
+        var_sample_secs: 5,
+        var_corr_window: 720,
+        kelly_fraction: 0.5,
+        kelly_fractions: HashMap::new(),
+        strategy_params: HashMap::new(),
+        strategy_toggles: HashMap::new(),
+        shadow_strategies: HashSet::new(),
+        ensemble: false,
+        deconflict_policy: String::new(),
+        deconflict_groups: Vec::new(),
+        max_direction_flips: 1,
+        house_side_min_confidence: 0.7,
+        hedge: false,
+        hedge_venue: HedgeVenue::Perp,
+        hedge_band_usd: 500.0,
+        hedge_step: 0.001,
+        hedge_fee_bps: 5.0,
+        hedge_dry_run: true,
+        hedge_rest_url: None,
+        binance_api_key: None,
+        binance_api_secret: None,
         dry_run: true,
         polymarket_private_key: None,
         polymarket_funder_address: None,
         polymarket_signature_type: 0,
+        polygon_rpc_url: String::new(),
     }
 }
@@ -152,6 +152,16 @@ impl TelegramClient {
         self.send_plain(&text).await;
     }
 
+    pub async fn send_breaker_alert(&self, b: &BreakerRecord) {
+        let text = format!(
+            "🚨 CIRCUIT BREAKER: {}\n\
+             Value: {:.4} | Limit: {:.4}\n\
+             Buys halted for {:.0}s",
+            b.breaker, b.value, b.limit, (b.until_ms - b.ts_ms) as f64 / 1000.0,
+        );
+        self.send_plain(&text).await;
+    }
+
     pub async fn send_market_summary(&self, m: &MarketEndRecord) {
         let outcome_str = match m.outcome {
             Side::Up => "🟢 UP",
@@ -166,14 +176,21 @@ impl TelegramClient {
             m.total_signals, m.total_orders, m.total_filled,
             m.gross_pnl,
         );
+        if m.per_strategy.iter().any(|ps| ps.shadow) {
+            text.push_str(&format!("\nShadow PnL (paper): ${:.2}", m.shadow_pnl));
+        }
+        if let Some(hedge_pnl) = m.hedge_pnl {
+            text.push_str(&format!("\nHedge PnL: ${:.2}", hedge_pnl));
+        }
 
-        // Per-strategy breakdown
+        // Per-strategy breakdown (shadow strategies tagged; their PnL is paper)
         if !m.per_strategy.is_empty() {
             text.push_str("\n\n📊 <b>Per Strategy:</b>");
             for ps in &m.per_strategy {
                 text.push_str(&format!(
-                    "\n  <code>{}</code>: sig={} ord={} fill={} pnl=${:.2}",
-                    ps.strategy, ps.signals, ps.orders, ps.filled, ps.gross_pnl,
+                    "\n  <code>{}</code>{}: sig={} ord={} fill={} pnl=${:.2}",
+                    ps.strategy, if ps.shadow { " (shadow)" } else { "" },
+                    ps.signals, ps.orders, ps.filled, ps.gross_pnl,
                 ));
             }
         }
@@ -61,6 +61,26 @@ pub async fn telemetry_writer(
         &format!("{}/clob_raw.csv", dir),
         "ts_ms,order_id,direction,raw_json",
     );
+    let mut exposure_csv = CsvWriter::new(
+        &format!("{}/exposure.csv", dir),
+        "ts_ms,strategy,pending,filled,realized_pnl,unrealized_pnl,unrealized_fair_pnl",
+    );
+    let mut mtm_csv = CsvWriter::new(
+        &format!("{}/mtm.csv", dir),
+        "ts_ms,time_left_s,realized_pnl,unrealized_pnl,unrealized_fair_pnl,stopped",
+    );
+    let mut rejections_csv = CsvWriter::new(
+        &format!("{}/risk_rejections.csv", dir),
+        "ts_ms,strategy,side,action,edge,rule,value,limit",
+    );
+    let mut breakers_csv = CsvWriter::new(
+        &format!("{}/breakers.csv", dir),
+        "ts_ms,breaker,value,limit,until_ms",
+    );
+    let mut hedges_csv = CsvWriter::new(
+        &format!("{}/hedges.csv", dir),
+        "ts_ms,qty,price,fee,position,binary_dollar_delta,dry_run",
+    );
 
     let tg = match (&config.tg_bot_token, &config.tg_chat_id) {
         (Some(token), Some(chat)) => {
@@ -156,6 +176,49 @@ pub async fn telemetry_writer(
                     tokio::spawn(async move { tg.send_strategy_metrics(&record).await; });
                 }
             }
+            TelemetryEvent::Exposure(e) => {
+                writeln!(
+                    exposure_csv.file,
+                    "{},{},{:.2},{:.2},{:.4},{:.4},{:.4}",
+                    e.ts_ms, e.strategy, e.pending, e.filled,
+                    e.realized_pnl, e.unrealized_pnl, e.unrealized_fair_pnl,
+                ).ok();
+            }
+            TelemetryEvent::Mtm(m) => {
+                writeln!(
+                    mtm_csv.file,
+                    "{},{:.1},{:.4},{:.4},{:.4},{}",
+                    m.ts_ms, m.time_left_s, m.realized_pnl, m.unrealized_pnl,
+                    m.unrealized_fair_pnl, if m.stopped { 1 } else { 0 },
+                ).ok();
+            }
+            TelemetryEvent::RiskRejection(r) => {
+                writeln!(
+                    rejections_csv.file,
+                    "{},{},{:?},{:?},{:.4},{},{:.4},{:.4}",
+                    r.ts_ms, r.strategy, r.side, r.action, r.edge, r.rule, r.value, r.limit,
+                ).ok();
+            }
+            TelemetryEvent::Breaker(b) => {
+                writeln!(
+                    breakers_csv.file,
+                    "{},{},{:.4},{:.4},{}",
+                    b.ts_ms, b.breaker, b.value, b.limit, b.until_ms,
+                ).ok();
+                if let Some(tg) = &tg {
+                    let tg = tg.clone();
+                    let record = b.clone();
+                    tokio::spawn(async move { tg.send_breaker_alert(&record).await; });
+                }
+            }
+            TelemetryEvent::Hedge(h) => {
+                writeln!(
+                    hedges_csv.file,
+                    "{},{:.6},{:.2},{:.4},{:.6},{:.2},{}",
+                    h.ts_ms, h.qty, h.price, h.fee, h.position,
+                    h.binary_dollar_delta, if h.dry_run { 1 } else { 0 },
+                ).ok();
+            }
             TelemetryEvent::MarketEnd(m) => {
                 eprintln!(
                     "[TELEM] Market ended: {} outcome={:?} pnl=${:.2}",
@@ -172,11 +235,18 @@ pub async fn telemetry_writer(
                     writeln!(f, "total_orders={}", m.total_orders).ok();
                     writeln!(f, "total_filled={}", m.total_filled).ok();
                     writeln!(f, "gross_pnl={:.4}", m.gross_pnl).ok();
+                    if m.per_strategy.iter().any(|ps| ps.shadow) {
+                        writeln!(f, "shadow_pnl={:.4}", m.shadow_pnl).ok();
+                    }
+                    if let Some(hedge_pnl) = m.hedge_pnl {
+                        writeln!(f, "hedge_pnl={:.4}", hedge_pnl).ok();
+                    }
                     for ps in &m.per_strategy {
                         writeln!(
-                            f, "strat_{}=sig:{},ord:{},fill:{},pnl:{:.4},avg_edge:{:.4}",
+                            f, "strat_{}=sig:{},ord:{},fill:{},pnl:{:.4},avg_edge:{:.4}{}",
                             ps.strategy, ps.signals, ps.orders, ps.filled,
                             ps.gross_pnl, ps.avg_edge,
+                            if ps.shadow { ",shadow:1" } else { "" },
                         ).ok();
                     }
                 }
@@ -220,5 +290,10 @@ pub async fn telemetry_writer(
     orders_csv.flush();
     fills_csv.flush();
     clob_raw_csv.flush();
+    exposure_csv.flush();
+    mtm_csv.flush();
+    rejections_csv.flush();
+    hedges_csv.flush();
+    breakers_csv.flush();
     eprintln!("[TELEM] Writer stopped, files flushed");
 }
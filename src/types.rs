@@ -1,6 +1,8 @@
 use std::time::Instant;
 
 use crate::config::Interval;
+use crate::engine::var::PortfolioRisk;
+use crate::math::fees::{FeeSchedule, Liquidity};
 
 // ─── Feed Events (produced by WS tasks, consumed by engine) ───
 
@@ -9,7 +11,11 @@ pub enum FeedEvent {
     PolymarketQuote(PolymarketQuote),
     PolymarketBook(PolymarketBook),
     CrossMarketQuote(CrossMarketQuoteEvent),
+    /// Spot correlation and wallet positions (portfolio feed, `MAX_VAR_FRAC`).
+    Portfolio(PortfolioRisk),
     OrderAck(OrderAck),
+    /// Whole UP+DOWN pairs of the current market merged into USDC through the CTF.
+    PairsMerged(f64),
     Tick,
 }
 
@@ -45,7 +51,11 @@ pub struct CrossMarketQuoteEvent {
     pub down_bid: f64,
     pub down_ask: f64,
     pub strike: f64,
+    pub start_ms: i64,
     pub end_ms: i64,
+    /// CLOB token IDs, so orders can be routed to this market (empty if unknown).
+    pub up_token_id: String,
+    pub down_token_id: String,
 }
 
 // ─── Market Info ───
@@ -60,6 +70,10 @@ pub struct MarketInfo {
     pub strike: f64,
     pub tick_size: f64,
     pub neg_risk: bool,
+    /// Taker fee curve and maker rebate, fetched at discovery.
+    pub fees: FeeSchedule,
+    /// CTF condition ID (hex), empty if unknown. Used to merge UP+DOWN pairs.
+    pub condition_id: String,
 }
 
 /// Per-market context sent to the order gateway at market start.
@@ -69,11 +83,14 @@ pub struct MarketContext {
     pub down_token_id: String,
     pub tick_size: f64,
     pub neg_risk: bool,
+    pub condition_id: String,
+    /// Taker fee curve, for the break-even limit when chasing a pair leg.
+    pub fees: FeeSchedule,
 }
 
 // ─── Strategy Output ───
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Side {
     Up,
     Down,
@@ -88,6 +105,22 @@ impl std::fmt::Display for Side {
     }
 }
 
+/// Direction of an order on an outcome token. Strategies buy Up or Down tokens;
+/// only inventory-holding strategies (market making) ever sell them back.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OrderAction {
+    Buy,
+    Sell,
+}
+
+/// Market an order trades: the one this engine cycle runs, or the cross market
+/// of another interval tracked in `MarketState::cross_markets`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Instrument {
+    Current,
+    Cross(Interval),
+}
+
 /// Evaluation trigger: which event type a strategy wants to evaluate on.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum EvalTrigger {
@@ -109,17 +142,61 @@ pub struct Signal {
     /// If true, post at best bid instead of crossing at ask (GTD post_only).
     /// Used by convexity_fade and strike_misalign.
     pub use_bid: bool,
+    /// Buy the `side` token, or sell it out of inventory.
+    pub action: OrderAction,
+    /// Market-making quote: rests at `market_price` (GTC post_only), sized by the
+    /// strategy rather than Kelly, and cancelled/replaced by its own strategy.
+    pub quote: bool,
+    /// Leg of a two-leg package (UP + DOWN at the same share count): a
+    /// complement-parity pair, or a nested-window package across two markets.
+    /// Pair legs are emitted back to back, UP first, and go out both-or-neither
+    /// as one `OrderRequest::Pair`.
+    pub pair: bool,
+    /// Market the order is routed to.
+    pub instrument: Instrument,
+}
+
+impl Signal {
+    /// Liquidity role of the order this signal becomes: passive and
+    /// bid-posting signals go out post-only (maker), everything else crosses.
+    #[inline]
+    pub fn liquidity(&self) -> Liquidity {
+        if self.is_passive || self.use_bid || self.quote {
+            Liquidity::Maker
+        } else {
+            Liquidity::Taker
+        }
+    }
 }
 
 // ─── Settlement ───
 
 /// Recorded fill for settlement PnL computation.
+///
+/// Sell fills carry a negative `size`: settlement PnL, holdings and Greeks then
+/// net out against the earlier buys without special cases.
+#[derive(Clone)]
 pub struct Fill {
     pub order_id: u64,
     pub strategy: &'static str,
     pub side: Side,
     pub price: f64,
     pub size: f64,
+    /// Signed fee for this fill: positive = taker fee paid, negative = maker rebate.
+    pub fee: f64,
+}
+
+impl Fill {
+    /// Settlement PnL net of fees and rebates.
+    #[inline]
+    pub fn pnl(&self, outcome: Side) -> f64 {
+        let gross = if self.side == outcome {
+            (1.0 - self.price) * self.size
+        } else {
+            -(self.price * self.size)
+        };
+        gross - self.fee
+    }
 }
 
 // ─── Orders & Execution ───
@@ -151,8 +228,27 @@ pub struct Order {
     pub post_only: bool,
     /// GTD expiration timestamp in milliseconds (UTC). Only set for GTD orders.
     pub expiration_ms: Option<i64>,
-    /// CLOB token ID for the outcome being bought.
+    /// CLOB token ID for the outcome being bought or sold.
     pub token_id: String,
+    /// Buy or sell the outcome token.
+    pub action: OrderAction,
+    /// Market the token belongs to.
+    pub instrument: Instrument,
+}
+
+/// Engine → gateway request.
+pub enum OrderRequest {
+    Place(Order),
+    /// Cancel a resting order by engine order ID. Acked with `OrderStatus::Cancelled`
+    /// only if the order was still resting; fills that raced the cancel are acked as fills.
+    Cancel(u64),
+    /// Two-leg package: two FOK legs (UP, DOWN) for the same share count,
+    /// both buys or both sells, worth at least $1 per share together. The gateway
+    /// submits them together and resolves leg risk itself: a leg that fails alone
+    /// is chased to break-even, then the filled leg is unwound (`OrderStatus::Unwound`).
+    /// Filled buy pairs on the current market are merged back into USDC through
+    /// the CTF contract.
+    Pair(Order, Order),
 }
 
 pub struct OrderAck {
@@ -167,6 +263,25 @@ pub struct OrderAck {
     pub raw_response: Option<String>,
 }
 
+/// Engine → hedge gateway: a market order on the Binance hedge symbol.
+#[derive(Clone, Copy, Debug)]
+pub struct HedgeOrder {
+    pub id: u64,
+    /// Base-asset quantity: positive buys, negative sells.
+    pub qty: f64,
+}
+
+/// Hedge gateway → engine: the result of a `HedgeOrder`.
+#[derive(Clone, Debug)]
+pub struct HedgeFill {
+    pub id: u64,
+    /// Executed base quantity, signed like the order (0.0 on error).
+    pub qty: f64,
+    /// Average execution price.
+    pub price: f64,
+    pub error: Option<String>,
+}
+
 #[derive(Debug)]
 pub enum OrderStatus {
     Filled,
@@ -177,6 +292,20 @@ pub enum OrderStatus {
     Live,
     /// Post-only order rejected because it would cross the spread.
     Unmatched,
+    /// Resting order removed from the book on our request.
+    Cancelled,
+    /// Pair leg that filled (at `filled_price` × `filled_size`) while its other leg
+    /// did not, then was reversed at `exit_price` by the gateway. Both trades are
+    /// recorded; the position is flat afterwards.
+    Unwound { exit_price: f64 },
+}
+
+impl OrderStatus {
+    /// True once no further acks will arrive for the order. `Live` and
+    /// `PartialFill` orders keep resting and may still fill or be cancelled.
+    pub fn is_terminal(&self) -> bool {
+        !matches!(self, OrderStatus::Live | OrderStatus::PartialFill)
+    }
 }
 
 // ─── Telemetry Events ───
@@ -189,6 +318,16 @@ pub enum TelemetryEvent {
     MarketStart(MarketStartRecord),
     MarketEnd(MarketEndRecord),
     StrategyMetrics(StrategyMetricsRecord),
+    /// Per-strategy pending/filled exposure and mark-to-market PnL snapshot.
+    Exposure(ExposureRecord),
+    /// One point of the market's mark-to-market curve.
+    Mtm(MtmRecord),
+    /// A signal the risk rule chain rejected, and why.
+    RiskRejection(RiskRejectionRecord),
+    /// A delta-hedge fill on Binance (or its dry-run simulation).
+    Hedge(HedgeRecord),
+    /// A circuit breaker tripped and halted new buys. Triggers TG alert.
+    Breaker(BreakerRecord),
     /// Raw CLOB request/response JSON for exact-environment replay.
     RawClobResponse(RawClobRecord),
     /// Local order rejection (e.g., insufficient balance). Triggers TG alert.
@@ -273,6 +412,8 @@ pub struct PerStrategyEnd {
     pub filled: u32,
     pub gross_pnl: f64,
     pub avg_edge: f64,
+    /// Paper-traded (shadow mode): `gross_pnl` is simulated, not part of the market's PnL.
+    pub shadow: bool,
 }
 
 #[derive(Clone)]
@@ -286,6 +427,10 @@ pub struct MarketEndRecord {
     pub total_orders: u32,
     pub total_filled: u32,
     pub gross_pnl: f64,
+    /// Settled PnL of the shadow strategies' paper fills (excluded from `gross_pnl`).
+    pub shadow_pnl: f64,
+    /// Delta-hedge PnL marked at settlement (included in `gross_pnl`); `None` with hedging off.
+    pub hedge_pnl: Option<f64>,
     pub per_strategy: Vec<PerStrategyEnd>,
 }
 
@@ -300,6 +445,70 @@ pub struct StrategyMetricsRecord {
     pub avg_edge: f64,
 }
 
+#[derive(Clone)]
+pub struct ExposureRecord {
+    pub ts_ms: i64,
+    pub strategy: String,
+    pub pending: f64,
+    pub filled: f64,
+    pub realized_pnl: f64,
+    pub unrealized_pnl: f64,
+    pub unrealized_fair_pnl: f64,
+}
+
+#[derive(Clone)]
+pub struct MtmRecord {
+    pub ts_ms: i64,
+    pub time_left_s: f64,
+    pub realized_pnl: f64,
+    /// Open inventory at the best bids.
+    pub unrealized_pnl: f64,
+    /// Open inventory at the model fair value.
+    pub unrealized_fair_pnl: f64,
+    /// A market-level MTM stop has fired.
+    pub stopped: bool,
+}
+
+#[derive(Clone)]
+pub struct RiskRejectionRecord {
+    pub ts_ms: i64,
+    pub strategy: String,
+    pub side: Side,
+    pub action: OrderAction,
+    pub edge: f64,
+    /// Name of the rejecting rule (`RISK_RULES` name).
+    pub rule: &'static str,
+    /// What the rule measured, and the limit it failed.
+    pub value: f64,
+    pub limit: f64,
+}
+
+#[derive(Clone)]
+pub struct HedgeRecord {
+    pub ts_ms: i64,
+    /// Base-asset quantity traded (+ buy, − sell).
+    pub qty: f64,
+    pub price: f64,
+    pub fee: f64,
+    /// Hedge position after the fill.
+    pub position: f64,
+    /// Binary dollar delta the hedge was sized against.
+    pub binary_dollar_delta: f64,
+    pub dry_run: bool,
+}
+
+#[derive(Clone)]
+pub struct BreakerRecord {
+    pub ts_ms: i64,
+    /// Breaker name (`rejections`, `fill_latency`, `adverse_selection`, `calibration`).
+    pub breaker: &'static str,
+    /// What the breaker measured, and the limit it exceeded.
+    pub value: f64,
+    pub limit: f64,
+    /// Buys halted until (ms).
+    pub until_ms: i64,
+}
+
 /// Raw CLOB request/response for recording and replay.
 pub struct RawClobRecord {
     pub ts_ms: i64,